@@ -1597,7 +1597,9 @@ impl Default for AttributionTracker {
     }
 }
 
-/// Helper struct to track line boundaries in content
+/// Helper struct to track line boundaries in content.
+/// Lines end at '\n' only, as in git: a CRLF line keeps its '\r' and a lone '\r' doesn't
+/// start a new line, so line numbers agree with diff hunks and blame.
 struct LineBoundaries {
     /// Maps line number (1-indexed) to (start_byte, end_byte) exclusive end
     line_ranges: Vec<(usize, usize)>,
@@ -1739,13 +1741,35 @@ pub fn attributions_to_line_attributions(
     merged_line_authors
 }
 
+/// Dominant author of each line of `content`, or `None` for lines no attribution covers.
+/// Unlike `attributions_to_line_attributions`, human lines are kept.
+pub fn line_authors(attributions: &[Attribution], content: &str) -> Vec<Option<String>> {
+    let boundaries = LineBoundaries::new(content);
+    (1..=boundaries.line_count())
+        .map(|line_num| {
+            dominant_author_for_line(line_num, &boundaries, attributions, content)
+                .map(|(author, _)| author)
+        })
+        .collect()
+}
+
 /// Find the dominant author for a specific line based on non-whitespace character count
 fn find_dominant_author_for_line(
     line_num: u32,
     boundaries: &LineBoundaries,
-    attributions: &Vec<Attribution>,
+    attributions: &[Attribution],
     full_content: &str,
 ) -> (String, Option<String>) {
+    dominant_author_for_line(line_num, boundaries, attributions, full_content)
+        .unwrap_or_else(|| (CheckpointKind::Human.to_str(), None))
+}
+
+fn dominant_author_for_line(
+    line_num: u32,
+    boundaries: &LineBoundaries,
+    attributions: &[Attribution],
+    full_content: &str,
+) -> Option<(String, Option<String>)> {
     let (line_start, line_end) = boundaries.get_line_range(line_num).unwrap();
     let line_content = &full_content[line_start..line_end];
     let is_line_empty = line_content.is_empty() || line_content.chars().all(|c| c.is_whitespace());
@@ -1790,7 +1814,7 @@ fn find_dominant_author_for_line(
     }
 
     if candidate_attrs.is_empty() {
-        return (CheckpointKind::Human.to_str(), None);
+        return None;
     }
 
     // Choose the author with the latest timestamp
@@ -1818,7 +1842,7 @@ fn find_dominant_author_for_line(
         }
         _ => None,
     };
-    return (latest_author[0].clone(), overrode);
+    Some((latest_author[0].clone(), overrode))
 }
 
 /// Merge consecutive lines with the same author into LineAttribution ranges
@@ -2172,6 +2196,55 @@ mod tests {
         assert_eq!(line_attrs[0].author_id, "Alice");
     }
 
+    #[test]
+    fn crlf_and_mixed_line_endings_count_lines_like_git() {
+        // Only '\n' ends a line, so a lone '\r' stays inside its line
+        let content = "one\r\ntwo\r\nthree\rstill three\nfour";
+        let two_start = content.find("two").unwrap();
+        let four_start = content.find("four").unwrap();
+        let attrs = vec![
+            Attribution::new(0, two_start, "Alice".into(), TEST_TS),
+            Attribution::new(two_start, four_start, "Bob".into(), TEST_TS),
+            Attribution::new(four_start, content.len(), "Alice".into(), TEST_TS),
+        ];
+
+        let line_attrs = attributions_to_line_attributions(&attrs, content);
+        assert_eq!(
+            line_attrs,
+            vec![
+                LineAttribution::new(1, 1, "Alice".into(), None),
+                LineAttribution::new(2, 3, "Bob".into(), None),
+                LineAttribution::new(4, 4, "Alice".into(), None),
+            ]
+        );
+
+        let round_trip = line_attributions_to_attributions(&line_attrs, content, TEST_TS);
+        assert_eq!(round_trip, attrs);
+    }
+
+    #[test]
+    fn line_ending_conversion_preserves_attribution() {
+        let tracker = AttributionTracker::new();
+        let old = "let a = 1;\nlet b = 2;\n";
+        let new = "let a = 1;\r\nlet b = 2;\r\n";
+        let old_attrs = vec![Attribution::new(0, old.len(), "Alice".into(), TEST_TS)];
+
+        let updated = tracker
+            .update_attributions(old, new, &old_attrs, "Bob", TEST_TS + 1)
+            .unwrap();
+
+        assert_non_ws_owned_by(
+            &updated,
+            new,
+            "Alice",
+            "switching to CRLF should not steal tokens",
+        );
+        assert_eq!(
+            attributions_to_line_attributions(&updated, new),
+            vec![LineAttribution::new(1, 2, "Alice".into(), None)]
+        );
+    }
+
     #[test]
     fn unattributed_ranges_are_filled() {
         let tracker = AttributionTracker::new();
@@ -201,6 +201,9 @@ pub struct PromptRecord {
     /// Full URL to CAS-stored messages (format: {api_base_url}/cas/{hash})
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub messages_url: Option<String>,
+    /// Version of the AI tool, when its hooks report one (e.g. "0.45.2" for Cursor)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_version: Option<String>,
 }
 
 impl Eq for PromptRecord {}
@@ -254,6 +257,7 @@ mod tests {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         }
     }
 
@@ -281,4 +285,23 @@ mod tests {
                 || records[1].total_deletions > 0
         );
     }
+
+    #[test]
+    fn test_prompt_record_tool_version_is_optional() {
+        // Records written before tool versions were recorded still parse
+        let old = r#"{"agent_id":{"tool":"cursor","id":"abc","model":"gpt-5"},"human_author":null,"messages":[]}"#;
+        let record: PromptRecord = serde_json::from_str(old).unwrap();
+        assert_eq!(record.tool_version, None);
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(!json.contains("tool_version"), "{}", json);
+
+        let record = PromptRecord {
+            tool_version: Some("0.45.2".to_string()),
+            ..create_prompt_record(0, 0, 0)
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(r#""tool_version":"0.45.2""#), "{}", json);
+        let parsed: PromptRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.tool_version.as_deref(), Some("0.45.2"));
+    }
 }
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::repository::Repository;
+
+/// How many commits an [`AuthorshipLogCache`] holds unless told otherwise
+pub const DEFAULT_AUTHORSHIP_LOG_CACHE_CAPACITY: usize = 1024;
+
+/// Parsed authorship logs keyed by commit SHA, for tools that run many diffs or blames in one
+/// process and would otherwise read and parse the same notes again each time. Commits without
+/// a log are remembered as well. At most `capacity` commits are kept; the least recently used
+/// is evicted first. Shareable across threads, e.g. behind an `Arc`.
+#[derive(Debug)]
+pub struct AuthorshipLogCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// Bumped on every access, so entries can be ordered by last use
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    log: Option<AuthorshipLog>,
+    last_used: u64,
+}
+
+impl Default for AuthorshipLogCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_AUTHORSHIP_LOG_CACHE_CAPACITY)
+    }
+}
+
+impl AuthorshipLogCache {
+    pub fn new(capacity: usize) -> Self {
+        AuthorshipLogCache {
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// The authorship log of `commit_sha`, read from its note the first time it's asked for.
+    /// None when the commit has no (readable) log.
+    pub fn get_or_load(&self, repo: &Repository, commit_sha: &str) -> Option<AuthorshipLog> {
+        if let Some(log) = self.get(commit_sha) {
+            return log;
+        }
+
+        // Read outside the lock so concurrent blames don't queue behind each other's git calls
+        let log = get_reference_as_authorship_log_v3(repo, commit_sha).ok();
+        self.insert(commit_sha, log.clone());
+        log
+    }
+
+    /// Number of commits currently cached
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Some(log) when `commit_sha` is cached, where the log itself may be None
+    fn get(&self, commit_sha: &str) -> Option<Option<AuthorshipLog>> {
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        let entry = state.entries.get_mut(commit_sha)?;
+        entry.last_used = clock;
+        Some(entry.log.clone())
+    }
+
+    fn insert(&self, commit_sha: &str, log: Option<AuthorshipLog>) {
+        let mut state = self.lock();
+        state.clock += 1;
+        let last_used = state.clock;
+        state
+            .entries
+            .insert(commit_sha.to_string(), CacheEntry { log, last_used });
+
+        if state.entries.len() > self.capacity {
+            let least_recent = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(sha, _)| sha.clone());
+            if let Some(sha) = least_recent {
+                state.entries.remove(&sha);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        // Entries are only ever whole, so a panic elsewhere can't leave them half-written
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_authorship_log_cache_evicts_least_recently_used() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut shas = Vec::new();
+        for (index, name) in ["a.txt", "b.txt", "c.txt"].iter().enumerate() {
+            tmp_repo.write_file(name, "AI line\n", true).unwrap();
+            tmp_repo
+                .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+                .unwrap();
+            tmp_repo
+                .commit_with_message(&format!("AI commit {}", index))
+                .unwrap();
+            shas.push(tmp_repo.get_head_commit_sha().unwrap());
+        }
+        let repo = tmp_repo.gitai_repo();
+
+        let cache = AuthorshipLogCache::new(2);
+        assert!(cache.is_empty());
+        assert!(cache.get_or_load(repo, &shas[0]).is_some());
+        assert!(cache.get_or_load(repo, &shas[1]).is_some());
+
+        // Touching the first commit makes the second the least recently used
+        assert!(cache.get(&shas[0]).is_some());
+        assert!(cache.get_or_load(repo, &shas[2]).is_some());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&shas[0]).is_some());
+        assert!(cache.get(&shas[1]).is_none());
+        assert!(cache.get(&shas[2]).is_some());
+
+        // Commits without a note are remembered as having none
+        let cache = AuthorshipLogCache::default();
+        assert!(cache.get_or_load(repo, "HEAD~5").is_none());
+        assert!(matches!(cache.get("HEAD~5"), Some(None)));
+    }
+}
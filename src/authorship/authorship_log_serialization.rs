@@ -20,6 +20,32 @@ pub const GIT_AI_VERSION: &str = concat!("development:", env!("CARGO_PKG_VERSION
 #[cfg(not(debug_assertions))]
 pub const GIT_AI_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Where a line's AI attribution came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttributionProvenance {
+    /// The prompt is recorded in the authorship log of the commit that last touched the line
+    Direct,
+    /// The prompt is only recorded in another commit's log, e.g. one merged or rebased in
+    Merged,
+    /// Carried over from the line in another file it was copied or renamed from
+    CarriedOverRename,
+    /// Carried over from the position in the same file the line was moved from
+    #[serde(rename = "carried-over-move")]
+    Moved,
+}
+
+impl AttributionProvenance {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttributionProvenance::Direct => "direct",
+            AttributionProvenance::Merged => "merged",
+            AttributionProvenance::CarriedOverRename => "carried-over-rename",
+            AttributionProvenance::Moved => "carried-over-move",
+        }
+    }
+}
+
 /// Metadata section that goes below the divider as JSON
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AuthorshipMetadata {
@@ -228,14 +254,20 @@ impl AuthorshipLog {
         Self::deserialize_from_string(&content)
     }
 
-    /// Lookup the author and optional prompt for a given file and line
+    /// Lookup the author and optional prompt for a given file and line, with where the
+    /// prompt was found
     pub fn get_line_attribution(
         &self,
         repo: &Repository,
         file: &str,
         line: u32,
         foreign_prompts_cache: &mut HashMap<String, Option<PromptRecord>>,
-    ) -> Option<(Author, Option<String>, Option<PromptRecord>)> {
+    ) -> Option<(
+        Author,
+        Option<String>,
+        Option<PromptRecord>,
+        AttributionProvenance,
+    )> {
         // Find the file attestation
         let file_attestation = self.attestations.iter().find(|f| f.file_path == file)?;
 
@@ -257,40 +289,23 @@ impl AuthorshipLog {
                         author,
                         Some(entry.hash.clone()),
                         Some(prompt_record.clone()),
+                        AttributionProvenance::Direct,
                     ));
                 } else {
-                    // Check cache first before grepping
-                    let prompt_record = if let Some(cached_result) =
-                        foreign_prompts_cache.get(&entry.hash)
-                    {
-                        cached_result.clone()
-                    } else {
-                        // Try to find prompt record using git grep
-                        let shas =
-                            crate::git::refs::grep_ai_notes(repo, &format!("\"{}\"", &entry.hash))
-                                .unwrap_or_default();
-                        let result = if let Some(latest_sha) = shas.first() {
-                            if let Some(authorship_log) =
-                                crate::git::refs::get_authorship(repo, latest_sha)
-                            {
-                                authorship_log.metadata.prompts.get(&entry.hash).cloned()
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        };
-                        // Cache the result (even if None) to avoid repeated grepping
-                        foreign_prompts_cache.insert(entry.hash.clone(), result.clone());
-                        result
-                    };
+                    let prompt_record =
+                        find_foreign_prompt(repo, &entry.hash, foreign_prompts_cache);
 
                     if let Some(prompt_record) = prompt_record {
                         let author = Author {
                             username: prompt_record.agent_id.tool.clone(),
                             email: String::new(), // AI agents don't have email
                         };
-                        return Some((author, Some(entry.hash.clone()), Some(prompt_record)));
+                        return Some((
+                            author,
+                            Some(entry.hash.clone()),
+                            Some(prompt_record),
+                            AttributionProvenance::Merged,
+                        ));
                     }
                 }
             }
@@ -298,6 +313,57 @@ impl AuthorshipLog {
         None
     }
 
+    /// The attribution `get_line_attribution` gives each attributed line of `file`, computed
+    /// in one pass over the file's attestations instead of one scan per line. Each entry's
+    /// prompt is resolved once; later entries win, and entries whose prompt can't be found
+    /// leave earlier ones in place, as in `get_line_attribution`.
+    pub fn line_attribution_map(
+        &self,
+        repo: &Repository,
+        file: &str,
+        foreign_prompts_cache: &mut HashMap<String, Option<PromptRecord>>,
+    ) -> HashMap<
+        u32,
+        (
+            Author,
+            Option<String>,
+            Option<PromptRecord>,
+            AttributionProvenance,
+        ),
+    > {
+        let mut lines = HashMap::new();
+        let Some(file_attestation) = self.attestations.iter().find(|f| f.file_path == file) else {
+            return lines;
+        };
+
+        for entry in &file_attestation.entries {
+            let (prompt_record, provenance) = match self.metadata.prompts.get(&entry.hash) {
+                Some(prompt_record) => (prompt_record.clone(), AttributionProvenance::Direct),
+                None => match find_foreign_prompt(repo, &entry.hash, foreign_prompts_cache) {
+                    Some(prompt_record) => (prompt_record, AttributionProvenance::Merged),
+                    None => continue,
+                },
+            };
+            let author = Author {
+                username: prompt_record.agent_id.tool.clone(),
+                email: String::new(), // AI agents don't have email
+            };
+
+            for line in entry.line_ranges.iter().flat_map(LineRange::expand) {
+                lines.insert(
+                    line,
+                    (
+                        author.clone(),
+                        Some(entry.hash.clone()),
+                        Some(prompt_record.clone()),
+                        provenance,
+                    ),
+                );
+            }
+        }
+        lines
+    }
+
     /// Convert authorship log to working log checkpoints for merge --squash
     ///
     /// Creates one checkpoint per file per session that touched that file. This ensures that:
@@ -555,6 +621,30 @@ fn parse_line_ranges(input: &str) -> Result<Vec<LineRange>, Box<dyn std::error::
     Ok(ranges)
 }
 
+/// Find a prompt recorded in another commit's authorship note (e.g. a prompt
+/// whose lines were carried over by a rebase or cherry-pick)
+pub fn find_foreign_prompt(
+    repo: &Repository,
+    hash: &str,
+    foreign_prompts_cache: &mut HashMap<String, Option<PromptRecord>>,
+) -> Option<PromptRecord> {
+    // Check cache first before grepping
+    if let Some(cached_result) = foreign_prompts_cache.get(hash) {
+        return cached_result.clone();
+    }
+
+    // Try to find prompt record using git grep
+    let shas = crate::git::refs::grep_ai_notes(repo, &format!("\"{}\"", hash)).unwrap_or_default();
+    let result = shas.first().and_then(|latest_sha| {
+        crate::git::refs::get_authorship(repo, latest_sha)
+            .and_then(|authorship_log| authorship_log.metadata.prompts.get(hash).cloned())
+    });
+
+    // Cache the result (even if None) to avoid repeated grepping
+    foreign_prompts_cache.insert(hash.to_string(), result.clone());
+    result
+}
+
 /// Parse the attestation section (before the divider)
 fn parse_attestation_section(
     lines: &[&str],
@@ -775,6 +865,7 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                tool_version: None,
             },
         );
 
@@ -842,6 +933,7 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                tool_version: None,
             },
         );
 
@@ -868,6 +960,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_line_attribution_map_matches_per_line_lookup() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut log = AuthorshipLog::new();
+        for (tool, id) in [("cursor", "session_1"), ("claude", "session_2")] {
+            let agent_id = crate::authorship::working_log::AgentId {
+                tool: tool.to_string(),
+                id: id.to_string(),
+                model: "model".to_string(),
+            };
+            log.metadata.prompts.insert(
+                generate_short_hash(id, tool),
+                crate::authorship::authorship_log::PromptRecord {
+                    agent_id,
+                    human_author: None,
+                    messages: vec![],
+                    total_additions: 0,
+                    total_deletions: 0,
+                    accepted_lines: 0,
+                    overriden_lines: 0,
+                    messages_url: None,
+                    tool_version: None,
+                },
+            );
+        }
+
+        // The second prompt rewrote lines 4-6; an unresolvable prompt doesn't shadow them
+        let mut file = FileAttestation::new("src/lib.rs".to_string());
+        file.add_entry(AttestationEntry::new(
+            generate_short_hash("session_1", "cursor"),
+            vec![LineRange::Range(1, 6), LineRange::Single(9)],
+        ));
+        file.add_entry(AttestationEntry::new(
+            generate_short_hash("session_2", "claude"),
+            vec![LineRange::Range(4, 7)],
+        ));
+        file.add_entry(AttestationEntry::new(
+            "0000000000000000".to_string(),
+            vec![LineRange::Range(5, 8)],
+        ));
+        log.attestations.push(file);
+
+        let repo = tmp_repo.gitai_repo();
+        let mut cache = HashMap::new();
+        let map = log.line_attribution_map(repo, "src/lib.rs", &mut cache);
+        assert_eq!(map.len(), 8);
+        for line in 1..=10 {
+            let expected = log
+                .get_line_attribution(repo, "src/lib.rs", line, &mut cache)
+                .map(|(author, hash, _, provenance)| (author.username, hash, provenance));
+            let actual = map.get(&line).map(|(author, hash, _, provenance)| {
+                (author.username.clone(), hash.clone(), *provenance)
+            });
+            assert_eq!(actual, expected, "line {}", line);
+        }
+        assert!(
+            log.line_attribution_map(repo, "missing.rs", &mut cache)
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_serialize_deserialize_no_attestations() {
         // Test that serialization and deserialization work correctly when there are no attestations
@@ -891,6 +1046,7 @@ mod tests {
                 accepted_lines: 0,
                 overriden_lines: 0,
                 messages_url: None,
+                tool_version: None,
             },
         );
 
@@ -1070,6 +1226,7 @@ mod tests {
                 accepted_lines: 11,
                 overriden_lines: 0,
                 messages_url: None,
+                tool_version: None,
             },
         );
 
@@ -1241,6 +1398,7 @@ mod tests {
                 accepted_lines: 10,
                 overriden_lines: 0,
                 messages_url: None,
+                tool_version: None,
             },
         );
 
@@ -1265,6 +1423,7 @@ mod tests {
                 accepted_lines: 20,
                 overriden_lines: 0,
                 messages_url: None,
+                tool_version: None,
             },
         );
 
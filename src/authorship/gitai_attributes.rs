@@ -0,0 +1,178 @@
+use glob::{MatchOptions, Pattern};
+
+use crate::git::repository::Repository;
+
+/// Name of the optional file at the repo root assigning attribution attributes to paths
+pub const GITAI_ATTRIBUTES_FILENAME: &str = ".gitai-attributes";
+
+/// Per-path attribution settings read from `.gitai-attributes`.
+///
+/// Each line is a gitignore-style pattern followed by attributes, like `.gitattributes`:
+///
+/// ```text
+/// tests/fixtures/ attribution=ignore
+/// *.snap          attribution=ignore
+/// /keep.snap      -attribution
+/// ```
+///
+/// Paths with `attribution=ignore` keep their authorship notes but are shown as `NoData` in
+/// `git-ai diff` and left out of stats. As in `.gitattributes`, the last matching line wins.
+#[derive(Debug, Clone, Default)]
+pub struct GitAiAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+#[derive(Debug, Clone)]
+struct AttributeRule {
+    pattern: Pattern,
+    /// The pattern contains a slash, so it matches from the repo root
+    anchored: bool,
+    /// The pattern ends in a slash, so it matches directories (and everything under them)
+    dir_only: bool,
+    ignore: bool,
+}
+
+impl GitAiAttributes {
+    /// Read `.gitai-attributes` at the repo root; a missing file yields no rules
+    pub fn load(repo: &Repository) -> Self {
+        Self::parse(&Self::read_content(repo))
+    }
+
+    /// The raw contents of `.gitai-attributes` at the repo root, empty when there is none
+    pub fn read_content(repo: &Repository) -> String {
+        repo.workdir()
+            .ok()
+            .and_then(|workdir| {
+                std::fs::read_to_string(workdir.join(GITAI_ATTRIBUTES_FILENAME)).ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parse attribute lines. Blank lines, `#` comments, lines without an `attribution`
+    /// attribute and invalid patterns are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut rules = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            let mut ignore = None;
+            for attribute in fields {
+                match attribute {
+                    "attribution=ignore" => ignore = Some(true),
+                    "-attribution" | "!attribution" => ignore = Some(false),
+                    _ if attribute.starts_with("attribution=") => ignore = Some(false),
+                    _ => {}
+                }
+            }
+            let Some(ignore) = ignore else {
+                continue;
+            };
+
+            if let Some(rule) = AttributeRule::new(pattern, ignore) {
+                rules.push(rule);
+            }
+        }
+
+        Self { rules }
+    }
+
+    /// Whether attribution for `path` (relative to the repo root) should be ignored
+    pub fn is_ignored(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path))
+            .is_some_and(|rule| rule.ignore)
+    }
+}
+
+impl AttributeRule {
+    fn new(pattern: &str, ignore: bool) -> Option<Self> {
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(AttributeRule {
+            pattern: Pattern::new(pattern).ok()?,
+            anchored,
+            dir_only,
+            ignore,
+        })
+    }
+
+    /// Like gitignore: a pattern matching a directory matches everything under it
+    fn matches(&self, path: &str) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+
+        let components: Vec<&str> = path.split('/').collect();
+        (1..=components.len()).any(|end| {
+            let is_dir = end < components.len();
+            if self.dir_only && !is_dir {
+                return false;
+            }
+            if self.anchored {
+                self.pattern
+                    .matches_with(&components[..end].join("/"), options)
+            } else {
+                self.pattern.matches_with(components[end - 1], options)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unanchored_patterns_match_at_any_depth() {
+        let attributes =
+            GitAiAttributes::parse("*.snap attribution=ignore\nfixtures/ attribution=ignore\n");
+        assert!(attributes.is_ignored("ui.snap"));
+        assert!(attributes.is_ignored("src/__snapshots__/ui.snap"));
+        assert!(attributes.is_ignored("tests/fixtures/input.rs"));
+        assert!(attributes.is_ignored("fixtures/deep/input.rs"));
+        assert!(!attributes.is_ignored("src/fixtures.rs"));
+        assert!(!attributes.is_ignored("src/main.rs"));
+    }
+
+    #[test]
+    fn test_anchored_patterns_match_from_root() {
+        let attributes = GitAiAttributes::parse(
+            "/generated attribution=ignore\ndocs/**/*.md attribution=ignore\n",
+        );
+        assert!(attributes.is_ignored("generated/api.rs"));
+        assert!(!attributes.is_ignored("src/generated/api.rs"));
+        assert!(attributes.is_ignored("docs/guide.md"));
+        assert!(attributes.is_ignored("docs/a/b/guide.md"));
+        assert!(!attributes.is_ignored("src/docs/guide.md"));
+    }
+
+    #[test]
+    fn test_last_matching_line_wins() {
+        let attributes = GitAiAttributes::parse(
+            "# fixtures are noise\n\
+             tests/ attribution=ignore\n\
+             tests/integration.rs -attribution\n\
+             *.rs text\n",
+        );
+        assert!(attributes.is_ignored("tests/data.json"));
+        assert!(!attributes.is_ignored("tests/integration.rs"));
+        assert!(!GitAiAttributes::default().is_ignored("tests/data.json"));
+    }
+}
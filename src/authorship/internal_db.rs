@@ -138,7 +138,7 @@ impl PromptDbRecord {
     /// Convert PromptDbRecord to PromptRecord
     pub fn to_prompt_record(&self) -> crate::authorship::authorship_log::PromptRecord {
         use crate::authorship::authorship_log::PromptRecord;
-        use crate::authorship::working_log::AgentId;
+        use crate::authorship::working_log::{AgentId, TOOL_VERSION_METADATA_KEY};
 
         PromptRecord {
             agent_id: AgentId {
@@ -153,6 +153,11 @@ impl PromptDbRecord {
             accepted_lines: self.accepted_lines.unwrap_or(0),
             overriden_lines: self.overridden_lines.unwrap_or(0),
             messages_url: None,
+            tool_version: self
+                .agent_metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(TOOL_VERSION_METADATA_KEY))
+                .cloned(),
         }
     }
 
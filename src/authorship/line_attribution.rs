@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use crate::authorship::authorship_log::PromptRecord;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::tool_names::normalize_tool_name;
+use crate::commands::diff::Attribution;
+use crate::error::GitAiError;
+use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::repository::{Repository, exec_git};
+
+/// Attribution of a single line as recorded in the authorship note of `commit`.
+///
+/// `line` is numbered as in `file` at `commit`, and only that commit's note is consulted,
+/// so pass the commit that last changed the line (e.g. from `git blame`). AI lines are
+/// `Ai(tool)`; other lines of an annotated commit are `Human(author)`, named after the
+/// commit's git author; commits without a note are `NoData`.
+pub fn attribution_at(
+    repo: &Repository,
+    commit: &str,
+    file: &str,
+    line: u32,
+) -> Result<Attribution, GitAiError> {
+    AttributionLookup::new(repo).attribution_at(commit, file, line)
+}
+
+/// `attribution_at` for many `(file, line)` pairs of one commit, loading its note once.
+/// Attributions are returned in the order of `lines`.
+pub fn attributions_at(
+    repo: &Repository,
+    commit: &str,
+    lines: &[(&str, u32)],
+) -> Result<Vec<Attribution>, GitAiError> {
+    let mut lookup = AttributionLookup::new(repo);
+    lines
+        .iter()
+        .map(|(file, line)| lookup.attribution_at(commit, file, *line))
+        .collect()
+}
+
+/// Line attribution lookups sharing the notes and foreign prompts they have loaded, for
+/// callers that look up lines across many commits
+pub struct AttributionLookup<'a> {
+    repo: &'a Repository,
+    /// Each commit's note and git author name; None when the commit has no note
+    notes: HashMap<String, Option<(AuthorshipLog, String)>>,
+    foreign_prompts_cache: HashMap<String, Option<PromptRecord>>,
+}
+
+impl<'a> AttributionLookup<'a> {
+    pub fn new(repo: &'a Repository) -> Self {
+        AttributionLookup {
+            repo,
+            notes: HashMap::new(),
+            foreign_prompts_cache: HashMap::new(),
+        }
+    }
+
+    /// See `attribution_at`
+    pub fn attribution_at(
+        &mut self,
+        commit: &str,
+        file: &str,
+        line: u32,
+    ) -> Result<Attribution, GitAiError> {
+        if !self.notes.contains_key(commit) {
+            let note = match get_reference_as_authorship_log_v3(self.repo, commit) {
+                Ok(log) => Some((log, commit_author(self.repo, commit)?)),
+                Err(_) => None,
+            };
+            self.notes.insert(commit.to_string(), note);
+        }
+        let Some((log, author)) = &self.notes[commit] else {
+            return Ok(Attribution::NoData);
+        };
+
+        let record = log
+            .get_line_attribution(self.repo, file, line, &mut self.foreign_prompts_cache)
+            .and_then(|(_, _, record, _)| record);
+        Ok(match record {
+            Some(record) => Attribution::Ai(normalize_tool_name(&record.agent_id.tool)),
+            None => Attribution::Human(author.clone()),
+        })
+    }
+}
+
+fn commit_author(repo: &Repository, commit: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("show".to_string());
+    args.push("-s".to_string());
+    args.push("--format=%an".to_string());
+    args.push(commit.to_string());
+
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_attribution_at_reads_the_commit_note() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "human\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "human\nai one\nai two\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let attribution = attribution_at(repo, &head, "a.txt", 2).unwrap();
+        assert!(matches!(attribution, Attribution::Ai(tool) if tool == "cursor"));
+
+        let attributions =
+            attributions_at(repo, &head, &[("a.txt", 1), ("a.txt", 3), ("b.txt", 1)]).unwrap();
+        assert!(matches!(&attributions[0], Attribution::Human(name) if name == "Test User"));
+        assert!(matches!(&attributions[1], Attribution::Ai(_)));
+        assert!(matches!(&attributions[2], Attribution::Human(_)));
+
+        // A commit made without git-ai has no note to read
+        tmp_repo
+            .git_command(&["commit", "--allow-empty", "-m", "Plain commit"])
+            .unwrap();
+        let attribution = attribution_at(repo, "HEAD", "a.txt", 2).unwrap();
+        assert!(matches!(attribution, Attribution::NoData));
+    }
+}
@@ -1,15 +1,20 @@
 pub mod attribution_tracker;
 pub mod authorship_log;
+pub mod authorship_log_cache;
 pub mod authorship_log_serialization;
+pub mod gitai_attributes;
 pub mod imara_diff_utils;
 pub mod internal_db;
+pub mod line_attribution;
 pub mod move_detection;
 pub mod post_commit;
 pub mod pre_commit;
 pub mod range_authorship;
 pub mod rebase_authorship;
+pub mod repo_authorship;
 pub mod secrets;
 pub mod stats;
+pub mod tool_names;
 pub mod transcript;
 pub mod virtual_attribution;
 pub mod working_log;
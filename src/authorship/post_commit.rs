@@ -400,7 +400,10 @@ fn enqueue_prompt_messages_to_cas(
 
 #[cfg(test)]
 mod tests {
+    use crate::authorship::authorship_log::LineRange;
+    use crate::authorship::authorship_log_serialization::AttributionProvenance;
     use crate::git::test_utils::TmpRepo;
+    use std::collections::HashMap;
 
     #[test]
     fn test_post_commit_empty_repo_with_checkpoint() {
@@ -463,4 +466,49 @@ mod tests {
             "Should have empty attestations when no checkpoints exist"
         );
     }
+
+    #[test]
+    fn test_post_commit_crlf_file_attributes_the_right_lines() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\nthree\r\nfour\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("ai_session_1", None, None)
+            .unwrap();
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\nthree\r\nfour\r\nfive\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        let authorship_log = tmp_repo.commit_with_message("CRLF commit").unwrap();
+
+        let entries = &authorship_log.attestations[0].entries;
+        assert_eq!(entries.len(), 1, "entries: {:?}", entries);
+        assert_eq!(entries[0].line_ranges, vec![LineRange::Range(3, 4)]);
+
+        let mut foreign_prompts_cache = HashMap::new();
+        for (line, is_ai) in [(2, false), (3, true), (4, true), (5, false)] {
+            let attribution = authorship_log.get_line_attribution(
+                tmp_repo.gitai_repo(),
+                "foo.py",
+                line,
+                &mut foreign_prompts_cache,
+            );
+            assert_eq!(attribution.is_some(), is_ai, "line {}", line);
+            if let Some((_, _, _, provenance)) = attribution {
+                // The prompt is in this commit's own log
+                assert_eq!(provenance, AttributionProvenance::Direct);
+            }
+        }
+    }
 }
@@ -41,12 +41,8 @@ pub fn find_prompt_in_commit(
     let commit_sha = commit.id();
 
     // Get the authorship log for this commit
-    let authorship_log = get_authorship(repo, &commit_sha).ok_or_else(|| {
-        GitAiError::Generic(format!(
-            "No authorship data found for commit: {}",
-            commit_rev
-        ))
-    })?;
+    let authorship_log = get_authorship(repo, &commit_sha)
+        .ok_or_else(|| GitAiError::NotesMissing(commit_rev.to_string()))?;
 
     // Look for the prompt in the log
     authorship_log
@@ -1,21 +1,84 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::authorship::stats::{CommitStats, stats_for_commit_stats, stats_from_authorship_log};
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::{AUTHORSHIP_LOG_VERSION, AuthorshipLog};
+use crate::authorship::gitai_attributes::GitAiAttributes;
+use crate::authorship::stats::{
+    CommitStats, get_git_diff_stats, stats_for_commit_stats, stats_from_authorship_log,
+};
+use crate::authorship::virtual_attribution::AttributionConflict;
 use crate::error::GitAiError;
-use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
+use crate::git::refs::{
+    CommitAuthorship, get_authorship, get_commits_with_notes_from_list, range_cache_notes_add,
+    show_range_cache_note,
+};
 use crate::git::repository::{CommitRange, Repository};
 use crate::utils::debug_log;
 
 use std::io::IsTerminal;
+use std::time::{Duration, Instant};
 
 /// The git empty tree hash - represents an empty repository state
 /// This is the hash of the empty tree object that git uses internally
 const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
 
+/// How `range_authorship` computes a range's stats
+#[derive(Debug, Clone, Default)]
+pub struct RangeAuthorshipOptions {
+    /// Fetch the range's branch from its remote first (`git-ai stats --fetch`)
+    pub pre_fetch_contents: bool,
+    /// With `pre_fetch_contents`, print the remote and refspec to stderr instead of running
+    /// `git fetch` (`git-ai stats --fetch-dry-run`)
+    pub fetch_dry_run: bool,
+    /// Paths matching these globs are left out of the stats
+    pub ignore_patterns: Vec<String>,
+    /// Read and write range stats through the `ai-range-cache` note
+    pub use_cache: bool,
+    /// Only count commits whose git author contains this, ignoring case
+    pub author: Option<String>,
+    /// Print the duration of each step to stderr (`git-ai stats --timings`)
+    pub print_timings: bool,
+}
+
+/// Durations of the steps of `create_authorship_log_for_range`, in the order they ran
+#[derive(Default)]
+struct RangeTimings {
+    steps: Vec<(&'static str, Duration)>,
+}
+
+impl RangeTimings {
+    fn record(&mut self, step: &'static str, duration: Duration) {
+        self.steps.push((step, duration));
+    }
+
+    /// Time `f` as `step`
+    fn time<T>(&mut self, step: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(step, start.elapsed());
+        result
+    }
+
+    fn format(&self, start_sha: &str, end_sha: &str, total: Duration) -> String {
+        let mut out = format!(
+            "Range authorship timings ({}..{}):\n",
+            &start_sha[..start_sha.len().min(7)],
+            &end_sha[..end_sha.len().min(7)]
+        );
+        let total = std::iter::once(("total", total));
+        for (step, duration) in self.steps.iter().copied().chain(total) {
+            let duration = format!("{:.1?}", duration);
+            out.push_str(&format!("  {:<22} {:>10}\n", step, duration));
+        }
+        out
+    }
+}
+
 /// Check if a file path should be ignored based on the provided patterns
 /// Supports both exact matches and glob patterns (e.g., "*.lock", "**/*.generated.js")
 pub fn should_ignore_file(path: &str, ignore_patterns: &[String]) -> bool {
@@ -39,101 +102,308 @@ pub fn should_ignore_file(path: &str, ignore_patterns: &[String]) -> bool {
     })
 }
 
+/// Name of the optional file at the repo root listing paths to leave out of stats
+pub const GITAIIGNORE_FILENAME: &str = ".gitaiignore";
+
+/// Read glob patterns from `.gitaiignore` at the repo root, one per line.
+/// Blank lines and lines starting with `#` are skipped; a missing file yields no patterns.
+pub fn load_gitaiignore_patterns(repo: &Repository) -> Vec<String> {
+    let Ok(workdir) = repo.workdir() else {
+        return Vec::new();
+    };
+
+    match std::fs::read_to_string(workdir.join(GITAIIGNORE_FILENAME)) {
+        Ok(content) => parse_gitaiignore(&content),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn parse_gitaiignore(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RangeAuthorshipStats {
+    /// Whether any commit in the range has an authorship log. False means the stats were
+    /// computed but nobody committed with git-ai (`git-ai stats --exit-code` exits with 2).
+    #[serde(default)]
+    pub has_ai_authorship: bool,
     pub authorship_stats: RangeAuthorshipStatsData,
     pub range_stats: CommitStats,
+    /// Line stats per git author ("Name <email>"), summed over their commits in the range
+    #[serde(default)]
+    pub author_stats: BTreeMap<String, CommitStats>,
+    /// AI vs human additions per changed file path in the range
+    #[serde(default)]
+    pub file_stats: BTreeMap<String, FileAuthorshipStats>,
+    /// Each commit's own stats, in range order
+    #[serde(default)]
+    pub commit_stats: Vec<RangeCommitStats>,
+    /// Lines the start and end commits attributed differently, e.g. after a rebase or
+    /// squash rewrote their authorship. The stats use the end commit's attribution.
+    #[serde(default)]
+    pub attribution_conflicts: Vec<RangeAttributionConflict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeAttributionConflict {
+    pub file_path: String,
+    /// 1-indexed line at the end commit
+    pub line: u32,
+    /// Author id ("human" or a prompt id) at the end commit
+    pub end_attribution: String,
+    /// Author id at the start commit
+    pub start_attribution: String,
+}
+
+impl From<AttributionConflict> for RangeAttributionConflict {
+    fn from(conflict: AttributionConflict) -> Self {
+        // The range merge favors the end commit, so it is the primary side
+        RangeAttributionConflict {
+            file_path: conflict.file_path,
+            line: conflict.line,
+            end_attribution: conflict.primary_author,
+            start_attribution: conflict.secondary_author,
+        }
+    }
+}
+
+/// Range totals, per-file stats and attribution conflicts
+type RangeStatsResult = (
+    CommitStats,
+    BTreeMap<String, FileAuthorshipStats>,
+    Vec<RangeAttributionConflict>,
+);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeCommitStats {
+    pub sha: String,
+    pub git_author: String,
+    pub has_authorship: bool,
+    pub stats: CommitStats,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileAuthorshipStats {
+    pub ai_additions: u32,
+    pub human_additions: u32,
+}
+
+impl FileAuthorshipStats {
+    pub fn ai_percentage(&self) -> f64 {
+        let total = self.ai_additions + self.human_additions;
+        if total == 0 {
+            0.0
+        } else {
+            self.ai_additions as f64 / total as f64 * 100.0
+        }
+    }
 }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RangeAuthorshipStatsData {
     pub total_commits: usize,
     pub commits_with_authorship: usize,
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub authors_committing_authorship: HashSet<String>,
+    #[serde(serialize_with = "serialize_sorted_set")]
     pub authors_not_committing_authorship: HashSet<String>,
     pub commits_without_authorship: Vec<String>,
     pub commits_without_authorship_with_authors: Vec<(String, String)>, // (sha, git_author)
 }
 
+/// Serialize a set as a sorted array so JSON output is stable across runs
+fn serialize_sorted_set<S>(set: &HashSet<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let mut items: Vec<&String> = set.iter().collect();
+    items.sort();
+    serializer.collect_seq(items)
+}
+
+/// The commits on HEAD since it diverged from `base`: the range starts at
+/// `git merge-base <base> HEAD`, so stats for a feature branch leave out commits already on
+/// `base` and diff against the point the branch started from rather than `base`'s tip.
+pub fn merge_base_range<'a>(
+    repo: &'a Repository,
+    base: &str,
+) -> Result<CommitRange<'a>, GitAiError> {
+    let head = repo.revparse_single("HEAD")?.oid;
+    let base_sha = repo
+        .revparse_single(base)
+        .map_err(|_| GitAiError::CommitNotFound(base.to_string()))?
+        .oid;
+    let start = repo.merge_base(base_sha, head.clone()).map_err(|_| {
+        GitAiError::Generic(format!("No merge base found between {} and HEAD", base))
+    })?;
+
+    // A start equal to the end would be read as the single commit HEAD
+    if start == head {
+        return Err(GitAiError::Generic(format!(
+            "HEAD has no commits since its merge base with {}",
+            base
+        )));
+    }
+
+    CommitRange::new_infer_refname(repo, start, head, None)
+}
+
+/// The `(remote, refspec)` to fetch so a range ending at `refname` has its contents locally
+pub fn resolve_fetch_target(
+    repository: &Repository,
+    refname: &str,
+) -> Result<(String, String), GitAiError> {
+    let remotes: Vec<String> = repository
+        .remotes()?
+        .into_iter()
+        .filter(|remote| !remote.is_empty())
+        .collect();
+
+    // Get default remote, fallback to "origin" if not found
+    let default_remote = repository
+        .get_default_remote()?
+        .unwrap_or_else(|| "origin".to_string());
+
+    // Extract remote and branch from refname
+    let target = if let Some(without_prefix) = refname.strip_prefix("refs/remotes/") {
+        // Remote branch: refs/remotes/origin/branch-name -> origin, refs/heads/branch-name
+        match split_remote_branch(&remotes, without_prefix)
+            .or_else(|| without_prefix.split_once('/'))
+        {
+            Some((remote, branch)) => (remote.to_string(), format!("refs/heads/{}", branch)),
+            None => (default_remote, refname.to_string()),
+        }
+    } else if refname.starts_with("refs/heads/") {
+        // Local branch: refs/heads/branch-name -> default_remote, refs/heads/branch-name
+        (default_remote, refname.to_string())
+    } else if refname.starts_with("refs/") {
+        (default_remote, refname.to_string())
+    } else if let Some((remote, branch)) = split_remote_branch(&remotes, refname) {
+        // Simple remote format: origin/branch-name -> origin, refs/heads/branch-name
+        (remote.to_string(), format!("refs/heads/{}", branch))
+    } else {
+        // Branch name, possibly with slashes: feature/foo -> default_remote, refs/heads/feature/foo
+        (default_remote, format!("refs/heads/{}", refname))
+    };
+
+    Ok(target)
+}
+
+/// Split `<remote>/<branch>` where `<remote>` is one of `remotes`, so the slashes in
+/// `feature/foo` aren't mistaken for a remote. The longest matching remote wins, since
+/// remote names can contain slashes too.
+fn split_remote_branch<'a>(remotes: &[String], name: &'a str) -> Option<(&'a str, &'a str)> {
+    remotes
+        .iter()
+        .filter_map(|remote| {
+            let branch = name.strip_prefix(remote.as_str())?.strip_prefix('/')?;
+            (!branch.is_empty()).then(|| (&name[..remote.len()], branch))
+        })
+        .max_by_key(|(remote, _)| remote.len())
+}
+
+fn fetch_range_ref(
+    repository: &Repository,
+    remote: &str,
+    fetch_refspec: &str,
+) -> Result<(), GitAiError> {
+    let mut args = repository.global_args_for_exec();
+    args.push("fetch".to_string());
+    args.push(remote.to_string());
+    args.push(fetch_refspec.to_string());
+
+    let output = crate::git::repository::exec_git(&args)?;
+
+    if !output.status.success() {
+        return Err(GitAiError::Generic(format!(
+            "Failed to fetch {} from {}: {}",
+            fetch_refspec,
+            remote,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    debug_log(&format!("✓ Fetched {} from {}", fetch_refspec, remote));
+    Ok(())
+}
+
+/// Compute authorship stats for a commit range.
+///
+/// With `commit_range.first_parent`, the per-commit breakdown and commit counts only cover
+/// first-parent history, so commits merged in from side branches aren't counted on their
+/// own. The range totals still come from the start..end diff, attributed using every commit
+/// in between.
+///
+/// With `options.author`, only commits whose git author ("Name <email>") contains it, ignoring
+/// case, are counted. Their totals are the sum of each commit's own diff rather than the
+/// start..end diff, so lines the author added and someone else later changed or removed
+/// still count, and the range cache isn't used.
 pub fn range_authorship(
     commit_range: CommitRange,
-    pre_fetch_contents: bool,
-    ignore_patterns: &[String],
+    options: &RangeAuthorshipOptions,
 ) -> Result<RangeAuthorshipStats, GitAiError> {
     if let Err(e) = commit_range.is_valid() {
         return Err(e);
     }
+    let ignore_patterns = &options.ignore_patterns;
+    let author = options.author.as_deref();
 
     // Fetch the branch if pre_fetch_contents is true
-    if pre_fetch_contents {
+    if options.pre_fetch_contents {
         let repository = commit_range.repo();
         let refname = &commit_range.refname;
 
-        // Get default remote, fallback to "origin" if not found
-        let default_remote = repository
-            .get_default_remote()?
-            .unwrap_or_else(|| "origin".to_string());
-
-        // Extract remote and branch from refname
-        let (remote, fetch_refspec) = if refname.starts_with("refs/remotes/") {
-            // Remote branch: refs/remotes/origin/branch-name -> origin, refs/heads/branch-name
-            let without_prefix = refname.strip_prefix("refs/remotes/").unwrap();
-            let parts: Vec<&str> = without_prefix.splitn(2, '/').collect();
-            if parts.len() == 2 {
-                (parts[0].to_string(), format!("refs/heads/{}", parts[1]))
-            } else {
-                (default_remote.clone(), refname.to_string())
-            }
-        } else if refname.starts_with("refs/heads/") {
-            // Local branch: refs/heads/branch-name -> default_remote, refs/heads/branch-name
-            (default_remote.clone(), refname.to_string())
-        } else if refname.contains('/') && !refname.starts_with("refs/") {
-            // Simple remote format: origin/branch-name -> origin, refs/heads/branch-name
-            let parts: Vec<&str> = refname.splitn(2, '/').collect();
-            if parts.len() == 2 {
-                (parts[0].to_string(), format!("refs/heads/{}", parts[1]))
-            } else {
-                (default_remote.clone(), format!("refs/heads/{}", refname))
-            }
-        } else {
-            // Plain branch name: branch-name -> default_remote, refs/heads/branch-name
-            (default_remote.clone(), format!("refs/heads/{}", refname))
-        };
-
-        let mut args = repository.global_args_for_exec();
-        args.push("fetch".to_string());
-        args.push(remote.clone());
-        args.push(fetch_refspec.clone());
-
-        let output = crate::git::repository::exec_git(&args)?;
+        let (remote, fetch_refspec) = resolve_fetch_target(repository, refname)?;
 
-        if !output.status.success() {
-            return Err(GitAiError::Generic(format!(
-                "Failed to fetch {} from {}: {}",
-                fetch_refspec,
-                remote,
-                String::from_utf8_lossy(&output.stderr)
-            )));
+        if options.fetch_dry_run {
+            eprintln!("Would fetch {} from {}", fetch_refspec, remote);
+        } else {
+            fetch_range_ref(repository, &remote, &fetch_refspec)?;
         }
-
-        debug_log(&format!("✓ Fetched {} from {}", fetch_refspec, remote));
     }
 
-    // Clone commit_range before consuming it
+    // Clone commit_range before consuming it. Lines merged in from side branches are part of
+    // the start..end diff, so the range totals need those commits' attributions too.
     let repository = commit_range.repo();
-    let commit_range_clone = commit_range.clone();
+    let commit_range_clone = commit_range.clone().with_first_parent(false);
 
     // Collect commit SHAs from the range
     let commit_shas: Vec<String> = commit_range
         .into_iter()
         .map(|c| c.id().to_string())
         .collect();
-    let commit_authorship = get_commits_with_notes_from_list(repository, &commit_shas)?;
+    let mut commit_authorship = get_commits_with_notes_from_list(repository, &commit_shas)?;
+    if let Some(author) = author {
+        commit_authorship.retain(|ca| git_author_matches(ca, author));
+    }
+
+    let commit_stats = calculate_commit_stats(repository, &commit_authorship, ignore_patterns)?;
 
     // Calculate range stats - now just pass start, end, and commits
-    let range_stats =
-        calculate_range_stats_direct(repository, commit_range_clone, ignore_patterns)?;
+    let (range_stats, file_stats, attribution_conflicts) = if author.is_some() {
+        // The endpoint diff mixes in other authors' commits, so sum the matching commits instead
+        calculate_range_stats_from_commits(
+            repository,
+            &commit_authorship,
+            &commit_stats,
+            ignore_patterns,
+        )?
+    } else if options.use_cache {
+        calculate_range_stats_cached(repository, commit_range_clone, options)?
+    } else {
+        calculate_range_stats_direct(repository, commit_range_clone, options)?
+    };
+    let author_stats = calculate_author_stats(&commit_stats);
 
     Ok(RangeAuthorshipStats {
+        has_ai_authorship: commit_authorship
+            .iter()
+            .any(|ca| matches!(ca, CommitAuthorship::Log { .. })),
         authorship_stats: RangeAuthorshipStatsData {
             total_commits: commit_authorship.len(),
             commits_with_authorship: commit_authorship
@@ -172,17 +442,79 @@ pub fn range_authorship(
                 .collect(),
         },
         range_stats,
+        author_stats,
+        file_stats,
+        commit_stats,
+        attribution_conflicts,
     })
 }
 
 /// Create an in-memory authorship log for a commit range by treating it as a squash
-/// Similar to rewrite_authorship_after_squash_or_rebase but tailored for ranges
+/// Similar to rewrite_authorship_after_squash_or_rebase but tailored for ranges.
+/// With `print_timings`, the duration of each step is printed to stderr.
 fn create_authorship_log_for_range(
     repo: &Repository,
     start_sha: &str,
     end_sha: &str,
     commit_shas: &[String],
     ignore_patterns: &[String],
+    conflicts: &mut Vec<AttributionConflict>,
+    print_timings: bool,
+) -> Result<crate::authorship::authorship_log_serialization::AuthorshipLog, GitAiError> {
+    let start = Instant::now();
+    let mut timings = RangeTimings::default();
+    let result = build_authorship_log_for_range(
+        repo,
+        start_sha,
+        end_sha,
+        commit_shas,
+        ignore_patterns,
+        conflicts,
+        &mut timings,
+    );
+    if print_timings {
+        eprint!("{}", timings.format(start_sha, end_sha, start.elapsed()));
+    }
+    result
+}
+
+/// Reconstruct the authorship log of a single commit that has none, treating it as the range
+/// from its first parent (the empty tree for a root commit) to the commit. Only AI lines whose
+/// prompts blame can resolve to the commit are attested; every other line it added is left to
+/// the commit's git author, as in any authorship log.
+pub fn create_authorship_log_for_commit(
+    repo: &Repository,
+    commit_sha: &str,
+) -> Result<AuthorshipLog, GitAiError> {
+    let commit = repo.find_commit(commit_sha.to_string())?;
+    let parent_sha = match commit.parent_count()? {
+        0 => EMPTY_TREE_HASH.to_string(),
+        _ => commit.parent(0)?.id(),
+    };
+
+    let mut conflicts = Vec::new();
+    let mut authorship_log = create_authorship_log_for_range(
+        repo,
+        &parent_sha,
+        commit_sha,
+        &[commit_sha.to_string()],
+        &[],
+        &mut conflicts,
+        false,
+    )?;
+    authorship_log.metadata.schema_version = AUTHORSHIP_LOG_VERSION.to_string();
+    authorship_log.metadata.base_commit_sha = commit_sha.to_string();
+    Ok(authorship_log)
+}
+
+fn build_authorship_log_for_range(
+    repo: &Repository,
+    start_sha: &str,
+    end_sha: &str,
+    commit_shas: &[String],
+    ignore_patterns: &[String],
+    conflicts: &mut Vec<AttributionConflict>,
+    timings: &mut RangeTimings,
 ) -> Result<crate::authorship::authorship_log_serialization::AuthorshipLog, GitAiError> {
     use crate::authorship::virtual_attribution::{
         VirtualAttributions, merge_attributions_favoring_first,
@@ -194,7 +526,9 @@ fn create_authorship_log_for_range(
     ));
 
     // Step 1: Get list of changed files between the two commits
-    let all_changed_files = repo.diff_changed_files(start_sha, end_sha)?;
+    let all_changed_files = timings.time("diff changed files", || {
+        repo.diff_changed_files(start_sha, end_sha)
+    })?;
 
     // Filter out ignored files from the changed files
     let changed_files: Vec<String> = all_changed_files
@@ -236,14 +570,17 @@ fn create_authorship_log_for_range(
         debug_log("Start is empty tree - using only end commit attributions");
 
         let repo_clone = repo.clone();
-        let mut end_va = smol::block_on(async {
-            VirtualAttributions::new_for_base_commit(
-                repo_clone,
-                end_sha.to_string(),
-                &changed_files,
-                None,
-            )
-            .await
+        let mut end_va = timings.time("build end VA", || {
+            smol::block_on(async {
+                VirtualAttributions::new_for_base_commit(
+                    repo_clone,
+                    end_sha.to_string(),
+                    &changed_files,
+                    None,
+                    None,
+                )
+                .await
+            })
         })?;
 
         // Filter to only include prompts from commits in this range
@@ -251,7 +588,7 @@ fn create_authorship_log_for_range(
         end_va.filter_to_commits(&commit_set);
 
         // Convert to AuthorshipLog
-        let mut authorship_log = end_va.to_authorship_log()?;
+        let mut authorship_log = timings.time("convert", || end_va.to_authorship_log())?;
         authorship_log.metadata.base_commit_sha = end_sha.to_string();
 
         debug_log(&format!(
@@ -263,29 +600,50 @@ fn create_authorship_log_for_range(
         return Ok(authorship_log);
     }
 
-    // Step 2: Create VirtualAttributions for start commit (older)
-    let repo_clone = repo.clone();
-    let mut start_va = smol::block_on(async {
-        VirtualAttributions::new_for_base_commit(
-            repo_clone,
-            start_sha.to_string(),
-            &changed_files,
-            None,
-        )
-        .await
+    // Step 2: Read committed files from end commit (final state). The end commit's
+    // VirtualAttributions reuses these rather than reading the same blobs again.
+    let committed_files = timings.time("read committed files", || {
+        get_committed_files_content(repo, end_sha, &changed_files)
     })?;
 
-    // Step 3: Create VirtualAttributions for end commit (newer)
-    let repo_clone = repo.clone();
-    let mut end_va = smol::block_on(async {
-        VirtualAttributions::new_for_base_commit(
-            repo_clone,
-            end_sha.to_string(),
-            &changed_files,
-            None,
+    debug_log(&format!(
+        "Read {} committed files from end commit",
+        committed_files.len()
+    ));
+
+    // Step 3: Create VirtualAttributions for the start (older) and end (newer)
+    // commits concurrently - they are independent of each other, so each is timed on its own
+    let ((mut start_va, start_va_time), (mut end_va, end_va_time)) = smol::block_on(async {
+        futures::future::try_join(
+            async {
+                let start = Instant::now();
+                let va = VirtualAttributions::new_for_base_commit(
+                    repo.clone(),
+                    start_sha.to_string(),
+                    &changed_files,
+                    None,
+                    None,
+                )
+                .await?;
+                Ok::<_, GitAiError>((va, start.elapsed()))
+            },
+            async {
+                let start = Instant::now();
+                let va = VirtualAttributions::new_for_base_commit(
+                    repo.clone(),
+                    end_sha.to_string(),
+                    &changed_files,
+                    None,
+                    Some(&committed_files),
+                )
+                .await?;
+                Ok::<_, GitAiError>((va, start.elapsed()))
+            },
         )
         .await
     })?;
+    timings.record("build start VA", start_va_time);
+    timings.record("build end VA", end_va_time);
 
     // Step 3.5: Filter both VirtualAttributions to only include prompts from commits in this range
     // This ensures we only count AI contributions that happened during these commits,
@@ -294,19 +652,13 @@ fn create_authorship_log_for_range(
     start_va.filter_to_commits(&commit_set);
     end_va.filter_to_commits(&commit_set);
 
-    // Step 4: Read committed files from end commit (final state)
-    let committed_files = get_committed_files_content(repo, end_sha, &changed_files)?;
-
-    debug_log(&format!(
-        "Read {} committed files from end commit",
-        committed_files.len()
-    ));
-
-    // Step 5: Merge VirtualAttributions, favoring end commit (newer state)
-    let merged_va = merge_attributions_favoring_first(end_va, start_va, committed_files)?;
+    // Step 4: Merge VirtualAttributions, favoring end commit (newer state)
+    let merged_va = timings.time("merge", || {
+        merge_attributions_favoring_first(end_va, start_va, committed_files, Some(conflicts))
+    })?;
 
-    // Step 6: Convert to AuthorshipLog
-    let mut authorship_log = merged_va.to_authorship_log()?;
+    // Step 5: Convert to AuthorshipLog
+    let mut authorship_log = timings.time("convert", || merged_va.to_authorship_log())?;
     authorship_log.metadata.base_commit_sha = end_sha.to_string();
 
     debug_log(&format!(
@@ -319,34 +671,75 @@ fn create_authorship_log_for_range(
 }
 
 /// Get file contents from a commit tree for specified pathspecs
+/// Blobs are read concurrently; files missing from the commit are skipped
 fn get_committed_files_content(
     repo: &Repository,
     commit_sha: &str,
     pathspecs: &[String],
 ) -> Result<HashMap<String, String>, GitAiError> {
+    use std::sync::Arc;
+
+    const MAX_CONCURRENT: usize = 30;
+
     let commit = repo.find_commit(commit_sha.to_string())?;
-    let tree = commit.tree()?;
+    let tree_oid = commit.tree()?.id();
+
+    let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
+    let tasks: Vec<_> = pathspecs
+        .iter()
+        .map(|file_path| {
+            let repo = repo.clone();
+            let tree_oid = tree_oid.clone();
+            let file_path = file_path.clone();
+            let semaphore = Arc::clone(&semaphore);
+
+            smol::spawn(async move {
+                // Acquire semaphore permit to limit concurrency
+                let _permit = semaphore.acquire().await;
+
+                // Wrap blocking git operations in smol::unblock
+                smol::unblock(move || {
+                    let content = read_tree_file_content(&repo, &tree_oid, &file_path);
+                    (file_path, content)
+                })
+                .await
+            })
+        })
+        .collect();
 
-    let mut files = HashMap::new();
+    let results = smol::block_on(futures::future::join_all(tasks));
 
-    for file_path in pathspecs {
-        match tree.get_path(std::path::Path::new(file_path)) {
-            Ok(entry) => {
-                if let Ok(blob) = repo.find_blob(entry.id()) {
-                    let blob_content = blob.content().unwrap_or_default();
-                    let content = String::from_utf8_lossy(&blob_content).to_string();
-                    files.insert(file_path.clone(), content);
-                }
-            }
-            Err(_) => {
-                // File doesn't exist in this commit (could be deleted), skip it
-            }
+    let mut files = HashMap::new();
+    for (file_path, content) in results {
+        if let Some(content) = content? {
+            files.insert(file_path, content);
         }
     }
 
     Ok(files)
 }
 
+/// Read a file's content from a tree, or None if the path doesn't exist in it
+fn read_tree_file_content(
+    repo: &Repository,
+    tree_oid: &str,
+    file_path: &str,
+) -> Result<Option<String>, GitAiError> {
+    let tree = repo.find_tree(tree_oid.to_string())?;
+
+    match tree.get_path(std::path::Path::new(file_path)) {
+        Ok(entry) => match repo.find_blob(entry.id()) {
+            Ok(blob) => {
+                let blob_content = blob.content().unwrap_or_default();
+                Ok(Some(String::from_utf8_lossy(&blob_content).to_string()))
+            }
+            Err(_) => Ok(None),
+        },
+        // File doesn't exist in this commit (could be deleted), skip it
+        Err(_) => Ok(None),
+    }
+}
+
 /// Get git diff statistics for a commit range (start..end)
 fn get_git_diff_stats_for_range(
     repo: &Repository,
@@ -354,17 +747,33 @@ fn get_git_diff_stats_for_range(
     end_sha: &str,
     ignore_patterns: &[String],
 ) -> Result<(u32, u32), GitAiError> {
-    // Use git diff --numstat to get diff statistics for the range
+    let per_file = get_git_diff_stats_per_file(
+        repo,
+        &["diff".to_string(), format!("{}..{}", start_sha, end_sha)],
+        ignore_patterns,
+    )?;
+
+    Ok(per_file
+        .values()
+        .fold((0, 0), |(added, deleted), (a, d)| (added + a, deleted + d)))
+}
+
+/// Run `git <diff_args> --numstat` and return (added, deleted) lines per file path,
+/// skipping ignored files
+fn get_git_diff_stats_per_file(
+    repo: &Repository,
+    diff_args: &[String],
+    ignore_patterns: &[String],
+) -> Result<BTreeMap<String, (u32, u32)>, GitAiError> {
     let mut args = repo.global_args_for_exec();
-    args.push("diff".to_string());
+    args.extend(diff_args.iter().cloned());
     args.push("--numstat".to_string());
-    args.push(format!("{}..{}", start_sha, end_sha));
 
     let output = crate::git::repository::exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)?;
+    let attributes = GitAiAttributes::load(repo);
 
-    let mut added_lines = 0u32;
-    let mut deleted_lines = 0u32;
+    let mut per_file = BTreeMap::new();
 
     // Parse numstat output
     for line in stdout.lines() {
@@ -377,25 +786,18 @@ fn get_git_diff_stats_for_range(
         if parts.len() >= 3 {
             // Check if this file should be ignored and skip it
             let filename = parts[2];
-            if should_ignore_file(filename, ignore_patterns) {
+            if should_ignore_file(filename, ignore_patterns) || attributes.is_ignored(filename) {
                 continue;
             }
 
-            // Parse added lines
-            if let Ok(added) = parts[0].parse::<u32>() {
-                added_lines += added;
-            }
-
-            // Parse deleted lines (handle "-" for binary files)
-            if parts[1] != "-" {
-                if let Ok(deleted) = parts[1].parse::<u32>() {
-                    deleted_lines += deleted;
-                }
-            }
+            // Binary files report "-" for both counts
+            let added = parts[0].parse::<u32>().unwrap_or(0);
+            let deleted = parts[1].parse::<u32>().unwrap_or(0);
+            per_file.insert(filename.to_string(), (added, deleted));
         }
     }
 
-    Ok((added_lines, deleted_lines))
+    Ok(per_file)
 }
 
 /// Calculate AI vs human line contributions for a commit range
@@ -403,13 +805,22 @@ fn get_git_diff_stats_for_range(
 fn calculate_range_stats_direct(
     repo: &Repository,
     commit_range: CommitRange,
-    ignore_patterns: &[String],
-) -> Result<CommitStats, GitAiError> {
+    options: &RangeAuthorshipOptions,
+) -> Result<RangeStatsResult, GitAiError> {
+    let ignore_patterns = &options.ignore_patterns;
     let start_sha = commit_range.start_oid.clone();
     let end_sha = commit_range.end_oid.clone();
     // Special case: single commit range (start == end)
     if start_sha == end_sha {
-        return stats_for_commit_stats(repo, &end_sha, ignore_patterns);
+        let stats = stats_for_commit_stats(repo, &end_sha, ignore_patterns)?;
+        let per_file_diff = get_git_diff_stats_per_file(
+            repo,
+            &["show".to_string(), "--format=".to_string(), end_sha.clone()],
+            ignore_patterns,
+        )?;
+        let authorship_log = get_authorship(repo, &end_sha);
+        let file_stats = file_stats_from_authorship_log(authorship_log.as_ref(), &per_file_diff);
+        return Ok((stats, file_stats, Vec::new()));
     }
 
     // Step 1: Get git diff stats between start and end
@@ -418,24 +829,442 @@ fn calculate_range_stats_direct(
 
     // Step 2: Create in-memory authorship log for the range, filtered to only commits in the range
     let commit_shas = commit_range.clone().all_commits();
-    let authorship_log =
-        create_authorship_log_for_range(repo, &start_sha, &end_sha, &commit_shas, ignore_patterns)?;
+    let mut conflicts = Vec::new();
+    let authorship_log = create_authorship_log_for_range(
+        repo,
+        &start_sha,
+        &end_sha,
+        &commit_shas,
+        ignore_patterns,
+        &mut conflicts,
+        options.print_timings,
+    )?;
 
     // Step 3: Calculate stats from the authorship log
     let stats = stats_from_authorship_log(
         Some(&authorship_log),
         git_diff_added_lines,
         git_diff_deleted_lines,
+        &GitAiAttributes::load(repo),
+    );
+
+    // Step 4: Break AI vs human additions down per file
+    let per_file_diff = get_git_diff_stats_per_file(
+        repo,
+        &["diff".to_string(), format!("{}..{}", start_sha, end_sha)],
+        ignore_patterns,
+    )?;
+    let file_stats = file_stats_from_authorship_log(Some(&authorship_log), &per_file_diff);
+    let conflicts = conflicts.into_iter().map(Into::into).collect();
+
+    Ok((stats, file_stats, conflicts))
+}
+
+/// Whether a commit's git author ("Name <email>") contains `pattern`, ignoring case
+fn git_author_matches(commit_authorship: &CommitAuthorship, pattern: &str) -> bool {
+    let git_author = match commit_authorship {
+        CommitAuthorship::Log { git_author, .. } | CommitAuthorship::NoLog { git_author, .. } => {
+            git_author
+        }
+    };
+    git_author.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+/// Range and per-file stats as the sum of the given commits' own stats, for when only some
+/// of the range's commits are counted
+fn calculate_range_stats_from_commits(
+    repo: &Repository,
+    commit_authorship: &[CommitAuthorship],
+    commit_stats: &[RangeCommitStats],
+    ignore_patterns: &[String],
+) -> Result<RangeStatsResult, GitAiError> {
+    let mut range_stats = CommitStats::default();
+    for commit in commit_stats {
+        add_commit_stats(&mut range_stats, &commit.stats);
+    }
+
+    let mut file_stats: BTreeMap<String, FileAuthorshipStats> = BTreeMap::new();
+    for ca in commit_authorship {
+        let (sha, authorship_log) = match ca {
+            CommitAuthorship::Log {
+                sha,
+                authorship_log,
+                ..
+            } => (sha, Some(authorship_log)),
+            CommitAuthorship::NoLog { sha, .. } => (sha, None),
+        };
+
+        let per_file_diff = get_git_diff_stats_per_file(
+            repo,
+            &["show".to_string(), "--format=".to_string(), sha.clone()],
+            ignore_patterns,
+        )?;
+        for (file_path, stats) in file_stats_from_authorship_log(authorship_log, &per_file_diff) {
+            let total = file_stats.entry(file_path).or_default();
+            total.ai_additions += stats.ai_additions;
+            total.human_additions += stats.human_additions;
+        }
+    }
+
+    // Commits are counted one by one, so there is no start/end merge to conflict
+    Ok((range_stats, file_stats, Vec::new()))
+}
+
+/// Version of the `CachedRangeStats` format. Bump it whenever the cached stats gain or change
+/// a field, so entries written by older versions are recomputed instead of read back with
+/// the new fields defaulted.
+const RANGE_CACHE_VERSION: u32 = 1;
+
+/// Range stats stored in the `ai-range-cache` notes ref
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRangeStats {
+    /// `RANGE_CACHE_VERSION` the entry was written with; entries from before it existed are 0
+    #[serde(default)]
+    version: u32,
+    range_stats: CommitStats,
+    file_stats: BTreeMap<String, FileAuthorshipStats>,
+    #[serde(default)]
+    attribution_conflicts: Vec<RangeAttributionConflict>,
+}
+
+/// Deterministic cache key for a range: sha256 of both endpoints, the sorted ignore patterns
+/// and the contents of `.gitai-attributes`, whose `attribution=ignore` rules also shape the stats
+fn range_cache_key(
+    start_sha: &str,
+    end_sha: &str,
+    ignore_patterns: &[String],
+    attributes: &str,
+) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut patterns: Vec<&String> = ignore_patterns.iter().collect();
+    patterns.sort();
+    patterns.dedup();
+
+    let mut hasher = Sha256::new();
+    hasher.update(start_sha.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(end_sha.as_bytes());
+    for pattern in patterns {
+        hasher.update(b"\n");
+        hasher.update(pattern.as_bytes());
+    }
+    hasher.update(b"\0");
+    hasher.update(attributes.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Like `calculate_range_stats_direct`, but reuses results cached in a note on the end
+/// commit. Entries are keyed by both endpoints, so moving either one misses the cache, as
+/// does changing the ignore patterns or `.gitai-attributes`.
+fn calculate_range_stats_cached(
+    repo: &Repository,
+    commit_range: CommitRange,
+    options: &RangeAuthorshipOptions,
+) -> Result<RangeStatsResult, GitAiError> {
+    let end_sha = commit_range.end_oid.clone();
+    let key = range_cache_key(
+        &commit_range.start_oid,
+        &end_sha,
+        &options.ignore_patterns,
+        &GitAiAttributes::read_content(repo),
+    );
+
+    let mut cache: BTreeMap<String, CachedRangeStats> = show_range_cache_note(repo, &end_sha)
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    if let Some(cached) = cache
+        .get(&key)
+        .filter(|cached| cached.version == RANGE_CACHE_VERSION)
+    {
+        debug_log(&format!("Using cached range stats for {}", key));
+        return Ok((
+            cached.range_stats.clone(),
+            cached.file_stats.clone(),
+            cached.attribution_conflicts.clone(),
+        ));
+    }
+
+    let (range_stats, file_stats, attribution_conflicts) =
+        calculate_range_stats_direct(repo, commit_range, options)?;
+
+    // Entries in an older format can never be read again
+    cache.retain(|_, cached| cached.version == RANGE_CACHE_VERSION);
+    cache.insert(
+        key,
+        CachedRangeStats {
+            version: RANGE_CACHE_VERSION,
+            range_stats: range_stats.clone(),
+            file_stats: file_stats.clone(),
+            attribution_conflicts: attribution_conflicts.clone(),
+        },
     );
+    // Caching is best-effort: a read-only repo or missing identity shouldn't fail stats
+    let write_result = serde_json::to_string(&cache)
+        .map_err(GitAiError::from)
+        .and_then(|content| range_cache_notes_add(repo, &end_sha, &content));
+    if let Err(e) = write_result {
+        debug_log(&format!("Failed to write range stats cache: {}", e));
+    }
+
+    Ok((range_stats, file_stats, attribution_conflicts))
+}
+
+/// Split each changed file's added lines into AI and human additions using the
+/// authorship log's AI attestations
+fn file_stats_from_authorship_log(
+    authorship_log: Option<&AuthorshipLog>,
+    per_file_diff: &BTreeMap<String, (u32, u32)>,
+) -> BTreeMap<String, FileAuthorshipStats> {
+    let mut ai_lines_per_file: HashMap<&str, u32> = HashMap::new();
+    if let Some(log) = authorship_log {
+        for file_attestation in &log.attestations {
+            let ai_lines: u32 = file_attestation
+                .entries
+                .iter()
+                .filter(|entry| log.metadata.prompts.contains_key(&entry.hash))
+                .flat_map(|entry| entry.line_ranges.iter())
+                .map(|range| match range {
+                    LineRange::Single(_) => 1,
+                    LineRange::Range(start, end) => end - start + 1,
+                })
+                .sum();
+            *ai_lines_per_file
+                .entry(file_attestation.file_path.as_str())
+                .or_insert(0) += ai_lines;
+        }
+    }
+
+    per_file_diff
+        .iter()
+        .filter(|(_, (added, _))| *added > 0)
+        .map(|(file_path, (added, _))| {
+            let ai_additions = ai_lines_per_file
+                .get(file_path.as_str())
+                .copied()
+                .unwrap_or(0)
+                .min(*added);
+            (
+                file_path.clone(),
+                FileAuthorshipStats {
+                    ai_additions,
+                    human_additions: added - ai_additions,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Calculate each commit's own line stats, in range order
+pub(crate) fn calculate_commit_stats(
+    repo: &Repository,
+    commit_authorship: &[CommitAuthorship],
+    ignore_patterns: &[String],
+) -> Result<Vec<RangeCommitStats>, GitAiError> {
+    let mut commit_stats = Vec::with_capacity(commit_authorship.len());
+    let attributes = GitAiAttributes::load(repo);
+
+    for ca in commit_authorship {
+        let (sha, git_author, authorship_log) = match ca {
+            CommitAuthorship::Log {
+                sha,
+                git_author,
+                authorship_log,
+            } => (sha, git_author, Some(authorship_log)),
+            CommitAuthorship::NoLog { sha, git_author } => (sha, git_author, None),
+        };
 
-    Ok(stats)
+        let (added_lines, deleted_lines) = get_git_diff_stats(repo, sha, ignore_patterns)?;
+        commit_stats.push(RangeCommitStats {
+            sha: sha.clone(),
+            git_author: git_author.clone(),
+            has_authorship: authorship_log.is_some(),
+            stats: stats_from_authorship_log(
+                authorship_log,
+                added_lines,
+                deleted_lines,
+                &attributes,
+            ),
+        });
+    }
+
+    Ok(commit_stats)
+}
+
+/// Sum per-commit stats by git author.
+/// The merged range attributions only know which prompt produced an AI line, not who
+/// committed the surrounding human lines, so this sums each commit's own stats instead.
+pub(crate) fn calculate_author_stats(
+    commit_stats: &[RangeCommitStats],
+) -> BTreeMap<String, CommitStats> {
+    let mut author_stats: BTreeMap<String, CommitStats> = BTreeMap::new();
+
+    for commit in commit_stats {
+        add_commit_stats(
+            author_stats.entry(commit.git_author.clone()).or_default(),
+            &commit.stats,
+        );
+    }
+
+    author_stats
+}
+
+/// Add one commit's stats into a running total
+fn add_commit_stats(total: &mut CommitStats, stats: &CommitStats) {
+    total.human_additions += stats.human_additions;
+    total.mixed_additions += stats.mixed_additions;
+    total.ai_additions += stats.ai_additions;
+    total.ai_accepted += stats.ai_accepted;
+    total.total_ai_additions += stats.total_ai_additions;
+    total.total_ai_deletions += stats.total_ai_deletions;
+    total.time_waiting_for_ai += stats.time_waiting_for_ai;
+    total.git_diff_deleted_lines += stats.git_diff_deleted_lines;
+    total.git_diff_added_lines += stats.git_diff_added_lines;
+
+    for (key, tool_stats) in &stats.tool_model_breakdown {
+        let total_tool = total.tool_model_breakdown.entry(key.clone()).or_default();
+        total_tool.ai_additions += tool_stats.ai_additions;
+        total_tool.mixed_additions += tool_stats.mixed_additions;
+        total_tool.ai_accepted += tool_stats.ai_accepted;
+        total_tool.total_ai_additions += tool_stats.total_ai_additions;
+        total_tool.total_ai_deletions += tool_stats.total_ai_deletions;
+        total_tool.time_waiting_for_ai += tool_stats.time_waiting_for_ai;
+    }
+}
+
+/// Print one row per author, most lines added first
+fn print_author_stats(author_stats: &BTreeMap<String, CommitStats>) {
+    if author_stats.is_empty() {
+        return;
+    }
+
+    let mut rows: Vec<(&String, &CommitStats)> = author_stats.iter().collect();
+    rows.sort_by(|a, b| {
+        let a_total = a.1.ai_additions + a.1.human_additions;
+        let b_total = b.1.ai_additions + b.1.human_additions;
+        b_total.cmp(&a_total).then_with(|| a.0.cmp(b.0))
+    });
+
+    let name_width = rows
+        .iter()
+        .map(|(author, _)| author.len())
+        .max()
+        .unwrap_or(0);
+
+    println!("\n  By author:");
+    for (author, stats) in rows {
+        println!(
+            "    {:<width$}  {:>6} ai  {:>6} human",
+            author,
+            stats.ai_additions,
+            stats.human_additions,
+            width = name_width
+        );
+    }
+}
+
+/// Print the `top` changed files with the highest share of AI additions
+fn print_file_stats(file_stats: &BTreeMap<String, FileAuthorshipStats>, top: usize) {
+    if file_stats.is_empty() || top == 0 {
+        return;
+    }
+
+    let mut rows: Vec<(&String, &FileAuthorshipStats)> = file_stats.iter().collect();
+    rows.sort_by(|a, b| {
+        b.1.ai_percentage()
+            .total_cmp(&a.1.ai_percentage())
+            .then_with(|| b.1.ai_additions.cmp(&a.1.ai_additions))
+            .then_with(|| a.0.cmp(b.0))
+    });
+    rows.truncate(top);
+
+    let name_width = rows.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+
+    println!("\n  By file (top {}):", rows.len());
+    for (path, stats) in rows {
+        println!(
+            "    {:<width$}  {:>5.1}% ai  {:>6} ai  {:>6} human",
+            path,
+            stats.ai_percentage(),
+            stats.ai_additions,
+            stats.human_additions,
+            width = name_width
+        );
+    }
+}
+
+/// Render one CSV row per commit: sha, git_author, has_authorship, ai_additions, human_additions
+pub fn range_authorship_stats_to_csv(stats: &RangeAuthorshipStats) -> String {
+    let mut output = String::from("sha,git_author,has_authorship,ai_additions,human_additions\n");
+
+    for commit in &stats.commit_stats {
+        output.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&commit.sha),
+            csv_field(&commit.git_author),
+            commit.has_authorship,
+            commit.stats.ai_additions,
+            commit.stats.human_additions
+        ));
+    }
+
+    output
+}
+
+/// The headline numbers of a range for badges and scripts (`git-ai stats --stat-only`).
+/// Percentages of the added lines, rounded like the detailed report's percentage line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RangeStatSummary {
+    pub ai_percent: u32,
+    /// Includes AI lines a human edited before committing, which the detailed report
+    /// shows separately as mixed
+    pub human_percent: u32,
+    pub total_lines: u32,
+}
+
+impl RangeStatSummary {
+    pub fn from_range_stats(stats: &CommitStats) -> Self {
+        let total_lines = stats.human_additions + stats.ai_additions;
+        let percent = |lines: u32| {
+            if total_lines > 0 {
+                ((lines as f64 / total_lines as f64) * 100.0).round() as u32
+            } else {
+                0
+            }
+        };
+        RangeStatSummary {
+            ai_percent: percent(stats.ai_additions),
+            human_percent: percent(stats.human_additions),
+            total_lines,
+        }
+    }
+}
+
+/// Render `--stat-only` output: just the AI percentage (e.g. `37%`), or the summary as JSON
+pub fn format_range_stat_summary(stats: &RangeAuthorshipStats, json: bool) -> String {
+    let summary = RangeStatSummary::from_range_stats(&stats.range_stats);
+    if json {
+        format!("{}\n", serde_json::to_string(&summary).unwrap())
+    } else {
+        format!("{}%\n", summary.ai_percent)
+    }
+}
+
+/// Quote a CSV field if it contains a comma, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
-pub fn print_range_authorship_stats(stats: &RangeAuthorshipStats) {
+pub fn print_range_authorship_stats(stats: &RangeAuthorshipStats, top_files: usize) {
     println!("\n");
 
     // If there's no AI authorship in the range, show the special message
-    if !stats.authorship_stats.commits_with_authorship > stats.authorship_stats.total_commits {
+    if !stats.has_ai_authorship {
         println!("Committers are not using git-ai");
         return;
     }
@@ -446,6 +1275,8 @@ pub fn print_range_authorship_stats(stats: &RangeAuthorshipStats) {
     // Only print stats if we're in an interactive terminal
     let is_interactive = std::io::stdout().is_terminal();
     write_stats_to_terminal(&stats.range_stats, is_interactive);
+    print_author_stats(&stats.author_stats);
+    print_file_stats(&stats.file_stats, top_files);
 
     // Check if all individual commits have authorship logs (for optional breakdown)
     let all_have_authorship =
@@ -514,7 +1345,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify stats
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -558,7 +1393,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify stats - should include all commits from beginning
         assert_eq!(stats.authorship_stats.total_commits, 2);
@@ -602,7 +1441,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // For single commit, should use stats_for_commit_stats
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -659,7 +1502,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify stats
         assert_eq!(stats.authorship_stats.total_commits, 3);
@@ -697,7 +1544,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Should have 1 commit but no diffs since start == end
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -736,7 +1587,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify all files are included
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -796,7 +1651,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify lockfile is excluded: only 2 lines added (from main.rs), not 1000+ from lockfile
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -867,7 +1726,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Key assertion: git_diff should only count lib.rs changes (3 lines), not package-lock.json (3000 lines)
         assert_eq!(stats.authorship_stats.total_commits, 2);
@@ -932,7 +1795,11 @@ mod tests {
             "poetry.lock".to_string(),
             "go.sum".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify: only the 1 README line is counted, all lockfiles excluded (2000 lines ignored)
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -988,7 +1855,11 @@ mod tests {
             "package-lock.json".to_string(),
             "yarn.lock".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &lockfile_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: lockfile_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Verify: no lines counted since only lockfiles changed
         assert_eq!(stats.authorship_stats.total_commits, 1);
@@ -998,12 +1869,693 @@ mod tests {
     }
 
     #[test]
-    fn test_should_ignore_file_with_patterns() {
-        let lockfile_patterns = vec![
-            "package-lock.json".to_string(),
-            "yarn.lock".to_string(),
-            "Cargo.lock".to_string(),
-            "go.sum".to_string(),
+    fn test_range_authorship_author_stats() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo.write_file("test.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        file.append("Human Line 2\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+
+        file.append("AI Line 3\nAI Line 4\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let commit_range = CommitRange::new(
+            &tmp_repo.gitai_repo(),
+            first_sha,
+            head_sha,
+            "HEAD".to_string(),
+        )
+        .unwrap();
+
+        let stats = range_authorship(commit_range, &RangeAuthorshipOptions::default()).unwrap();
+
+        // Both commits share the test repo's git author
+        assert_eq!(stats.author_stats.len(), 1);
+        let author = stats.author_stats.values().next().unwrap();
+        assert_eq!(author.ai_additions, 2);
+        assert_eq!(author.human_additions, 1);
+        assert_eq!(author.git_diff_added_lines, 3);
+    }
+
+    #[test]
+    fn test_range_authorship_file_stats() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("base.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("ai.txt", "AI Line 1\nAI Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        tmp_repo
+            .write_file("human.txt", "Human Line 1\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let commit_range = CommitRange::new(
+            &tmp_repo.gitai_repo(),
+            first_sha,
+            head_sha,
+            "HEAD".to_string(),
+        )
+        .unwrap();
+
+        let stats = range_authorship(commit_range, &RangeAuthorshipOptions::default()).unwrap();
+
+        assert_eq!(
+            stats.file_stats.get("ai.txt"),
+            Some(&FileAuthorshipStats {
+                ai_additions: 2,
+                human_additions: 0,
+            })
+        );
+        assert_eq!(
+            stats.file_stats.get("human.txt"),
+            Some(&FileAuthorshipStats {
+                ai_additions: 0,
+                human_additions: 1,
+            })
+        );
+        assert!(!stats.file_stats.contains_key("base.txt"));
+    }
+
+    #[test]
+    fn test_range_authorship_first_parent() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("main.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let main_branch = tmp_repo.current_branch().unwrap();
+
+        // Side branch with AI work, merged back after main moved on
+        tmp_repo.create_branch("feature").unwrap();
+        tmp_repo
+            .write_file("feature.txt", "AI Line 1\nAI Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Feature commit").unwrap();
+        let feature_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo.switch_branch(&main_branch).unwrap();
+        tmp_repo
+            .write_file("main.txt", "Line 1\nHuman Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Main commit").unwrap();
+        tmp_repo.merge_branch("feature", "Merge feature").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let range = |first_parent: bool| {
+            CommitRange::new(
+                tmp_repo.gitai_repo(),
+                first_sha.clone(),
+                head_sha.clone(),
+                "HEAD".to_string(),
+            )
+            .unwrap()
+            .with_first_parent(first_parent)
+        };
+
+        let all = range_authorship(range(false), &RangeAuthorshipOptions::default()).unwrap();
+        assert_eq!(all.authorship_stats.total_commits, 3);
+        assert!(all.commit_stats.iter().any(|c| c.sha == feature_sha));
+
+        let first_parent =
+            range_authorship(range(true), &RangeAuthorshipOptions::default()).unwrap();
+        assert_eq!(first_parent.authorship_stats.total_commits, 2);
+        assert!(
+            first_parent
+                .commit_stats
+                .iter()
+                .all(|c| c.sha != feature_sha),
+            "Merged-in commits shouldn't be broken down separately"
+        );
+
+        // The range totals still cover everything merged in
+        assert_eq!(first_parent.range_stats.ai_additions, 2);
+        assert_eq!(
+            first_parent.range_stats.ai_additions,
+            all.range_stats.ai_additions
+        );
+        assert_eq!(
+            first_parent.range_stats.human_additions,
+            all.range_stats.human_additions
+        );
+    }
+
+    #[test]
+    fn test_range_authorship_author_filter() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("test.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("test.txt", "Line 1\nAI Line 2\nAI Line 3\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        // A commit by someone else, made without git-ai so it has no authorship log
+        tmp_repo
+            .write_file("other.txt", "Other line\n", true)
+            .unwrap();
+        tmp_repo
+            .git_command(&[
+                "-c",
+                "user.name=Other Dev",
+                "-c",
+                "user.email=other@example.com",
+                "commit",
+                "-m",
+                "Other commit",
+            ])
+            .unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let range_for = |author: &str| {
+            let commit_range = CommitRange::new(
+                &tmp_repo.gitai_repo(),
+                first_sha.clone(),
+                head_sha.clone(),
+                "HEAD".to_string(),
+            )
+            .unwrap();
+            let options = RangeAuthorshipOptions {
+                author: Some(author.to_string()),
+                ..Default::default()
+            };
+            range_authorship(commit_range, &options).unwrap()
+        };
+
+        let stats = range_for("TEST@example.com");
+        assert_eq!(stats.authorship_stats.total_commits, 1);
+        assert_eq!(stats.range_stats.ai_additions, 2);
+        assert_eq!(stats.range_stats.human_additions, 0);
+        assert_eq!(stats.range_stats.git_diff_added_lines, 2);
+        assert!(!stats.file_stats.contains_key("other.txt"));
+
+        let stats = range_for("other dev");
+        assert_eq!(stats.authorship_stats.total_commits, 1);
+        assert_eq!(stats.authorship_stats.commits_with_authorship, 0);
+        assert_eq!(stats.range_stats.ai_additions, 0);
+        assert_eq!(stats.range_stats.git_diff_added_lines, 1);
+        assert_eq!(
+            stats.author_stats.keys().collect::<Vec<_>>(),
+            vec!["Other Dev <other@example.com>"]
+        );
+
+        let stats = range_for("nobody");
+        assert_eq!(stats.authorship_stats.total_commits, 0);
+        assert_eq!(stats.range_stats.git_diff_added_lines, 0);
+        assert!(stats.file_stats.is_empty());
+    }
+
+    #[test]
+    fn test_range_authorship_stats_json_sorts_author_sets() {
+        let stats = RangeAuthorshipStats {
+            has_ai_authorship: true,
+            authorship_stats: RangeAuthorshipStatsData {
+                total_commits: 3,
+                commits_with_authorship: 2,
+                authors_committing_authorship: ["zoe", "alice", "mallory"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                authors_not_committing_authorship: ["bob", "ann"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                commits_without_authorship: vec!["abc123".to_string()],
+                commits_without_authorship_with_authors: vec![(
+                    "abc123".to_string(),
+                    "bob".to_string(),
+                )],
+            },
+            range_stats: CommitStats::default(),
+            author_stats: BTreeMap::new(),
+            file_stats: BTreeMap::new(),
+            commit_stats: Vec::new(),
+            attribution_conflicts: Vec::new(),
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&stats).unwrap();
+        assert_eq!(
+            json["authorship_stats"]["authors_committing_authorship"],
+            serde_json::json!(["alice", "mallory", "zoe"])
+        );
+        assert_eq!(
+            json["authorship_stats"]["authors_not_committing_authorship"],
+            serde_json::json!(["ann", "bob"])
+        );
+        assert!(json["range_stats"].is_object());
+    }
+
+    #[test]
+    fn test_range_authorship_stats_to_csv() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo.write_file("test.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        file.append("AI Line 2\nAI Line 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let commit_range = CommitRange::new(
+            &tmp_repo.gitai_repo(),
+            first_sha,
+            head_sha.clone(),
+            "HEAD".to_string(),
+        )
+        .unwrap();
+
+        let stats = range_authorship(commit_range, &RangeAuthorshipOptions::default()).unwrap();
+        let csv = range_authorship_stats_to_csv(&stats);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "sha,git_author,has_authorship,ai_additions,human_additions"
+        );
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with(&format!("{},", head_sha)));
+        assert!(lines[1].ends_with(",true,2,0"));
+    }
+
+    #[test]
+    fn test_range_stat_summary_matches_report_rounding() {
+        let stats = CommitStats {
+            human_additions: 5,
+            mixed_additions: 1,
+            ai_additions: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            RangeStatSummary::from_range_stats(&stats),
+            RangeStatSummary {
+                ai_percent: 38,
+                human_percent: 63,
+                total_lines: 8,
+            }
+        );
+
+        let empty = RangeStatSummary::from_range_stats(&CommitStats::default());
+        assert_eq!(empty.ai_percent, 0);
+        assert_eq!(empty.total_lines, 0);
+    }
+
+    #[test]
+    fn test_range_authorship_cache_reused() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo.write_file("test.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        file.append("AI Line 2\nAI Line 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let range =
+            CommitRange::new(repo, first_sha.clone(), head_sha.clone(), "HEAD".into()).unwrap();
+        let options = RangeAuthorshipOptions {
+            use_cache: true,
+            ..Default::default()
+        };
+        let stats = range_authorship(range, &options).unwrap();
+        assert_eq!(stats.range_stats.ai_additions, 2);
+
+        // The cache note now holds an entry for this range
+        let key = range_cache_key(&first_sha, &head_sha, &[], "");
+        let note = show_range_cache_note(repo, &head_sha).unwrap();
+        let mut cache: BTreeMap<String, CachedRangeStats> = serde_json::from_str(&note).unwrap();
+        assert!(cache.contains_key(&key));
+
+        // Tamper with the entry to prove the next run reads from the cache
+        cache.get_mut(&key).unwrap().range_stats.ai_additions = 42;
+        range_cache_notes_add(repo, &head_sha, &serde_json::to_string(&cache).unwrap()).unwrap();
+
+        let range =
+            CommitRange::new(repo, first_sha.clone(), head_sha.clone(), "HEAD".into()).unwrap();
+        let cached = range_authorship(range, &options).unwrap();
+        assert_eq!(cached.range_stats.ai_additions, 42);
+
+        // An entry written in another cache format is a miss
+        cache.get_mut(&key).unwrap().version = RANGE_CACHE_VERSION - 1;
+        range_cache_notes_add(repo, &head_sha, &serde_json::to_string(&cache).unwrap()).unwrap();
+        let range =
+            CommitRange::new(repo, first_sha.clone(), head_sha.clone(), "HEAD".into()).unwrap();
+        let recomputed = range_authorship(range, &options).unwrap();
+        assert_eq!(recomputed.range_stats.ai_additions, 2);
+
+        // --no-cache recomputes
+        let range = CommitRange::new(repo, first_sha, head_sha, "HEAD".into()).unwrap();
+        let fresh = range_authorship(range, &RangeAuthorshipOptions::default()).unwrap();
+        assert_eq!(fresh.range_stats.ai_additions, 2);
+    }
+
+    #[test]
+    fn test_range_cache_key_ignores_pattern_order() {
+        let a = range_cache_key("a", "b", &["*.lock".to_string(), "dist/**".to_string()], "");
+        let b = range_cache_key("a", "b", &["dist/**".to_string(), "*.lock".to_string()], "");
+        assert_eq!(a, b);
+        assert_ne!(a, range_cache_key("a", "c", &[], ""));
+    }
+
+    #[test]
+    fn test_range_cache_key_changes_with_gitai_attributes() {
+        let without = range_cache_key("a", "b", &[], "");
+        let with = range_cache_key("a", "b", &[], "*.snap attribution=ignore\n");
+        assert_ne!(without, with);
+        assert_eq!(
+            with,
+            range_cache_key("a", "b", &[], "*.snap attribution=ignore\n")
+        );
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(
+            csv_field("Doe, Jane <jane@example.com>"),
+            "\"Doe, Jane <jane@example.com>\""
+        );
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_parse_gitaiignore() {
+        let content = "# generated code\n\nvendor/**\n  *.pb.go  \n#dist/**\n";
+        assert_eq!(
+            parse_gitaiignore(content),
+            vec!["vendor/**".to_string(), "*.pb.go".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_range_timings_format() {
+        let mut timings = RangeTimings::default();
+        timings.record("diff changed files", Duration::from_millis(12));
+        let value = timings.time("merge", || 42);
+        assert_eq!(value, 42);
+
+        let output = timings.format(
+            "0123456789abcdef",
+            "fedcba9876543210",
+            Duration::from_millis(1500),
+        );
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Range authorship timings (0123456..fedcba9):");
+        assert_eq!(lines[1], "  diff changed files         12.0ms");
+        assert!(lines[2].starts_with("  merge "));
+        assert_eq!(lines[3], "  total                        1.5s");
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn test_resolve_fetch_target() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .git_command(&["remote", "add", "upstream", "https://example.com/repo.git"])
+            .unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let target = |refname: &str| resolve_fetch_target(repo, refname).unwrap();
+        let expected = |remote: &str, refspec: &str| (remote.to_string(), refspec.to_string());
+
+        // Without an origin, the only configured remote is the default
+        assert_eq!(
+            target("refs/heads/main"),
+            expected("upstream", "refs/heads/main")
+        );
+        assert_eq!(target("main"), expected("upstream", "refs/heads/main"));
+        assert_eq!(
+            target("refs/remotes/upstream/dev"),
+            expected("upstream", "refs/heads/dev")
+        );
+        assert_eq!(
+            target("upstream/dev"),
+            expected("upstream", "refs/heads/dev")
+        );
+    }
+
+    #[test]
+    fn test_resolve_fetch_target_multi_slash_branches() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        for remote in ["origin", "upstream", "team/upstream"] {
+            tmp_repo
+                .git_command(&["remote", "add", remote, "https://example.com/repo.git"])
+                .unwrap();
+        }
+        let repo = tmp_repo.gitai_repo();
+
+        let target = |refname: &str| resolve_fetch_target(repo, refname).unwrap();
+        let expected = |remote: &str, refspec: &str| (remote.to_string(), refspec.to_string());
+
+        assert_eq!(
+            target("origin/feature/foo"),
+            expected("origin", "refs/heads/feature/foo")
+        );
+        assert_eq!(
+            target("refs/remotes/origin/feature/foo"),
+            expected("origin", "refs/heads/feature/foo")
+        );
+
+        // `feature` isn't a remote, so the whole name is the branch
+        assert_eq!(
+            target("feature/foo/bar"),
+            expected("origin", "refs/heads/feature/foo/bar")
+        );
+
+        // The longest configured remote wins over its slash-free prefix
+        assert_eq!(
+            target("upstream/release/1.0"),
+            expected("upstream", "refs/heads/release/1.0")
+        );
+        assert_eq!(
+            target("team/upstream/release/1.0"),
+            expected("team/upstream", "refs/heads/release/1.0")
+        );
+        assert_eq!(
+            target("refs/remotes/team/upstream/dev"),
+            expected("team/upstream", "refs/heads/dev")
+        );
+    }
+
+    #[test]
+    fn test_merge_base_range_skips_base_commits() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("base.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let fork_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let main_branch = tmp_repo.current_branch().unwrap();
+
+        tmp_repo.create_branch("feature").unwrap();
+        tmp_repo
+            .write_file("feature.txt", "AI Line 1\nAI Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI feature commit").unwrap();
+        let feature_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        // main moves on after the branch point
+        tmp_repo.switch_branch(&main_branch).unwrap();
+        tmp_repo
+            .write_file("main.txt", "Main 1\nMain 2\nMain 3\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Main commit").unwrap();
+        tmp_repo.switch_branch("feature").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let commit_range = merge_base_range(repo, &main_branch).unwrap();
+        assert_eq!(commit_range.start_oid, fork_sha);
+        assert_eq!(commit_range.end_oid, feature_sha);
+
+        let stats = range_authorship(commit_range, &RangeAuthorshipOptions::default()).unwrap();
+        assert_eq!(stats.authorship_stats.total_commits, 1);
+        assert_eq!(stats.range_stats.git_diff_added_lines, 2);
+        assert_eq!(stats.range_stats.git_diff_deleted_lines, 0);
+        assert_eq!(stats.range_stats.ai_additions, 2);
+
+        // Against a base that already contains HEAD there are no commits to count
+        assert!(merge_base_range(repo, "feature").is_err());
+    }
+
+    #[test]
+    fn test_range_authorship_crlf_file_attributes_the_right_lines() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\nthree\r\nfour\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let ai_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        // A human line above the AI lines shifts them down by one in the end state
+        tmp_repo
+            .write_file(
+                "foo.py",
+                "zero\r\none\r\ntwo\r\nthree\r\nfour\r\nfive\r\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let mut conflicts = Vec::new();
+        let authorship_log = create_authorship_log_for_range(
+            tmp_repo.gitai_repo(),
+            &first_sha,
+            &head_sha,
+            &[ai_sha, head_sha.clone()],
+            &[],
+            &mut conflicts,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(authorship_log.attestations.len(), 1);
+        let entries = &authorship_log.attestations[0].entries;
+        assert_eq!(entries.len(), 1, "entries: {:?}", entries);
+        assert_eq!(entries[0].line_ranges, vec![LineRange::Range(4, 5)]);
+        assert!(conflicts.is_empty(), "conflicts: {:?}", conflicts);
+    }
+
+    #[test]
+    fn test_range_authorship_gitaiignore_excludes_files() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("src.txt", "Line 1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("vendor/lib.txt", "AI Vendored 1\nAI Vendored 2\n", true)
+            .unwrap();
+        tmp_repo
+            .write_file("src.txt", "Line 1\nAI Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        std::fs::write(
+            tmp_repo.path().join(GITAIIGNORE_FILENAME),
+            "# vendored\nvendor/**\n",
+        )
+        .unwrap();
+        let patterns = load_gitaiignore_patterns(tmp_repo.gitai_repo());
+        assert_eq!(patterns, vec!["vendor/**".to_string()]);
+
+        let commit_range = CommitRange::new(
+            &tmp_repo.gitai_repo(),
+            first_sha,
+            head_sha,
+            "HEAD".to_string(),
+        )
+        .unwrap();
+
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
+
+        assert_eq!(stats.range_stats.git_diff_added_lines, 1);
+        assert_eq!(stats.range_stats.ai_additions, 1);
+        assert!(!stats.file_stats.contains_key("vendor/lib.txt"));
+    }
+
+    #[test]
+    fn test_should_ignore_file_with_patterns() {
+        let lockfile_patterns = vec![
+            "package-lock.json".to_string(),
+            "yarn.lock".to_string(),
+            "Cargo.lock".to_string(),
+            "go.sum".to_string(),
         ];
 
         // Test that specified patterns are ignored
@@ -1429,7 +2981,11 @@ mod tests {
             "*lock.json".to_string(), // Matches package-lock.json
             "*.generated.*".to_string(),
         ];
-        let stats = range_authorship(commit_range, false, &glob_patterns).unwrap();
+        let options = RangeAuthorshipOptions {
+            ignore_patterns: glob_patterns,
+            ..Default::default()
+        };
+        let stats = range_authorship(commit_range, &options).unwrap();
 
         // Should only count the 1 line in main.rs, ignoring 1700 lines in lockfiles and generated files
         assert_eq!(stats.range_stats.git_diff_added_lines, 1);
@@ -142,6 +142,7 @@ pub fn prepare_working_log_after_squash(
             source_head_sha.to_string(),
             &changed_files,
             merge_base_clone,
+            None,
         )
         .await
     })?;
@@ -153,6 +154,7 @@ pub fn prepare_working_log_after_squash(
             target_branch_head_sha.to_string(),
             &changed_files,
             merge_base,
+            None,
         )
         .await
     })?;
@@ -161,7 +163,7 @@ pub fn prepare_working_log_after_squash(
     let staged_files = repo.get_all_staged_files_content(&changed_files)?;
 
     // Step 4: Merge VirtualAttributions, favoring target branch (HEAD)
-    let merged_va = merge_attributions_favoring_first(target_va, source_va, staged_files)?;
+    let merged_va = merge_attributions_favoring_first(target_va, source_va, staged_files, None)?;
 
     // Step 5: Convert to INITIAL (everything is uncommitted in a squash)
     // Pass same SHA for parent and commit to get empty diff (no committed hunks)
@@ -266,6 +268,7 @@ pub fn rewrite_authorship_after_squash_or_rebase(
             source_head_sha.to_string(),
             &changed_files,
             merge_base_clone,
+            None,
         )
         .await
     })?;
@@ -277,6 +280,7 @@ pub fn rewrite_authorship_after_squash_or_rebase(
             target_branch_head_sha.clone(),
             &changed_files,
             merge_base,
+            None,
         )
         .await
     })?;
@@ -290,7 +294,7 @@ pub fn rewrite_authorship_after_squash_or_rebase(
     ));
 
     // Step 5: Merge VirtualAttributions, favoring target branch (base)
-    let merged_va = merge_attributions_favoring_first(target_va, source_va, committed_files)?;
+    let merged_va = merge_attributions_favoring_first(target_va, source_va, committed_files, None)?;
 
     // Step 6: Convert to AuthorshipLog (everything is committed in CI merge)
     let mut authorship_log = merged_va.to_authorship_log()?;
@@ -384,6 +388,7 @@ pub fn rewrite_authorship_after_rebase_v2(
             original_head_clone,
             &pathspecs_clone,
             None,
+            None,
         )
         .await
     })?;
@@ -579,6 +584,7 @@ pub fn rewrite_authorship_after_cherry_pick(
             source_head_clone,
             &pathspecs_clone,
             None,
+            None,
         )
         .await
     })?;
@@ -965,6 +971,7 @@ pub fn reconstruct_working_log_after_reset(
             target_clone,
             &pathspecs_clone,
             Some(target_commit_sha.to_string()),
+            None,
         )
         .await
     })?;
@@ -1001,6 +1008,7 @@ pub fn reconstruct_working_log_after_reset(
         old_head_va,
         target_va,
         final_state.clone(),
+        None,
     )?;
 
     debug_log(&format!(
@@ -1071,6 +1079,7 @@ pub fn filter_pathspecs_to_ai_touched_files(
     let touched_files = smol::block_on(load_ai_touched_files_for_commits(
         repo,
         commit_shas.to_vec(),
+        None,
     ))?;
     Ok(pathspecs
         .iter()
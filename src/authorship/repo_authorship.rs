@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::authorship::authorship_log_cache::AuthorshipLogCache;
+use crate::authorship::range_authorship::should_ignore_file;
+use crate::authorship::tool_names::normalize_tool_name;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::load_ai_touched_files;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+
+/// The git empty tree hash, diffed against to count the lines of every file in a tree
+const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Most files blamed at once
+const MAX_CONCURRENT_BLAMES: usize = 30;
+
+/// Authorship of the lines that make up a commit's tree (`git-ai stats --repo`). Unlike
+/// range stats, which count the lines a range added, this counts the code that's there now.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepoAuthorshipStats {
+    pub commit_sha: String,
+    /// Text files counted, after ignore patterns
+    pub files: usize,
+    /// Files with at least one AI-authored line
+    pub ai_files: usize,
+    pub total_lines: u32,
+    pub ai_lines: u32,
+    pub human_lines: u32,
+    /// AI-authored lines per tool
+    pub tools: BTreeMap<String, u32>,
+}
+
+impl RepoAuthorshipStats {
+    /// `lines` as a percentage of all the lines counted
+    pub fn percentage(&self, lines: u32) -> f64 {
+        if self.total_lines == 0 {
+            0.0
+        } else {
+            lines as f64 / self.total_lines as f64 * 100.0
+        }
+    }
+}
+
+/// Count the AI- and human-authored lines of every text file in `revision`'s tree.
+///
+/// Only files named in some authorship note can hold AI lines, so just those are blamed, in
+/// parallel; every line of any other file is counted as human. Paths matching
+/// `ignore_patterns` are left out entirely.
+pub fn repo_authorship(
+    repo: &Repository,
+    revision: &str,
+    ignore_patterns: &[String],
+) -> Result<RepoAuthorshipStats, GitAiError> {
+    let commit_sha = repo.revparse_single(revision)?.peel_to_commit()?.id();
+
+    let line_counts: BTreeMap<String, u32> = tree_line_counts(repo, &commit_sha)?
+        .into_iter()
+        .filter(|(path, _)| !should_ignore_file(path, ignore_patterns))
+        .collect();
+
+    let ai_touched = smol::block_on(load_ai_touched_files(repo, None, None))?;
+    let candidates: Vec<String> = line_counts
+        .iter()
+        .filter(|(path, lines)| **lines > 0 && ai_touched.contains(*path))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let mut stats = RepoAuthorshipStats {
+        commit_sha: commit_sha.clone(),
+        files: line_counts.len(),
+        total_lines: line_counts.values().sum(),
+        ..Default::default()
+    };
+    for file_tools in blame_ai_lines_parallel(repo, &commit_sha, candidates) {
+        if file_tools.is_empty() {
+            continue;
+        }
+        stats.ai_files += 1;
+        for (tool, lines) in file_tools {
+            stats.ai_lines += lines;
+            *stats.tools.entry(tool).or_insert(0) += lines;
+        }
+    }
+    stats.human_lines = stats.total_lines.saturating_sub(stats.ai_lines);
+
+    Ok(stats)
+}
+
+/// Line count of each text file in the tree of `commit_sha`. Binary files are left out.
+fn tree_line_counts(
+    repo: &Repository,
+    commit_sha: &str,
+) -> Result<BTreeMap<String, u32>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("--numstat".to_string());
+    args.push("-z".to_string());
+    args.push("--no-renames".to_string());
+    args.push(EMPTY_TREE_HASH.to_string());
+    args.push(commit_sha.to_string());
+
+    let output = exec_git(&args)?;
+    Ok(parse_numstat_z(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git diff --numstat -z` records ("<added>\t<deleted>\t<path>\0") into added line
+/// counts by path. Binary files, whose counts are `-`, are skipped.
+fn parse_numstat_z(raw: &str) -> BTreeMap<String, u32> {
+    raw.split('\0')
+        .filter_map(|record| {
+            let mut fields = record.splitn(3, '\t');
+            let (added, _deleted, path) = (fields.next()?, fields.next()?, fields.next()?);
+            Some((path.to_string(), added.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Blame each file at `commit_sha` and count its AI-authored lines per tool, in file order.
+/// A file that fails to blame counts as having no AI lines.
+fn blame_ai_lines_parallel(
+    repo: &Repository,
+    commit_sha: &str,
+    files: Vec<String>,
+) -> Vec<BTreeMap<String, u32>> {
+    let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT_BLAMES));
+    let cache = Arc::new(AuthorshipLogCache::default());
+    let tasks: Vec<_> = files
+        .into_iter()
+        .map(|file_path| {
+            let repo = repo.clone();
+            let commit_sha = commit_sha.to_string();
+            let semaphore = Arc::clone(&semaphore);
+            let cache = Arc::clone(&cache);
+
+            smol::spawn(async move {
+                let _permit = semaphore.acquire().await;
+                smol::unblock(move || {
+                    blame_ai_lines(&repo, &commit_sha, &file_path, cache).unwrap_or_else(|e| {
+                        debug_log(&format!("Failed to blame {}: {}", file_path, e));
+                        BTreeMap::new()
+                    })
+                })
+                .await
+            })
+        })
+        .collect();
+
+    smol::block_on(futures::future::join_all(tasks))
+}
+
+/// AI-authored lines per tool in one file at `commit_sha`
+fn blame_ai_lines(
+    repo: &Repository,
+    commit_sha: &str,
+    file_path: &str,
+    cache: Arc<AuthorshipLogCache>,
+) -> Result<BTreeMap<String, u32>, GitAiError> {
+    let mut options = GitAiBlameOptions::default();
+    options.newest_commit = Some(commit_sha.to_string());
+    options.no_output = true;
+    // AI lines are named by prompt hash, so the prompt record can be looked up
+    options.use_prompt_hashes_as_names = true;
+    options.authorship_log_cache = Some(cache);
+    // Every line of the file is blamed
+    options.batch_line_attribution = true;
+
+    let (line_authors, prompt_records) = repo.blame(file_path, &options)?;
+    let mut tools = BTreeMap::new();
+    for author in line_authors.values() {
+        if let Some(record) = prompt_records.get(author) {
+            *tools
+                .entry(normalize_tool_name(&record.agent_id.tool))
+                .or_insert(0) += 1;
+        }
+    }
+    Ok(tools)
+}
+
+/// Print repository authorship stats for the terminal
+pub fn print_repo_authorship_stats(stats: &RepoAuthorshipStats) {
+    println!(
+        "Authorship of {} lines in {} files at {}",
+        stats.total_lines,
+        stats.files,
+        &stats.commit_sha[..7.min(stats.commit_sha.len())]
+    );
+    println!(
+        "  AI     {:>8} lines  {:>5.1}%  ({} files)",
+        stats.ai_lines,
+        stats.percentage(stats.ai_lines),
+        stats.ai_files
+    );
+    println!(
+        "  Human  {:>8} lines  {:>5.1}%",
+        stats.human_lines,
+        stats.percentage(stats.human_lines)
+    );
+
+    let mut tools: Vec<(&String, &u32)> = stats.tools.iter().collect();
+    tools.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    for (tool, lines) in tools {
+        println!(
+            "    {:<12} {:>8} lines  {:>5.1}%",
+            tool,
+            lines,
+            stats.percentage(*lines)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_parse_numstat_z() {
+        let raw = [
+            "3\t0\tsrc/main.rs",
+            "-\t-\tlogo.png",
+            "1\t0\tfile with spaces.txt",
+            "",
+        ]
+        .join("\0");
+        let counts = parse_numstat_z(&raw);
+        assert_eq!(counts.get("src/main.rs"), Some(&3));
+        assert_eq!(counts.get("logo.png"), None);
+        assert_eq!(counts.get("file with spaces.txt"), Some(&1));
+    }
+
+    #[test]
+    fn test_repo_authorship_counts_surviving_lines() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("human.txt", "one\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+
+        let mut ai_file = tmp_repo
+            .write_file("ai.txt", "AI Line 1\nAI Line 2\nAI Line 3\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        // A human replaces one of the AI lines, which no longer counts as AI
+        ai_file
+            .update("Human line\nAI Line 2\nAI Line 3\n")
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human edit").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let stats = repo_authorship(repo, "HEAD", &[]).unwrap();
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.total_lines, 6);
+        assert_eq!(stats.ai_lines, 2);
+        assert_eq!(stats.human_lines, 4);
+        assert_eq!(stats.ai_files, 1);
+        assert_eq!(stats.tools.values().sum::<u32>(), 2);
+
+        let stats = repo_authorship(repo, "HEAD", &["ai.txt".to_string()]).unwrap();
+        assert_eq!(stats.files, 1);
+        assert_eq!(stats.total_lines, 3);
+        assert_eq!(stats.ai_lines, 0);
+    }
+}
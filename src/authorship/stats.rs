@@ -1,3 +1,5 @@
+use crate::authorship::gitai_attributes::GitAiAttributes;
+use crate::authorship::tool_names::normalize_tool_name;
 use crate::authorship::transcript::Message;
 use crate::error::GitAiError;
 use crate::git::refs::get_authorship;
@@ -63,6 +65,29 @@ impl Default for CommitStats {
     }
 }
 
+impl CommitStats {
+    /// AI additions per tool (e.g. "cursor", "claude"), summed across models.
+    /// Keys of `tool_model_breakdown` are "tool::model".
+    pub fn tool_additions(&self) -> BTreeMap<String, u32> {
+        let mut by_tool: BTreeMap<String, u32> = BTreeMap::new();
+        for (key, tool_stats) in &self.tool_model_breakdown {
+            let tool = key.split_once("::").map_or(key.as_str(), |(tool, _)| tool);
+            *by_tool.entry(tool.to_string()).or_insert(0) += tool_stats.ai_additions;
+        }
+        by_tool
+    }
+}
+
+/// Share of added lines attributed to AI, from 0.0 to 1.0. Uses the same denominator as the
+/// terminal bar (`human_additions + ai_additions`); 0.0 when nothing was added.
+pub fn ai_ratio(stats: &CommitStats) -> f64 {
+    let total_additions = stats.human_additions + stats.ai_additions;
+    if total_additions == 0 {
+        return 0.0;
+    }
+    stats.ai_additions as f64 / total_additions as f64
+}
+
 pub fn stats_command(
     repo: &Repository,
     commit_sha: Option<&str>,
@@ -78,7 +103,7 @@ pub fn stats_command(
                 (full_sha, format!("{}", sha))
             }
             Err(GitAiError::GitCliError { .. }) => {
-                return Err(GitAiError::Generic(format!("No commit found: {}", sha)));
+                return Err(GitAiError::CommitNotFound(sha.to_string()));
             }
             Err(e) => return Err(e),
         }
@@ -306,6 +331,29 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
         if print {
             println!("{}", ai_acceptance_str);
         }
+
+        // Break AI additions down by tool, largest first
+        let mut tools: Vec<(String, u32)> = stats
+            .tool_additions()
+            .into_iter()
+            .filter(|(_, additions)| *additions > 0)
+            .collect();
+        if !tools.is_empty() {
+            tools.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let tools_str = format!(
+                "     \x1b[90m{}\x1b[0m",
+                tools
+                    .iter()
+                    .map(|(tool, additions)| format!("{} {}", tool, additions))
+                    .collect::<Vec<_>>()
+                    .join(" · ")
+            );
+            output.push_str(&tools_str);
+            output.push('\n');
+            if print {
+                println!("{}", tools_str);
+            }
+        }
     }
     return output;
 }
@@ -462,11 +510,13 @@ pub fn write_stats_to_markdown(stats: &CommitStats) -> String {
 }
 
 /// Calculate commit stats from an authorship log
-/// This helper can work with both fetched and in-memory authorship logs
+/// This helper can work with both fetched and in-memory authorship logs.
+/// Attestations for paths marked `attribution=ignore` in `attributes` aren't counted.
 pub fn stats_from_authorship_log(
     authorship_log: Option<&crate::authorship::authorship_log_serialization::AuthorshipLog>,
     git_diff_added_lines: u32,
     git_diff_deleted_lines: u32,
+    attributes: &GitAiAttributes,
 ) -> CommitStats {
     let mut commit_stats = CommitStats {
         human_additions: 0,
@@ -485,6 +535,9 @@ pub fn stats_from_authorship_log(
     if let Some(log) = authorship_log {
         // Count lines by author type
         for file_attestation in &log.attestations {
+            if attributes.is_ignored(&file_attestation.file_path) {
+                continue;
+            }
             for entry in &file_attestation.entries {
                 // Count lines in this entry
                 let lines_in_entry: u32 = entry
@@ -503,7 +556,8 @@ pub fn stats_from_authorship_log(
 
                     let key = format!(
                         "{}::{}",
-                        prompt_record.agent_id.tool, prompt_record.agent_id.model
+                        normalize_tool_name(&prompt_record.agent_id.tool),
+                        prompt_record.agent_id.model
                     );
                     let tool_stats = commit_stats.tool_model_breakdown.entry(key).or_default();
                     tool_stats.ai_accepted += lines_in_entry;
@@ -518,7 +572,8 @@ pub fn stats_from_authorship_log(
 
             let key = format!(
                 "{}::{}",
-                prompt_record.agent_id.tool, prompt_record.agent_id.model
+                normalize_tool_name(&prompt_record.agent_id.tool),
+                prompt_record.agent_id.model
             );
             let tool_stats = commit_stats.tool_model_breakdown.entry(key).or_default();
             tool_stats.total_ai_additions += prompt_record.total_additions;
@@ -576,10 +631,12 @@ pub fn stats_for_commit_stats(
         authorship_log.as_ref(),
         git_diff_added_lines,
         git_diff_deleted_lines,
+        &GitAiAttributes::load(repo),
     ))
 }
 
-/// Get git diff statistics between commit and its parent
+/// Get git diff statistics between commit and its parent.
+/// Files matching `ignore_patterns` or marked `attribution=ignore` aren't counted.
 pub fn get_git_diff_stats(
     repo: &Repository,
     commit_sha: &str,
@@ -594,6 +651,7 @@ pub fn get_git_diff_stats(
 
     let output = crate::git::repository::exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)?;
+    let attributes = GitAiAttributes::load(repo);
 
     let mut added_lines = 0u32;
     let mut deleted_lines = 0u32;
@@ -614,7 +672,9 @@ pub fn get_git_diff_stats(
         if parts.len() >= 3 {
             // Check if this file should be ignored
             let filename = parts[2];
-            if crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns) {
+            if crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns)
+                || attributes.is_ignored(filename)
+            {
                 continue;
             }
 
@@ -691,6 +751,26 @@ mod tests {
     use super::*;
     use crate::git::test_utils::TmpRepo;
 
+    #[test]
+    fn test_ai_ratio() {
+        assert_eq!(ai_ratio(&CommitStats::default()), 0.0);
+
+        let stats = CommitStats {
+            human_additions: 3,
+            ai_additions: 1,
+            git_diff_added_lines: 4,
+            ..Default::default()
+        };
+        assert_eq!(ai_ratio(&stats), 0.25);
+
+        let all_ai = CommitStats {
+            ai_additions: 5,
+            git_diff_added_lines: 5,
+            ..Default::default()
+        };
+        assert_eq!(ai_ratio(&all_ai), 1.0);
+    }
+
     #[test]
     fn test_terminal_stats_display() {
         // Test with mixed human/AI stats
@@ -779,6 +859,97 @@ mod tests {
         assert_debug_snapshot!(deletion_only_output);
     }
 
+    #[test]
+    fn test_terminal_stats_tool_breakdown() {
+        let mut tool_model_breakdown = BTreeMap::new();
+        tool_model_breakdown.insert(
+            "cursor::gpt-4o".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 30,
+                ..Default::default()
+            },
+        );
+        tool_model_breakdown.insert(
+            "cursor::claude-3-sonnet".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 10,
+                ..Default::default()
+            },
+        );
+        tool_model_breakdown.insert(
+            "claude::claude-3-opus".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 20,
+                ..Default::default()
+            },
+        );
+
+        let stats = CommitStats {
+            human_additions: 40,
+            ai_additions: 60,
+            ai_accepted: 60,
+            git_diff_added_lines: 100,
+            total_ai_additions: 60,
+            tool_model_breakdown,
+            ..Default::default()
+        };
+
+        let by_tool = stats.tool_additions();
+        assert_eq!(by_tool.get("cursor"), Some(&40));
+        assert_eq!(by_tool.get("claude"), Some(&20));
+        assert_eq!(by_tool.values().sum::<u32>(), stats.ai_additions);
+
+        let output = write_stats_to_terminal(&stats, false);
+        assert!(output.contains("cursor 40 · claude 20"));
+    }
+
+    #[test]
+    fn test_stats_tool_breakdown_normalizes_tool_names() {
+        use crate::authorship::authorship_log_serialization::{
+            AttestationEntry, AuthorshipLog, FileAttestation,
+        };
+        use crate::authorship::working_log::AgentId;
+
+        let mut log = AuthorshipLog::new();
+        let mut file = FileAttestation::new("src/main.rs".to_string());
+        for (hash, tool, line) in [("aaaa", "Cursor", 1), ("bbbb", "cursor-ide", 2)] {
+            file.add_entry(AttestationEntry::new(
+                hash.to_string(),
+                vec![LineRange::Single(line)],
+            ));
+            log.metadata.prompts.insert(
+                hash.to_string(),
+                crate::authorship::authorship_log::PromptRecord {
+                    agent_id: AgentId {
+                        tool: tool.to_string(),
+                        id: hash.to_string(),
+                        model: "gpt-4".to_string(),
+                    },
+                    human_author: None,
+                    messages: vec![],
+                    total_additions: 1,
+                    total_deletions: 0,
+                    accepted_lines: 1,
+                    overriden_lines: 0,
+                    messages_url: None,
+                    tool_version: None,
+                },
+            );
+        }
+        log.attestations.push(file);
+
+        let stats = stats_from_authorship_log(Some(&log), 2, 0, &GitAiAttributes::default());
+        let keys: Vec<&String> = stats.tool_model_breakdown.keys().collect();
+        assert_eq!(keys, vec!["cursor::gpt-4"]);
+        assert_eq!(stats.tool_model_breakdown["cursor::gpt-4"].ai_accepted, 2);
+
+        // Ignored paths keep their notes but don't count as accepted AI lines
+        let attributes = GitAiAttributes::parse("src/ attribution=ignore");
+        let stats = stats_from_authorship_log(Some(&log), 2, 0, &attributes);
+        assert_eq!(stats.ai_accepted, 0);
+        assert_eq!(stats.human_additions, 2);
+    }
+
     #[test]
     fn test_markdown_stats_display() {
         // Test with mixed human/AI stats
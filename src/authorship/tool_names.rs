@@ -0,0 +1,95 @@
+use crate::config::Config;
+use std::collections::HashMap;
+
+/// Known spellings of agent tool identifiers, keyed by their lowercased form, and the
+/// canonical name each one is shown as. Canonical names are the identifiers the agent
+/// presets write today, so notes from current versions display unchanged.
+const BUILTIN_TOOL_ALIASES: &[(&str, &str)] = &[
+    ("cursor", "cursor"),
+    ("cursor-ide", "cursor"),
+    ("cursor-agent", "cursor"),
+    ("claude", "claude"),
+    ("claude-code", "claude"),
+    ("claude code", "claude"),
+    ("github-copilot", "github-copilot"),
+    ("copilot", "github-copilot"),
+    ("github copilot", "github-copilot"),
+    ("vscode-copilot", "github-copilot"),
+    ("gemini", "gemini"),
+    ("gemini-cli", "gemini"),
+    ("continue-cli", "continue-cli"),
+    ("continue", "continue-cli"),
+    ("windsurf", "windsurf"),
+    ("windsurf-ide", "windsurf"),
+];
+
+/// Map a `PromptRecord.agent_id.tool` value to the name it's displayed and grouped under,
+/// applying the `tool_aliases` map from the user's config on top of the built-in table.
+pub fn normalize_tool_name(tool: &str) -> String {
+    normalize_tool_name_with(tool, Config::get().tool_aliases())
+}
+
+/// Same as [`normalize_tool_name`] with an explicit alias map (keys lowercased).
+/// An alias can name a raw identifier or a built-in canonical name, so both `acme-bot` and
+/// `cursor` can be renamed. Unknown tools pass through unchanged.
+pub fn normalize_tool_name_with(tool: &str, aliases: &HashMap<String, String>) -> String {
+    let key = tool.trim().to_lowercase();
+    if let Some(name) = aliases.get(&key) {
+        return name.clone();
+    }
+
+    let Some(canonical) = BUILTIN_TOOL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, canonical)| *canonical)
+    else {
+        return tool.to_string();
+    };
+
+    aliases
+        .get(canonical)
+        .cloned()
+        .unwrap_or_else(|| canonical.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tool_name_builtin_aliases() {
+        let aliases = HashMap::new();
+        assert_eq!(normalize_tool_name_with("cursor", &aliases), "cursor");
+        assert_eq!(normalize_tool_name_with("Cursor", &aliases), "cursor");
+        assert_eq!(normalize_tool_name_with("cursor-ide", &aliases), "cursor");
+        assert_eq!(normalize_tool_name_with("Claude-Code", &aliases), "claude");
+        assert_eq!(
+            normalize_tool_name_with("copilot", &aliases),
+            "github-copilot"
+        );
+    }
+
+    #[test]
+    fn test_normalize_tool_name_unknown_passes_through() {
+        let aliases = HashMap::new();
+        assert_eq!(normalize_tool_name_with("Acme-Bot", &aliases), "Acme-Bot");
+    }
+
+    #[test]
+    fn test_normalize_tool_name_config_overrides() {
+        let aliases: HashMap<String, String> = [
+            ("acme-bot".to_string(), "Acme Assistant".to_string()),
+            ("cursor".to_string(), "Cursor".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            normalize_tool_name_with("ACME-BOT", &aliases),
+            "Acme Assistant"
+        );
+        // Renaming a canonical name also renames its spellings
+        assert_eq!(normalize_tool_name_with("cursor-ide", &aliases), "Cursor");
+        assert_eq!(normalize_tool_name_with("claude", &aliases), "claude");
+    }
+}
@@ -2,7 +2,7 @@ use crate::authorship::attribution_tracker::{
     Attribution, LineAttribution, line_attributions_to_attributions,
 };
 use crate::authorship::authorship_log::{LineRange, PromptRecord};
-use crate::authorship::working_log::CheckpointKind;
+use crate::authorship::working_log::{CheckpointKind, TOOL_VERSION_METADATA_KEY};
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
 use crate::error::GitAiError;
 use crate::git::repository::Repository;
@@ -27,11 +27,15 @@ pub struct VirtualAttributions {
 
 impl VirtualAttributions {
     /// Create a new VirtualAttributions for the given base commit with initial pathspecs
+    ///
+    /// `file_contents` can supply the base commit's content for some or all pathspecs when
+    /// the caller has already read those blobs; files not in it are read from the commit.
     pub async fn new_for_base_commit(
         repo: Repository,
         base_commit: String,
         pathspecs: &[String],
         blame_start_commit: Option<String>,
+        file_contents: Option<&HashMap<String, String>>,
     ) -> Result<Self, GitAiError> {
         let ts = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -50,7 +54,9 @@ impl VirtualAttributions {
 
         // Process all pathspecs concurrently
         if !pathspecs.is_empty() {
-            virtual_attrs.add_pathspecs_concurrent(pathspecs).await?;
+            virtual_attrs
+                .add_pathspecs_concurrent(pathspecs, file_contents)
+                .await?;
         }
 
         // After running blame, discover and load any missing prompts from blamed commits
@@ -173,11 +179,17 @@ impl VirtualAttributions {
     /// Add a single pathspec to the virtual attributions
     #[allow(dead_code)]
     pub async fn add_pathspec(&mut self, pathspec: &str) -> Result<(), GitAiError> {
-        self.add_pathspecs_concurrent(&[pathspec.to_string()]).await
+        self.add_pathspecs_concurrent(&[pathspec.to_string()], None)
+            .await
     }
 
-    /// Add multiple pathspecs concurrently
-    async fn add_pathspecs_concurrent(&mut self, pathspecs: &[String]) -> Result<(), GitAiError> {
+    /// Add multiple pathspecs concurrently, taking file content from `file_contents` where
+    /// present instead of reading it from the base commit
+    async fn add_pathspecs_concurrent(
+        &mut self,
+        pathspecs: &[String],
+        file_contents: Option<&HashMap<String, String>>,
+    ) -> Result<(), GitAiError> {
         const MAX_CONCURRENT: usize = 30;
 
         let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
@@ -189,6 +201,7 @@ impl VirtualAttributions {
             let base_commit = self.base_commit.clone();
             let ts = self.ts;
             let blame_start_commit = self.blame_start_commit.clone();
+            let file_content = file_contents.and_then(|contents| contents.get(&pathspec).cloned());
             let semaphore = Arc::clone(&semaphore);
 
             let task = smol::spawn(async move {
@@ -203,6 +216,7 @@ impl VirtualAttributions {
                         &pathspec,
                         ts,
                         blame_start_commit,
+                        file_content,
                     )
                 })
                 .await
@@ -364,6 +378,11 @@ impl VirtualAttributions {
                     accepted_lines: 0,
                     overriden_lines: 0,
                     messages_url: None,
+                    tool_version: checkpoint
+                        .agent_metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.get(TOOL_VERSION_METADATA_KEY))
+                        .cloned(),
                 };
 
                 prompts
@@ -439,6 +458,7 @@ impl VirtualAttributions {
             base_commit.clone(),
             pathspecs,
             blame_start_commit,
+            None,
         )
         .await?;
 
@@ -454,7 +474,8 @@ impl VirtualAttributions {
         // Step 3: Merge blame and checkpoint attributions
         // Checkpoint attributions should override blame attributions for overlapping lines
         let final_state = checkpoint_va.file_contents.clone();
-        let merged_va = merge_attributions_favoring_first(checkpoint_va, blame_va, final_state)?;
+        let merged_va =
+            merge_attributions_favoring_first(checkpoint_va, blame_va, final_state, None)?;
 
         Ok(merged_va)
     }
@@ -1301,11 +1322,74 @@ impl VirtualAttributions {
         }
     }
 }
-/// Merge two VirtualAttributions, favoring the primary for overlaps
+/// A line of the final state that both sides of a merge attributed, to different authors.
+/// The merge keeps the primary's author.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributionConflict {
+    pub file_path: String,
+    /// 1-indexed line in the final state
+    pub line: u32,
+    /// Author id ("human" or a prompt id) the primary gave the line
+    pub primary_author: String,
+    /// Author id the secondary gave the line, which the merge dropped
+    pub secondary_author: String,
+}
+
+/// Lines both sides attribute, to different authors. Each side is its attributions
+/// transformed to the final state with insertions kept, or `None` when it lacks the file.
+/// Lines a side inserted are skipped, and lines it had but didn't attribute count as human,
+/// since blame-based VirtualAttributions only store AI lines.
+fn find_attribution_conflicts(
+    file_path: &str,
+    primary: Option<&[Attribution]>,
+    secondary: Option<&[Attribution]>,
+    final_content: &str,
+) -> Vec<AttributionConflict> {
+    use crate::authorship::attribution_tracker::line_authors;
+
+    let (Some(primary), Some(secondary)) = (primary, secondary) else {
+        return Vec::new();
+    };
+
+    let side_line_authors = |attributions: &[Attribution]| -> Vec<Option<String>> {
+        let (inserted, kept): (Vec<Attribution>, Vec<Attribution>) = attributions
+            .iter()
+            .cloned()
+            .partition(|attr| attr.author_id == NEW_CONTENT_AUTHOR);
+        line_authors(&kept, final_content)
+            .into_iter()
+            .zip(line_authors(&inserted, final_content))
+            .map(|(author, insertion)| match insertion {
+                Some(_) => None,
+                None => Some(author.unwrap_or_else(|| CheckpointKind::Human.to_str())),
+            })
+            .collect()
+    };
+
+    side_line_authors(primary)
+        .into_iter()
+        .zip(side_line_authors(secondary))
+        .enumerate()
+        .filter_map(|(idx, authors)| match authors {
+            (Some(primary_author), Some(secondary_author)) => Some(AttributionConflict {
+                file_path: file_path.to_string(),
+                line: idx as u32 + 1,
+                primary_author,
+                secondary_author,
+            }),
+            _ => None,
+        })
+        .filter(|conflict| conflict.primary_author != conflict.secondary_author)
+        .collect()
+}
+
+/// Merge two VirtualAttributions, favoring the primary for overlaps.
+/// With `conflicts`, lines where the two disagreed are appended to it.
 pub fn merge_attributions_favoring_first(
     primary: VirtualAttributions,
     secondary: VirtualAttributions,
     final_state: HashMap<String, String>,
+    mut conflicts: Option<&mut Vec<AttributionConflict>>,
 ) -> Result<VirtualAttributions, GitAiError> {
     use crate::authorship::attribution_tracker::AttributionTracker;
 
@@ -1349,26 +1433,43 @@ pub fn merge_attributions_favoring_first(
         let secondary_content = secondary.get_file_content(&file_path);
 
         // Transform both to final state
-        let transformed_primary =
-            if let (Some(attrs), Some(content)) = (primary_attrs, primary_content) {
-                transform_attributions_to_final(&tracker, content, attrs, final_content, ts)?
-            } else {
-                Vec::new()
-            };
+        let transformed_primary = match (primary_attrs, primary_content) {
+            (Some(attrs), Some(content)) => Some(transform_attributions_to_final(
+                &tracker,
+                content,
+                attrs,
+                final_content,
+                ts,
+            )?),
+            _ => None,
+        };
 
-        let transformed_secondary =
-            if let (Some(attrs), Some(content)) = (secondary_attrs, secondary_content) {
-                transform_attributions_to_final(&tracker, content, attrs, final_content, ts)?
-            } else {
-                Vec::new()
-            };
+        let transformed_secondary = match (secondary_attrs, secondary_content) {
+            (Some(attrs), Some(content)) => Some(transform_attributions_to_final(
+                &tracker,
+                content,
+                attrs,
+                final_content,
+                ts,
+            )?),
+            _ => None,
+        };
+
+        if let Some(conflicts) = conflicts.as_deref_mut() {
+            conflicts.extend(find_attribution_conflicts(
+                &file_path,
+                transformed_primary.as_deref(),
+                transformed_secondary.as_deref(),
+                final_content,
+            ));
+        }
+
+        let transformed_primary = without_insertions(transformed_primary);
+        let transformed_secondary = without_insertions(transformed_secondary);
 
         // Merge: primary wins overlaps, secondary fills gaps
-        let merged_char_attrs = merge_char_attributions(
-            &transformed_primary,
-            &transformed_secondary,
-            final_content,
-        );
+        let merged_char_attrs =
+            merge_char_attributions(&transformed_primary, &transformed_secondary, final_content);
 
         // Convert to line attributions
         let merged_line_attrs =
@@ -1484,7 +1585,8 @@ pub fn restore_stashed_va(
     };
 
     // Merge VAs, favoring the stashed VA (our original work)
-    let merged_va = match merge_attributions_favoring_first(stashed_va, new_va, working_files) {
+    let merge_result = merge_attributions_favoring_first(stashed_va, new_va, working_files, None);
+    let merged_va = match merge_result {
         Ok(va) => va,
         Err(e) => {
             debug_log(&format!("Failed to merge VirtualAttributions: {}", e));
@@ -1522,7 +1624,11 @@ pub fn restore_stashed_va(
     }
 }
 
-/// Transform attributions from old content to new content
+/// Author given to text inserted between the old and new content
+const NEW_CONTENT_AUTHOR: &str = "__DUMMY__";
+
+/// Transform attributions from old content to new content. Inserted text is attributed
+/// to `NEW_CONTENT_AUTHOR`; use `without_insertions` to discard it.
 fn transform_attributions_to_final(
     tracker: &crate::authorship::attribution_tracker::AttributionTracker,
     old_content: &str,
@@ -1530,24 +1636,22 @@ fn transform_attributions_to_final(
     new_content: &str,
     ts: u128,
 ) -> Result<Vec<Attribution>, GitAiError> {
-    // Use a dummy author for new insertions (we'll discard them anyway)
-    let dummy_author = "__DUMMY__";
-
-    let transformed = tracker.update_attributions(
+    tracker.update_attributions(
         old_content,
         new_content,
         old_attributions,
-        dummy_author,
+        NEW_CONTENT_AUTHOR,
         ts,
-    )?;
+    )
+}
 
-    // Filter out dummy attributions (new insertions)
-    let filtered: Vec<Attribution> = transformed
+/// Drop the attributions `transform_attributions_to_final` gave to inserted text
+fn without_insertions(transformed: Option<Vec<Attribution>>) -> Vec<Attribution> {
+    transformed
+        .unwrap_or_default()
         .into_iter()
-        .filter(|attr| attr.author_id != dummy_author)
-        .collect();
-
-    Ok(filtered)
+        .filter(|attr| attr.author_id != NEW_CONTENT_AUTHOR)
+        .collect()
 }
 
 /// Merge character-level attributions, with primary winning overlaps
@@ -1652,6 +1756,7 @@ fn compute_attributions_for_file(
     file_path: &str,
     ts: u128,
     blame_start_commit: Option<String>,
+    file_content: Option<String>,
 ) -> Result<Option<(String, String, Vec<Attribution>, Vec<LineAttribution>)>, GitAiError> {
     // Set up blame options
     let mut ai_blame_opts = GitAiBlameOptions::default();
@@ -1683,8 +1788,11 @@ fn compute_attributions_for_file(
             }
 
             // Get the file content at this commit to convert to character attributions
-            // We need to read the file content that blame operated on
-            let file_content = get_file_content_at_commit(repo, base_commit, file_path)?;
+            // We need the file content that blame operated on, unless the caller supplied it
+            let file_content = match file_content {
+                Some(content) => content,
+                None => get_file_content_at_commit(repo, base_commit, file_path)?,
+            };
 
             // Convert line attributions to character attributions
             let char_attributions =
@@ -1770,6 +1878,7 @@ mod tests {
                 commit_sha.clone(),
                 &["test_file.rs".to_string()],
                 None,
+                None,
             )
             .await
         })
@@ -1794,4 +1903,89 @@ mod tests {
 
         assert!(!virtual_attributions.files().is_empty());
     }
+
+    #[test]
+    fn test_new_for_base_commit_uses_supplied_file_contents() {
+        let repo = TmpRepo::new().unwrap();
+        repo.write_file("a.rs", "one\ntwo\n", true).unwrap();
+        repo.trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        repo.commit_with_message("AI commit").unwrap();
+        let commit_sha = repo.head_commit_sha().unwrap();
+        let pathspecs = ["a.rs".to_string()];
+
+        let from_commit = smol::block_on(VirtualAttributions::new_for_base_commit(
+            repo.gitai_repo().clone(),
+            commit_sha.clone(),
+            &pathspecs,
+            None,
+            None,
+        ))
+        .unwrap();
+
+        let supplied: HashMap<String, String> =
+            HashMap::from([("a.rs".to_string(), "one\ntwo\n".to_string())]);
+        let from_supplied = smol::block_on(VirtualAttributions::new_for_base_commit(
+            repo.gitai_repo().clone(),
+            commit_sha,
+            &pathspecs,
+            None,
+            Some(&supplied),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            from_supplied.get_file_content("a.rs"),
+            from_commit.get_file_content("a.rs")
+        );
+        assert!(from_supplied.get_line_attributions("a.rs").is_some());
+        assert_eq!(
+            from_supplied.get_line_attributions("a.rs"),
+            from_commit.get_line_attributions("a.rs")
+        );
+    }
+
+    #[test]
+    fn test_find_attribution_conflicts() {
+        let content = "a\nb\nc\nd\n";
+        let primary = vec![
+            Attribution::new(0, 2, "p1".to_string(), 1),
+            Attribution::new(4, 6, "p1".to_string(), 1),
+            Attribution::new(6, 8, NEW_CONTENT_AUTHOR.to_string(), 1),
+        ];
+        let secondary = vec![
+            Attribution::new(0, 2, "p1".to_string(), 1),
+            Attribution::new(2, 4, "p2".to_string(), 1),
+            Attribution::new(6, 8, "p2".to_string(), 1),
+        ];
+
+        // Line 1 agrees, line 4 is new on the primary side, so only lines 2 and 3 conflict
+        let conflicts = find_attribution_conflicts(
+            "f.rs",
+            Some(primary.as_slice()),
+            Some(secondary.as_slice()),
+            content,
+        );
+        assert_eq!(
+            conflicts,
+            vec![
+                AttributionConflict {
+                    file_path: "f.rs".to_string(),
+                    line: 2,
+                    primary_author: "human".to_string(),
+                    secondary_author: "p2".to_string(),
+                },
+                AttributionConflict {
+                    file_path: "f.rs".to_string(),
+                    line: 3,
+                    primary_author: "p1".to_string(),
+                    secondary_author: "human".to_string(),
+                },
+            ]
+        );
+
+        // A side without the file has no opinion
+        let one_sided = find_attribution_conflicts("f.rs", Some(primary.as_slice()), None, content);
+        assert!(one_sided.is_empty());
+    }
 }
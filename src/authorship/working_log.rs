@@ -8,6 +8,9 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 pub const CHECKPOINT_API_VERSION: &str = "checkpoint/1.0.0";
 
+/// `agent_metadata` key for the version of the AI tool that made a checkpoint, e.g. "0.45.2"
+pub const TOOL_VERSION_METADATA_KEY: &str = "tool_version";
+
 /// Represents a working log entry for a specific file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkingLogEntry {
@@ -0,0 +1,115 @@
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::range_authorship::create_authorship_log_for_commit;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::{notes_add, show_authorship_note};
+use crate::git::repository::Repository;
+
+pub fn handle_backfill(args: &[String]) {
+    let revision = match args {
+        [revision] if !revision.starts_with('-') => revision,
+        _ => {
+            eprintln!("Usage: git-ai backfill <commit>");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match backfill_authorship(&repo, revision) {
+        Ok((commit_sha, authorship_log)) => {
+            let ai_lines: usize = authorship_log
+                .attestations
+                .iter()
+                .flat_map(|attestation| &attestation.entries)
+                .flat_map(|entry| &entry.line_ranges)
+                .map(|range| range.expand().len())
+                .sum();
+            println!(
+                "Wrote authorship log for {}: {} AI lines from {} prompts",
+                commit_sha,
+                ai_lines,
+                authorship_log.metadata.prompts.len()
+            );
+        }
+        Err(e) => {
+            eprintln!("Backfill failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Reconstruct the authorship log of `revision` and write it as its note. Commits that
+/// already have a note are left alone, since the recorded log is more accurate than any
+/// reconstruction. Returns the commit sha and the log written.
+pub fn backfill_authorship(
+    repo: &Repository,
+    revision: &str,
+) -> Result<(String, AuthorshipLog), GitAiError> {
+    let commit_sha = repo.revparse_single(revision)?.peel_to_commit()?.id();
+    if show_authorship_note(repo, &commit_sha).is_some() {
+        return Err(GitAiError::Generic(format!(
+            "{} already has an authorship log",
+            commit_sha
+        )));
+    }
+
+    let authorship_log = create_authorship_log_for_commit(repo, &commit_sha)?;
+    let note = authorship_log
+        .serialize_to_string()
+        .map_err(|e| GitAiError::Generic(format!("Failed to serialize authorship log: {}", e)))?;
+    notes_add(repo, &commit_sha, &note)?;
+
+    Ok((commit_sha, authorship_log))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::get_reference_as_authorship_log_v3;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_backfill_writes_note_for_commit_without_one() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "one\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head = tmp_repo.get_head_commit_sha().unwrap();
+
+        // Drop the note, as if the commit had been made without git-ai
+        let repo = tmp_repo.gitai_repo();
+        let mut args = repo.global_args_for_exec();
+        args.extend(["notes", "--ref=ai", "remove", head.as_str()].map(str::to_string));
+        crate::git::repository::exec_git(&args).unwrap();
+        assert!(show_authorship_note(repo, &head).is_none());
+
+        let (commit_sha, authorship_log) = backfill_authorship(repo, "HEAD").unwrap();
+        assert_eq!(commit_sha, head);
+        assert_eq!(authorship_log.metadata.base_commit_sha, head);
+        // No prompt is left to resolve, so the added lines belong to the git author
+        assert!(authorship_log.attestations.is_empty());
+
+        let written = get_reference_as_authorship_log_v3(repo, &head).unwrap();
+        assert_eq!(written.metadata.base_commit_sha, head);
+
+        let err = backfill_authorship(repo, "HEAD").unwrap_err();
+        assert!(err.to_string().contains("already has an authorship log"));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::authorship::authorship_log::PromptRecord;
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::authorship_log_cache::AuthorshipLogCache;
+use crate::authorship::authorship_log_serialization::{AttributionProvenance, AuthorshipLog};
 use crate::authorship::working_log::CheckpointKind;
 use crate::error::GitAiError;
 use crate::git::refs::get_reference_as_authorship_log_v3;
@@ -12,7 +13,7 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::fs;
 use std::io::{self, IsTerminal, Write};
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
 //🐰🥚 @todo use actual date Git AI was installed in each repo
 pub static OLDEST_AI_BLAME_DATE: LazyLock<DateTime<FixedOffset>> = LazyLock::new(|| {
@@ -141,6 +142,14 @@ pub struct GitAiBlameOptions {
     // When true, a single git blame hunk may be split into multiple hunks
     // if different lines were authored by different humans working with AI
     pub split_hunks_by_ai_author: bool,
+
+    // Authorship logs shared with other blames in this process; each blame caches its own
+    // when None
+    pub authorship_log_cache: Option<Arc<AuthorshipLogCache>>,
+
+    // Resolve each blamed commit's attributions for the whole file in one pass, rather than
+    // line by line. Faster when many lines of the file are blamed.
+    pub batch_line_attribution: bool,
 }
 
 impl Default for GitAiBlameOptions {
@@ -185,6 +194,8 @@ impl Default for GitAiBlameOptions {
             json: false,
             mark_unknown: false,
             split_hunks_by_ai_author: true,
+            authorship_log_cache: None,
+            batch_line_attribution: false,
         }
     }
 }
@@ -195,6 +206,23 @@ impl Repository {
         file_path: &str,
         options: &GitAiBlameOptions,
     ) -> Result<(HashMap<u32, String>, HashMap<String, PromptRecord>), GitAiError> {
+        let (line_authors, prompt_records, _) = self.blame_with_provenance(file_path, options)?;
+        Ok((line_authors, prompt_records))
+    }
+
+    /// Like `blame`, also returning where each AI line's prompt was found
+    pub fn blame_with_provenance(
+        &self,
+        file_path: &str,
+        options: &GitAiBlameOptions,
+    ) -> Result<
+        (
+            HashMap<u32, String>,
+            HashMap<String, PromptRecord>,
+            HashMap<u32, AttributionProvenance>,
+        ),
+        GitAiError,
+    > {
         // Use repo root for file system operations
         let repo_root = self.workdir().or_else(|e| {
             Err(GitAiError::Generic(format!(
@@ -325,19 +353,24 @@ impl Repository {
 
         let lines: Vec<&str> = file_content.lines().collect();
 
-        // Determine the line ranges to process
-        let line_ranges = if options.line_ranges.is_empty() {
+        // Determine the line ranges to process. Like git blame, a range may run past the
+        // end of the file but must start inside it.
+        let line_ranges: Vec<(u32, u32)> = if options.line_ranges.is_empty() {
             vec![(1, total_lines)]
         } else {
-            options.line_ranges.clone()
+            options
+                .line_ranges
+                .iter()
+                .map(|(start, end)| (*start, (*end).min(total_lines)))
+                .collect()
         };
 
         // Validate line ranges
         for (start, end) in &line_ranges {
-            if *start == 0 || *end == 0 || start > end || *end > total_lines {
+            if *start == 0 || *end == 0 || start > end {
                 return Err(GitAiError::Generic(format!(
-                    "Invalid line range: {}:{}. File has {} lines",
-                    start, end, total_lines
+                    "Invalid line range: {}:{}. File {} has only {} lines",
+                    start, end, relative_file_path, total_lines
                 )));
             }
         }
@@ -350,11 +383,11 @@ impl Repository {
         }
 
         // Step 2: Overlay AI authorship information
-        let (line_authors, prompt_records, authorship_logs, prompt_commits) =
+        let (line_authors, prompt_records, line_provenance, authorship_logs, prompt_commits) =
             overlay_ai_authorship(self, &all_blame_hunks, &relative_file_path, &options)?;
 
         if options.no_output {
-            return Ok((line_authors, prompt_records));
+            return Ok((line_authors, prompt_records, line_provenance));
         }
 
         // Output based on format
@@ -395,7 +428,7 @@ impl Repository {
             )?;
         }
 
-        Ok((line_authors, prompt_records))
+        Ok((line_authors, prompt_records, line_provenance))
     }
 
     pub fn blame_hunks(
@@ -716,10 +749,7 @@ impl Repository {
             {
                 cached.clone()
             } else {
-                let authorship = match get_reference_as_authorship_log_v3(self, &hunk.commit_sha) {
-                    Ok(v3_log) => Some(v3_log),
-                    Err(_) => None, // No AI authorship data for this commit
-                };
+                let authorship = load_authorship_log(self, &hunk.commit_sha, options);
                 commit_authorship_cache.insert(hunk.commit_sha.clone(), authorship.clone());
                 authorship
             };
@@ -733,18 +763,19 @@ impl Repository {
                 for i in 0..num_lines {
                     let orig_line_num = hunk.orig_range.0 + i;
 
-                    let human_author = if let Some((_author, _prompt_hash, Some(prompt_record))) =
-                        authorship_log.get_line_attribution(
-                            self,
-                            file_path,
-                            orig_line_num,
-                            &mut foreign_prompts_cache,
-                        )
-                    {
-                        prompt_record.human_author.clone()
-                    } else {
-                        None
-                    };
+                    let human_author =
+                        if let Some((_author, _prompt_hash, Some(prompt_record), _)) =
+                            authorship_log.get_line_attribution(
+                                self,
+                                file_path,
+                                orig_line_num,
+                                &mut foreign_prompts_cache,
+                            )
+                        {
+                            prompt_record.human_author.clone()
+                        } else {
+                            None
+                        };
                     line_authors.push(human_author);
                 }
 
@@ -801,6 +832,19 @@ impl Repository {
     }
 }
 
+/// The authorship log of a blamed commit, through the shared cache when the options carry
+/// one. None when the commit has no AI authorship data.
+fn load_authorship_log(
+    repo: &Repository,
+    commit_sha: &str,
+    options: &GitAiBlameOptions,
+) -> Option<AuthorshipLog> {
+    match &options.authorship_log_cache {
+        Some(cache) => cache.get_or_load(repo, commit_sha),
+        None => get_reference_as_authorship_log_v3(repo, commit_sha).ok(),
+    }
+}
+
 fn overlay_ai_authorship(
     repo: &Repository,
     blame_hunks: &[BlameHunk],
@@ -810,6 +854,7 @@ fn overlay_ai_authorship(
     (
         HashMap<u32, String>,
         HashMap<String, PromptRecord>,
+        HashMap<u32, AttributionProvenance>,
         Vec<AuthorshipLog>,
         HashMap<String, Vec<String>>, // prompt_hash -> commit_shas
     ),
@@ -817,6 +862,7 @@ fn overlay_ai_authorship(
 > {
     let mut line_authors: HashMap<u32, String> = HashMap::new();
     let mut prompt_records: HashMap<String, PromptRecord> = HashMap::new();
+    let mut line_provenance: HashMap<u32, AttributionProvenance> = HashMap::new();
     // Track which commits contain each prompt hash
     let mut prompt_commits: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
 
@@ -824,6 +870,8 @@ fn overlay_ai_authorship(
     let mut commit_authorship_cache: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
     // Cache for foreign prompts to avoid repeated grepping
     let mut foreign_prompts_cache: HashMap<String, Option<PromptRecord>> = HashMap::new();
+    // Each commit's attributions for the whole file, with batch_line_attribution
+    let mut line_attribution_maps = HashMap::new();
 
     for hunk in blame_hunks {
         // Check if we've already looked up this commit's authorship
@@ -831,10 +879,7 @@ fn overlay_ai_authorship(
             cached.clone()
         } else {
             // Try to get authorship log for this commit
-            let authorship = match get_reference_as_authorship_log_v3(repo, &hunk.commit_sha) {
-                Ok(v3_log) => Some(v3_log),
-                Err(_) => None, // No AI authorship data for this commit
-            };
+            let authorship = load_authorship_log(repo, &hunk.commit_sha, options);
             commit_authorship_cache.insert(hunk.commit_sha.clone(), authorship.clone());
             authorship
         };
@@ -844,16 +889,34 @@ fn overlay_ai_authorship(
             // Check each line in this hunk for AI authorship using compact schema
             // IMPORTANT: Use the original line numbers from the commit, not the current line numbers
             let num_lines = hunk.range.1 - hunk.range.0 + 1;
+            let line_attribution_map = if options.batch_line_attribution {
+                let map = line_attribution_maps
+                    .entry(hunk.commit_sha.clone())
+                    .or_insert_with(|| {
+                        authorship_log.line_attribution_map(
+                            repo,
+                            file_path,
+                            &mut foreign_prompts_cache,
+                        )
+                    });
+                Some(&*map)
+            } else {
+                None
+            };
             for i in 0..num_lines {
                 let current_line_num = hunk.range.0 + i;
                 let orig_line_num = hunk.orig_range.0 + i;
 
-                if let Some((author, prompt_hash, prompt)) = authorship_log.get_line_attribution(
-                    repo,
-                    file_path,
-                    orig_line_num,
-                    &mut foreign_prompts_cache,
-                ) {
+                let attribution = match line_attribution_map {
+                    Some(map) => map.get(&orig_line_num).cloned(),
+                    None => authorship_log.get_line_attribution(
+                        repo,
+                        file_path,
+                        orig_line_num,
+                        &mut foreign_prompts_cache,
+                    ),
+                };
+                if let Some((author, prompt_hash, prompt, provenance)) = attribution {
                     // If this line is AI-assisted, display the tool name; otherwise the human username
                     if let Some(prompt_record) = prompt {
                         let prompt_hash = prompt_hash.unwrap();
@@ -869,6 +932,7 @@ fn overlay_ai_authorship(
                                 .insert(current_line_num, prompt_record.agent_id.tool.clone());
                         }
                         prompt_records.insert(prompt_hash, prompt_record.clone());
+                        line_provenance.insert(current_line_num, provenance);
                     } else {
                         // Has authorship log but line not AI = human-authored
                         if options.return_human_authors_as_human {
@@ -924,6 +988,7 @@ fn overlay_ai_authorship(
     Ok((
         line_authors,
         prompt_records,
+        line_provenance,
         authorship_logs,
         prompt_commits_vec,
     ))
@@ -1531,17 +1596,14 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
                 if i + 1 >= args.len() {
                     return Err(GitAiError::Generic("Missing argument for -L".to_string()));
                 }
-                let range_str = &args[i + 1];
-                if let Some((start, end)) = parse_line_range(range_str) {
-                    options.line_ranges.push((start, end));
-                } else {
-                    return Err(GitAiError::Generic(format!(
-                        "Invalid line range: {}",
-                        range_str
-                    )));
-                }
+                let range = parse_line_range_arg(&args[i + 1])?;
+                options.line_ranges.push(range);
                 i += 2;
             }
+            arg if arg.starts_with("-L") => {
+                options.line_ranges.push(parse_line_range_arg(&arg[2..])?);
+                i += 1;
+            }
 
             // Output format options
             "--porcelain" => {
@@ -1804,17 +1866,39 @@ pub fn parse_blame_args(args: &[String]) -> Result<(String, GitAiBlameOptions),
     Ok((file_path, options))
 }
 
-fn parse_line_range(range_str: &str) -> Option<(u32, u32)> {
-    if let Some(dash_pos) = range_str.find(',') {
-        let start_str = &range_str[..dash_pos];
-        let end_str = &range_str[dash_pos + 1..];
+fn parse_line_range_arg(range_str: &str) -> Result<(u32, u32), GitAiError> {
+    parse_line_range(range_str)
+        .ok_or_else(|| GitAiError::Generic(format!("Invalid line range: {}", range_str)))
+}
 
-        if let (Ok(start), Ok(end)) = (start_str.parse::<u32>(), end_str.parse::<u32>()) {
-            return Some((start, end));
-        }
-    } else if let Ok(line) = range_str.parse::<u32>() {
-        return Some((line, line));
+/// Parse a `-L` range the way git blame does: `<start>,<end>` (in either order),
+/// `<start>,+<count>`, `<start>,-<count>` (the lines ending at `<start>`), `<start>` or
+/// `<start>,` (to the end of the file) and `,<end>` (from the first line). The end may
+/// be past the last line; blame clamps it once the file's length is known.
+fn parse_line_range(range_str: &str) -> Option<(u32, u32)> {
+    if range_str.is_empty() {
+        return None;
     }
 
-    None
+    let (start_str, end_str) = range_str.split_once(',').unwrap_or((range_str, ""));
+    let start = if start_str.is_empty() {
+        1
+    } else {
+        start_str.parse::<u32>().ok()?
+    };
+
+    let range = if end_str.is_empty() {
+        (start, u32::MAX)
+    } else if let Some(count) = end_str.strip_prefix('+') {
+        let count = count.parse::<u32>().ok().filter(|count| *count > 0)?;
+        (start, start.saturating_add(count - 1))
+    } else if let Some(count) = end_str.strip_prefix('-') {
+        let count = count.parse::<u32>().ok().filter(|count| *count > 0)?;
+        (start.saturating_sub(count - 1).max(1), start)
+    } else {
+        let end = end_str.parse::<u32>().ok()?;
+        (start.min(end), start.max(end))
+    };
+
+    Some(range)
 }
@@ -1,7 +1,7 @@
 use crate::{
     authorship::{
         transcript::{AiTranscript, Message},
-        working_log::{AgentId, CheckpointKind},
+        working_log::{AgentId, CheckpointKind, TOOL_VERSION_METADATA_KEY},
     },
     error::GitAiError,
     observability::log_error,
@@ -712,6 +712,15 @@ impl AgentCheckpointPreset for CursorPreset {
             .map(|s| s.to_string())
             .unwrap_or_else(|| "unknown".to_string());
 
+        // Cursor reports its own version with every hook; kept so attributions can tell
+        // Cursor releases apart
+        let agent_metadata = hook_data
+            .get("cursor_version")
+            .and_then(|v| v.as_str())
+            .map(|version| {
+                HashMap::from([(TOOL_VERSION_METADATA_KEY.to_string(), version.to_string())])
+            });
+
         // Validate hook_event_name
         if hook_event_name != "beforeSubmitPrompt" && hook_event_name != "afterFileEdit" {
             return Err(GitAiError::PresetError(format!(
@@ -821,7 +830,7 @@ impl AgentCheckpointPreset for CursorPreset {
 
         Ok(AgentRunResult {
             agent_id,
-            agent_metadata: None,
+            agent_metadata,
             checkpoint_kind: CheckpointKind::AiAgent,
             transcript: Some(transcript),
             repo_working_dir: Some(repo_working_dir),
@@ -1617,3 +1626,84 @@ impl AgentCheckpointPreset for AiTabPreset {
         })
     }
 }
+
+// Windsurf (Cascade hooks) to checkpoint preset
+pub struct WindsurfPreset;
+
+#[derive(Debug, Deserialize)]
+struct WindsurfHookInput {
+    agent_action_name: String,
+    trajectory_id: Option<String>,
+    execution_id: Option<String>,
+    tool_info: Option<WindsurfToolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WindsurfToolInfo {
+    file_path: Option<String>,
+}
+
+impl AgentCheckpointPreset for WindsurfPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Windsurf preset".to_string())
+        })?;
+
+        let hook_input: WindsurfHookInput = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        if hook_input.agent_action_name != "pre_write_code"
+            && hook_input.agent_action_name != "post_write_code"
+        {
+            return Err(GitAiError::PresetError(format!(
+                "Invalid agent_action_name: {}. Expected 'pre_write_code' or 'post_write_code'",
+                hook_input.agent_action_name
+            )));
+        }
+
+        // One trajectory is one Cascade conversation
+        let id = hook_input
+            .trajectory_id
+            .or(hook_input.execution_id)
+            .ok_or_else(|| {
+                GitAiError::PresetError("trajectory_id not found in hook_input".to_string())
+            })?;
+
+        let agent_id = AgentId {
+            tool: "windsurf".to_string(),
+            id,
+            // Cascade hooks don't report the model in use
+            model: "unknown".to_string(),
+        };
+
+        let file_path_as_vec = hook_input
+            .tool_info
+            .and_then(|info| info.file_path)
+            .map(|path| vec![path]);
+
+        if hook_input.agent_action_name == "pre_write_code" {
+            // Early return for human checkpoint
+            return Ok(AgentRunResult {
+                agent_id,
+                agent_metadata: None,
+                checkpoint_kind: CheckpointKind::Human,
+                transcript: None,
+                repo_working_dir: None,
+                edited_filepaths: None,
+                will_edit_filepaths: file_path_as_vec,
+                dirty_files: None,
+            });
+        }
+
+        Ok(AgentRunResult {
+            agent_id,
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: None,
+            repo_working_dir: None,
+            edited_filepaths: file_path_as_vec,
+            will_edit_filepaths: None,
+            dirty_files: None,
+        })
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde_json::Value;
 use dirs;
 
@@ -107,6 +109,7 @@ fn print_config_help() {
     eprintln!("  feature_flags                Feature flags (object)");
     eprintln!("  api_key                      API key for X-API-Key header");
     eprintln!("  prompt_storage               Prompt storage mode (default/notes/local)");
+    eprintln!("  tool_aliases                 Display names for AI tool identifiers (object)");
     eprintln!("");
     eprintln!("Repository Patterns:");
     eprintln!("  For exclude/allow/exclude_prompts_in_repositories, you can provide:");
@@ -122,6 +125,7 @@ fn print_config_help() {
     eprintln!("  git-ai config --add exclude_repositories \"temp/*\"");
     eprintln!("  git-ai config --add allow_repositories ~/projects/my-repo");
     eprintln!("  git-ai config --add feature_flags.my_flag true");
+    eprintln!("  git-ai config set tool_aliases.acme-bot \"Acme Assistant\"");
     eprintln!("  git-ai config unset exclude_repositories");
     eprintln!("");
     std::process::exit(0);
@@ -294,6 +298,13 @@ fn show_all_config() -> Result<(), String> {
         effective_config.insert("api_key".to_string(), Value::String(masked));
     }
 
+    if !runtime_config.tool_aliases().is_empty() {
+        effective_config.insert(
+            "tool_aliases".to_string(),
+            serde_json::to_value(runtime_config.tool_aliases()).unwrap(),
+        );
+    }
+
     let json = serde_json::to_string_pretty(&effective_config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
@@ -356,6 +367,7 @@ fn get_config_value(key: &str) -> Result<(), String> {
                 }
             }
             "prompt_storage" => Value::String(runtime_config.prompt_storage().to_string()),
+            "tool_aliases" => serde_json::to_value(runtime_config.tool_aliases()).unwrap(),
             _ => return Err(format!("Unknown config key: {}", key)),
         };
 
@@ -384,7 +396,18 @@ fn get_config_value(key: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    Err(format!("Nested keys are only supported for feature_flags"))
+    if key_path[0] == "tool_aliases" && key_path.len() == 2 {
+        let name = runtime_config
+            .tool_aliases()
+            .get(&key_path[1].to_lowercase())
+            .ok_or_else(|| format!("Config key not found: {}", key))?;
+        println!("{}", Value::String(name.clone()));
+        return Ok(());
+    }
+
+    Err(format!(
+        "Nested keys are only supported for feature_flags and tool_aliases"
+    ))
 }
 
 fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String> {
@@ -485,6 +508,16 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 eprintln!("[prompt_storage]: {}", value);
             }
+            "tool_aliases" => {
+                if add_mode {
+                    return Err("Cannot use --add with tool_aliases at top level. Use dot notation: tool_aliases.<tool>".to_string());
+                }
+                let aliases: HashMap<String, String> = serde_json::from_str(value)
+                    .map_err(|e| format!("tool_aliases must be a JSON object of strings: {}", e))?;
+                file_config.tool_aliases = Some(aliases);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[tool_aliases]: {}", value);
+            }
             _ => return Err(format!("Unknown config key: {}", key)),
         }
 
@@ -541,7 +574,19 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
         return Ok(());
     }
 
-    Err(format!("Nested keys are only supported for feature_flags"))
+    if key_path[0] == "tool_aliases" && key_path.len() == 2 {
+        file_config
+            .tool_aliases
+            .get_or_insert_with(HashMap::new)
+            .insert(key_path[1].clone(), value.to_string());
+        crate::config::save_file_config(&file_config)?;
+        eprintln!("+ [{}]: {}", key_path[1], value);
+        return Ok(());
+    }
+
+    Err(format!(
+        "Nested keys are only supported for feature_flags and tool_aliases"
+    ))
 }
 
 fn unset_config_value(key: &str) -> Result<(), String> {
@@ -635,6 +680,15 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [prompt_storage]: {}", v);
                 }
             }
+            "tool_aliases" => {
+                let old_value = file_config.tool_aliases.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(aliases) = old_value {
+                    for (tool, name) in aliases {
+                        eprintln!("- [{}]: {}", tool, name);
+                    }
+                }
+            }
             _ => return Err(format!("Unknown config key: {}", key)),
         }
 
@@ -695,7 +749,33 @@ fn unset_config_value(key: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    Err(format!("Nested keys are only supported for feature_flags"))
+    if key_path[0] == "tool_aliases" && key_path.len() == 2 {
+        // Keys are matched case-insensitively, so remove any spelling of this tool
+        let tool = key_path[1].to_lowercase();
+        let mut aliases = file_config
+            .tool_aliases
+            .take()
+            .ok_or_else(|| format!("Config key not found: {}", key))?;
+        let removed: Vec<(String, String)> = aliases
+            .iter()
+            .filter(|(alias, _)| alias.to_lowercase() == tool)
+            .map(|(alias, name)| (alias.clone(), name.clone()))
+            .collect();
+        if removed.is_empty() {
+            return Err(format!("Config key not found: {}", key));
+        }
+        aliases.retain(|alias, _| alias.to_lowercase() != tool);
+        file_config.tool_aliases = Some(aliases);
+        crate::config::save_file_config(&file_config)?;
+        for (alias, name) in removed {
+            eprintln!("- [{}]: {}", alias, name);
+        }
+        return Ok(());
+    }
+
+    Err(format!(
+        "Nested keys are only supported for feature_flags and tool_aliases"
+    ))
 }
 
 fn parse_key_path(key: &str) -> Vec<String> {
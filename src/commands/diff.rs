@@ -1,44 +1,185 @@
 use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_cache::AuthorshipLogCache;
+use crate::authorship::authorship_log_serialization::AttributionProvenance;
+use crate::authorship::gitai_attributes::GitAiAttributes;
+use crate::authorship::tool_names::normalize_tool_name;
+use crate::authorship::transcript::Message;
+use crate::authorship::virtual_attribution::VirtualAttributions;
+use crate::authorship::working_log::CheckpointKind;
 use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
-use crate::git::repository::{Repository, exec_git};
+use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::repository::{Repository, exec_git, exec_git_streaming};
 use serde::{Deserialize, Serialize, Serializer};
-use std::collections::{BTreeMap, HashMap};
-use std::io::IsTerminal;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::io::{BufRead, IsTerminal, Write};
+use std::ops::Range;
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::Arc;
 
 // ============================================================================
 // Data Structures
 // ============================================================================
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DiffSpec {
     SingleCommit(String),      // SHA
     TwoCommit(String, String), // start..end
+    MergeBase(String, String), // base...head (diff from merge-base of base and head to head)
+    WorkingTree,               // No argument: uncommitted changes against HEAD
+    Cached,                    // --cached / --staged: staged changes against HEAD
+}
+
+/// Parses what `git-ai diff` takes as its revision: `<commit>`, `<commit>..<commit>`,
+/// `<commit>...<commit>` or two commits separated by whitespace (`A B`, the same as `A..B`).
+/// An empty string is the working tree and `--cached` / `--staged` is the index.
+impl FromStr for DiffSpec {
+    type Err = GitAiError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let revisions: Vec<&str> = spec.split_whitespace().collect();
+        let arg = match revisions.as_slice() {
+            [] => return Ok(DiffSpec::WorkingTree),
+            ["--cached" | "--staged"] => return Ok(DiffSpec::Cached),
+            [arg] => *arg,
+            // `git diff A B` is the same as `git diff A..B`
+            [start, end] if !start.contains("..") && !end.contains("..") => {
+                return Ok(DiffSpec::TwoCommit(start.to_string(), end.to_string()));
+            }
+            _ => {
+                return Err(GitAiError::Generic(
+                    "Expected one <commit>, a <commit>..<commit> range or two commits; put paths after --"
+                        .to_string(),
+                ));
+            }
+        };
+
+        // Check for symmetric range (base...head) before two-dot ranges, since "..." contains ".."
+        if arg.contains("...") {
+            if let Some((base, head)) = arg.split_once("...") {
+                if !base.is_empty()
+                    && !head.is_empty()
+                    && !head.starts_with('.')
+                    && !head.contains("..")
+                {
+                    return Ok(DiffSpec::MergeBase(base.to_string(), head.to_string()));
+                }
+            }
+            return Err(GitAiError::Generic(
+                "Invalid commit range format. Expected: <commit>...<commit>".to_string(),
+            ));
+        }
+
+        // Check for commit range (start..end)
+        if arg.contains("..") {
+            let parts: Vec<&str> = arg.split("..").collect();
+            if parts.len() == 2 && !parts[0].is_empty() && !parts[1].is_empty() {
+                return Ok(DiffSpec::TwoCommit(
+                    parts[0].to_string(),
+                    parts[1].to_string(),
+                ));
+            } else {
+                return Err(GitAiError::Generic(
+                    "Invalid commit range format. Expected: <commit>..<commit>".to_string(),
+                ));
+            }
+        }
+
+        // Single commit
+        Ok(DiffSpec::SingleCommit(arg.to_string()))
+    }
+}
+
+/// The inverse of [`DiffSpec::from_str`]: two commits are written as `A..B`
+impl fmt::Display for DiffSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffSpec::SingleCommit(commit) => write!(f, "{}", commit),
+            DiffSpec::TwoCommit(start, end) => write!(f, "{}..{}", start, end),
+            DiffSpec::MergeBase(base, head) => write!(f, "{}...{}", base, head),
+            DiffSpec::WorkingTree => Ok(()),
+            DiffSpec::Cached => write!(f, "--cached"),
+        }
+    }
+}
+
+/// Stand-in for the `to` revision when diffing against the working tree.
+/// Resolved revisions are always hex SHAs, so this can never collide with one.
+const WORKING_TREE: &str = "WORKING_TREE";
+
+/// Stand-in for the `to` revision when diffing against the index, like `WORKING_TREE`
+const INDEX: &str = "INDEX";
+
+/// Whether `to` is one of the stand-ins for uncommitted content rather than a commit
+fn is_uncommitted(to: &str) -> bool {
+    to == WORKING_TREE || to == INDEX
 }
 
+/// Pager used when none of `$GIT_PAGER`, `core.pager` or `$PAGER` is set
+const DEFAULT_PAGER: &str = "less -R";
+
+#[derive(Debug, Clone, Copy)]
 pub enum DiffFormat {
     Json,
     GitCompatibleTerminal,
+    Porcelain,
+    GithubAnnotations, // --output=github
+    Stat,              // --stat
+    NoDataOnly,        // --no-data-only
+    Html,              // --format=html
+    SummaryJson,       // --summary-json
 }
 
 #[derive(Debug)]
 pub struct DiffHunk {
-    pub file_path: String,
+    pub file_path: String,     // Path in the NEW tree (added lines)
+    pub old_file_path: String, // Path in the OLD tree (deleted lines), differs on rename/copy
+    pub copied: bool,          // The new file is a copy of old_file_path
     pub old_start: u32,
     pub old_count: u32,
     pub new_start: u32,
     pub new_count: u32,
-    pub deleted_lines: Vec<u32>, // Absolute line numbers in OLD file
-    pub added_lines: Vec<u32>,   // Absolute line numbers in NEW file
+    pub deleted: Range<u32>, // Absolute line numbers in OLD file
+    pub added: Range<u32>,   // Absolute line numbers in NEW file
+}
+
+impl DiffHunk {
+    /// Line numbers of the deleted lines in the old file. Hunks keep ranges rather than
+    /// line lists so that a rewrite of a huge generated file doesn't allocate per line.
+    pub fn deleted_lines(&self) -> Range<u32> {
+        self.deleted.clone()
+    }
+
+    /// Line numbers of the added lines in the new file
+    pub fn added_lines(&self) -> Range<u32> {
+        self.added.clone()
+    }
 }
 
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub struct DiffLineKey {
-    pub file: String,
+    /// Shared by the keys of a file's lines, so building them doesn't copy the path
+    pub file: Arc<str>,
     pub line: u32,
     pub side: LineSide,
 }
 
+/// Line number of a file-level `DiffLineKey`, for changes that have no lines (binary files)
+pub const FILE_LEVEL_LINE: u32 = 0;
+
+impl DiffLineKey {
+    /// Key for an attribution covering a whole file in the new tree
+    pub fn file_level(file: &str) -> Self {
+        DiffLineKey {
+            file: file.into(),
+            line: FILE_LEVEL_LINE,
+            side: LineSide::New,
+        }
+    }
+}
+
 /// JSON output format for git-ai diff --json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffJson {
@@ -48,6 +189,47 @@ pub struct DiffJson {
     pub prompts: BTreeMap<String, PromptRecord>,
 }
 
+/// JSON output format for git-ai diff --summary-json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffSummaryJson {
+    /// Attribution totals over the added lines
+    pub summary: DiffAttributionSummary,
+    /// One entry per changed line, left out with --no-lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<Vec<DiffLineJson>>,
+}
+
+/// Line counts by attribution for the added lines of a diff
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiffAttributionSummary {
+    pub ai_lines: usize,
+    pub human_lines: usize,
+    pub no_data_lines: usize,
+    /// AI-attributed added lines per tool
+    pub tools: BTreeMap<String, usize>,
+}
+
+/// A changed line in --summary-json output, with the same fields as a --porcelain record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffLineJson {
+    /// `old` for deleted lines, `new` for added ones
+    pub side: String,
+    pub file: String,
+    pub line: u32,
+    /// `ai`, `human` or `nodata`
+    pub kind: String,
+    /// The AI tool or human username, absent for `nodata`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// `direct`, `merged`, `carried-over-rename` or `carried-over-move` for `ai` lines, absent
+    /// otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+    /// Version of the AI tool for `ai` lines, when it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_version: Option<String>,
+}
+
 /// Per-file diff information in JSON output
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileDiffJson {
@@ -67,200 +249,1210 @@ pub enum LineSide {
     New, // For added lines
 }
 
-#[derive(Debug, Clone)]
+/// The prompts behind AI-attributed diff lines, collected alongside the attributions
+#[derive(Debug, Default)]
+pub struct LinePrompts {
+    /// Prompt hash for each line whose prompt has text
+    pub by_line: HashMap<DiffLineKey, String>,
+    /// The prompt's first user message, keyed by prompt hash
+    pub texts: HashMap<String, String>,
+    /// Where each blamed or carried-over AI line's prompt was found. AI lines missing here
+    /// are `Direct`.
+    pub provenance: HashMap<DiffLineKey, AttributionProvenance>,
+    /// Version of the AI tool behind each line whose prompt record has one
+    pub tool_versions: HashMap<DiffLineKey, String>,
+}
+
+impl LinePrompts {
+    fn record(&mut self, key: DiffLineKey, prompt_hash: &str, record: &PromptRecord) {
+        if let Some(version) = &record.tool_version {
+            self.tool_versions.insert(key.clone(), version.clone());
+        }
+        let Some(text) = record.messages.iter().find_map(|message| match message {
+            Message::User { text, .. } if !text.trim().is_empty() => Some(text.trim()),
+            _ => None,
+        }) else {
+            return;
+        };
+        self.texts
+            .entry(prompt_hash.to_string())
+            .or_insert_with(|| text.to_string());
+        self.by_line.insert(key, prompt_hash.to_string());
+    }
+
+    /// Add prompts collected separately; texts already known are kept, as in `record`
+    fn merge(&mut self, other: LinePrompts) {
+        for (prompt_hash, text) in other.texts {
+            self.texts.entry(prompt_hash).or_insert(text);
+        }
+        self.by_line.extend(other.by_line);
+        self.provenance.extend(other.provenance);
+        self.tool_versions.extend(other.tool_versions);
+    }
+
+    /// The provenance of an AI-attributed line
+    fn provenance_of(&self, key: &DiffLineKey) -> AttributionProvenance {
+        self.provenance
+            .get(key)
+            .copied()
+            .unwrap_or(AttributionProvenance::Direct)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Attribution {
     Ai(String),    // Tool name: "cursor", "claude", etc.
     Human(String), // Username
     NoData,        // No authorship data available
 }
 
-// ============================================================================
-// Main Entry Point
-// ============================================================================
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributionFilter {
+    OnlyAi,    // --only-ai
+    OnlyHuman, // --only-human
+}
 
-pub fn handle_diff(repo: &Repository, args: &[String]) -> Result<(), GitAiError> {
-    if args.is_empty() {
-        eprintln!("Error: diff requires a commit or commit range argument");
-        eprintln!("Usage: git-ai diff <commit>");
-        eprintln!("       git-ai diff <commit1>..<commit2>");
-        std::process::exit(1);
+/// When to emit ANSI colors (--color=always|never|auto)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    Always,
+    Never,
+    #[default]
+    Auto, // Color when stdout is a terminal and NO_COLOR is unset
+}
+
+impl ColorMode {
+    pub fn use_color(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
     }
+}
 
-    let (spec, format) = parse_diff_args(args)?;
-    let output = execute_diff(repo, spec, format)?;
-    print!("{}", output);
+/// Directory whose changes `git-ai diff` shows, with paths relative to it
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RelativeTo {
+    /// Follow the `diff.relative` config, like git
+    #[default]
+    Config,
+    /// The repository root (--no-relative)
+    Root,
+    /// The current directory (--relative)
+    Cwd,
+    /// A directory relative to the repository root (--relative=<path>)
+    Dir(String),
+}
 
-    Ok(())
+/// Options for `git-ai diff` parsed from its flags
+#[derive(Debug, Default, Clone)]
+pub struct AnnotatedDiffOptions {
+    /// Only show added/deleted lines with this kind of attribution
+    pub attribution_filter: Option<AttributionFilter>,
+    /// Number of context lines to display (-U<n> / --unified=<n>), git's default when None
+    pub context_lines: Option<u32>,
+    /// Pathspecs given after `--`, restricting the diff to matching files
+    pub pathspecs: Vec<String>,
+    /// Whether to colorize terminal output
+    pub color: ColorMode,
+    /// Show inline word-level changes (--word-diff) instead of whole lines
+    pub word_diff: bool,
+    /// Swap the two sides of the diff (-R / --reverse), like `git diff -R`
+    pub reverse: bool,
+    /// Print straight to stdout even on a terminal (--no-pager)
+    pub no_pager: bool,
+    /// Follow AI annotations with the prompt that produced the line (--show-prompt)
+    pub show_prompt: bool,
+    /// Only show changes under a directory, with paths relative to it (--relative)
+    pub relative: RelativeTo,
+    /// Also consider unmodified files as copy sources (--find-copies-harder)
+    pub find_copies_harder: bool,
+    /// Keep the attribution of lines moved within a file (--detect-moves)
+    pub detect_moves: bool,
+    /// Glyph for AI annotations (--annotate-ai=<str>, or git config `git-ai.ai-glyph`)
+    pub ai_glyph: Option<String>,
+    /// Glyph for human annotations (--annotate-human=<str>, or `git-ai.human-glyph`)
+    pub human_glyph: Option<String>,
+    /// Annotate as `[AI:tool]` / `[human:user]` instead of with emoji (--ascii)
+    pub ascii: bool,
+    /// Which parent a single commit is diffed against (`-m -<n>` / `--parent=<n>`).
+    /// Merge commits default to their first parent, like `git diff <merge>^ <merge>`.
+    pub parent: Option<u32>,
+    /// Leave the per-line array out of --summary-json output (--no-lines)
+    pub no_lines: bool,
+    /// Also annotate context lines, blamed on the new side (--context-attribution)
+    pub context_attribution: bool,
+    /// Write the output to this file instead of stdout (--output <file>)
+    pub output_path: Option<String>,
+    /// Name AI tools with their version where known, e.g. `cursor@0.45.2` (--show-version)
+    pub show_version: bool,
 }
 
-// ============================================================================
-// Argument Parsing
-// ============================================================================
+impl AnnotatedDiffOptions {
+    /// Whether a changed line with the given attribution should be printed
+    fn matches(&self, attribution: Option<&Attribution>) -> bool {
+        match self.attribution_filter {
+            None => true,
+            Some(AttributionFilter::OnlyAi) => matches!(attribution, Some(Attribution::Ai(_))),
+            Some(AttributionFilter::OnlyHuman) => {
+                matches!(attribution, Some(Attribution::Human(_)))
+            }
+        }
+    }
 
-pub fn parse_diff_args(args: &[String]) -> Result<(DiffSpec, DiffFormat), GitAiError> {
-    let arg = &args[0];
+    /// The glyphs to annotate with: `--annotate-*` over `--ascii` over the emoji defaults
+    fn glyphs(&self) -> AnnotationGlyphs {
+        let base = if self.ascii {
+            AnnotationGlyphs::ascii()
+        } else {
+            AnnotationGlyphs::default()
+        };
+        AnnotationGlyphs {
+            ai: self.ai_glyph.clone().unwrap_or(base.ai),
+            human: self.human_glyph.clone().unwrap_or(base.human),
+        }
+    }
+
+    /// Whether the diff is going to a terminal: stdout is one and no --output file was given.
+    /// The summary footer and the prompt notes' wrapping are only for interactive use.
+    fn writes_to_terminal(&self) -> bool {
+        self.output_path.is_none() && std::io::stdout().is_terminal()
+    }
+
+    /// Repository-relative directory, ending in `/`, that displayed paths are relative to.
+    /// Empty unless `relative` has been resolved to a subdirectory.
+    fn relative_dir(&self) -> &str {
+        match &self.relative {
+            RelativeTo::Dir(dir) => dir,
+            _ => "",
+        }
+    }
+}
+
+/// Marks that introduce the tool or user in AI and human annotations, e.g. `🤖cursor`.
+/// A glyph containing `{}` has the name substituted there instead, e.g. `[AI:{}]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotationGlyphs {
+    pub ai: String,
+    pub human: String,
+}
+
+impl Default for AnnotationGlyphs {
+    fn default() -> Self {
+        AnnotationGlyphs {
+            ai: "🤖".to_string(),
+            human: "👤".to_string(),
+        }
+    }
+}
+
+impl AnnotationGlyphs {
+    /// Plain-text glyphs for terminals without emoji and for screen readers (--ascii)
+    pub fn ascii() -> Self {
+        AnnotationGlyphs {
+            ai: "[AI:{}]".to_string(),
+            human: "[human:{}]".to_string(),
+        }
+    }
+
+    fn ai_label(&self, tool: &str) -> String {
+        apply_glyph(&self.ai, tool)
+    }
+
+    fn human_label(&self, username: &str) -> String {
+        apply_glyph(&self.human, username)
+    }
+}
 
-    let format = if args.iter().any(|arg| arg == "--json") {
-        DiffFormat::Json
+fn apply_glyph(glyph: &str, name: &str) -> String {
+    if glyph.contains("{}") {
+        glyph.replace("{}", name)
     } else {
-        DiffFormat::GitCompatibleTerminal
-    };
+        format!("{}{}", glyph, name)
+    }
+}
 
-    // Check for commit range (start..end)
-    if arg.contains("..") {
-        let parts: Vec<&str> = arg.split("..").collect();
-        if parts.len() == 2 && !parts[0].is_empty() && !parts[1].is_empty() {
-            return Ok((
-                DiffSpec::TwoCommit(parts[0].to_string(), parts[1].to_string()),
-                format,
-            ));
-        } else {
-            return Err(GitAiError::Generic(
-                "Invalid commit range format. Expected: <commit>..<commit>".to_string(),
-            ));
+/// Resolve `relative` to the repository-relative directory it names (see `relative_dir`)
+fn resolve_relative_dir(repo: &Repository, relative: &RelativeTo) -> String {
+    match relative {
+        RelativeTo::Root => String::new(),
+        RelativeTo::Cwd => cwd_relative_dir(repo),
+        RelativeTo::Dir(dir) => normalize_relative_dir(dir),
+        RelativeTo::Config => {
+            let enabled = repo
+                .config_get_str("diff.relative")
+                .ok()
+                .flatten()
+                .is_some_and(|value| {
+                    matches!(value.to_lowercase().as_str(), "true" | "1" | "yes" | "on")
+                });
+            if enabled {
+                cwd_relative_dir(repo)
+            } else {
+                String::new()
+            }
         }
     }
+}
+
+/// The current directory relative to the repository root, or empty outside of it
+fn cwd_relative_dir(repo: &Repository) -> String {
+    let Ok(cwd) = std::env::current_dir().and_then(|dir| dir.canonicalize()) else {
+        return String::new();
+    };
+    match cwd.strip_prefix(repo.canonical_workdir()) {
+        Ok(relative) => normalize_relative_dir(&relative.to_string_lossy().replace('\\', "/")),
+        Err(_) => String::new(),
+    }
+}
 
-    // Single commit
-    Ok((DiffSpec::SingleCommit(arg.to_string()), format))
+/// `src`, `./src/` and `src/` all become `src/`; the root becomes an empty string
+fn normalize_relative_dir(dir: &str) -> String {
+    let dir = dir.trim_start_matches("./").trim_end_matches('/');
+    if dir.is_empty() || dir == "." {
+        String::new()
+    } else {
+        format!("{}/", dir)
+    }
 }
 
 // ============================================================================
-// Core Execution Logic
+// Main Entry Point
 // ============================================================================
 
-pub fn execute_diff(
-    repo: &Repository,
-    spec: DiffSpec,
-    format: DiffFormat,
-) -> Result<String, GitAiError> {
-    // Resolve commits to get from/to SHAs
-    let (from_commit, to_commit) = match spec {
-        DiffSpec::TwoCommit(start, end) => {
-            // Resolve both commits
-            let from = resolve_commit(repo, &start)?;
-            let to = resolve_commit(repo, &end)?;
-            (from, to)
+pub fn handle_diff(repo: &Repository, args: &[String]) -> Result<(), GitAiError> {
+    let (spec, format) = parse_diff_args(args)?;
+    let mut options = parse_annotated_diff_options(args)?;
+    // Whether stdout is a terminal says nothing about a file, so only color it on request
+    if options.output_path.is_some() && options.color != ColorMode::Always {
+        options.color = ColorMode::Never;
+    }
+    if args
+        .iter()
+        .take_while(|arg| arg.as_str() != "--")
+        .any(|arg| arg == "--stdin")
+    {
+        if spec != DiffSpec::WorkingTree {
+            return Err(GitAiError::Generic(
+                "--stdin reads the commits to diff from standard input and doesn't take a revision"
+                    .to_string(),
+            ));
         }
-        DiffSpec::SingleCommit(commit) => {
-            // Resolve the commit and its parent
-            let to = resolve_commit(repo, &commit)?;
-            let from = resolve_parent(repo, &to)?;
-            (from, to)
+        let stdout = std::io::stdout();
+        let mut out: Box<dyn Write> = match &options.output_path {
+            Some(path) => Box::new(std::io::BufWriter::new(create_output_file(path)?)),
+            None => Box::new(stdout.lock()),
+        };
+        return execute_diff_batch(repo, std::io::stdin().lock(), &mut out, format, &options);
+    }
+    // JSON and porcelain output are for scripts and never paged
+    let pageable = matches!(format, DiffFormat::GitCompatibleTerminal) && !options.no_pager;
+
+    if let Some(path) = &options.output_path {
+        let mut file = std::io::BufWriter::new(create_output_file(path)?);
+        execute_diff(repo, spec, format, &options, &mut file)?;
+        file.flush()?;
+        return Ok(());
+    }
+
+    // The diff is written out as it's rendered, so it's never held in memory as a whole
+    let result = match resolve_pager(repo)
+        .filter(|_| pageable)
+        .and_then(|pager| spawn_pager(&pager))
+    {
+        Some(mut pager) => {
+            let mut result = Ok(());
+            if let Some(stdin) = pager.stdin.take() {
+                let mut out = std::io::BufWriter::new(stdin);
+                result = execute_diff(repo, spec, format, &options, &mut out)
+                    .and_then(|()| out.flush().map_err(GitAiError::from));
+            }
+            // The pager's stdin is closed by now, so it exits once the user is done
+            let _ = pager.wait();
+            result
+        }
+        None => {
+            let mut out = std::io::stdout().lock();
+            execute_diff(repo, spec, format, &options, &mut out)
+                .and_then(|()| out.flush().map_err(GitAiError::from))
         }
     };
+    ignore_broken_pipe(result)
+}
 
-    // Step 1: Get diff hunks with line numbers
-    let hunks = get_diff_with_line_numbers(repo, &from_commit, &to_commit)?;
+/// Quitting the pager early (or piping into `head`) closes the pipe, which isn't an error
+fn ignore_broken_pipe(result: Result<(), GitAiError>) -> Result<(), GitAiError> {
+    match result {
+        Err(GitAiError::IoError(e)) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
 
-    // Step 2: Overlay AI attributions
-    let attributions = overlay_diff_attributions(repo, &from_commit, &to_commit, &hunks)?;
+/// Create (or truncate) the --output file, naming it in the error if that fails
+fn create_output_file(path: &str) -> Result<std::fs::File, GitAiError> {
+    std::fs::File::create(path)
+        .map_err(|e| GitAiError::Generic(format!("Failed to write {}: {}", path, e)))
+}
 
-    // Step 3: Format and output annotated diff
-    let output = match format {
-        DiffFormat::Json => {
-            let diff_json = build_diff_json(repo, &from_commit, &to_commit, &hunks, &attributions)?;
-            serde_json::to_string(&diff_json)
-                .map_err(|e| GitAiError::Generic(format!("Failed to serialize JSON: {}", e)))?
-        }
-        DiffFormat::GitCompatibleTerminal => {
-            format_annotated_diff(repo, &from_commit, &to_commit, &attributions)?
-        }
+/// The pager for terminal output, or None when stdout isn't a terminal or paging is off
+fn resolve_pager(repo: &Repository) -> Option<String> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    select_pager(
+        std::env::var("GIT_PAGER").ok(),
+        repo.config_get_str("core.pager").ok().flatten(),
+        std::env::var("PAGER").ok(),
+    )
+}
+
+/// Pick a pager in git's order of precedence: `$GIT_PAGER`, `core.pager`, `$PAGER`, then
+/// the default. An empty value or `cat` means no pager.
+fn select_pager(
+    git_pager: Option<String>,
+    core_pager: Option<String>,
+    pager: Option<String>,
+) -> Option<String> {
+    let pager = git_pager
+        .or(core_pager)
+        .or(pager)
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let pager = pager.trim();
+    if pager.is_empty() || pager == "cat" {
+        None
+    } else {
+        Some(pager.to_string())
+    }
+}
+
+/// Start `pager` with its stdin piped, or None (so output goes to stdout) if it can't be
+/// started.
+///
+/// The pager writes to the terminal we checked in `resolve_pager`, so the `ColorMode::Auto`
+/// decision (also based on stdout being a terminal) holds for paged output.
+fn spawn_pager(pager: &str) -> Option<Child> {
+    // Run through the shell like git does, so pagers with arguments work
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(pager);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(pager);
+        command
     };
+    // Same as git: quit if it fits on one screen, pass ANSI colors through, keep the screen
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
 
-    Ok(output)
+    command.stdin(Stdio::piped()).spawn().ok()
 }
 
 // ============================================================================
-// Commit Resolution
+// Argument Parsing
 // ============================================================================
 
-fn resolve_commit(repo: &Repository, rev: &str) -> Result<String, GitAiError> {
-    let mut args = repo.global_args_for_exec();
-    args.push("rev-parse".to_string());
-    args.push(rev.to_string());
+/// Flags that pick the output format or what to diff rather than how to annotate it
+const DIFF_SPEC_FLAGS: &[&str] = &[
+    "--json",
+    "--porcelain",
+    "--output=github",
+    "--stat",
+    "--no-data-only",
+    "--summary-json",
+    "--cached",
+    "--staged",
+    "--stdin",
+];
 
-    let output = exec_git(&args)?;
-    let sha = String::from_utf8(output.stdout)
-        .map_err(|e| GitAiError::Generic(format!("Failed to parse rev-parse output: {}", e)))?
-        .trim()
-        .to_string();
+pub fn parse_diff_args(args: &[String]) -> Result<(DiffSpec, DiffFormat), GitAiError> {
+    // Rejects unknown flags, which would otherwise be taken for neither a flag nor a revision
+    parse_annotated_diff_options(args)?;
 
-    if sha.is_empty() {
+    let has_flag = |flag: &str| {
+        args.iter()
+            .take_while(|arg| arg.as_str() != "--")
+            .any(|arg| arg == flag)
+    };
+    // Any other `--output=<value>` is the file to write to, as in git
+    let github = has_flag("--output=github");
+    let html = match args
+        .iter()
+        .take_while(|arg| arg.as_str() != "--")
+        .find_map(|arg| arg.strip_prefix("--format="))
+    {
+        Some("html") => true,
+        Some(value) => {
+            return Err(GitAiError::Generic(format!(
+                "Invalid --format value: {} (expected html)",
+                value
+            )));
+        }
+        None => false,
+    };
+    let mut requested: Vec<(&str, DiffFormat)> = Vec::new();
+    if has_flag("--json") {
+        requested.push(("--json", DiffFormat::Json));
+    }
+    if has_flag("--porcelain") {
+        requested.push(("--porcelain", DiffFormat::Porcelain));
+    }
+    if github {
+        requested.push(("--output=github", DiffFormat::GithubAnnotations));
+    }
+    if has_flag("--stat") {
+        requested.push(("--stat", DiffFormat::Stat));
+    }
+    if has_flag("--no-data-only") {
+        requested.push(("--no-data-only", DiffFormat::NoDataOnly));
+    }
+    if html {
+        requested.push(("--format=html", DiffFormat::Html));
+    }
+    if has_flag("--summary-json") {
+        requested.push(("--summary-json", DiffFormat::SummaryJson));
+    }
+    if requested.len() > 1 {
         return Err(GitAiError::Generic(format!(
-            "Could not resolve commit: {}",
-            rev
+            "{} and {} cannot be used together",
+            requested[0].0, requested[1].0
         )));
     }
-
-    Ok(sha)
+    let format = requested
+        .pop()
+        .map_or(DiffFormat::GitCompatibleTerminal, |(_, format)| format);
+
+    // The commit or range is the first argument that isn't a flag or the file given to
+    // --output (pathspecs follow `--`). Without one, diff the working tree (or the index
+    // with --cached) against HEAD.
+    let flags: Vec<&String> = args.iter().take_while(|arg| arg.as_str() != "--").collect();
+    let revisions: Vec<&String> = flags
+        .iter()
+        .enumerate()
+        .filter(|(i, arg)| {
+            !arg.starts_with('-') && (*i == 0 || flags[i - 1].as_str() != "--output")
+        })
+        .map(|(_, arg)| *arg)
+        .collect();
+    if has_flag("--cached") || has_flag("--staged") {
+        if !revisions.is_empty() {
+            return Err(GitAiError::Generic(
+                "--cached compares the index with HEAD and doesn't take a revision".to_string(),
+            ));
+        }
+        return Ok((DiffSpec::Cached, format));
+    }
+    let revisions: Vec<&str> = revisions.iter().map(|arg| arg.as_str()).collect();
+    Ok((revisions.join(" ").parse()?, format))
 }
 
-fn resolve_parent(repo: &Repository, commit: &str) -> Result<String, GitAiError> {
-    let parent_rev = format!("{}^", commit);
+pub fn parse_annotated_diff_options(args: &[String]) -> Result<AnnotatedDiffOptions, GitAiError> {
+    let mut options = AnnotatedDiffOptions::default();
 
-    // Try to resolve parent
-    let mut args = repo.global_args_for_exec();
-    args.push("rev-parse".to_string());
-    args.push(parent_rev);
+    // Everything after `--` is a pathspec
+    let (flags, pathspecs) = match args.iter().position(|arg| arg == "--") {
+        Some(separator) => (&args[..separator], &args[separator + 1..]),
+        None => (args, &args[args.len()..]),
+    };
+    options.pathspecs = pathspecs.to_vec();
 
-    let output = exec_git(&args);
+    let mut flags = flags.iter();
+    while let Some(arg) = flags.next() {
+        if let Some(value) = arg.strip_prefix("--parent=") {
+            options.parent = Some(parse_parent_number(value)?);
+            continue;
+        }
 
-    match output {
-        Ok(out) => {
-            let sha = String::from_utf8(out.stdout)
-                .map_err(|e| GitAiError::Generic(format!("Failed to parse parent SHA: {}", e)))?
-                .trim()
-                .to_string();
+        if arg == "--output" {
+            let path = flags
+                .next()
+                .ok_or_else(|| GitAiError::Generic("--output requires a file path".to_string()))?;
+            options.output_path = Some(path.clone());
+            continue;
+        }
 
-            if sha.is_empty() {
-                // No parent, this is initial commit - use empty tree
-                Ok("4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string())
-            } else {
-                Ok(sha)
+        // `--output=github` picks the annotations format (see parse_diff_args)
+        if let Some(path) = arg
+            .strip_prefix("--output=")
+            .filter(|path| *path != "github")
+        {
+            if path.is_empty() {
+                return Err(GitAiError::Generic(
+                    "--output requires a file path".to_string(),
+                ));
             }
+            options.output_path = Some(path.to_string());
+            continue;
         }
-        Err(_) => {
-            // No parent, this is initial commit - use empty tree hash
-            Ok("4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string())
+
+        // `-m -2` picks the merge parent to diff against, as in `git show -m`
+        if arg == "-m" {
+            let value = flags
+                .next()
+                .and_then(|next| next.strip_prefix('-'))
+                .ok_or_else(|| {
+                    GitAiError::Generic("-m expects a parent, e.g. -m -2".to_string())
+                })?;
+            options.parent = Some(parse_parent_number(value)?);
+            continue;
         }
-    }
-}
 
-// ============================================================================
-// Diff Retrieval with Line Numbers
-// ============================================================================
+        if let Some(value) = arg
+            .strip_prefix("--unified=")
+            .or_else(|| arg.strip_prefix("-U"))
+        {
+            let context_lines = value.parse::<u32>().map_err(|_| {
+                GitAiError::Generic(format!("Invalid context line count: {}", value))
+            })?;
+            options.context_lines = Some(context_lines);
+            continue;
+        }
 
-pub fn get_diff_with_line_numbers(
-    repo: &Repository,
-    from: &str,
-    to: &str,
-) -> Result<Vec<DiffHunk>, GitAiError> {
-    let mut args = repo.global_args_for_exec();
-    args.push("diff".to_string());
-    args.push("-U0".to_string()); // No context lines, just changes
-    args.push("--no-color".to_string());
-    args.push(from.to_string());
-    args.push(to.to_string());
+        if let Some(dir) = arg.strip_prefix("--relative=") {
+            options.relative = RelativeTo::Dir(dir.to_string());
+            continue;
+        }
 
-    let output = exec_git(&args)?;
-    let diff_text = String::from_utf8(output.stdout)
-        .map_err(|e| GitAiError::Generic(format!("Failed to parse diff output: {}", e)))?;
+        if let Some(glyph) = arg.strip_prefix("--annotate-ai=") {
+            options.ai_glyph = Some(glyph.to_string());
+            continue;
+        }
+
+        if let Some(glyph) = arg.strip_prefix("--annotate-human=") {
+            options.human_glyph = Some(glyph.to_string());
+            continue;
+        }
+
+        if let Some(value) = arg.strip_prefix("--color=") {
+            options.color = match value {
+                "always" => ColorMode::Always,
+                "never" => ColorMode::Never,
+                "auto" => ColorMode::Auto,
+                _ => {
+                    return Err(GitAiError::Generic(format!(
+                        "Invalid --color value: {} (expected always, never or auto)",
+                        value
+                    )));
+                }
+            };
+            continue;
+        }
+
+        let filter = match arg.as_str() {
+            "--word-diff" => {
+                options.word_diff = true;
+                continue;
+            }
+            "--color" => {
+                options.color = ColorMode::Always;
+                continue;
+            }
+            "--no-color" => {
+                options.color = ColorMode::Never;
+                continue;
+            }
+            "-R" | "--reverse" => {
+                options.reverse = true;
+                continue;
+            }
+            "--no-pager" => {
+                options.no_pager = true;
+                continue;
+            }
+            "--show-prompt" => {
+                options.show_prompt = true;
+                continue;
+            }
+            "--show-version" => {
+                options.show_version = true;
+                continue;
+            }
+            "--find-copies-harder" => {
+                options.find_copies_harder = true;
+                continue;
+            }
+            "--detect-moves" => {
+                options.detect_moves = true;
+                continue;
+            }
+            "--ascii" => {
+                options.ascii = true;
+                continue;
+            }
+            "--no-lines" => {
+                options.no_lines = true;
+                continue;
+            }
+            "--context-attribution" => {
+                options.context_attribution = true;
+                continue;
+            }
+            "--relative" => {
+                options.relative = RelativeTo::Cwd;
+                continue;
+            }
+            "--no-relative" => {
+                options.relative = RelativeTo::Root;
+                continue;
+            }
+            "--only-ai" => AttributionFilter::OnlyAi,
+            "--only-human" => AttributionFilter::OnlyHuman,
+            // Revisions, and the flags parse_diff_args and handle_diff read
+            other
+                if !other.starts_with('-')
+                    || DIFF_SPEC_FLAGS.contains(&other)
+                    || other.starts_with("--format=") =>
+            {
+                continue;
+            }
+            other => {
+                return Err(GitAiError::Generic(format!(
+                    "Unknown diff option: {}",
+                    other
+                )));
+            }
+        };
+        if options
+            .attribution_filter
+            .is_some_and(|existing| existing != filter)
+        {
+            return Err(GitAiError::Generic(
+                "--only-ai and --only-human cannot be used together".to_string(),
+            ));
+        }
+        options.attribution_filter = Some(filter);
+    }
+
+    Ok(options)
+}
+
+/// Parse a 1-based parent number for `-m -<n>` / `--parent=<n>`
+fn parse_parent_number(value: &str) -> Result<u32, GitAiError> {
+    match value.parse::<u32>() {
+        Ok(parent) if parent >= 1 => Ok(parent),
+        _ => Err(GitAiError::Generic(format!(
+            "Invalid parent number: {} (expected 1 or more)",
+            value
+        ))),
+    }
+}
+
+// ============================================================================
+// Core Execution Logic
+// ============================================================================
+
+/// Render the diff of `spec` in `format`, writing it to `out`
+pub fn execute_diff(
+    repo: &Repository,
+    spec: DiffSpec,
+    format: DiffFormat,
+    options: &AnnotatedDiffOptions,
+    out: &mut dyn Write,
+) -> Result<(), GitAiError> {
+    let cache = Arc::new(AuthorshipLogCache::default());
+    execute_diff_with_cache(repo, spec, format, options, &cache, out)
+}
+
+/// One line of `git-ai diff --stdin` output: the spec as read, and either its diff or the
+/// reason it failed
+#[derive(Debug, Serialize)]
+struct BatchDiffResult<'a> {
+    spec: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Diff every spec read from `input` (`<commit>`, `A..B`, `A...B`, one per line) and write
+/// one JSON object per spec to `out` as soon as it's done. All of them share one authorship
+/// log cache, so commits that appear in several specs are only read once. A spec that fails
+/// is reported inline as `{"spec": ..., "error": ...}` and the batch carries on.
+///
+/// The output format is --summary-json unless --json is given.
+pub fn execute_diff_batch(
+    repo: &Repository,
+    input: impl BufRead,
+    out: &mut dyn Write,
+    format: DiffFormat,
+    options: &AnnotatedDiffOptions,
+) -> Result<(), GitAiError> {
+    let format = match format {
+        DiffFormat::Json | DiffFormat::SummaryJson => format,
+        DiffFormat::GitCompatibleTerminal => DiffFormat::SummaryJson,
+        _ => {
+            return Err(GitAiError::Generic(
+                "--stdin only supports --json and --summary-json output".to_string(),
+            ));
+        }
+    };
+    let cache = Arc::new(AuthorshipLogCache::default());
+
+    for line in input.lines() {
+        let line = line?;
+        let spec_text = line.trim();
+        if spec_text.is_empty() {
+            continue;
+        }
+
+        let output = spec_text
+            .parse::<DiffSpec>()
+            .and_then(|spec| match spec {
+                DiffSpec::WorkingTree | DiffSpec::Cached => Err(GitAiError::Generic(
+                    "--stdin only diffs commits, not the working tree or index".to_string(),
+                )),
+                spec => {
+                    let mut json = Vec::new();
+                    execute_diff_with_cache(repo, spec, format, options, &cache, &mut json)
+                        .map(|()| json)
+                }
+            })
+            .and_then(|json| {
+                serde_json::from_slice::<serde_json::Value>(&json).map_err(GitAiError::from)
+            });
+        let result = match output {
+            Ok(json) => BatchDiffResult {
+                spec: spec_text,
+                result: Some(json),
+                error: None,
+            },
+            Err(e) => BatchDiffResult {
+                spec: spec_text,
+                result: None,
+                error: Some(e.to_string()),
+            },
+        };
+        writeln!(out, "{}", serde_json::to_string(&result)?)?;
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+/// [`execute_diff`] reading authorship logs through `cache`
+pub fn execute_diff_with_cache(
+    repo: &Repository,
+    spec: DiffSpec,
+    format: DiffFormat,
+    options: &AnnotatedDiffOptions,
+    cache: &Arc<AuthorshipLogCache>,
+    out: &mut dyn Write,
+) -> Result<(), GitAiError> {
+    if options.parent.is_some() && !matches!(spec, DiffSpec::SingleCommit(_)) {
+        return Err(GitAiError::Generic(
+            "-m/--parent only applies when diffing a single commit".to_string(),
+        ));
+    }
+
+    // Resolve commits to get from/to SHAs
+    let (from_commit, to_commit) = match spec {
+        DiffSpec::TwoCommit(start, end) => {
+            // Resolve both commits
+            let from = resolve_commit(repo, &start)?;
+            let to = resolve_commit(repo, &end)?;
+            (from, to)
+        }
+        DiffSpec::MergeBase(base, head) => {
+            // Like `git diff A...B`: changes on head since it diverged from base
+            let base_sha = resolve_commit(repo, &base)?;
+            let to = resolve_commit(repo, &head)?;
+            let from = repo.merge_base(base_sha, to.clone()).map_err(|_| {
+                GitAiError::Generic(format!("No merge base found between {} and {}", base, head))
+            })?;
+            (from, to)
+        }
+        DiffSpec::SingleCommit(commit) => {
+            // Resolve the commit and its parent (the first one for merges, unless -m picks one)
+            let to = resolve_commit(repo, &commit)?;
+            let from = match options.parent {
+                Some(parent) => resolve_nth_parent(repo, &to, parent)?,
+                None => resolve_parent(repo, &to)?,
+            };
+            (from, to)
+        }
+        DiffSpec::WorkingTree => {
+            let from = resolve_commit(repo, "HEAD")?;
+            (from, WORKING_TREE.to_string())
+        }
+        DiffSpec::Cached => {
+            let from = resolve_commit(repo, "HEAD")?;
+            (from, INDEX.to_string())
+        }
+    };
+
+    if options.detect_moves && (options.reverse || is_uncommitted(&to_commit)) {
+        return Err(GitAiError::Generic(
+            "--detect-moves requires a commit or commit range argument and no --reverse"
+                .to_string(),
+        ));
+    }
+
+    // Like `git diff -R`: additions become deletions and vice versa
+    let (from_commit, to_commit) = if options.reverse {
+        if is_uncommitted(&to_commit) {
+            return Err(GitAiError::Generic(
+                "--reverse requires a commit or commit range argument".to_string(),
+            ));
+        }
+        if matches!(format, DiffFormat::Json) {
+            return Err(GitAiError::Generic(
+                "--reverse cannot be used with --json".to_string(),
+            ));
+        }
+        (to_commit, from_commit)
+    } else {
+        (from_commit, to_commit)
+    };
+
+    if options.no_lines && !matches!(format, DiffFormat::SummaryJson) {
+        return Err(GitAiError::Generic(
+            "--no-lines only applies to --summary-json".to_string(),
+        ));
+    }
+
+    if options.context_attribution
+        && (is_uncommitted(&to_commit)
+            || options.word_diff
+            || !matches!(format, DiffFormat::GitCompatibleTerminal))
+    {
+        return Err(GitAiError::Generic(
+            "--context-attribution requires a commit or commit range argument and the default \
+             output format, without --word-diff"
+                .to_string(),
+        ));
+    }
+
+    if matches!(format, DiffFormat::NoDataOnly) && options.attribution_filter.is_some() {
+        return Err(GitAiError::Generic(
+            "--no-data-only cannot be used with --only-ai or --only-human".to_string(),
+        ));
+    }
+
+    // Glyphs from git config apply unless the flags already chose them
+    let config_glyph = |flag: &Option<String>, key: &str| match flag {
+        Some(glyph) => Some(glyph.clone()),
+        None if options.ascii => None,
+        None => repo.config_get_str(key).ok().flatten(),
+    };
+
+    // Like `git diff --relative`: only changes under the directory, shown relative to it.
+    // Attributions stay keyed by repository-root paths; the formatters translate.
+    let options = &AnnotatedDiffOptions {
+        relative: RelativeTo::Dir(resolve_relative_dir(repo, &options.relative)),
+        ai_glyph: config_glyph(&options.ai_glyph, "git-ai.ai-glyph"),
+        human_glyph: config_glyph(&options.human_glyph, "git-ai.human-glyph"),
+        ..options.clone()
+    };
+
+    // Step 1: Get diff hunks with line numbers
+    let mut hunks = get_diff_with_line_numbers(
+        repo,
+        &from_commit,
+        &to_commit,
+        &options.pathspecs,
+        options.find_copies_harder,
+    )?;
+    let relative_dir = options.relative_dir();
+    if !relative_dir.is_empty() {
+        hunks.retain(|hunk| {
+            hunk.file_path.starts_with(relative_dir) || hunk.old_file_path.starts_with(relative_dir)
+        });
+    }
+
+    // Step 2: Overlay AI attributions
+    let mut prompts = LinePrompts::default();
+    let mut attributions = if to_commit == WORKING_TREE {
+        overlay_working_tree_attributions(repo, &from_commit, &hunks, &mut prompts)?
+    } else if to_commit == INDEX {
+        overlay_index_attributions(repo, &from_commit, &hunks, &mut prompts)?
+    } else if options.reverse {
+        overlay_reversed_diff_attributions(repo, &from_commit, &to_commit, &hunks, &mut prompts)?
+    } else {
+        overlay_diff_attributions_with_cache(
+            repo,
+            &from_commit,
+            &to_commit,
+            &hunks,
+            &mut prompts,
+            cache,
+        )?
+    };
+    if options.detect_moves {
+        carry_moved_attributions(
+            repo,
+            &from_commit,
+            &to_commit,
+            &hunks,
+            &mut attributions,
+            &mut prompts,
+            cache,
+        );
+    }
+    if !is_uncommitted(&to_commit) {
+        overlay_binary_file_attributions(
+            repo,
+            &from_commit,
+            &to_commit,
+            &options.pathspecs,
+            &mut attributions,
+        )?;
+    }
+    if !relative_dir.is_empty() {
+        attributions.retain(|key, _| key.file.starts_with(relative_dir));
+    }
+    // JSON output has the version in a field of its own
+    if options.show_version && !matches!(format, DiffFormat::Json | DiffFormat::SummaryJson) {
+        label_tool_versions(&mut attributions, &prompts);
+    }
+
+    // Step 3: Format and output annotated diff
+    let output = match format {
+        DiffFormat::Json if is_uncommitted(&to_commit) => {
+            return Err(GitAiError::Generic(
+                "--json requires a commit or commit range argument".to_string(),
+            ));
+        }
+        DiffFormat::Json => {
+            let diff_json = build_diff_json(repo, &from_commit, &to_commit, &hunks, &attributions)?;
+            serde_json::to_string(&diff_json)
+                .map_err(|e| GitAiError::Generic(format!("Failed to serialize JSON: {}", e)))?
+        }
+        DiffFormat::Porcelain => format_porcelain_diff(&hunks, &attributions, &prompts, options),
+        DiffFormat::SummaryJson => {
+            let summary_json = build_diff_summary_json(&hunks, &attributions, &prompts, options);
+            serde_json::to_string(&summary_json)
+                .map_err(|e| GitAiError::Generic(format!("Failed to serialize JSON: {}", e)))?
+        }
+        DiffFormat::GithubAnnotations => format_github_annotations(&hunks, &attributions),
+        DiffFormat::Stat => format_diff_stat(&hunks, &attributions, relative_dir),
+        DiffFormat::NoDataOnly => format_no_data_lines(
+            repo,
+            &from_commit,
+            &to_commit,
+            &hunks,
+            &attributions,
+            relative_dir,
+        ),
+        DiffFormat::Html => {
+            format_html_diff(repo, &from_commit, &to_commit, &attributions, options)?
+        }
+        DiffFormat::GitCompatibleTerminal if options.word_diff => format_word_diff(
+            repo,
+            &from_commit,
+            &to_commit,
+            &attributions,
+            &prompts,
+            options,
+        )?,
+        DiffFormat::GitCompatibleTerminal => {
+            let mut context_attributions = HashMap::new();
+            if options.context_attribution {
+                let context_lines = display_context_lines(repo, &from_commit, &to_commit, options)?;
+                let mut context_prompts = LinePrompts::default();
+                blame_lines_into_parallel(
+                    repo,
+                    None,
+                    &to_commit,
+                    context_lines,
+                    LineSide::New,
+                    &mut context_attributions,
+                    &mut context_prompts,
+                    cache,
+                );
+                if options.show_version {
+                    label_tool_versions(&mut context_attributions, &context_prompts);
+                }
+            }
+            // Written as git produces it rather than collected, since diffs can be huge
+            return format_annotated_diff(
+                repo,
+                &from_commit,
+                &to_commit,
+                &attributions,
+                &context_attributions,
+                &prompts,
+                options,
+                out,
+            );
+        }
+    };
+
+    out.write_all(output.as_bytes())?;
+    Ok(())
+}
+
+// ============================================================================
+// Commit Resolution
+// ============================================================================
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-parse".to_string());
+    args.push(rev.to_string());
+
+    let output = exec_git(&args).map_err(|e| match e {
+        GitAiError::GitCliError { .. } => GitAiError::CommitNotFound(rev.to_string()),
+        e => e,
+    })?;
+    let sha = String::from_utf8(output.stdout)
+        .map_err(|e| GitAiError::Generic(format!("Failed to parse rev-parse output: {}", e)))?
+        .trim()
+        .to_string();
+
+    if sha.is_empty() {
+        return Err(GitAiError::CommitNotFound(rev.to_string()));
+    }
+
+    Ok(sha)
+}
+
+fn resolve_parent(repo: &Repository, commit: &str) -> Result<String, GitAiError> {
+    let parent_rev = format!("{}^", commit);
+
+    // Try to resolve parent
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-parse".to_string());
+    args.push(parent_rev);
+
+    let output = exec_git(&args);
+
+    match output {
+        Ok(out) => {
+            let sha = String::from_utf8(out.stdout)
+                .map_err(|e| GitAiError::Generic(format!("Failed to parse parent SHA: {}", e)))?
+                .trim()
+                .to_string();
+
+            if sha.is_empty() {
+                // No parent, this is initial commit - use empty tree
+                Ok("4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string())
+            } else {
+                Ok(sha)
+            }
+        }
+        Err(_) => {
+            // No parent, this is initial commit - use empty tree hash
+            Ok("4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string())
+        }
+    }
+}
+
+/// Resolve the `parent`-th parent of a commit (1-based), failing if it doesn't have one
+fn resolve_nth_parent(repo: &Repository, commit: &str, parent: u32) -> Result<String, GitAiError> {
+    let parent_rev = format!("{}^{}", commit, parent);
+    resolve_commit(repo, &parent_rev).map_err(|e| match e {
+        GitAiError::CommitNotFound(_) => GitAiError::CommitNotFound(parent_rev.clone()),
+        e => e,
+    })
+}
+
+// ============================================================================
+// Diff Retrieval with Line Numbers
+// ============================================================================
+
+pub fn get_diff_with_line_numbers(
+    repo: &Repository,
+    from: &str,
+    to: &str,
+    pathspecs: &[String],
+    find_copies_harder: bool,
+) -> Result<Vec<DiffHunk>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("-U0".to_string()); // No context lines, just changes
+    push_copy_detection(&mut args, find_copies_harder);
+    args.push("--no-color".to_string());
+    push_diff_revisions(&mut args, from, to);
+    push_pathspecs(&mut args, pathspecs);
+
+    let output = exec_git(&args)?;
+    let diff_text =
+        String::from_utf8(output.stdout).map_err(|e| GitAiError::DiffParse(e.to_string()))?;
 
     parse_diff_hunks(&diff_text)
 }
 
+/// Append the revisions to compare to a git diff invocation. When `to` is the
+/// working tree only `from` is passed, which makes git compare against the worktree;
+/// for the index `--cached` is added as well.
+fn push_diff_revisions(args: &mut Vec<String>, from: &str, to: &str) {
+    if to == INDEX {
+        args.push("--cached".to_string());
+    }
+    args.push(from.to_string());
+    if !is_uncommitted(to) {
+        args.push(to.to_string());
+    }
+}
+
+/// Detect renames and copies, so attributions follow moved and copied files. The displayed
+/// diff must use the same detection as the `-U0` pass for attribution keys to line up.
+fn push_copy_detection(args: &mut Vec<String>, find_copies_harder: bool) {
+    args.push("-M".to_string());
+    args.push("-C".to_string());
+    if find_copies_harder {
+        args.push("--find-copies-harder".to_string());
+    }
+}
+
+/// Ask git diff to show paths relative to the resolved `--relative` directory, if any
+fn push_relative(args: &mut Vec<String>, options: &AnnotatedDiffOptions) {
+    let relative_dir = options.relative_dir();
+    if !relative_dir.is_empty() {
+        args.push(format!("--relative={}", relative_dir));
+    }
+}
+
+/// Append pathspecs to a git diff invocation, after a `--` separator
+fn push_pathspecs(args: &mut Vec<String>, pathspecs: &[String]) {
+    if !pathspecs.is_empty() {
+        args.push("--".to_string());
+        args.extend(pathspecs.iter().cloned());
+    }
+}
+
 fn parse_diff_hunks(diff_text: &str) -> Result<Vec<DiffHunk>, GitAiError> {
     let mut hunks = Vec::new();
     let mut current_file = String::new();
+    let mut current_old_file = String::new();
+    let mut current_copied = false;
 
     for line in diff_text.lines() {
-        if line.starts_with("+++ b/") {
-            // New file path
-            current_file = line[6..].to_string();
+        if line.starts_with("diff --git") {
+            current_file.clear();
+            current_old_file.clear();
+            current_copied = false;
+        } else if let Some(old_path) = line
+            .strip_prefix("rename from ")
+            .or_else(|| line.strip_prefix("copy from "))
+        {
+            // Renamed/copied file: deleted lines live under the old path
+            current_old_file = unquote_git_path(old_path);
+            current_copied = line.starts_with("copy from ");
+        } else if let Some(new_path) = line
+            .strip_prefix("rename to ")
+            .or_else(|| line.strip_prefix("copy to "))
+        {
+            current_file = unquote_git_path(new_path);
+        } else if let Some(old_path) = diff_header_path(line, "--- ", "a/") {
+            current_old_file = old_path;
+        } else if let Some(new_path) = diff_header_path(line, "+++ ", "b/") {
+            current_file = new_path;
+        } else if line == "+++ /dev/null" {
+            // Deleted file: it only has an old path, which identifies it
+            current_file = current_old_file.clone();
         } else if line.starts_with("@@ ") {
             // Hunk header
-            if let Some(hunk) = parse_hunk_line(line, &current_file)? {
+            if let Some(mut hunk) = parse_hunk_line(line, &current_file)? {
+                if !current_old_file.is_empty() {
+                    hunk.old_file_path = current_old_file.clone();
+                }
+                hunk.copied = current_copied;
                 hunks.push(hunk);
             }
         }
@@ -269,6 +1461,63 @@ fn parse_diff_hunks(diff_text: &str) -> Result<Vec<DiffHunk>, GitAiError> {
     Ok(hunks)
 }
 
+/// The path in a `--- a/<path>` or `+++ b/<path>` header line, as other git commands name
+/// it. `None` for `/dev/null`. Every diff pass reads paths through this, so `DiffLineKey`s
+/// built while attributing and while printing always agree, even for quoted names.
+fn diff_header_path(line: &str, marker: &str, prefix: &str) -> Option<String> {
+    let path = line.strip_prefix(marker)?;
+    // git appends a tab to names containing spaces
+    let path = path.strip_suffix('\t').unwrap_or(path);
+    unquote_git_path(path)
+        .strip_prefix(prefix)
+        .map(str::to_string)
+}
+
+/// Undo git's C-style quoting of unusual paths (`"na\303\257ve.txt"`). Unquoted paths are
+/// returned as-is. Octal escapes are raw bytes, so multi-byte characters come back intact.
+fn unquote_git_path(path: &str) -> String {
+    let Some(quoted) = path
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut rest = quoted.bytes().peekable();
+    while let Some(byte) = rest.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match rest.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'v') => bytes.push(0x0b),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = (digit - b'0') as u32;
+                for _ in 0..2 {
+                    match rest.peek() {
+                        Some(next @ b'0'..=b'7') => {
+                            value = value * 8 + (next - b'0') as u32;
+                            rest.next();
+                        }
+                        _ => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
 fn parse_hunk_line(line: &str, file_path: &str) -> Result<Option<DiffHunk>, GitAiError> {
     // Parse hunk header format: @@ -old_start,old_count +new_start,new_count @@
     // Also handles: @@ -old_start +new_start,new_count @@ (single line deletion)
@@ -312,27 +1561,20 @@ fn parse_hunk_line(line: &str, file_path: &str) -> Result<Option<DiffHunk>, GitA
         (0, 0)
     };
 
-    // Build line number lists
-    let deleted_lines: Vec<u32> = if old_count > 0 {
-        (old_start..old_start + old_count).collect()
-    } else {
-        Vec::new()
-    };
-
-    let added_lines: Vec<u32> = if new_count > 0 {
-        (new_start..new_start + new_count).collect()
-    } else {
-        Vec::new()
-    };
+    // Line ranges, clamped so a bogus header can't overflow
+    let deleted = old_start..old_start.saturating_add(old_count);
+    let added = new_start..new_start.saturating_add(new_count);
 
     Ok(Some(DiffHunk {
         file_path: file_path.to_string(),
+        old_file_path: file_path.to_string(),
+        copied: false,
         old_start,
         old_count,
         new_start,
         new_count,
-        deleted_lines,
-        added_lines,
+        deleted,
+        added,
     }))
 }
 
@@ -340,410 +1582,2520 @@ fn parse_hunk_line(line: &str, file_path: &str) -> Result<Option<DiffHunk>, GitA
 // Attribution Overlay
 // ============================================================================
 
+/// Attribute the added lines of a commit diff by blaming them over the range, one file per
+/// worker. Files marked `attribution=ignore` in `.gitai-attributes` aren't blamed; their
+/// lines are `NoData`.
 pub fn overlay_diff_attributions(
     repo: &Repository,
     from_commit: &str,
     to_commit: &str,
     hunks: &[DiffHunk],
+    prompts: &mut LinePrompts,
 ) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
-    let mut attributions = HashMap::new();
+    let cache = Arc::new(AuthorshipLogCache::default());
+    overlay_diff_attributions_with_cache(repo, from_commit, to_commit, hunks, prompts, &cache)
+}
 
-    // Group added lines by file
-    let mut lines_by_file: HashMap<String, Vec<u32>> = HashMap::new();
-    for hunk in hunks {
-        if !hunk.added_lines.is_empty() {
-            lines_by_file
-                .entry(hunk.file_path.clone())
-                .or_insert_with(Vec::new)
-                .extend(&hunk.added_lines);
+/// `overlay_diff_attributions` reading authorship logs through `cache`, so callers running
+/// many diffs in one process (e.g. against the same base) parse each commit's log once
+pub fn overlay_diff_attributions_with_cache(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    prompts: &mut LinePrompts,
+    cache: &Arc<AuthorshipLogCache>,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
+    overlay_diff_attributions_with(repo, from_commit, to_commit, hunks, prompts, true, cache)
+}
+
+/// `overlay_diff_attributions` blaming one file at a time. The result is the same; this is
+/// kept to check the parallel version against and to benchmark it.
+pub fn overlay_diff_attributions_serial(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    prompts: &mut LinePrompts,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
+    let cache = Arc::new(AuthorshipLogCache::default());
+    overlay_diff_attributions_with(repo, from_commit, to_commit, hunks, prompts, false, &cache)
+}
+
+fn overlay_diff_attributions_with(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    prompts: &mut LinePrompts,
+    parallel: bool,
+    cache: &Arc<AuthorshipLogCache>,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
+    let mut attributions = HashMap::new();
+    let attributes = GitAiAttributes::load(repo);
+
+    // Group added line ranges by file
+    let mut lines_by_file: BTreeMap<String, Vec<Range<u32>>> = BTreeMap::new();
+    for hunk in hunks {
+        if hunk.added.is_empty() {
+            continue;
         }
+        if attributes.is_ignored(&hunk.file_path) {
+            let file: Arc<str> = hunk.file_path.as_str().into();
+            for line in hunk.added_lines() {
+                let key = DiffLineKey {
+                    file: Arc::clone(&file),
+                    line,
+                    side: LineSide::New,
+                };
+                attributions.insert(key, Attribution::NoData);
+            }
+            continue;
+        }
+        lines_by_file
+            .entry(hunk.file_path.clone())
+            .or_insert_with(Vec::new)
+            .push(hunk.added_lines());
     }
 
-    // For each file, call blame with the appropriate line ranges
-    for (file_path, mut lines) in lines_by_file {
-        // Sort and convert to contiguous ranges for efficient -L format
-        lines.sort_unstable();
-        lines.dedup();
-        let line_ranges = lines_to_ranges(&lines);
+    if hunks.iter().any(|hunk| hunk.copied) {
+        carry_copied_attributions(
+            repo,
+            from_commit,
+            to_commit,
+            hunks,
+            &mut lines_by_file,
+            &mut attributions,
+            prompts,
+            cache,
+        );
+    }
+
+    if parallel {
+        blame_lines_into_parallel(
+            repo,
+            Some(from_commit),
+            to_commit,
+            lines_by_file,
+            LineSide::New,
+            &mut attributions,
+            prompts,
+            cache,
+        );
+    } else {
+        blame_lines_into(
+            repo,
+            Some(from_commit),
+            to_commit,
+            lines_by_file,
+            LineSide::New,
+            &mut attributions,
+            prompts,
+            cache,
+        );
+    }
+
+    Ok(attributions)
+}
 
-        if line_ranges.is_empty() {
+/// Carry attribution over to lines of a copied file that git reports as added but that
+/// appear verbatim in the copy source, e.g. because they moved within the copy. Those lines
+/// are blamed in the source at `from_commit`, so they keep the source's authorship log, and
+/// are taken out of the ranges in `lines_by_file`. Lines with no verbatim source line, or
+/// whose source line has no data, stay in `lines_by_file` to be blamed against the new commit.
+fn carry_copied_attributions(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    lines_by_file: &mut BTreeMap<String, Vec<Range<u32>>>,
+    attributions: &mut HashMap<DiffLineKey, Attribution>,
+    prompts: &mut LinePrompts,
+    cache: &Arc<AuthorshipLogCache>,
+) {
+    let copy_sources: BTreeMap<&str, &str> = hunks
+        .iter()
+        .filter(|hunk| hunk.copied)
+        .map(|hunk| (hunk.file_path.as_str(), hunk.old_file_path.as_str()))
+        .collect();
+
+    for (target, source) in copy_sources {
+        let Some(lines) = lines_by_file.get_mut(target) else {
+            continue;
+        };
+        let (Ok(target_content), Ok(source_content)) = (
+            repo.get_file_content(target, to_commit),
+            repo.get_file_content(source, from_commit),
+        ) else {
             continue;
+        };
+        let target_content = String::from_utf8_lossy(&target_content);
+        let source_content = String::from_utf8_lossy(&source_content);
+
+        let target_lines: Vec<&str> = target_content.lines().collect();
+        let mut source_line_numbers: HashMap<&str, u32> = HashMap::new();
+        for (index, text) in source_content.lines().enumerate() {
+            if !text.trim().is_empty() {
+                source_line_numbers.entry(text).or_insert(index as u32 + 1);
+            }
         }
 
-        // Build blame options
-        let mut options = GitAiBlameOptions::default();
-        options.oldest_commit = Some(from_commit.to_string());
-        options.newest_commit = Some(to_commit.to_string());
-        options.line_ranges = line_ranges;
-        options.no_output = true;
-
-        // Call blame to get attributions
-        let blame_result = repo.blame(&file_path, &options);
-
-        match blame_result {
-            Ok((line_authors, prompt_records)) => {
-                // Map blame results to Attribution enum
-                for line in &lines {
-                    if let Some(author) = line_authors.get(line) {
-                        // Check if this author is an AI tool by looking up in prompt_records
-                        let attribution = if prompt_records
-                            .values()
-                            .any(|pr| &pr.agent_id.tool == author)
-                        {
-                            Attribution::Ai(author.clone())
-                        } else {
-                            Attribution::Human(author.clone())
-                        };
-
-                        let key = DiffLineKey {
-                            file: file_path.clone(),
-                            line: *line,
-                            side: LineSide::New,
-                        };
-                        attributions.insert(key, attribution);
-                    } else {
-                        // No blame data for this line
-                        let key = DiffLineKey {
-                            file: file_path.clone(),
-                            line: *line,
-                            side: LineSide::New,
-                        };
-                        attributions.insert(key, Attribution::NoData);
-                    }
+        // (line in the copy, line in the source) for added lines found in the source
+        let copied_lines: Vec<(u32, u32)> = lines
+            .iter()
+            .flat_map(|range| range.clone())
+            .filter_map(|line| {
+                let text = target_lines.get((line as usize).checked_sub(1)?)?;
+                Some((line, *source_line_numbers.get(text)?))
+            })
+            .collect();
+        if copied_lines.is_empty() {
+            continue;
+        }
+
+        let mut source_attributions = HashMap::new();
+        let mut source_prompts = LinePrompts::default();
+        let source_lines = copied_lines
+            .iter()
+            .map(|(_, line)| *line..*line + 1)
+            .collect();
+        blame_lines_into(
+            repo,
+            None,
+            from_commit,
+            BTreeMap::from([(source.to_string(), source_lines)]),
+            LineSide::New,
+            &mut source_attributions,
+            &mut source_prompts,
+            cache,
+        );
+
+        let source_file: Arc<str> = source.into();
+        let target_file: Arc<str> = target.into();
+        let mut carried = BTreeSet::new();
+        for (line, source_line) in copied_lines {
+            let source_key = DiffLineKey {
+                file: Arc::clone(&source_file),
+                line: source_line,
+                side: LineSide::New,
+            };
+            let attribution = match source_attributions.get(&source_key) {
+                Some(Attribution::NoData) | None => continue,
+                Some(attribution) => attribution.clone(),
+            };
+            let key = DiffLineKey {
+                file: Arc::clone(&target_file),
+                line,
+                side: LineSide::New,
+            };
+            if let Some(hash) = source_prompts.by_line.get(&source_key) {
+                if let Some(text) = source_prompts.texts.get(hash) {
+                    prompts.texts.insert(hash.clone(), text.clone());
                 }
+                prompts.by_line.insert(key.clone(), hash.clone());
             }
-            Err(_) => {
-                // Blame failed, mark all lines as NoData
-                for line in &lines {
-                    let key = DiffLineKey {
-                        file: file_path.clone(),
-                        line: *line,
-                        side: LineSide::New,
-                    };
-                    attributions.insert(key, Attribution::NoData);
-                }
+            if let Some(version) = source_prompts.tool_versions.get(&source_key) {
+                prompts.tool_versions.insert(key.clone(), version.clone());
             }
+            prompts
+                .provenance
+                .insert(key.clone(), AttributionProvenance::CarriedOverRename);
+            attributions.insert(key, attribution);
+            carried.insert(line);
         }
+        let remaining = collect_line_ranges(
+            lines
+                .iter()
+                .flat_map(|range| range.clone())
+                .filter(|line| !carried.contains(line)),
+        );
+        *lines = remaining;
     }
-
-    Ok(attributions)
 }
 
-/// Convert a sorted list of line numbers to contiguous ranges
-/// e.g., [1, 2, 3, 5, 6, 10] -> [(1, 3), (5, 6), (10, 10)]
-fn lines_to_ranges(lines: &[u32]) -> Vec<(u32, u32)> {
-    if lines.is_empty() {
-        return Vec::new();
+/// Fewest alphanumeric characters a block of lines needs to count as moved, git's default
+/// for `--color-moved`, so that lines like `}` aren't taken for moves
+const MIN_MOVED_BLOCK_ALNUM: usize = 20;
+
+/// Give lines that moved within a file (--detect-moves) the attribution they had before the
+/// move. Like `git diff --color-moved`, a block of deleted lines that is added back verbatim
+/// elsewhere in the same file is a move; blocks are matched by content. The deleted lines are
+/// blamed at `from_commit` and their attribution replaces what the overlay gave the added
+/// lines, which would otherwise credit whoever made the move. Sources with no data are skipped.
+pub fn carry_moved_attributions(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    attributions: &mut HashMap<DiffLineKey, Attribution>,
+    prompts: &mut LinePrompts,
+    cache: &Arc<AuthorshipLogCache>,
+) {
+    // Deleted and added lines per file, leaving out renames and copies
+    let mut changed_lines: BTreeMap<&str, (Vec<u32>, Vec<u32>)> = BTreeMap::new();
+    for hunk in hunks {
+        if hunk.copied || hunk.file_path != hunk.old_file_path {
+            continue;
+        }
+        let (deleted, added) = changed_lines.entry(hunk.file_path.as_str()).or_default();
+        deleted.extend(hunk.deleted_lines());
+        added.extend(hunk.added_lines());
     }
 
-    let mut ranges = Vec::new();
-    let mut start = lines[0];
-    let mut end = lines[0];
+    for (file, (mut deleted, mut added)) in changed_lines {
+        if deleted.is_empty() || added.is_empty() {
+            continue;
+        }
+        let (Ok(old_content), Ok(new_content)) = (
+            repo.get_file_content(file, from_commit),
+            repo.get_file_content(file, to_commit),
+        ) else {
+            continue;
+        };
+        let old_content = String::from_utf8_lossy(&old_content);
+        let new_content = String::from_utf8_lossy(&new_content);
+        let old_lines: Vec<&str> = old_content.lines().collect();
+        let new_lines: Vec<&str> = new_content.lines().collect();
+
+        deleted.sort_unstable();
+        added.sort_unstable();
+        let with_text = |lines: &[u32], content: &[&str]| -> Vec<(u32, String)> {
+            lines
+                .iter()
+                .filter_map(|line| {
+                    let text = content.get((*line as usize).checked_sub(1)?)?;
+                    Some((*line, text.to_string()))
+                })
+                .collect()
+        };
+        let deleted = with_text(&deleted, &old_lines);
+        let added = with_text(&added, &new_lines);
 
-    for &line in &lines[1..] {
-        if line == end + 1 {
-            // Contiguous, extend the range
-            end = line;
-        } else {
-            // Gap found, save current range and start new one
-            ranges.push((start, end));
-            start = line;
-            end = line;
+        let moved = match_moved_blocks(&deleted, &added);
+        if moved.is_empty() {
+            continue;
         }
-    }
 
-    // Don't forget the last range
-    ranges.push((start, end));
+        let mut source_attributions = HashMap::new();
+        let mut source_prompts = LinePrompts::default();
+        let source_lines = moved
+            .iter()
+            .map(|(_, old_line)| *old_line..*old_line + 1)
+            .collect();
+        blame_lines_into(
+            repo,
+            None,
+            from_commit,
+            BTreeMap::from([(file.to_string(), source_lines)]),
+            LineSide::Old,
+            &mut source_attributions,
+            &mut source_prompts,
+            cache,
+        );
 
-    ranges
+        let path: Arc<str> = file.into();
+        for (new_line, old_line) in moved {
+            let source_key = DiffLineKey {
+                file: Arc::clone(&path),
+                line: old_line,
+                side: LineSide::Old,
+            };
+            let attribution = match source_attributions.get(&source_key) {
+                Some(Attribution::NoData) | None => continue,
+                Some(attribution) => attribution.clone(),
+            };
+            let key = DiffLineKey {
+                file: Arc::clone(&path),
+                line: new_line,
+                side: LineSide::New,
+            };
+            prompts.by_line.remove(&key);
+            if let Some(hash) = source_prompts.by_line.get(&source_key) {
+                if let Some(text) = source_prompts.texts.get(hash) {
+                    prompts.texts.insert(hash.clone(), text.clone());
+                }
+                prompts.by_line.insert(key.clone(), hash.clone());
+            }
+            prompts.tool_versions.remove(&key);
+            if let Some(version) = source_prompts.tool_versions.get(&source_key) {
+                prompts.tool_versions.insert(key.clone(), version.clone());
+            }
+            prompts
+                .provenance
+                .insert(key.clone(), AttributionProvenance::Moved);
+            attributions.insert(key, attribution);
+        }
+    }
 }
 
-// ============================================================================
-// JSON Output Building
-// ============================================================================
+/// Pair added lines with the deleted lines they were moved from, as (new line, old line).
+/// Both inputs are (line number, text) in ascending order. Each added line starts the longest
+/// run of consecutive lines matching consecutive unused deleted lines; runs with fewer than
+/// `MIN_MOVED_BLOCK_ALNUM` alphanumeric characters aren't moves.
+fn match_moved_blocks(deleted: &[(u32, String)], added: &[(u32, String)]) -> Vec<(u32, u32)> {
+    let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, (_, text)) in deleted.iter().enumerate() {
+        if !text.trim().is_empty() {
+            positions.entry(text.as_str()).or_default().push(index);
+        }
+    }
 
-/// Build the DiffJson structure for --json output
-fn build_diff_json(
+    let mut used = vec![false; deleted.len()];
+    let mut moved = Vec::new();
+    let mut next = 0;
+    while next < added.len() {
+        let run_length = |start: usize| {
+            (0..)
+                .take_while(|&offset| {
+                    let (Some(new), Some(old)) =
+                        (added.get(next + offset), deleted.get(start + offset))
+                    else {
+                        return false;
+                    };
+                    !used[start + offset]
+                        && new.1 == old.1
+                        && new.0 == added[next].0 + offset as u32
+                        && old.0 == deleted[start].0 + offset as u32
+                })
+                .count()
+        };
+        let best = positions
+            .get(added[next].1.as_str())
+            .into_iter()
+            .flatten()
+            .map(|&start| (start, run_length(start)))
+            .max_by_key(|&(start, length)| (length, std::cmp::Reverse(start)));
+
+        let Some((start, length)) = best.filter(|&(_, length)| length > 0) else {
+            next += 1;
+            continue;
+        };
+        let alnum: usize = added[next..next + length]
+            .iter()
+            .map(|(_, text)| text.chars().filter(|c| c.is_alphanumeric()).count())
+            .sum();
+        if alnum < MIN_MOVED_BLOCK_ALNUM {
+            next += 1;
+            continue;
+        }
+        for offset in 0..length {
+            used[start + offset] = true;
+            moved.push((added[next + offset].0, deleted[start + offset].0));
+        }
+        next += length;
+    }
+
+    moved
+}
+
+/// Attribute both sides of a reversed diff (`from_commit` is the newer commit). Added lines
+/// only exist in `to_commit`, so they're blamed there without a lower bound, since they
+/// predate the range. Deleted lines are the ones the forward diff adds, and are blamed over
+/// the forward range exactly as `overlay_diff_attributions` would.
+pub fn overlay_reversed_diff_attributions(
     repo: &Repository,
     from_commit: &str,
     to_commit: &str,
     hunks: &[DiffHunk],
-    attributions: &HashMap<DiffLineKey, Attribution>,
-) -> Result<DiffJson, GitAiError> {
-    let mut files: BTreeMap<String, FileDiffJson> = BTreeMap::new();
-    let mut all_prompts: BTreeMap<String, PromptRecord> = BTreeMap::new();
+    prompts: &mut LinePrompts,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
+    let mut attributions = HashMap::new();
+    let cache = &Arc::new(AuthorshipLogCache::default());
 
-    // Get the full diff output and split by file
-    let file_diffs = get_diff_split_by_file(repo, from_commit, to_commit)?;
+    let mut added_by_file: BTreeMap<String, Vec<Range<u32>>> = BTreeMap::new();
+    let mut deleted_by_file: BTreeMap<String, Vec<Range<u32>>> = BTreeMap::new();
+    for hunk in hunks {
+        if !hunk.added.is_empty() {
+            added_by_file
+                .entry(hunk.file_path.clone())
+                .or_default()
+                .push(hunk.added_lines());
+        }
+        if !hunk.deleted.is_empty() {
+            deleted_by_file
+                .entry(hunk.old_file_path.clone())
+                .or_default()
+                .push(hunk.deleted_lines());
+        }
+    }
 
-    // Get unique files from hunks
-    let mut unique_files: Vec<String> = hunks.iter().map(|h| h.file_path.clone()).collect();
-    unique_files.sort();
-    unique_files.dedup();
+    blame_lines_into(
+        repo,
+        None,
+        to_commit,
+        added_by_file,
+        LineSide::New,
+        &mut attributions,
+        prompts,
+        cache,
+    );
+    blame_lines_into(
+        repo,
+        Some(to_commit),
+        from_commit,
+        deleted_by_file,
+        LineSide::Old,
+        &mut attributions,
+        prompts,
+        cache,
+    );
 
-    // For each file, collect annotations, diff, and base content
-    for file_path in &unique_files {
-        // Get annotations for this file (lines attributed to AI prompts)
-        let file_annotations =
-            collect_file_annotations(repo, from_commit, to_commit, file_path, hunks)?;
+    Ok(attributions)
+}
 
-        // Merge prompt records into the global map
-        for (hash, prompt_record) in &file_annotations.1 {
-            all_prompts.insert(hash.clone(), prompt_record.clone());
+/// Attribute the binary files added or modified between `from_commit` and `to_commit`.
+/// They have no lines to blame, so each gets a file-level key, attributed to the last commit
+/// up to `to_commit` that touched it: the AI tool when that commit's note attests the file,
+/// otherwise the commit's git author.
+pub fn overlay_binary_file_attributions(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    pathspecs: &[String],
+    attributions: &mut HashMap<DiffLineKey, Attribution>,
+) -> Result<(), GitAiError> {
+    for file_path in get_changed_binary_files(repo, from_commit, to_commit, pathspecs)? {
+        if let Some(attribution) = attribute_file_at(repo, to_commit, &file_path)? {
+            attributions.insert(DiffLineKey::file_level(&file_path), attribution);
         }
+    }
 
-        // Get the diff for this file
-        let diff = file_diffs.get(file_path).cloned().unwrap_or_default();
+    Ok(())
+}
 
-        // Get base content (file content at from_commit)
-        let base_content = match repo.get_file_content(file_path, from_commit) {
-            Ok(bytes) => String::from_utf8(bytes).unwrap_or_default(),
-            Err(_) => String::new(), // File didn't exist in from_commit (new file)
+/// Paths in the new tree of the binary files added or modified between `from` and `to`,
+/// using the same rename/copy detection as the displayed diff
+fn get_changed_binary_files(
+    repo: &Repository,
+    from: &str,
+    to: &str,
+    pathspecs: &[String],
+) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("--numstat".to_string());
+    args.push("-z".to_string());
+    args.push("-M".to_string());
+    args.push("-C".to_string());
+    args.push("--diff-filter=d".to_string()); // Deleted files have nothing to attribute
+    push_diff_revisions(&mut args, from, to);
+    push_pathspecs(&mut args, pathspecs);
+
+    let output = exec_git(&args)?;
+    let numstat =
+        String::from_utf8(output.stdout).map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+
+    Ok(parse_binary_numstat(&numstat))
+}
+
+/// Pick the files git reports as binary ("-\t-") out of `git diff --numstat -z` output.
+/// Renames and copies have an empty path followed by the old and new paths as separate fields.
+fn parse_binary_numstat(numstat: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut fields = numstat.split('\0');
+
+    while let Some(record) = fields.next() {
+        let mut parts = record.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let path = if path.is_empty() {
+            let _old_path = fields.next();
+            match fields.next() {
+                Some(new_path) => new_path,
+                None => break,
+            }
+        } else {
+            path
         };
 
-        files.insert(
-            file_path.clone(),
-            FileDiffJson {
-                annotations: file_annotations.0,
-                diff,
-                base_content,
-            },
+        if added == "-" && deleted == "-" {
+            files.push(path.to_string());
+        }
+    }
+
+    files
+}
+
+/// Attribute a whole file to the last commit up to `commit` that touched it
+fn attribute_file_at(
+    repo: &Repository,
+    commit: &str,
+    file_path: &str,
+) -> Result<Option<Attribution>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("-1".to_string());
+    args.push("--format=%H%x1f%an".to_string());
+    args.push(commit.to_string());
+    args.push("--".to_string());
+    args.push(file_path.to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|e| GitAiError::Generic(format!("Failed to parse log output: {}", e)))?;
+    let Some((sha, author)) = stdout.trim_end().split_once('\u{1f}') else {
+        return Ok(None);
+    };
+
+    let tool = get_reference_as_authorship_log_v3(repo, sha)
+        .ok()
+        .and_then(|log| {
+            log.attestations
+                .iter()
+                .find(|attestation| attestation.file_path == file_path)
+                .and_then(|attestation| attestation.entries.first())
+                .and_then(|entry| log.metadata.prompts.get(&entry.hash))
+                .map(|record| record.agent_id.tool.clone())
+        });
+
+    Ok(Some(match tool {
+        Some(tool) => Attribution::Ai(normalize_tool_name(&tool)),
+        None => Attribution::Human(author.to_string()),
+    }))
+}
+
+/// The file-level attribution for a "Binary files a/<old> and b/<new> differ" marker
+fn binary_marker_attribution<'a>(
+    line: &str,
+    relative_dir: &str,
+    attributions: &'a HashMap<DiffLineKey, Attribution>,
+) -> Option<&'a Attribution> {
+    let paths = line
+        .strip_prefix("Binary files ")?
+        .strip_suffix(" differ")?;
+    let (_, new_path) = paths.rsplit_once(" and ")?;
+    let new_path = unquote_git_path(new_path);
+    let new_path = format!("{}{}", relative_dir, new_path.strip_prefix("b/")?);
+    attributions.get(&DiffLineKey::file_level(&new_path))
+}
+
+/// Blame the given line ranges of each file at `newest_commit` (stopping at `oldest_commit`
+/// when set) and record the result for `side`, along with the prompt behind each AI line.
+/// Lines blame can't resolve are marked `NoData`.
+fn blame_lines_into(
+    repo: &Repository,
+    oldest_commit: Option<&str>,
+    newest_commit: &str,
+    lines_by_file: BTreeMap<String, Vec<Range<u32>>>,
+    side: LineSide,
+    attributions: &mut HashMap<DiffLineKey, Attribution>,
+    prompts: &mut LinePrompts,
+    cache: &Arc<AuthorshipLogCache>,
+) {
+    // For each file, call blame with the appropriate line ranges
+    for (file_path, ranges) in lines_by_file {
+        let (file_attributions, file_prompts) = blame_file_lines(
+            repo,
+            oldest_commit,
+            newest_commit,
+            file_path,
+            ranges,
+            &side,
+            cache,
         );
+        attributions.extend(file_attributions);
+        prompts.merge(file_prompts);
+    }
+}
+
+/// `blame_lines_into` with files blamed concurrently. Each file collects its own prompts,
+/// merged in file order afterwards, so the result is the same as the serial version's.
+fn blame_lines_into_parallel(
+    repo: &Repository,
+    oldest_commit: Option<&str>,
+    newest_commit: &str,
+    lines_by_file: BTreeMap<String, Vec<Range<u32>>>,
+    side: LineSide,
+    attributions: &mut HashMap<DiffLineKey, Attribution>,
+    prompts: &mut LinePrompts,
+    cache: &Arc<AuthorshipLogCache>,
+) {
+    const MAX_CONCURRENT: usize = 30;
+
+    let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
+    let tasks: Vec<_> = lines_by_file
+        .into_iter()
+        .map(|(file_path, ranges)| {
+            let repo = repo.clone();
+            let oldest_commit = oldest_commit.map(str::to_string);
+            let newest_commit = newest_commit.to_string();
+            let side = side.clone();
+            let semaphore = Arc::clone(&semaphore);
+            let cache = Arc::clone(cache);
+
+            smol::spawn(async move {
+                // Acquire semaphore permit to limit concurrency
+                let _permit = semaphore.acquire().await;
+
+                // Wrap blocking git operations in smol::unblock
+                smol::unblock(move || {
+                    blame_file_lines(
+                        &repo,
+                        oldest_commit.as_deref(),
+                        &newest_commit,
+                        file_path,
+                        ranges,
+                        &side,
+                        &cache,
+                    )
+                })
+                .await
+            })
+        })
+        .collect();
+
+    // join_all keeps task order, which is file order
+    let results = smol::block_on(futures::future::join_all(tasks));
+    for (file_attributions, file_prompts) in results {
+        attributions.extend(file_attributions);
+        prompts.merge(file_prompts);
+    }
+}
+
+/// Changed lines in a file from which blame resolves each commit's attributions for the whole
+/// file at once, instead of scanning the authorship log once per line
+const BATCH_ATTRIBUTION_MIN_LINES: usize = 200;
+
+/// Blame the lines in `ranges` of one file, returning their attributions and the prompts
+/// behind them. Lines blame has no data for, or all lines if blame fails, are `NoData`.
+fn blame_file_lines(
+    repo: &Repository,
+    oldest_commit: Option<&str>,
+    newest_commit: &str,
+    file_path: String,
+    ranges: Vec<Range<u32>>,
+    side: &LineSide,
+    cache: &Arc<AuthorshipLogCache>,
+) -> (HashMap<DiffLineKey, Attribution>, LinePrompts) {
+    let mut attributions = HashMap::new();
+    let mut prompts = LinePrompts::default();
+
+    // Sort and merge into disjoint ranges for efficient -L format
+    let line_ranges = merge_line_ranges(ranges);
+
+    if line_ranges.is_empty() {
+        return (attributions, prompts);
+    }
+    let line_count: usize = line_ranges
+        .iter()
+        .map(|(start, end)| (end - start) as usize + 1)
+        .sum();
+
+    // Build blame options
+    let mut options = GitAiBlameOptions::default();
+    options.oldest_commit = oldest_commit.map(str::to_string);
+    options.newest_commit = Some(newest_commit.to_string());
+    options.line_ranges = line_ranges.clone();
+    options.no_output = true;
+    // AI lines are named by prompt hash, so the prompt record can be looked up
+    options.use_prompt_hashes_as_names = true;
+    options.authorship_log_cache = Some(Arc::clone(cache));
+    options.batch_line_attribution = line_count >= BATCH_ATTRIBUTION_MIN_LINES;
+
+    // Call blame to get attributions
+    let blame_result = repo.blame_with_provenance(&file_path, &options);
+
+    let file: Arc<str> = file_path.into();
+    let lines = line_ranges.iter().flat_map(|(start, end)| *start..=*end);
+    match blame_result {
+        Ok((line_authors, prompt_records, line_provenance)) => {
+            // Map blame results to Attribution enum
+            for line in lines {
+                let key = DiffLineKey {
+                    file: Arc::clone(&file),
+                    line,
+                    side: side.clone(),
+                };
+                if let Some(author) = line_authors.get(&line) {
+                    // AI lines are named by a prompt hash found in prompt_records
+                    let attribution = match prompt_records.get(author) {
+                        Some(record) => {
+                            prompts.record(key.clone(), author, record);
+                            if let Some(provenance) = line_provenance.get(&line) {
+                                prompts.provenance.insert(key.clone(), *provenance);
+                            }
+                            Attribution::Ai(normalize_tool_name(&record.agent_id.tool))
+                        }
+                        None => Attribution::Human(author.clone()),
+                    };
+                    attributions.insert(key, attribution);
+                } else {
+                    // No blame data for this line
+                    attributions.insert(key, Attribution::NoData);
+                }
+            }
+        }
+        Err(_) => {
+            // Blame failed, mark all lines as NoData
+            for line in lines {
+                let key = DiffLineKey {
+                    file: Arc::clone(&file),
+                    line,
+                    side: side.clone(),
+                };
+                attributions.insert(key, Attribution::NoData);
+            }
+        }
+    }
+
+    (attributions, prompts)
+}
+
+/// Attribute added lines in a working tree diff using the in-progress working log
+/// for `base_commit` (the same data post-commit turns into an authorship log).
+/// Lines the working log doesn't cover are marked `NoData`.
+pub fn overlay_working_tree_attributions(
+    repo: &Repository,
+    base_commit: &str,
+    hunks: &[DiffHunk],
+    prompts: &mut LinePrompts,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
+    let mut attributions = HashMap::new();
+
+    let human_author = match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    };
+
+    let working_va = VirtualAttributions::from_just_working_log(
+        repo.clone(),
+        base_commit.to_string(),
+        Some(human_author.clone()),
+    )
+    .ok();
+
+    for hunk in hunks {
+        let file: Arc<str> = hunk.file_path.as_str().into();
+        for line in hunk.added_lines() {
+            let key = DiffLineKey {
+                file: Arc::clone(&file),
+                line,
+                side: LineSide::New,
+            };
+            let attribution = working_va
+                .as_ref()
+                .and_then(|va| working_log_attribution(va, &hunk.file_path, line, &human_author))
+                .map(|(attribution, prompt)| {
+                    if let Some((prompt_hash, record)) = prompt {
+                        prompts.record(key.clone(), prompt_hash, record);
+                    }
+                    attribution
+                })
+                .unwrap_or(Attribution::NoData);
+            attributions.insert(key, attribution);
+        }
+    }
+
+    Ok(attributions)
+}
+
+/// Attribute a diff of the index against `base_commit` (HEAD). Staged added lines take their
+/// attribution from the working log, which tracks the working tree, so each is first mapped
+/// to its line in the working tree; a line changed again since staging has no data. Deleted
+/// lines still exist in HEAD and are blamed there.
+pub fn overlay_index_attributions(
+    repo: &Repository,
+    base_commit: &str,
+    hunks: &[DiffHunk],
+    prompts: &mut LinePrompts,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError> {
+    let mut attributions = HashMap::new();
+    let cache = &Arc::new(AuthorshipLogCache::default());
+
+    let human_author = match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    };
+
+    let working_va = VirtualAttributions::from_just_working_log(
+        repo.clone(),
+        base_commit.to_string(),
+        Some(human_author.clone()),
+    )
+    .ok();
+
+    // Index -> working tree changes, keyed by the file's path in the index
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("-U0".to_string());
+    args.push("--no-color".to_string());
+    let output = exec_git(&args)?;
+    let diff_text =
+        String::from_utf8(output.stdout).map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+    let mut unstaged_by_file: HashMap<String, Vec<DiffHunk>> = HashMap::new();
+    for hunk in parse_diff_hunks(&diff_text)? {
+        unstaged_by_file
+            .entry(hunk.old_file_path.clone())
+            .or_default()
+            .push(hunk);
+    }
+
+    let mut deleted_by_file: BTreeMap<String, Vec<Range<u32>>> = BTreeMap::new();
+    for hunk in hunks {
+        let unstaged = unstaged_by_file
+            .get(&hunk.file_path)
+            .map_or(&[][..], Vec::as_slice);
+        let file: Arc<str> = hunk.file_path.as_str().into();
+        for line in hunk.added_lines() {
+            let key = DiffLineKey {
+                file: Arc::clone(&file),
+                line,
+                side: LineSide::New,
+            };
+            let attribution = index_line_to_worktree(unstaged, line)
+                .and_then(|worktree_line| {
+                    working_va.as_ref().and_then(|va| {
+                        working_log_attribution(va, &hunk.file_path, worktree_line, &human_author)
+                    })
+                })
+                .map(|(attribution, prompt)| {
+                    if let Some((prompt_hash, record)) = prompt {
+                        prompts.record(key.clone(), prompt_hash, record);
+                    }
+                    attribution
+                })
+                .unwrap_or(Attribution::NoData);
+            attributions.insert(key, attribution);
+        }
+
+        if !hunk.deleted.is_empty() {
+            deleted_by_file
+                .entry(hunk.old_file_path.clone())
+                .or_default()
+                .push(hunk.deleted_lines());
+        }
+    }
+
+    blame_lines_into(
+        repo,
+        None,
+        base_commit,
+        deleted_by_file,
+        LineSide::Old,
+        &mut attributions,
+        prompts,
+        cache,
+    );
+
+    Ok(attributions)
+}
+
+/// Map a line of a file's staged content to the same line in the working tree, given the
+/// file's `-U0` hunks from the index to the working tree. None if the line was changed or
+/// removed after it was staged.
+fn index_line_to_worktree(unstaged: &[DiffHunk], line: u32) -> Option<u32> {
+    let mut offset: i64 = 0;
+    for hunk in unstaged {
+        if hunk.old_count == 0 {
+            // Pure insertion after old_start
+            if line <= hunk.old_start {
+                break;
+            }
+        } else {
+            if line < hunk.old_start {
+                break;
+            }
+            if line < hunk.old_start + hunk.old_count {
+                return None;
+            }
+        }
+        offset += hunk.new_count as i64 - hunk.old_count as i64;
+    }
+    u32::try_from(line as i64 + offset).ok()
+}
+
+/// Look up the working log attribution for a single line of a file, with the prompt hash
+/// and record for AI lines
+fn working_log_attribution<'a>(
+    working_va: &'a VirtualAttributions,
+    file_path: &str,
+    line: u32,
+    human_author: &str,
+) -> Option<(Attribution, Option<(&'a str, &'a PromptRecord)>)> {
+    let line_attr = working_va
+        .get_line_attributions(file_path)?
+        .iter()
+        .find(|attr| attr.start_line <= line && line <= attr.end_line)?;
+
+    if line_attr.author_id == CheckpointKind::Human.to_str() {
+        return Some((Attribution::Human(human_author.to_string()), None));
+    }
+
+    let (prompt_hash, records) = working_va.prompts().get_key_value(&line_attr.author_id)?;
+    let record = records.values().next()?;
+    Some((
+        Attribution::Ai(normalize_tool_name(&record.agent_id.tool)),
+        Some((prompt_hash.as_str(), record)),
+    ))
+}
+
+/// Sort and merge line ranges into disjoint inclusive (start, end) pairs, the form blame's
+/// -L takes, e.g. [5..7, 1..3, 2..4] -> [(1, 3), (5, 6)]
+fn merge_line_ranges(mut ranges: Vec<Range<u32>>) -> Vec<(u32, u32)> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut merged: Vec<(u32, u32)> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            // Overlapping or adjacent, extend the last range
+            Some((_, end)) if range.start <= *end + 1 => *end = (*end).max(range.end - 1),
+            _ => merged.push((range.start, range.end - 1)),
+        }
+    }
+    merged
+}
+
+/// Add `line` to `ranges`, extending the last range when `line` directly follows it
+fn push_line(ranges: &mut Vec<Range<u32>>, line: u32) {
+    match ranges.last_mut() {
+        Some(last) if last.end == line => last.end += 1,
+        _ => ranges.push(line..line + 1),
+    }
+}
+
+/// Collect line numbers into ranges of consecutive lines, e.g. [1, 2, 3, 5] -> [1..4, 5..6]
+fn collect_line_ranges(lines: impl IntoIterator<Item = u32>) -> Vec<Range<u32>> {
+    let mut ranges = Vec::new();
+    for line in lines {
+        push_line(&mut ranges, line);
+    }
+    ranges
+}
+
+/// Convert a sorted list of line numbers to contiguous ranges
+/// e.g., [1, 2, 3, 5, 6, 10] -> [(1, 3), (5, 6), (10, 10)]
+fn lines_to_ranges(lines: &[u32]) -> Vec<(u32, u32)> {
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranges = Vec::new();
+    let mut start = lines[0];
+    let mut end = lines[0];
+
+    for &line in &lines[1..] {
+        if line == end + 1 {
+            // Contiguous, extend the range
+            end = line;
+        } else {
+            // Gap found, save current range and start new one
+            ranges.push((start, end));
+            start = line;
+            end = line;
+        }
+    }
+
+    // Don't forget the last range
+    ranges.push((start, end));
+
+    ranges
+}
+
+// ============================================================================
+// JSON Output Building
+// ============================================================================
+
+/// Build the DiffJson structure for --json output
+fn build_diff_json(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    attributions: &HashMap<DiffLineKey, Attribution>,
+) -> Result<DiffJson, GitAiError> {
+    let mut files: BTreeMap<String, FileDiffJson> = BTreeMap::new();
+    let mut all_prompts: BTreeMap<String, PromptRecord> = BTreeMap::new();
+
+    // Get the full diff output and split by file
+    let file_diffs = get_diff_split_by_file(repo, from_commit, to_commit)?;
+
+    // Get unique files from hunks
+    let mut unique_files: Vec<String> = hunks.iter().map(|h| h.file_path.clone()).collect();
+    unique_files.sort();
+    unique_files.dedup();
+
+    // For each file, collect annotations, diff, and base content
+    for file_path in &unique_files {
+        // Get annotations for this file (lines attributed to AI prompts)
+        let file_annotations =
+            collect_file_annotations(repo, from_commit, to_commit, file_path, hunks)?;
+
+        // Merge prompt records into the global map
+        for (hash, prompt_record) in &file_annotations.1 {
+            all_prompts.insert(hash.clone(), prompt_record.clone());
+        }
+
+        // Get the diff for this file
+        let diff = file_diffs.get(file_path).cloned().unwrap_or_default();
+
+        // Get base content (file content at from_commit)
+        let base_content = match repo.get_file_content(file_path, from_commit) {
+            Ok(bytes) => String::from_utf8(bytes).unwrap_or_default(),
+            Err(_) => String::new(), // File didn't exist in from_commit (new file)
+        };
+
+        files.insert(
+            file_path.clone(),
+            FileDiffJson {
+                annotations: file_annotations.0,
+                diff,
+                base_content,
+            },
+        );
+    }
+
+    Ok(DiffJson {
+        files,
+        prompts: all_prompts,
+    })
+}
+
+/// Get the unified diff split by file path
+fn get_diff_split_by_file(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+) -> Result<HashMap<String, String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("-M".to_string());
+    args.push("-C".to_string());
+    args.push("--no-color".to_string());
+    args.push(from_commit.to_string());
+    args.push(to_commit.to_string());
+
+    let output = exec_git(&args)?;
+    let diff_text =
+        String::from_utf8(output.stdout).map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+
+    let mut file_diffs: HashMap<String, String> = HashMap::new();
+    let mut current_file = String::new();
+    let mut current_diff = String::new();
+
+    for line in diff_text.lines() {
+        if line.starts_with("diff --git") {
+            // Save previous file's diff if any
+            if !current_file.is_empty() && !current_diff.is_empty() {
+                file_diffs.insert(current_file.clone(), current_diff.clone());
+            }
+            current_diff = format!("{}\n", line);
+            current_file.clear();
+        } else if let Some(new_path) = diff_header_path(line, "+++ ", "b/") {
+            current_file = new_path;
+            current_diff.push_str(line);
+            current_diff.push('\n');
+        } else {
+            current_diff.push_str(line);
+            current_diff.push('\n');
+        }
+    }
+
+    // Don't forget the last file
+    if !current_file.is_empty() && !current_diff.is_empty() {
+        file_diffs.insert(current_file, current_diff);
+    }
+
+    Ok(file_diffs)
+}
+
+/// Collect annotations for a specific file, returning (annotations_map, prompt_records_map)
+fn collect_file_annotations(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    file_path: &str,
+    hunks: &[DiffHunk],
+) -> Result<
+    (
+        BTreeMap<String, Vec<LineRange>>,
+        HashMap<String, PromptRecord>,
+    ),
+    GitAiError,
+> {
+    let mut annotations: BTreeMap<String, Vec<LineRange>> = BTreeMap::new();
+    let mut prompt_records: HashMap<String, PromptRecord> = HashMap::new();
+
+    // Collect all added lines for this file
+    let mut added_lines: Vec<u32> = Vec::new();
+    for hunk in hunks {
+        if hunk.file_path == file_path {
+            added_lines.extend(hunk.added_lines());
+        }
+    }
+
+    if added_lines.is_empty() {
+        return Ok((annotations, prompt_records));
+    }
+
+    added_lines.sort_unstable();
+    added_lines.dedup();
+    let line_ranges = lines_to_ranges(&added_lines);
+
+    if line_ranges.is_empty() {
+        return Ok((annotations, prompt_records));
+    }
+
+    // Build blame options - use prompt hashes as names to get the actual hash per line
+    let mut options = GitAiBlameOptions::default();
+    options.oldest_commit = Some(from_commit.to_string());
+    options.newest_commit = Some(to_commit.to_string());
+    options.line_ranges = line_ranges;
+    options.no_output = true;
+    options.use_prompt_hashes_as_names = true; // Key: get prompt hash instead of tool name
+
+    // Call blame to get attributions
+    let blame_result = repo.blame(file_path, &options);
+
+    match blame_result {
+        Ok((line_authors, blame_prompt_records)) => {
+            // Group lines by prompt hash
+            // With use_prompt_hashes_as_names=true, line_authors values are the prompt hashes
+            let mut lines_by_hash: HashMap<String, Vec<u32>> = HashMap::new();
+
+            for &line in &added_lines {
+                if let Some(prompt_hash) = line_authors.get(&line) {
+                    // Only include if this hash is in the prompt_records (i.e., it's an AI line)
+                    if blame_prompt_records.contains_key(prompt_hash) {
+                        lines_by_hash
+                            .entry(prompt_hash.clone())
+                            .or_insert_with(Vec::new)
+                            .push(line);
+                    }
+                }
+            }
+
+            // Convert lines to LineRange format and store in annotations
+            for (hash, mut lines) in lines_by_hash {
+                lines.sort_unstable();
+                lines.dedup();
+                let ranges = LineRange::compress_lines(&lines);
+                annotations.insert(hash, ranges);
+            }
+
+            // Store prompt records
+            prompt_records = blame_prompt_records;
+        }
+        Err(_) => {
+            // Blame failed, no annotations for this file
+        }
+    }
+
+    Ok((annotations, prompt_records))
+}
+
+// ============================================================================
+// Output Formatting
+// ============================================================================
+
+/// Write the annotated diff to `out` line by line while git produces it.
+///
+/// `context_attributions` annotate context lines, keyed by their new-side line; it's empty
+/// unless --context-attribution was given
+#[allow(clippy::too_many_arguments)]
+pub fn format_annotated_diff(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    context_attributions: &HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+    options: &AnnotatedDiffOptions,
+    out: &mut dyn Write,
+) -> Result<(), GitAiError> {
+    let args = display_diff_args(repo, from_commit, to_commit, options);
+    exec_git_streaming(&args, |diff| {
+        write_annotated_diff(
+            diff,
+            out,
+            attributions,
+            context_attributions,
+            prompts,
+            options,
+        )
+    })
+}
+
+/// The new-side line ranges of the context lines the displayed diff shows, per file, so
+/// they can be blamed for --context-attribution
+fn display_context_lines(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    options: &AnnotatedDiffOptions,
+) -> Result<BTreeMap<String, Vec<Range<u32>>>, GitAiError> {
+    let args = display_diff_args(repo, from_commit, to_commit, options);
+    exec_git_streaming(&args, |diff| {
+        parse_context_lines(diff, options.relative_dir())
+    })
+}
+
+/// Context line ranges (new side) per file in `git diff` output, with paths prefixed by
+/// `relative_dir` to match attribution keys
+fn parse_context_lines(
+    diff: &mut dyn BufRead,
+    relative_dir: &str,
+) -> Result<BTreeMap<String, Vec<Range<u32>>>, GitAiError> {
+    let mut context_lines: BTreeMap<String, Vec<Range<u32>>> = BTreeMap::new();
+    let mut current_file = String::new();
+    let mut new_line_num = 0u32;
+    let mut in_hunk = false;
+
+    for line in diff.lines() {
+        let line = line.map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+        let line = line.as_str();
+        if line.starts_with("diff --git") {
+            current_file.clear();
+            in_hunk = false;
+        } else if let Some(new_path) = diff_header_path(line, "+++ ", "b/") {
+            current_file = format!("{}{}", relative_dir, new_path);
+        } else if line.starts_with("@@ ") {
+            if let Some((_, new_start)) = parse_hunk_header_for_line_nums(line) {
+                new_line_num = new_start;
+                in_hunk = true;
+            }
+        } else if !in_hunk || current_file.is_empty() {
+            continue;
+        } else if line.starts_with('+') {
+            new_line_num += 1;
+        } else if line.starts_with(' ') {
+            push_line(
+                context_lines.entry(current_file.clone()).or_default(),
+                new_line_num,
+            );
+            new_line_num += 1;
+        }
+    }
+
+    Ok(context_lines)
+}
+
+/// The `git diff` invocation for displaying the diff with normal context, using the same
+/// rename/copy detection as get_diff_with_line_numbers so attribution keys line up with the
+/// displayed paths. The context width only affects display: attributions come from the -U0 pass.
+fn display_diff_args(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    options: &AnnotatedDiffOptions,
+) -> Vec<String> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    push_copy_detection(&mut args, options.find_copies_harder);
+    if let Some(context_lines) = options.context_lines {
+        args.push(format!("-U{}", context_lines));
+    }
+    push_relative(&mut args, options);
+    args.push("--no-color".to_string());
+    push_diff_revisions(&mut args, from_commit, to_commit);
+    push_pathspecs(&mut args, &options.pathspecs);
+    args
+}
+
+/// Annotate `git diff` output line by line as it's read from `diff`, writing each line to
+/// `out` as soon as it's final. Nothing is held unless filtering by attribution, when the
+/// current hunk is, so that it can be dropped if it's left without changes.
+fn write_annotated_diff(
+    diff: &mut dyn BufRead,
+    out: &mut dyn Write,
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    context_attributions: &HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+    options: &AnnotatedDiffOptions,
+) -> Result<(), GitAiError> {
+    // Displayed paths are relative to this; attributions are keyed by repository paths
+    let relative_dir = options.relative_dir();
+
+    // Check if we should use colors
+    let use_color = options.color.use_color();
+    let glyphs = options.glyphs();
+    let header = |line: &str| format_line(line, LineType::DiffHeader, use_color);
+
+    let mut prompt_notes = options
+        .show_prompt
+        .then(|| PromptNotes::new(prompts, terminal_width(options), &glyphs));
+
+    // Parse and annotate diff
+    out.write_all(format_tool_legend(attributions, use_color, &glyphs).as_bytes())?;
+    let mut current_file: Arc<str> = Arc::from("");
+    let mut current_old_file: Arc<str> = Arc::from("");
+    let mut old_line_num = 0u32;
+    let mut new_line_num = 0u32;
+
+    // When filtering by attribution, lines of the current hunk are buffered so context can
+    // be dropped for hunks that end up with no matching changes
+    let mut hunk = HunkBuffer::new(options.attribution_filter.is_some());
+
+    for line in diff.lines() {
+        let line = line.map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+        let line = line.as_str();
+        if line.starts_with("diff --git") {
+            // Diff header
+            hunk.flush_into(out)?;
+            out.write_all(header(line).as_bytes())?;
+            current_file = Arc::from("");
+            current_old_file = Arc::from("");
+            old_line_num = 0;
+            new_line_num = 0;
+        } else if line.starts_with("index ") {
+            out.write_all(header(line).as_bytes())?;
+        } else if line.starts_with("--- ") {
+            if let Some(old_path) = diff_header_path(line, "--- ", "a/") {
+                current_old_file = format!("{}{}", relative_dir, old_path).into();
+            }
+            out.write_all(header(line).as_bytes())?;
+        } else if let Some(new_path) = diff_header_path(line, "+++ ", "b/") {
+            current_file = format!("{}{}", relative_dir, new_path).into();
+            out.write_all(header(line).as_bytes())?;
+        } else if line.starts_with("@@ ") {
+            // Hunk header - update line counters
+            if let Some((old_start, new_start)) = parse_hunk_header_for_line_nums(line) {
+                old_line_num = old_start;
+                new_line_num = new_start;
+            }
+            hunk.start(format_line(line, LineType::HunkHeader, use_color), out)?;
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            // Deleted line
+            let key = DiffLineKey {
+                file: Arc::clone(&current_old_file),
+                line: old_line_num,
+                side: LineSide::Old,
+            };
+            let attribution = attributions.get(&key);
+            if options.matches(attribution) {
+                let note = prompt_notes
+                    .as_mut()
+                    .and_then(|notes| notes.note(&key, line, attribution));
+                hunk.push_change(
+                    format_annotated_line(
+                        line,
+                        LineType::Deletion,
+                        use_color,
+                        attribution,
+                        note.as_deref(),
+                        &glyphs,
+                    ),
+                    out,
+                )?;
+            }
+            old_line_num += 1;
+        } else if line.starts_with('+') && !line.starts_with("+++") {
+            // Added line
+            let key = DiffLineKey {
+                file: Arc::clone(&current_file),
+                line: new_line_num,
+                side: LineSide::New,
+            };
+            let attribution = attributions.get(&key);
+            if options.matches(attribution) {
+                let note = prompt_notes
+                    .as_mut()
+                    .and_then(|notes| notes.note(&key, line, attribution));
+                hunk.push_change(
+                    format_annotated_line(
+                        line,
+                        LineType::Addition,
+                        use_color,
+                        attribution,
+                        note.as_deref(),
+                        &glyphs,
+                    ),
+                    out,
+                )?;
+            }
+            new_line_num += 1;
+        } else if line.starts_with(' ') {
+            // Context line, annotated only with --context-attribution
+            let key = DiffLineKey {
+                file: Arc::clone(&current_file),
+                line: new_line_num,
+                side: LineSide::New,
+            };
+            hunk.push_context(
+                format_annotated_line(
+                    line,
+                    LineType::Context,
+                    use_color,
+                    context_attributions.get(&key),
+                    None,
+                    &glyphs,
+                ),
+                out,
+            )?;
+            old_line_num += 1;
+            new_line_num += 1;
+        } else if line.starts_with("Binary files") {
+            // Binary file marker, annotated with the file-level attribution
+            let attribution = binary_marker_attribution(line, relative_dir, attributions);
+            let binary_line = format_annotated_line(
+                line,
+                LineType::Binary,
+                use_color,
+                attribution,
+                None,
+                &glyphs,
+            );
+            out.write_all(binary_line.as_bytes())?;
+        } else if !hunk.started() {
+            // Extended header lines (e.g., "new file mode", "rename from")
+            out.write_all(format_line(line, LineType::Context, use_color).as_bytes())?;
+        } else {
+            // Other lines (e.g., "\ No newline at end of file")
+            hunk.push_context(format_line(line, LineType::Context, use_color), out)?;
+        }
+    }
+    hunk.flush_into(out)?;
+    let mut tail = String::new();
+    if let Some(notes) = &prompt_notes {
+        tail.push_str(&notes.footer());
+    }
+    if options.writes_to_terminal() {
+        push_attribution_summary(&mut tail, attributions, use_color);
+    }
+    out.write_all(tail.as_bytes())?;
+
+    Ok(())
+}
+
+/// Render the diff as a standalone HTML page (--format=html) to share outside the terminal.
+/// Every style is inline so the page survives being emailed. Added and deleted lines are
+/// tinted and carry a badge with the tool or username; a header gives the AI share of the
+/// added lines. All diff text is HTML-escaped.
+pub fn format_html_diff(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    options: &AnnotatedDiffOptions,
+) -> Result<String, GitAiError> {
+    let args = display_diff_args(repo, from_commit, to_commit, options);
+    let output = exec_git(&args)?;
+    let diff_text =
+        String::from_utf8(output.stdout).map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+
+    let relative_dir = options.relative_dir();
+    let glyphs = options.glyphs();
+
+    let (ai_lines, total_lines) = count_ai_lines(attributions);
+    let title = format!(
+        "{}..{}",
+        html_revision_label(from_commit),
+        html_revision_label(to_commit)
+    );
+    let mut page = String::new();
+    page.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    page.push_str(&format!(
+        "<title>git-ai diff {}</title>\n</head>\n",
+        escape_html(&title)
+    ));
+    page.push_str(&format!("<body style=\"{}\">\n", HTML_BODY_STYLE));
+    page.push_str(&format!(
+        "<h1 style=\"font-size:20px;margin:0 0 4px\">git-ai diff {}</h1>\n",
+        escape_html(&title)
+    ));
+    page.push_str(&format!(
+        "<p style=\"margin:0 0 4px\"><strong>{}% AI</strong> ({} of {} added lines)</p>\n",
+        ai_percentage(ai_lines, total_lines),
+        ai_lines,
+        total_lines
+    ));
+    let summary = format_attribution_summary(attributions);
+    if !summary.is_empty() {
+        page.push_str(&format!(
+            "<p style=\"margin:0 0 16px;color:#57606a\">{}</p>\n",
+            escape_html(&summary)
+        ));
+    }
+
+    let mut current_file: Arc<str> = Arc::from("");
+    let mut current_old_file: Arc<str> = Arc::from("");
+    let mut display_file = String::new();
+    let mut old_line_num = 0u32;
+    let mut new_line_num = 0u32;
+    let mut in_table = false;
+    let mut in_hunk = false;
+
+    for line in diff_text.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            if in_table {
+                page.push_str("</table>\n");
+                in_table = false;
+            }
+            in_hunk = false;
+            current_file = Arc::from("");
+            current_old_file = Arc::from("");
+            display_file = paths.to_string();
+        } else if !in_hunk && line.starts_with("--- ") {
+            if let Some(old_path) = diff_header_path(line, "--- ", "a/") {
+                current_old_file = format!("{}{}", relative_dir, old_path).into();
+                display_file = old_path;
+            }
+        } else if !in_hunk && line.starts_with("+++ ") {
+            if let Some(new_path) = diff_header_path(line, "+++ ", "b/") {
+                current_file = format!("{}{}", relative_dir, new_path).into();
+                display_file = new_path;
+            }
+        } else if line.starts_with("@@ ") {
+            if !in_table {
+                push_html_file_header(&mut page, &display_file);
+                in_table = true;
+            }
+            in_hunk = true;
+            if let Some((old_start, new_start)) = parse_hunk_header_for_line_nums(line) {
+                old_line_num = old_start;
+                new_line_num = new_start;
+            }
+            page.push_str(&format!(
+                "<tr style=\"background:#ddf4ff;color:#57606a\"><td colspan=\"4\" style=\"{}\">{}</td></tr>\n",
+                HTML_CELL_STYLE,
+                escape_html(line)
+            ));
+        } else if in_hunk && line.starts_with('-') {
+            let key = DiffLineKey {
+                file: Arc::clone(&current_old_file),
+                line: old_line_num,
+                side: LineSide::Old,
+            };
+            let attribution = attributions.get(&key);
+            if options.matches(attribution) {
+                push_html_row(
+                    &mut page,
+                    HtmlRow {
+                        background: "#ffebe9",
+                        old_line: Some(old_line_num),
+                        new_line: None,
+                        text: line,
+                        attribution,
+                        glyphs: &glyphs,
+                    },
+                );
+            }
+            old_line_num += 1;
+        } else if in_hunk && line.starts_with('+') {
+            let key = DiffLineKey {
+                file: Arc::clone(&current_file),
+                line: new_line_num,
+                side: LineSide::New,
+            };
+            let attribution = attributions.get(&key);
+            if options.matches(attribution) {
+                push_html_row(
+                    &mut page,
+                    HtmlRow {
+                        background: "#e6ffec",
+                        old_line: None,
+                        new_line: Some(new_line_num),
+                        text: line,
+                        attribution,
+                        glyphs: &glyphs,
+                    },
+                );
+            }
+            new_line_num += 1;
+        } else if in_hunk && line.starts_with(' ') {
+            push_html_row(
+                &mut page,
+                HtmlRow {
+                    background: "#ffffff",
+                    old_line: Some(old_line_num),
+                    new_line: Some(new_line_num),
+                    text: line,
+                    attribution: None,
+                    glyphs: &glyphs,
+                },
+            );
+            old_line_num += 1;
+            new_line_num += 1;
+        } else if line.starts_with("Binary files") {
+            if !in_table {
+                push_html_file_header(&mut page, &display_file);
+                in_table = true;
+            }
+            push_html_row(
+                &mut page,
+                HtmlRow {
+                    background: "#f6f8fa",
+                    old_line: None,
+                    new_line: None,
+                    text: line,
+                    attribution: binary_marker_attribution(line, relative_dir, attributions),
+                    glyphs: &glyphs,
+                },
+            );
+        } else if in_hunk {
+            // Other lines (e.g., "\ No newline at end of file")
+            push_html_row(
+                &mut page,
+                HtmlRow {
+                    background: "#ffffff",
+                    old_line: None,
+                    new_line: None,
+                    text: line,
+                    attribution: None,
+                    glyphs: &glyphs,
+                },
+            );
+        }
+    }
+    if in_table {
+        page.push_str("</table>\n");
+    }
+    page.push_str("</body>\n</html>\n");
+
+    Ok(page)
+}
+
+const HTML_BODY_STYLE: &str = "margin:24px;font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;font-size:14px;color:#24292f;background:#ffffff";
+const HTML_CELL_STYLE: &str = "padding:0 8px;font-family:ui-monospace,Menlo,Consolas,monospace;font-size:12px;white-space:pre;vertical-align:top";
+
+/// One line of the HTML diff table
+struct HtmlRow<'a> {
+    background: &'a str,
+    old_line: Option<u32>,
+    new_line: Option<u32>,
+    text: &'a str,
+    attribution: Option<&'a Attribution>,
+    glyphs: &'a AnnotationGlyphs,
+}
+
+fn push_html_file_header(page: &mut String, file: &str) {
+    page.push_str(&format!(
+        "<h2 style=\"font-size:14px;margin:16px 0 0;padding:8px;background:#f6f8fa;border:1px solid #d0d7de;font-family:ui-monospace,Menlo,Consolas,monospace\">{}</h2>\n",
+        escape_html(file)
+    ));
+    page.push_str(
+        "<table style=\"border-collapse:collapse;width:100%;border:1px solid #d0d7de;border-top:0\">\n",
+    );
+}
+
+fn push_html_row(page: &mut String, row: HtmlRow) {
+    let line_number = |line: Option<u32>| line.map(|n| n.to_string()).unwrap_or_default();
+    let badge = row
+        .attribution
+        .map(|attribution| html_badge(attribution, row.glyphs))
+        .unwrap_or_default();
+    page.push_str(&format!(
+        "<tr style=\"background:{bg}\"><td style=\"{cell};color:#8c959f;text-align:right\">{}</td><td style=\"{cell};color:#8c959f;text-align:right\">{}</td><td style=\"{cell};width:100%\">{}</td><td style=\"{cell}\">{}</td></tr>\n",
+        line_number(row.old_line),
+        line_number(row.new_line),
+        escape_html(row.text),
+        badge,
+        bg = row.background,
+        cell = HTML_CELL_STYLE,
+    ));
+}
+
+/// An attribution badge: filled for AI tools, outlined for humans and lines without data
+fn html_badge(attribution: &Attribution, glyphs: &AnnotationGlyphs) -> String {
+    let style = match attribution {
+        Attribution::Ai(_) => "background:#8250df;color:#ffffff;border:1px solid #8250df",
+        Attribution::Human(_) => "background:#ffffff;color:#24292f;border:1px solid #d0d7de",
+        Attribution::NoData => "background:#ffffff;color:#8c959f;border:1px dashed #d0d7de",
+    };
+    format!(
+        "<span style=\"{};border-radius:10px;padding:0 6px;font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif\">{}</span>",
+        style,
+        escape_html(&format_attribution(attribution, glyphs))
+    )
+}
+
+/// Short form of a revision for the HTML title
+fn html_revision_label(rev: &str) -> &str {
+    match rev {
+        WORKING_TREE => "working tree",
+        INDEX => "index",
+        _ => &rev[..rev.len().min(7)],
+    }
+}
+
+/// Lines added by AI and all added lines with an attribution, ignoring binary files
+fn count_ai_lines(attributions: &HashMap<DiffLineKey, Attribution>) -> (usize, usize) {
+    attributions
+        .iter()
+        .filter(|(key, _)| key.side == LineSide::New && key.line != FILE_LEVEL_LINE)
+        .fold((0, 0), |(ai, total), (_, attribution)| {
+            let is_ai = matches!(attribution, Attribution::Ai(_));
+            (ai + usize::from(is_ai), total + 1)
+        })
+}
+
+fn ai_percentage(ai_lines: usize, total_lines: usize) -> usize {
+    if total_lines == 0 {
+        0
+    } else {
+        (ai_lines * 100 + total_lines / 2) / total_lines
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render the diff as one tab-separated record per changed line, for editor integrations.
+///
+/// Grammar (one record per line, fields in this order, never reordered):
+///
+/// ```text
+/// record    = side TAB file TAB lineno TAB attr-kind TAB attr-value TAB provenance LF
+/// side      = "old" / "new"        ; "old" for deleted lines, "new" for added lines
+/// file      = path in the old tree for "old" records, in the new tree for "new" records
+/// lineno    = 1*DIGIT              ; 1-based line number in that file
+/// attr-kind = "ai" / "human" / "nodata"
+/// attr-value= tool name for "ai", username for "human", empty for "nodata"
+/// provenance= "direct" / "merged" / "carried-over-rename" / "carried-over-move" for "ai",
+///             empty otherwise
+/// ```
+///
+/// `direct` prompts are recorded in the blamed commit's own authorship log, `merged` ones only
+/// in another commit's log. `carried-over-rename` lines took their attribution from the line
+/// in another file they were copied or renamed from (--find-copies-harder), and
+/// `carried-over-move` lines from where they were moved from within the same file
+/// (--detect-moves).
+///
+/// Records follow diff order: per hunk, deleted lines then added lines, each ascending.
+/// Lines without an attribution (deleted lines, usually) are reported as `nodata`. With
+/// --relative, paths are relative to that directory.
+pub fn format_porcelain_diff(
+    hunks: &[DiffHunk],
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+    options: &AnnotatedDiffOptions,
+) -> String {
+    let mut result = String::new();
+
+    for line in changed_lines(hunks, attributions, prompts, options) {
+        result.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            line.side,
+            line.file,
+            line.line,
+            line.kind,
+            line.author.as_deref().unwrap_or(""),
+            line.provenance.as_deref().unwrap_or("")
+        ));
+    }
+
+    result
+}
+
+/// Build the --summary-json output: attribution totals over the added lines (per tool for
+/// AI), followed by every changed line that passes the attribution filter unless
+/// `--no-lines` was given
+pub fn build_diff_summary_json(
+    hunks: &[DiffHunk],
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+    options: &AnnotatedDiffOptions,
+) -> DiffSummaryJson {
+    let mut summary = DiffAttributionSummary::default();
+    for (key, attribution) in attributions {
+        // Binary files are attributed per file, so they don't count as lines
+        if key.side != LineSide::New || key.line == FILE_LEVEL_LINE {
+            continue;
+        }
+        match attribution {
+            Attribution::Ai(tool) => {
+                summary.ai_lines += 1;
+                *summary.tools.entry(tool.clone()).or_insert(0) += 1;
+            }
+            Attribution::Human(_) => summary.human_lines += 1,
+            Attribution::NoData => summary.no_data_lines += 1,
+        }
+    }
+
+    let lines = (!options.no_lines).then(|| changed_lines(hunks, attributions, prompts, options));
+    DiffSummaryJson { summary, lines }
+}
+
+/// Every deleted then added line of each hunk, with its attribution and, for AI lines, its
+/// provenance, skipping lines the attribution filter excludes. Paths are shown relative to
+/// the --relative directory, like the annotated diff.
+fn changed_lines(
+    hunks: &[DiffHunk],
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+    options: &AnnotatedDiffOptions,
+) -> Vec<DiffLineJson> {
+    let mut lines = Vec::new();
+    let relative_dir = options.relative_dir();
+
+    for hunk in hunks {
+        let old_lines = hunk
+            .deleted_lines()
+            .map(|line| (&hunk.old_file_path, line, LineSide::Old));
+        let new_lines = hunk
+            .added_lines()
+            .map(|line| (&hunk.file_path, line, LineSide::New));
+
+        for (file, line, side) in old_lines.chain(new_lines) {
+            let side_name = match side {
+                LineSide::Old => "old",
+                LineSide::New => "new",
+            };
+            let key = DiffLineKey {
+                file: file.as_str().into(),
+                line,
+                side,
+            };
+            let attribution = attributions.get(&key);
+            if !options.matches(attribution) {
+                continue;
+            }
+
+            let (kind, author) = match attribution {
+                Some(Attribution::Ai(tool)) => ("ai", Some(tool.clone())),
+                Some(Attribution::Human(username)) => ("human", Some(username.clone())),
+                Some(Attribution::NoData) | None => ("nodata", None),
+            };
+            let is_ai = matches!(attribution, Some(Attribution::Ai(_)));
+            let provenance = is_ai.then(|| prompts.provenance_of(&key).as_str().to_string());
+            let tool_version = prompts.tool_versions.get(&key).filter(|_| is_ai).cloned();
+            lines.push(DiffLineJson {
+                side: side_name.to_string(),
+                file: file.strip_prefix(relative_dir).unwrap_or(file).to_string(),
+                line,
+                kind: kind.to_string(),
+                author,
+                provenance,
+                tool_version,
+            });
+        }
     }
 
-    Ok(DiffJson {
-        files,
-        prompts: all_prompts,
-    })
+    lines
 }
 
-/// Get the unified diff split by file path
-fn get_diff_split_by_file(
-    repo: &Repository,
-    from_commit: &str,
-    to_commit: &str,
-) -> Result<HashMap<String, String>, GitAiError> {
-    let mut args = repo.global_args_for_exec();
-    args.push("diff".to_string());
-    args.push("--no-color".to_string());
-    args.push(from_commit.to_string());
-    args.push(to_commit.to_string());
-
-    let output = exec_git(&args)?;
-    let diff_text = String::from_utf8(output.stdout)
-        .map_err(|e| GitAiError::Generic(format!("Failed to parse diff output: {}", e)))?;
+/// Format AI-attributed added lines as GitHub Actions workflow commands, so a CI step can
+/// surface them inline on a pull request:
+///
+/// ```text
+/// ::notice file=src/main.rs,line=3,endLine=5::AI-authored by cursor
+/// ```
+///
+/// Consecutive lines from the same tool share one annotation.
+pub fn format_github_annotations(
+    hunks: &[DiffHunk],
+    attributions: &HashMap<DiffLineKey, Attribution>,
+) -> String {
+    let mut result = String::new();
 
-    let mut file_diffs: HashMap<String, String> = HashMap::new();
-    let mut current_file = String::new();
-    let mut current_diff = String::new();
+    for hunk in hunks {
+        // (first line, last line, tool) of the run being built
+        let mut run: Option<(u32, u32, &str)> = None;
+        for line in hunk.added_lines() {
+            let key = DiffLineKey {
+                file: hunk.file_path.as_str().into(),
+                line,
+                side: LineSide::New,
+            };
+            let tool = match attributions.get(&key) {
+                Some(Attribution::Ai(tool)) => Some(tool.as_str()),
+                _ => None,
+            };
 
-    for line in diff_text.lines() {
-        if line.starts_with("diff --git") {
-            // Save previous file's diff if any
-            if !current_file.is_empty() && !current_diff.is_empty() {
-                file_diffs.insert(current_file.clone(), current_diff.clone());
+            if let (Some((_, end, run_tool)), Some(tool)) = (&mut run, tool) {
+                if *end + 1 == line && *run_tool == tool {
+                    *end = line;
+                    continue;
+                }
             }
-            current_diff = format!("{}\n", line);
-            current_file.clear();
-        } else if line.starts_with("+++ b/") {
-            current_file = line[6..].to_string();
-            current_diff.push_str(line);
-            current_diff.push('\n');
-        } else {
-            current_diff.push_str(line);
-            current_diff.push('\n');
+            if let Some((start, end, run_tool)) = run.take() {
+                push_github_notice(&mut result, &hunk.file_path, start, end, run_tool);
+            }
+            run = tool.map(|tool| (line, line, tool));
+        }
+        if let Some((start, end, tool)) = run {
+            push_github_notice(&mut result, &hunk.file_path, start, end, tool);
         }
     }
 
-    // Don't forget the last file
-    if !current_file.is_empty() && !current_diff.is_empty() {
-        file_diffs.insert(current_file, current_diff);
+    result
+}
+
+fn push_github_notice(result: &mut String, file: &str, start: u32, end: u32, tool: &str) {
+    let lines = if start == end {
+        format!("line={}", start)
+    } else {
+        format!("line={},endLine={}", start, end)
+    };
+    result.push_str(&format!(
+        "::notice file={},{}::AI-authored by {}\n",
+        escape_workflow_property(file),
+        lines,
+        escape_workflow_data(tool)
+    ));
+}
+
+/// Escape a workflow command message, as the GitHub Actions toolkit does
+fn escape_workflow_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value, which additionally can't contain `:` or `,`
+fn escape_workflow_property(value: &str) -> String {
+    escape_workflow_data(value)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Changed-line counts for one file of `--stat` output
+#[derive(Debug, Default, PartialEq)]
+struct FileDiffStat {
+    added: usize,
+    deleted: usize,
+    ai: usize,
+    human: usize,
+    no_data: usize,
+}
+
+impl FileDiffStat {
+    fn changed(&self) -> usize {
+        self.added + self.deleted
     }
 
-    Ok(file_diffs)
+    /// The AI/human split, e.g. "8 ai, 2 human, 1 no-data", leaving out zero counts
+    fn split(&self) -> String {
+        [
+            (self.ai, "ai"),
+            (self.human, "human"),
+            (self.no_data, "no-data"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
 }
 
-/// Collect annotations for a specific file, returning (annotations_map, prompt_records_map)
-fn collect_file_annotations(
-    repo: &Repository,
-    from_commit: &str,
-    to_commit: &str,
-    file_path: &str,
+/// Format a `git diff --stat` style summary: one row per file with its changed line count,
+/// insertions and deletions, and how many of those lines are AI or human, then a totals
+/// line. Counts come from the same hunks and attributions as the line-by-line views.
+pub fn format_diff_stat(
     hunks: &[DiffHunk],
-) -> Result<
-    (
-        BTreeMap<String, Vec<LineRange>>,
-        HashMap<String, PromptRecord>,
-    ),
-    GitAiError,
-> {
-    let mut annotations: BTreeMap<String, Vec<LineRange>> = BTreeMap::new();
-    let mut prompt_records: HashMap<String, PromptRecord> = HashMap::new();
-
-    // Collect all added lines for this file
-    let mut added_lines: Vec<u32> = Vec::new();
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    relative_dir: &str,
+) -> String {
+    // Files in diff order, shown relative to `relative_dir` like the annotated diff
+    let mut files: Vec<(String, FileDiffStat)> = Vec::new();
+    let mut file_indices: HashMap<&str, usize> = HashMap::new();
     for hunk in hunks {
-        if hunk.file_path == file_path {
-            added_lines.extend(&hunk.added_lines);
+        let display_path = hunk
+            .file_path
+            .strip_prefix(relative_dir)
+            .unwrap_or(&hunk.file_path);
+        let index = *file_indices.entry(display_path).or_insert_with(|| {
+            files.push((display_path.to_string(), FileDiffStat::default()));
+            files.len() - 1
+        });
+        let stat = &mut files[index].1;
+
+        let old_lines = hunk
+            .deleted_lines()
+            .map(|line| (&hunk.old_file_path, line, LineSide::Old));
+        let new_lines = hunk
+            .added_lines()
+            .map(|line| (&hunk.file_path, line, LineSide::New));
+        for (file, line, side) in old_lines.chain(new_lines) {
+            match side {
+                LineSide::Old => stat.deleted += 1,
+                LineSide::New => stat.added += 1,
+            }
+            let key = DiffLineKey {
+                file: file.as_str().into(),
+                line,
+                side,
+            };
+            match attributions.get(&key) {
+                Some(Attribution::Ai(_)) => stat.ai += 1,
+                Some(Attribution::Human(_)) => stat.human += 1,
+                Some(Attribution::NoData) | None => stat.no_data += 1,
+            }
         }
     }
 
-    if added_lines.is_empty() {
-        return Ok((annotations, prompt_records));
+    if files.is_empty() {
+        return String::new();
     }
 
-    added_lines.sort_unstable();
-    added_lines.dedup();
-    let line_ranges = lines_to_ranges(&added_lines);
+    let path_width = files.iter().map(|(file, _)| file.len()).max().unwrap_or(0);
+    let count_width = files
+        .iter()
+        .map(|(_, stat)| stat.changed().to_string().len())
+        .max()
+        .unwrap_or(0);
 
-    if line_ranges.is_empty() {
-        return Ok((annotations, prompt_records));
+    let mut result = String::new();
+    let mut total = FileDiffStat::default();
+    for (file, stat) in &files {
+        result.push_str(&format!(
+            " {:<path_width$} | {:>count_width$} (+{} -{})  {}\n",
+            file,
+            stat.changed(),
+            stat.added,
+            stat.deleted,
+            stat.split(),
+            path_width = path_width,
+            count_width = count_width,
+        ));
+        total.added += stat.added;
+        total.deleted += stat.deleted;
+        total.ai += stat.ai;
+        total.human += stat.human;
+        total.no_data += stat.no_data;
     }
 
-    // Build blame options - use prompt hashes as names to get the actual hash per line
-    let mut options = GitAiBlameOptions::default();
-    options.oldest_commit = Some(from_commit.to_string());
-    options.newest_commit = Some(to_commit.to_string());
-    options.line_ranges = line_ranges;
-    options.no_output = true;
-    options.use_prompt_hashes_as_names = true; // Key: get prompt hash instead of tool name
-
-    // Call blame to get attributions
-    let blame_result = repo.blame(file_path, &options);
-
-    match blame_result {
-        Ok((line_authors, blame_prompt_records)) => {
-            // Group lines by prompt hash
-            // With use_prompt_hashes_as_names=true, line_authors values are the prompt hashes
-            let mut lines_by_hash: HashMap<String, Vec<u32>> = HashMap::new();
+    let counted = |count: usize, singular: &str, plural: &str| {
+        format!("{} {}", count, if count == 1 { singular } else { plural })
+    };
+    result.push_str(&format!(
+        " {}, {}(+), {}(-); {}\n",
+        counted(files.len(), "file changed", "files changed"),
+        counted(total.added, "insertion", "insertions"),
+        counted(total.deleted, "deletion", "deletions"),
+        total.split()
+    ));
+
+    result
+}
 
-            for &line in &added_lines {
-                if let Some(prompt_hash) = line_authors.get(&line) {
-                    // Only include if this hash is in the prompt_records (i.e., it's an AI line)
-                    if blame_prompt_records.contains_key(prompt_hash) {
-                        lines_by_hash
-                            .entry(prompt_hash.clone())
-                            .or_insert_with(Vec::new)
-                            .push(line);
-                    }
-                }
-            }
+/// List the changed lines that have no authorship data as `<file>:<line>: <+|-><content>`,
+/// then count them (--no-data-only). Paths are shown relative to `relative_dir`.
+pub fn format_no_data_lines(
+    repo: &Repository,
+    from_commit: &str,
+    to_commit: &str,
+    hunks: &[DiffHunk],
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    relative_dir: &str,
+) -> String {
+    let mut contents: HashMap<(&str, &str), Vec<String>> = HashMap::new();
+    let mut result = String::new();
+    let mut count = 0;
 
-            // Convert lines to LineRange format and store in annotations
-            for (hash, mut lines) in lines_by_hash {
-                lines.sort_unstable();
-                lines.dedup();
-                let ranges = LineRange::compress_lines(&lines);
-                annotations.insert(hash, ranges);
+    for hunk in hunks {
+        let old_lines = hunk
+            .deleted_lines()
+            .map(|line| (&hunk.old_file_path, line, LineSide::Old));
+        let new_lines = hunk
+            .added_lines()
+            .map(|line| (&hunk.file_path, line, LineSide::New));
+
+        for (file, line, side) in old_lines.chain(new_lines) {
+            let (rev, marker) = match side {
+                LineSide::Old => (from_commit, '-'),
+                LineSide::New => (to_commit, '+'),
+            };
+            let key = DiffLineKey {
+                file: file.as_str().into(),
+                line,
+                side,
+            };
+            if !matches!(attributions.get(&key), Some(Attribution::NoData) | None) {
+                continue;
             }
 
-            // Store prompt records
-            prompt_records = blame_prompt_records;
-        }
-        Err(_) => {
-            // Blame failed, no annotations for this file
+            let lines = contents
+                .entry((rev, file.as_str()))
+                .or_insert_with(|| file_lines_at(repo, rev, file));
+            let text = (line as usize)
+                .checked_sub(1)
+                .and_then(|index| lines.get(index))
+                .map_or("", String::as_str);
+            let display_path = file.strip_prefix(relative_dir).unwrap_or(file);
+            result.push_str(&format!("{}:{}: {}{}\n", display_path, line, marker, text));
+            count += 1;
         }
     }
 
-    Ok((annotations, prompt_records))
+    result.push_str(&format!(
+        "{} changed {} without authorship data\n",
+        count,
+        if count == 1 { "line" } else { "lines" }
+    ));
+    result
 }
 
-// ============================================================================
-// Output Formatting
-// ============================================================================
+/// Lines of `path` at `rev`, which may also be the working tree or the index. Empty when
+/// the file can't be read.
+fn file_lines_at(repo: &Repository, rev: &str, path: &str) -> Vec<String> {
+    let content = match rev {
+        WORKING_TREE => repo
+            .workdir()
+            .ok()
+            .and_then(|workdir| std::fs::read(workdir.join(path)).ok()),
+        INDEX => repo.get_file_content(path, "").ok(),
+        _ => repo.get_file_content(path, rev).ok(),
+    };
+    content
+        .map(|content| {
+            String::from_utf8_lossy(&content)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-pub fn format_annotated_diff(
+/// Append the at-a-glance attribution breakdown for interactive use
+fn push_attribution_summary(
+    result: &mut String,
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    use_color: bool,
+) {
+    let summary = format_attribution_summary(attributions);
+    if !summary.is_empty() {
+        result.push('\n');
+        result.push_str(&format_line(&summary, LineType::Context, use_color));
+    }
+}
+
+/// Format the diff with inline word-level changes, like `git diff --word-diff`.
+///
+/// Uses `--word-diff=porcelain`, where each token of a line is on its own row
+/// (` ` unchanged, `-` removed, `+` added) and `~` ends the line. Changed spans
+/// are rendered as `[-...-]`/`[+...+]`; added spans are tinted by the attribution
+/// of the line they land on, while unchanged spans stay plain. Attribution is still
+/// per line, so every added span on a line shares that line's attribution.
+pub fn format_word_diff(
     repo: &Repository,
     from_commit: &str,
     to_commit: &str,
     attributions: &HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+    options: &AnnotatedDiffOptions,
 ) -> Result<String, GitAiError> {
-    // Execute git diff with normal context
     let mut args = repo.global_args_for_exec();
     args.push("diff".to_string());
+    push_copy_detection(&mut args, options.find_copies_harder);
+    args.push("--word-diff=porcelain".to_string());
+    if let Some(context_lines) = options.context_lines {
+        args.push(format!("-U{}", context_lines));
+    }
+    push_relative(&mut args, options);
     args.push("--no-color".to_string());
-    args.push(from_commit.to_string());
-    args.push(to_commit.to_string());
+    push_diff_revisions(&mut args, from_commit, to_commit);
+    push_pathspecs(&mut args, &options.pathspecs);
 
     let output = exec_git(&args)?;
-    let diff_text = String::from_utf8(output.stdout)
-        .map_err(|e| GitAiError::Generic(format!("Failed to parse diff output: {}", e)))?;
-
-    // Check if we should use colors
-    let use_color = std::io::stdout().is_terminal();
-
-    // Parse and annotate diff
-    let mut result = String::new();
-    let mut current_file = String::new();
+    let diff_text =
+        String::from_utf8(output.stdout).map_err(|e| GitAiError::DiffParse(e.to_string()))?;
+
+    let use_color = options.color.use_color();
+    let glyphs = options.glyphs();
+    let mut prompt_notes = options
+        .show_prompt
+        .then(|| PromptNotes::new(prompts, terminal_width(options), &glyphs));
+
+    let relative_dir = options.relative_dir();
+    let mut result = format_tool_legend(attributions, use_color, &glyphs);
+    let mut current_file: Arc<str> = Arc::from("");
     let mut old_line_num = 0u32;
     let mut new_line_num = 0u32;
-
-    for line in diff_text.lines() {
-        if line.starts_with("diff --git") {
-            // Diff header
-            result.push_str(&format_line(line, LineType::DiffHeader, use_color, None));
-            current_file.clear();
-            old_line_num = 0;
-            new_line_num = 0;
-        } else if line.starts_with("index ") {
-            result.push_str(&format_line(line, LineType::DiffHeader, use_color, None));
-        } else if line.starts_with("--- ") {
-            result.push_str(&format_line(line, LineType::DiffHeader, use_color, None));
-        } else if line.starts_with("+++ b/") {
-            current_file = line[6..].to_string();
-            result.push_str(&format_line(line, LineType::DiffHeader, use_color, None));
-        } else if line.starts_with("@@ ") {
-            // Hunk header - update line counters
-            if let Some((old_start, new_start)) = parse_hunk_header_for_line_nums(line) {
+    let mut in_hunk = false;
+    let mut line = WordDiffLine::default();
+
+    for row in diff_text.lines() {
+        if row.starts_with("diff --git") {
+            in_hunk = false;
+            current_file = Arc::from("");
+            result.push_str(&format_line(row, LineType::DiffHeader, use_color));
+        } else if row.starts_with("@@ ") {
+            in_hunk = true;
+            if let Some((old_start, new_start)) = parse_hunk_header_for_line_nums(row) {
                 old_line_num = old_start;
                 new_line_num = new_start;
             }
-            result.push_str(&format_line(line, LineType::HunkHeader, use_color, None));
-        } else if line.starts_with('-') && !line.starts_with("---") {
-            // Deleted line
-            let key = DiffLineKey {
-                file: current_file.clone(),
-                line: old_line_num,
-                side: LineSide::Old,
+            result.push_str(&format_line(row, LineType::HunkHeader, use_color));
+        } else if !in_hunk {
+            if let Some(path) = diff_header_path(row, "+++ ", "b/") {
+                current_file = format!("{}{}", relative_dir, path).into();
+            }
+            let line_type = if row.starts_with("index ")
+                || row.starts_with("--- ")
+                || row.starts_with("+++ ")
+            {
+                LineType::DiffHeader
+            } else if row.starts_with("Binary files") {
+                LineType::Binary
+            } else {
+                LineType::Context
             };
-            let attribution = attributions.get(&key);
-            result.push_str(&format_line(
-                line,
-                LineType::Deletion,
+            let attribution = match line_type {
+                LineType::Binary => binary_marker_attribution(row, relative_dir, attributions),
+                _ => None,
+            };
+            result.push_str(&format_annotated_line(
+                row,
+                line_type,
                 use_color,
                 attribution,
+                None,
+                &glyphs,
             ));
-            old_line_num += 1;
-        } else if line.starts_with('+') && !line.starts_with("+++") {
-            // Added line
+        } else if row == "~" {
+            // End of a line in the new/old file
             let key = DiffLineKey {
-                file: current_file.clone(),
+                file: Arc::clone(&current_file),
                 line: new_line_num,
                 side: LineSide::New,
             };
-            let attribution = attributions.get(&key);
-            result.push_str(&format_line(
-                line,
-                LineType::Addition,
-                use_color,
-                attribution,
-            ));
-            new_line_num += 1;
-        } else if line.starts_with(' ') {
-            // Context line
-            result.push_str(&format_line(line, LineType::Context, use_color, None));
-            old_line_num += 1;
-            new_line_num += 1;
-        } else if line.starts_with("Binary files") {
-            // Binary file marker
-            result.push_str(&format_line(line, LineType::Binary, use_color, None));
+            let attribution = if line.has_added {
+                attributions.get(&key)
+            } else {
+                None
+            };
+
+            if !line.has_changes() || options.matches(attribution) {
+                let note = prompt_notes
+                    .as_mut()
+                    .filter(|_| line.has_added)
+                    .and_then(|notes| notes.note(&key, &line.plain_text(), attribution));
+                let rendered = line.render(attribution, use_color, note.as_deref(), &glyphs);
+                result.push_str(&rendered);
+            }
+
+            // A line made only of removed tokens doesn't exist in the new file,
+            // and one made only of added tokens doesn't exist in the old file
+            if line.has_common || line.has_removed {
+                old_line_num += 1;
+            }
+            if line.has_common || line.has_added {
+                new_line_num += 1;
+            }
+            line = WordDiffLine::default();
+        } else if let Some(token) = row.strip_prefix(' ') {
+            line.push(WordSpan::Common, token);
+        } else if let Some(token) = row.strip_prefix('-') {
+            line.push(WordSpan::Removed, token);
+        } else if let Some(token) = row.strip_prefix('+') {
+            line.push(WordSpan::Added, token);
+        } else {
+            // Other rows (e.g., "\ No newline at end of file")
+            result.push_str(&format_line(row, LineType::Context, use_color));
+        }
+    }
+
+    if let Some(notes) = &prompt_notes {
+        result.push_str(&notes.footer());
+    }
+    if options.writes_to_terminal() {
+        push_attribution_summary(&mut result, attributions, use_color);
+    }
+
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WordSpan {
+    Common,
+    Removed,
+    Added,
+}
+
+/// One line of a porcelain word diff, accumulated token by token
+#[derive(Default)]
+struct WordDiffLine {
+    spans: Vec<(WordSpan, String)>,
+    has_common: bool,
+    has_removed: bool,
+    has_added: bool,
+}
+
+impl WordDiffLine {
+    fn push(&mut self, kind: WordSpan, token: &str) {
+        match kind {
+            WordSpan::Common => self.has_common = true,
+            WordSpan::Removed => self.has_removed = true,
+            WordSpan::Added => self.has_added = true,
+        }
+        self.spans.push((kind, token.to_string()));
+    }
+
+    fn has_changes(&self) -> bool {
+        self.has_removed || self.has_added
+    }
+
+    /// The line as rendered without color or annotation
+    fn plain_text(&self) -> String {
+        self.render(None, false, None, &AnnotationGlyphs::default())
+            .trim_end_matches('\n')
+            .to_string()
+    }
+
+    fn render(
+        &self,
+        attribution: Option<&Attribution>,
+        use_color: bool,
+        note: Option<&str>,
+        glyphs: &AnnotationGlyphs,
+    ) -> String {
+        // Added spans take the color of the line's attribution
+        let added_color = match attribution {
+            Some(Attribution::Ai(_)) => "\x1b[35m",    // Magenta
+            Some(Attribution::Human(_)) => "\x1b[32m", // Green
+            _ => "\x1b[33m",                           // Yellow (no data)
+        };
+
+        let mut rendered = String::new();
+        for (kind, token) in &self.spans {
+            match (kind, use_color) {
+                (WordSpan::Common, _) => rendered.push_str(token),
+                (WordSpan::Removed, true) => {
+                    rendered.push_str(&format!("\x1b[31m[-{}-]\x1b[0m", token))
+                }
+                (WordSpan::Removed, false) => rendered.push_str(&format!("[-{}-]", token)),
+                (WordSpan::Added, true) => {
+                    rendered.push_str(&format!("{}[+{}+]\x1b[0m", added_color, token))
+                }
+                (WordSpan::Added, false) => rendered.push_str(&format!("[+{}+]", token)),
+            }
+        }
+
+        match attribution {
+            Some(attr) if use_color => format!(
+                "{}  {}{}\x1b[0m\n",
+                rendered,
+                annotation_color(attr),
+                format_annotation(attr, note, glyphs)
+            ),
+            Some(attr) => format!("{}  {}\n", rendered, format_annotation(attr, note, glyphs)),
+            None => format!("{}\n", rendered),
+        }
+    }
+}
+
+/// Summarize added-line attributions, e.g. "42 lines cursor, 30 lines alice, 5 no-data".
+/// AI tools come first, then humans, each ordered by line count; no-data lines last.
+/// Deleted lines are not counted since the overlay only attributes added lines.
+fn format_attribution_summary(attributions: &HashMap<DiffLineKey, Attribution>) -> String {
+    let mut ai_counts: HashMap<&str, usize> = HashMap::new();
+    let mut human_counts: HashMap<&str, usize> = HashMap::new();
+    let mut no_data = 0usize;
+
+    for (key, attribution) in attributions {
+        // Binary files are attributed per file, so they don't count as lines
+        if key.side != LineSide::New || key.line == FILE_LEVEL_LINE {
+            continue;
+        }
+        match attribution {
+            Attribution::Ai(tool) => *ai_counts.entry(tool.as_str()).or_insert(0) += 1,
+            Attribution::Human(username) => {
+                *human_counts.entry(username.as_str()).or_insert(0) += 1
+            }
+            Attribution::NoData => no_data += 1,
+        }
+    }
+
+    let sorted = |counts: HashMap<&str, usize>| {
+        let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts
+    };
+
+    let mut parts: Vec<String> = sorted(ai_counts)
+        .into_iter()
+        .chain(sorted(human_counts))
+        .map(|(name, count)| {
+            format!(
+                "{} {} {}",
+                count,
+                if count == 1 { "line" } else { "lines" },
+                name
+            )
+        })
+        .collect();
+    if no_data > 0 {
+        parts.push(format!("{} no-data", no_data));
+    }
+
+    parts.join(", ")
+}
+
+/// Output for a single hunk of the annotated diff. Without an attribution filter every line
+/// is written as soon as it's pushed; with one, the body is held until the hunk ends so it
+/// can be dropped when none of its changes matched.
+struct HunkBuffer {
+    buffered: bool,
+    started: bool,
+    header: String,
+    lines: Vec<String>,
+    has_changes: bool,
+}
+
+impl HunkBuffer {
+    fn new(buffered: bool) -> Self {
+        HunkBuffer {
+            buffered,
+            started: false,
+            header: String::new(),
+            lines: Vec::new(),
+            has_changes: false,
+        }
+    }
+
+    /// Whether a hunk header has been seen since the last file header
+    fn started(&self) -> bool {
+        self.started
+    }
+
+    /// End the previous hunk and begin one with `header`
+    fn start(&mut self, header: String, out: &mut dyn Write) -> std::io::Result<()> {
+        self.flush_into(out)?;
+        self.started = true;
+        if self.buffered {
+            self.header = header;
+            Ok(())
         } else {
-            // Other lines (e.g., "\ No newline at end of file")
-            result.push_str(&format_line(line, LineType::Context, use_color, None));
+            out.write_all(header.as_bytes())
         }
     }
 
-    Ok(result)
+    fn push_change(&mut self, line: String, out: &mut dyn Write) -> std::io::Result<()> {
+        self.has_changes = true;
+        self.push_context(line, out)
+    }
+
+    fn push_context(&mut self, line: String, out: &mut dyn Write) -> std::io::Result<()> {
+        if self.buffered {
+            self.lines.push(line);
+            Ok(())
+        } else {
+            out.write_all(line.as_bytes())
+        }
+    }
+
+    /// Write what's held of the hunk to `out` and reset. The header is always kept; the
+    /// body is dropped when every change in the hunk was filtered out.
+    fn flush_into(&mut self, out: &mut dyn Write) -> std::io::Result<()> {
+        out.write_all(self.header.as_bytes())?;
+        if self.has_changes {
+            for line in &self.lines {
+                out.write_all(line.as_bytes())?;
+            }
+        }
+        self.started = false;
+        self.header.clear();
+        self.lines.clear();
+        self.has_changes = false;
+        Ok(())
+    }
 }
 
 fn parse_hunk_header_for_line_nums(line: &str) -> Option<(u32, u32)> {
@@ -793,17 +4145,26 @@ enum LineType {
     Binary,
 }
 
-fn format_line(
+fn format_line(line: &str, line_type: LineType, use_color: bool) -> String {
+    let glyphs = AnnotationGlyphs::default();
+    format_annotated_line(line, line_type, use_color, None, None, &glyphs)
+}
+
+/// Like `format_line`, followed by the attribution and `note` (e.g. a --show-prompt summary)
+fn format_annotated_line(
     line: &str,
     line_type: LineType,
     use_color: bool,
     attribution: Option<&Attribution>,
+    note: Option<&str>,
+    glyphs: &AnnotationGlyphs,
 ) -> String {
     let annotation = if let Some(attr) = attribution {
-        format_attribution(attr)
+        format_annotation(attr, note, glyphs)
     } else {
         String::new()
     };
+    let annotation_color = attribution.map_or(DIM, annotation_color);
 
     if use_color {
         match line_type {
@@ -817,18 +4178,28 @@ fn format_line(
                 if annotation.is_empty() {
                     format!("\x1b[32m{}\x1b[0m\n", line) // Green
                 } else {
-                    format!("\x1b[32m{}\x1b[0m  \x1b[2m{}\x1b[0m\n", line, annotation) // Green + dim annotation
+                    format!(
+                        "\x1b[32m{}\x1b[0m  {}{}\x1b[0m\n",
+                        line, annotation_color, annotation
+                    ) // Green + tinted annotation
                 }
             }
             LineType::Deletion => {
                 if annotation.is_empty() {
                     format!("\x1b[31m{}\x1b[0m\n", line) // Red
                 } else {
-                    format!("\x1b[31m{}\x1b[0m  \x1b[2m{}\x1b[0m\n", line, annotation) // Red + dim annotation
+                    format!(
+                        "\x1b[31m{}\x1b[0m  {}{}\x1b[0m\n",
+                        line, annotation_color, annotation
+                    ) // Red + tinted annotation
                 }
             }
             LineType::Context | LineType::Binary => {
-                format!("{}\n", line)
+                if annotation.is_empty() {
+                    format!("{}\n", line)
+                } else {
+                    format!("{}  {}{}\x1b[0m\n", line, annotation_color, annotation) // Tinted annotation
+                }
             }
         }
     } else {
@@ -841,14 +4212,224 @@ fn format_line(
     }
 }
 
-fn format_attribution(attribution: &Attribution) -> String {
+const DIM: &str = "\x1b[2m";
+
+/// Annotation colors for AI tools. Green and red are left out so a tool's tint never
+/// reads as an addition or deletion.
+const TOOL_PALETTE: [&str; 6] = [
+    "\x1b[35m", // Magenta
+    "\x1b[34m", // Blue
+    "\x1b[33m", // Yellow
+    "\x1b[36m", // Cyan
+    "\x1b[95m", // Bright magenta
+    "\x1b[94m", // Bright blue
+];
+
+/// The palette color for an AI tool. Uses FNV-1a rather than the std hasher, whose output
+/// isn't guaranteed to be stable, so a tool keeps its color across runs and releases.
+fn tool_color(tool: &str) -> &'static str {
+    let hash = tool.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    // Fold the high bits in: FNV's low bits alone put "claude" and "cursor" together
+    let hash = hash ^ (hash >> 32);
+    TOOL_PALETTE[(hash % TOOL_PALETTE.len() as u64) as usize]
+}
+
+/// AI annotations are tinted with their tool's color; human and no-data ones stay dim
+fn annotation_color(attribution: &Attribution) -> &'static str {
+    match attribution {
+        Attribution::Ai(tool) => tool_color(tool),
+        _ => DIM,
+    }
+}
+
+/// A first line mapping each AI tool in the diff to its annotation color, e.g.
+/// "AI tools: 🤖claude  🤖cursor". Empty without color, where the annotations already
+/// name the tool, or when no line is attributed to AI.
+fn format_tool_legend(
+    attributions: &HashMap<DiffLineKey, Attribution>,
+    use_color: bool,
+    glyphs: &AnnotationGlyphs,
+) -> String {
+    if !use_color {
+        return String::new();
+    }
+
+    let tools: BTreeSet<&str> = attributions
+        .values()
+        .filter_map(|attribution| match attribution {
+            Attribution::Ai(tool) => Some(tool.as_str()),
+            _ => None,
+        })
+        .collect();
+    if tools.is_empty() {
+        return String::new();
+    }
+
+    let entries: Vec<String> = tools
+        .iter()
+        .map(|tool| format!("{}{}\x1b[0m", tool_color(tool), glyphs.ai_label(tool)))
+        .collect();
+    format!("{}AI tools:\x1b[0m {}\n", DIM, entries.join("  "))
+}
+
+fn format_attribution(attribution: &Attribution, glyphs: &AnnotationGlyphs) -> String {
     match attribution {
-        Attribution::Ai(tool) => format!("🤖{}", tool),
-        Attribution::Human(username) => format!("👤{}", username),
+        Attribution::Ai(tool) => glyphs.ai_label(tool),
+        Attribution::Human(username) => glyphs.human_label(username),
         Attribution::NoData => "[no-data]".to_string(),
     }
 }
 
+/// Name each AI attribution's tool with its version where one was recorded, e.g.
+/// `cursor@0.45.2`, so the annotations and per-tool totals tell releases apart
+fn label_tool_versions(
+    attributions: &mut HashMap<DiffLineKey, Attribution>,
+    prompts: &LinePrompts,
+) {
+    for (key, attribution) in attributions.iter_mut() {
+        if let (Attribution::Ai(tool), Some(version)) =
+            (attribution, prompts.tool_versions.get(key))
+        {
+            *tool = format!("{}@{}", tool, version);
+        }
+    }
+}
+
+/// The attribution followed by an optional note
+fn format_annotation(
+    attribution: &Attribution,
+    note: Option<&str>,
+    glyphs: &AnnotationGlyphs,
+) -> String {
+    let label = format_attribution(attribution, glyphs);
+    match note {
+        Some(note) => format!("{} {}", label, note),
+        None => label,
+    }
+}
+
+/// Longest prompt summary shown inline with --show-prompt, in characters
+const PROMPT_SUMMARY_CHARS: usize = 60;
+
+/// Shortest summary worth showing when the terminal leaves less room than that
+const MIN_PROMPT_SUMMARY_CHARS: usize = 16;
+
+/// Inline prompt notes for --show-prompt. A prompt behind a single line is summarized next
+/// to it; one behind several lines is referenced by a short index instead, and its full
+/// text is printed once in the footer.
+struct PromptNotes<'a> {
+    prompts: &'a LinePrompts,
+    line_counts: HashMap<&'a str, usize>,
+    /// Prompt hashes given an index so far, in order; the index is the position plus one
+    indexed: Vec<&'a str>,
+    width: Option<usize>,
+    glyphs: &'a AnnotationGlyphs,
+}
+
+impl<'a> PromptNotes<'a> {
+    /// `width` is the terminal width summaries are fitted to, if any
+    fn new(prompts: &'a LinePrompts, width: Option<usize>, glyphs: &'a AnnotationGlyphs) -> Self {
+        let mut line_counts: HashMap<&str, usize> = HashMap::new();
+        for prompt_hash in prompts.by_line.values() {
+            *line_counts.entry(prompt_hash.as_str()).or_insert(0) += 1;
+        }
+        PromptNotes {
+            prompts,
+            line_counts,
+            indexed: Vec::new(),
+            width,
+            glyphs,
+        }
+    }
+
+    /// The note for the diff line `line` at `key`, if its prompt is known
+    fn note(
+        &mut self,
+        key: &DiffLineKey,
+        line: &str,
+        attribution: Option<&Attribution>,
+    ) -> Option<String> {
+        let prompt_hash = self.prompts.by_line.get(key)?.as_str();
+
+        if self.line_counts.get(prompt_hash).copied().unwrap_or(0) > 1 {
+            let index = match self.indexed.iter().position(|hash| *hash == prompt_hash) {
+                Some(position) => position + 1,
+                None => {
+                    self.indexed.push(prompt_hash);
+                    self.indexed.len()
+                }
+            };
+            return Some(format!("[{}]", index));
+        }
+
+        let text = self.prompts.texts.get(prompt_hash)?;
+        let max_chars = match self.width {
+            Some(width) => {
+                // The line, two spaces, the annotation (an emoji glyph is two columns wide)
+                // and the space and quotes around the summary
+                let annotation_chars = attribution.map_or(0, |attr| {
+                    format_attribution(attr, self.glyphs).chars().count() + 1
+                });
+                let used = line.chars().count() + 2 + annotation_chars + 3;
+                width
+                    .saturating_sub(used)
+                    .clamp(MIN_PROMPT_SUMMARY_CHARS, PROMPT_SUMMARY_CHARS)
+            }
+            None => PROMPT_SUMMARY_CHARS,
+        };
+        Some(format!("\"{}\"", summarize_prompt(text, max_chars)))
+    }
+
+    /// The full text of every prompt referenced by index, or nothing if there were none
+    fn footer(&self) -> String {
+        if self.indexed.is_empty() {
+            return String::new();
+        }
+
+        let mut footer = String::from("\nPrompts:\n");
+        for (position, prompt_hash) in self.indexed.iter().enumerate() {
+            let Some(text) = self.prompts.texts.get(*prompt_hash) else {
+                continue;
+            };
+            let label = format!("  [{}] ", position + 1);
+            let indent = " ".repeat(label.len());
+            for (i, text_line) in text.lines().enumerate() {
+                let prefix = if i == 0 { &label } else { &indent };
+                footer.push_str(format!("{}{}", prefix, text_line).trim_end());
+                footer.push('\n');
+            }
+        }
+        footer
+    }
+}
+
+/// The first line of a prompt, cut to `max_chars` with an ellipsis when anything is left out
+fn summarize_prompt(text: &str, max_chars: usize) -> String {
+    let first_line = text.lines().next().unwrap_or_default().trim();
+    let truncated = first_line.chars().count() > max_chars || text.trim().lines().count() > 1;
+    if !truncated {
+        return first_line.to_string();
+    }
+
+    let kept: String = first_line
+        .chars()
+        .take(max_chars.saturating_sub(1))
+        .collect();
+    format!("{}…", kept.trim_end())
+}
+
+/// Width of the terminal the diff is written to, if it's going to one
+fn terminal_width(options: &AnnotatedDiffOptions) -> Option<usize> {
+    if !options.writes_to_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+}
+
 /// Custom serializer for annotations that converts LineRange to JSON tuples
 fn serialize_annotations<S>(
     annotations: &BTreeMap<String, Vec<LineRange>>,
@@ -912,10 +4493,16 @@ pub fn get_diff_json_filtered(
     let from_commit = resolve_parent(repo, &to_commit)?;
 
     // Get diff hunks with line numbers
-    let hunks = get_diff_with_line_numbers(repo, &from_commit, &to_commit)?;
+    let hunks = get_diff_with_line_numbers(repo, &from_commit, &to_commit, &[], false)?;
 
     // Get attributions for overlay (not used directly, but needed for build_diff_json)
-    let attributions = overlay_diff_attributions(repo, &from_commit, &to_commit, &hunks)?;
+    let attributions = overlay_diff_attributions(
+        repo,
+        &from_commit,
+        &to_commit,
+        &hunks,
+        &mut LinePrompts::default(),
+    )?;
 
     // Build the full DiffJson structure
     let mut diff_json = build_diff_json(repo, &from_commit, &to_commit, &hunks, &attributions)?;
@@ -966,32 +4553,610 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_diff_args_commit_range() {
-        let args = vec!["abc123..def456".to_string()];
-        let (spec, _format) = parse_diff_args(&args).unwrap();
+    fn test_parse_diff_args_commit_range() {
+        let args = vec!["abc123..def456".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+
+        match spec {
+            DiffSpec::TwoCommit(start, end) => {
+                assert_eq!(start, "abc123");
+                assert_eq!(end, "def456");
+            }
+            _ => panic!("Expected TwoCommit"),
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_args_invalid_range() {
+        let args = vec!["..".to_string()];
+        let result = parse_diff_args(&args);
+        assert!(result.is_err());
+
+        let args = vec!["abc..".to_string()];
+        let result = parse_diff_args(&args);
+        assert!(result.is_err());
+
+        let args = vec!["..def".to_string()];
+        let result = parse_diff_args(&args);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_args_three_dot_range() {
+        let args = vec!["main...feature".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+
+        match spec {
+            DiffSpec::MergeBase(base, head) => {
+                assert_eq!(base, "main");
+                assert_eq!(head, "feature");
+            }
+            _ => panic!("Expected MergeBase"),
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_args_invalid_three_dot_range() {
+        for arg in ["...", "abc...", "...def", "abc....def"] {
+            let args = vec![arg.to_string()];
+            assert!(
+                parse_diff_args(&args).is_err(),
+                "{} should be rejected",
+                arg
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_args_working_tree() {
+        let args: Vec<String> = vec![];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::WorkingTree));
+
+        let args = vec!["--only-ai".to_string(), "--".to_string(), "src".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::WorkingTree));
+    }
+
+    #[test]
+    fn test_parse_diff_args_cached() {
+        for flag in ["--cached", "--staged"] {
+            let args = vec![flag.to_string(), "--".to_string(), "src".to_string()];
+            let (spec, _format) = parse_diff_args(&args).unwrap();
+            assert!(matches!(spec, DiffSpec::Cached));
+        }
+
+        let args = vec!["--cached".to_string(), "abc123".to_string()];
+        assert!(parse_diff_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_index_line_to_worktree() {
+        let hunk = |old_start, old_count, new_start, new_count| DiffHunk {
+            file_path: "a.rs".to_string(),
+            old_file_path: "a.rs".to_string(),
+            copied: false,
+            old_start,
+            old_count,
+            new_start,
+            new_count,
+            deleted: 0..0,
+            added: 0..0,
+        };
+        // Two lines inserted after index line 2, index line 5 replaced by three lines
+        let unstaged = vec![hunk(2, 0, 3, 2), hunk(5, 1, 7, 3)];
+
+        assert_eq!(index_line_to_worktree(&unstaged, 1), Some(1));
+        assert_eq!(index_line_to_worktree(&unstaged, 2), Some(2));
+        assert_eq!(index_line_to_worktree(&unstaged, 3), Some(5));
+        assert_eq!(index_line_to_worktree(&unstaged, 4), Some(6));
+        assert_eq!(index_line_to_worktree(&unstaged, 5), None);
+        assert_eq!(index_line_to_worktree(&unstaged, 6), Some(10));
+        assert_eq!(index_line_to_worktree(&[], 4), Some(4));
+    }
+
+    #[test]
+    fn test_parse_diff_args_skips_flags() {
+        let args = vec!["--only-ai".to_string(), "abc123".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+
+        match spec {
+            DiffSpec::SingleCommit(sha) => assert_eq!(sha, "abc123"),
+            _ => panic!("Expected SingleCommit"),
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_args_rejects_unknown_flags() {
+        for flag in ["--only-a1", "--stats", "--jsonn", "-x"] {
+            let args = vec![flag.to_string(), "abc123".to_string()];
+            let err = parse_diff_args(&args).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains(&format!("Unknown diff option: {}", flag))
+            );
+            assert!(parse_annotated_diff_options(&args).is_err());
+        }
+
+        // Format and spec flags are read by parse_diff_args, not rejected
+        let args = vec!["--stat".to_string(), "--summary-json".to_string()];
+        assert!(parse_annotated_diff_options(&args).is_ok());
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_filters() {
+        let args = vec!["abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.attribution_filter, None);
+
+        let args = vec!["abc123".to_string(), "--only-ai".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.attribution_filter, Some(AttributionFilter::OnlyAi));
+        assert!(options.matches(Some(&Attribution::Ai("cursor".to_string()))));
+        assert!(!options.matches(Some(&Attribution::Human("alice".to_string()))));
+        assert!(!options.matches(None));
+
+        let args = vec!["abc123".to_string(), "--only-human".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert!(options.matches(Some(&Attribution::Human("alice".to_string()))));
+        assert!(!options.matches(Some(&Attribution::NoData)));
+
+        let args = vec!["--only-ai".to_string(), "--only-human".to_string()];
+        assert!(parse_annotated_diff_options(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_context_lines() {
+        let args = vec!["abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.context_lines, None);
+
+        let args = vec!["abc123".to_string(), "-U10".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.context_lines, Some(10));
+
+        let args = vec!["--unified=0".to_string(), "abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.context_lines, Some(0));
+
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+
+        let args = vec!["-Uabc".to_string()];
+        assert!(parse_annotated_diff_options(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_color() {
+        let args = vec!["abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.color, ColorMode::Auto);
+
+        let args = vec!["--color=always".to_string(), "abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.color, ColorMode::Always);
+        assert!(options.color.use_color());
+
+        let args = vec!["--color=never".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.color, ColorMode::Never);
+        assert!(!options.color.use_color());
+
+        let args = vec!["--color".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.color, ColorMode::Always);
+
+        let args = vec!["--color=sometimes".to_string()];
+        assert!(parse_annotated_diff_options(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_glyphs() {
+        let args = vec!["abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.glyphs(), AnnotationGlyphs::default());
+
+        let args = vec!["--ascii".to_string(), "abc123".to_string()];
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.glyphs(), AnnotationGlyphs::ascii());
+
+        // Explicit glyphs win over --ascii
+        let args = vec!["--ascii".to_string(), "--annotate-ai=AI:".to_string()];
+        let glyphs = parse_annotated_diff_options(&args).unwrap().glyphs();
+        assert_eq!(glyphs.ai, "AI:");
+        assert_eq!(glyphs.human, "[human:{}]");
+
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::WorkingTree));
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_reverse() {
+        let args = vec!["abc123".to_string()];
+        assert!(!parse_annotated_diff_options(&args).unwrap().reverse);
+
+        for flag in ["-R", "--reverse"] {
+            let args = vec![flag.to_string(), "abc123".to_string()];
+            assert!(parse_annotated_diff_options(&args).unwrap().reverse);
+
+            let (spec, _format) = parse_diff_args(&args).unwrap();
+            assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+        }
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_parent() {
+        let parent = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            parse_annotated_diff_options(&args).map(|options| options.parent)
+        };
+        assert_eq!(parent(&["abc123"]).unwrap(), None);
+        assert_eq!(parent(&["-m", "-2", "abc123"]).unwrap(), Some(2));
+        assert_eq!(parent(&["--parent=1", "abc123"]).unwrap(), Some(1));
+        assert!(parent(&["-m", "abc123"]).is_err());
+        assert!(parent(&["--parent=0", "abc123"]).is_err());
+
+        let args = vec!["-m".to_string(), "-2".to_string(), "abc123".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+    }
+
+    #[test]
+    fn test_build_diff_summary_json_counts_added_lines_per_tool() {
+        let hunk = DiffHunk {
+            file_path: "src/lib.rs".to_string(),
+            old_file_path: "src/lib.rs".to_string(),
+            copied: false,
+            old_start: 1,
+            old_count: 1,
+            new_start: 1,
+            new_count: 3,
+            deleted: 1..2,
+            added: 1..4,
+        };
+        let key = |line, side| DiffLineKey {
+            file: "src/lib.rs".into(),
+            line,
+            side,
+        };
+        let attributions = HashMap::from([
+            (
+                key(1, LineSide::Old),
+                Attribution::Human("alice".to_string()),
+            ),
+            (key(1, LineSide::New), Attribution::Ai("cursor".to_string())),
+            (key(2, LineSide::New), Attribution::Ai("claude".to_string())),
+            (key(3, LineSide::New), Attribution::NoData),
+            (
+                DiffLineKey::file_level("logo.png"),
+                Attribution::Ai("cursor".to_string()),
+            ),
+        ]);
+
+        let options = AnnotatedDiffOptions::default();
+        let summary_json =
+            build_diff_summary_json(&[hunk], &attributions, &LinePrompts::default(), &options);
+        assert_eq!(
+            summary_json.summary,
+            DiffAttributionSummary {
+                ai_lines: 2,
+                human_lines: 0,
+                no_data_lines: 1,
+                tools: BTreeMap::from([("claude".to_string(), 1), ("cursor".to_string(), 1)]),
+            }
+        );
+        let lines = summary_json.lines.unwrap();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0].author.as_deref(), Some("alice"));
+        assert_eq!(lines[0].provenance, None);
+        assert_eq!(lines[1].provenance.as_deref(), Some("direct"));
+        assert_eq!(lines[3].kind, "nodata");
+        assert_eq!(lines[3].author, None);
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_no_pager() {
+        let args = vec!["abc123".to_string()];
+        assert!(!parse_annotated_diff_options(&args).unwrap().no_pager);
+
+        let args = vec!["--no-pager".to_string(), "abc123".to_string()];
+        assert!(parse_annotated_diff_options(&args).unwrap().no_pager);
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_show_prompt() {
+        let args = vec!["abc123".to_string()];
+        assert!(!parse_annotated_diff_options(&args).unwrap().show_prompt);
+
+        let args = vec!["--show-prompt".to_string(), "abc123".to_string()];
+        assert!(parse_annotated_diff_options(&args).unwrap().show_prompt);
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+    }
+
+    #[test]
+    fn test_parse_annotated_diff_options_relative() {
+        let relative = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            parse_annotated_diff_options(&args).unwrap().relative
+        };
+        assert_eq!(relative(&["abc123"]), RelativeTo::Config);
+        assert_eq!(relative(&["--relative", "abc123"]), RelativeTo::Cwd);
+        assert_eq!(relative(&["--no-relative"]), RelativeTo::Root);
+        assert_eq!(
+            relative(&["--relative=src", "abc123"]),
+            RelativeTo::Dir("src".to_string())
+        );
+        // The last flag wins, as in git
+        assert_eq!(relative(&["--relative", "--no-relative"]), RelativeTo::Root);
+
+        let args = vec!["--relative=src".to_string(), "abc123".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+    }
+
+    #[test]
+    fn test_normalize_relative_dir() {
+        assert_eq!(normalize_relative_dir("src"), "src/");
+        assert_eq!(normalize_relative_dir("./src/"), "src/");
+        assert_eq!(normalize_relative_dir("src/commands"), "src/commands/");
+        assert_eq!(normalize_relative_dir(""), "");
+        assert_eq!(normalize_relative_dir("."), "");
+        assert_eq!(normalize_relative_dir("./"), "");
+    }
+
+    #[test]
+    fn test_select_pager() {
+        let some = |s: &str| Some(s.to_string());
+
+        assert_eq!(select_pager(None, None, None), some("less -R"));
+        assert_eq!(select_pager(None, None, some("more")), some("more"));
+        assert_eq!(
+            select_pager(None, some("delta"), some("more")),
+            some("delta")
+        );
+        assert_eq!(
+            select_pager(some("most"), some("delta"), some("more")),
+            some("most")
+        );
+        // An empty or `cat` pager at higher precedence disables paging
+        assert_eq!(select_pager(some("cat"), some("delta"), None), None);
+        assert_eq!(select_pager(None, some(""), some("more")), None);
+    }
+
+    #[test]
+    fn test_parse_diff_args_with_pathspecs() {
+        let args = vec![
+            "abc123..def456".to_string(),
+            "--".to_string(),
+            "src/".to_string(),
+            "--weird-name".to_string(),
+        ];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(
+            matches!(spec, DiffSpec::TwoCommit(start, end) if start == "abc123" && end == "def456")
+        );
+
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.pathspecs, vec!["src/", "--weird-name"]);
+
+        // A pathspec alone is not a commit
+        let args = vec!["--".to_string(), "src/".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::WorkingTree));
+    }
+
+    #[test]
+    fn test_resolve_commit_reports_missing_commits() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "line\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial").unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        assert!(resolve_commit(repo, "HEAD").is_ok());
+        assert!(matches!(
+            resolve_commit(repo, "does-not-exist"),
+            Err(GitAiError::CommitNotFound(rev)) if rev == "does-not-exist"
+        ));
+        assert!(matches!(
+            resolve_nth_parent(repo, "HEAD", 2),
+            Err(GitAiError::CommitNotFound(rev)) if rev == "HEAD^2"
+        ));
+    }
+
+    #[test]
+    fn test_overlay_diff_attributions_crlf_file() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("foo.py", "one\r\ntwo\r\nthree\r\nfour\r\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        tmp_repo
+            .write_file(
+                "foo.py",
+                "zero\r\none\r\ntwo\r\nthree\r\nfour\r\nfive\r\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let hunks = get_diff_with_line_numbers(repo, &first_sha, &head_sha, &[], false).unwrap();
+        let attributions = overlay_diff_attributions(
+            repo,
+            &first_sha,
+            &head_sha,
+            &hunks,
+            &mut LinePrompts::default(),
+        )
+        .unwrap();
+
+        assert_eq!(attributions.len(), 4, "attributions: {:?}", attributions);
+        for (line, is_ai) in [(1, false), (4, true), (5, true), (6, false)] {
+            let key = DiffLineKey {
+                file: "foo.py".into(),
+                line,
+                side: LineSide::New,
+            };
+            let attribution = attributions.get(&key);
+            if is_ai {
+                assert!(
+                    matches!(attribution, Some(Attribution::Ai(_))),
+                    "line {}: {:?}",
+                    line,
+                    attribution
+                );
+            } else {
+                assert!(
+                    matches!(attribution, Some(Attribution::Human(_))),
+                    "line {}: {:?}",
+                    line,
+                    attribution
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_diff_args_html_format() {
+        let args = vec!["--format=html".to_string(), "abc123".to_string()];
+        let (spec, format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(format, DiffFormat::Html));
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+
+        let args = vec!["--format=pdf".to_string()];
+        assert!(parse_diff_args(&args).is_err());
+        let args = vec!["--format=html".to_string(), "--json".to_string()];
+        assert!(parse_diff_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"if a < b && c > "d" { 'e' }"#),
+            "if a &lt; b &amp;&amp; c &gt; &quot;d&quot; { &#39;e&#39; }"
+        );
+        assert_eq!(ai_percentage(1, 3), 33);
+        assert_eq!(ai_percentage(2, 3), 67);
+        assert_eq!(ai_percentage(0, 0), 0);
+    }
+
+    #[test]
+    fn test_match_moved_blocks() {
+        let lines = |lines: &[(u32, &str)]| -> Vec<(u32, String)> {
+            lines
+                .iter()
+                .map(|(line, text)| (*line, text.to_string()))
+                .collect()
+        };
+        let deleted = lines(&[
+            (2, "fn helper() {"),
+            (3, "    compute_value(input)"),
+            (4, "}"),
+            (9, "}"),
+        ]);
+        let added = lines(&[
+            (1, "}"),
+            (6, "fn helper() {"),
+            (7, "    compute_value(input)"),
+            (8, "}"),
+        ]);
+
+        // The block moves as a whole; the lone brace is too short to count as a move
+        assert_eq!(
+            match_moved_blocks(&deleted, &added),
+            vec![(6, 2), (7, 3), (8, 4)]
+        );
+        assert!(match_moved_blocks(&lines(&[(1, "}")]), &lines(&[(5, "}")])).is_empty());
+    }
+
+    #[test]
+    fn test_diff_spec_round_trip() {
+        let specs = [
+            ("abc123", DiffSpec::SingleCommit("abc123".to_string())),
+            (
+                "abc123..def456",
+                DiffSpec::TwoCommit("abc123".to_string(), "def456".to_string()),
+            ),
+            (
+                "main...feature",
+                DiffSpec::MergeBase("main".to_string(), "feature".to_string()),
+            ),
+            ("", DiffSpec::WorkingTree),
+            ("--cached", DiffSpec::Cached),
+        ];
+        for (text, spec) in specs {
+            assert_eq!(text.parse::<DiffSpec>().unwrap(), spec);
+            assert_eq!(spec.to_string(), text);
+            assert_eq!(spec.to_string().parse::<DiffSpec>().unwrap(), spec);
+        }
 
-        match spec {
-            DiffSpec::TwoCommit(start, end) => {
-                assert_eq!(start, "abc123");
-                assert_eq!(end, "def456");
-            }
-            _ => panic!("Expected TwoCommit"),
+        // Two commits are written back as a range
+        let spec: DiffSpec = "abc123 def456".parse().unwrap();
+        assert_eq!(spec.to_string(), "abc123..def456");
+        assert_eq!("--staged".parse::<DiffSpec>().unwrap(), DiffSpec::Cached);
+
+        for invalid in ["a..", "..b", "a...", "a..b c", "a b c"] {
+            assert!(invalid.parse::<DiffSpec>().is_err(), "{}", invalid);
         }
     }
 
     #[test]
-    fn test_parse_diff_args_invalid_range() {
-        let args = vec!["..".to_string()];
-        let result = parse_diff_args(&args);
-        assert!(result.is_err());
+    fn test_parse_diff_args_two_positional_commits() {
+        let args: Vec<String> = ["abc123", "def456", "--json", "--", "src/"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (spec, format) = parse_diff_args(&args).unwrap();
+        assert!(
+            matches!(spec, DiffSpec::TwoCommit(start, end) if start == "abc123" && end == "def456")
+        );
+        assert!(matches!(format, DiffFormat::Json));
+        assert_eq!(
+            parse_annotated_diff_options(&args).unwrap().pathspecs,
+            vec!["src/"]
+        );
 
-        let args = vec!["abc..".to_string()];
-        let result = parse_diff_args(&args);
-        assert!(result.is_err());
+        // The joined form still parses to the same spec
+        let (spec, _format) = parse_diff_args(&["abc123..def456".to_string()]).unwrap();
+        assert!(
+            matches!(spec, DiffSpec::TwoCommit(start, end) if start == "abc123" && end == "def456")
+        );
 
-        let args = vec!["..def".to_string()];
-        let result = parse_diff_args(&args);
-        assert!(result.is_err());
+        // A range can't be paired with another revision, and a third revision is an error
+        for args in [
+            vec!["abc123..def456", "ghi789"],
+            vec!["abc123", "def456", "ghi789"],
+        ] {
+            let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+            assert!(parse_diff_args(&args).is_err(), "{:?}", args);
+        }
     }
 
     #[test]
@@ -1004,8 +5169,8 @@ mod tests {
         assert_eq!(result.old_count, 3);
         assert_eq!(result.new_start, 15);
         assert_eq!(result.new_count, 5);
-        assert_eq!(result.deleted_lines, vec![10, 11, 12]);
-        assert_eq!(result.added_lines, vec![15, 16, 17, 18, 19]);
+        assert_eq!(result.deleted, 10..13);
+        assert_eq!(result.added, 15..20);
     }
 
     #[test]
@@ -1017,8 +5182,8 @@ mod tests {
         assert_eq!(result.old_count, 1);
         assert_eq!(result.new_start, 10);
         assert_eq!(result.new_count, 2);
-        assert_eq!(result.deleted_lines, vec![10]);
-        assert_eq!(result.added_lines, vec![10, 11]);
+        assert_eq!(result.deleted, 10..11);
+        assert_eq!(result.added, 10..12);
     }
 
     #[test]
@@ -1030,8 +5195,8 @@ mod tests {
         assert_eq!(result.old_count, 2);
         assert_eq!(result.new_start, 10);
         assert_eq!(result.new_count, 1);
-        assert_eq!(result.deleted_lines, vec![10, 11]);
-        assert_eq!(result.added_lines, vec![10]);
+        assert_eq!(result.deleted, 10..12);
+        assert_eq!(result.added, 10..11);
     }
 
     #[test]
@@ -1043,8 +5208,8 @@ mod tests {
         assert_eq!(result.old_count, 0);
         assert_eq!(result.new_start, 1);
         assert_eq!(result.new_count, 3);
-        assert_eq!(result.deleted_lines.len(), 0);
-        assert_eq!(result.added_lines, vec![1, 2, 3]);
+        assert!(result.deleted.is_empty());
+        assert_eq!(result.added, 1..4);
     }
 
     #[test]
@@ -1056,8 +5221,36 @@ mod tests {
         assert_eq!(result.old_count, 3);
         assert_eq!(result.new_start, 0);
         assert_eq!(result.new_count, 0);
-        assert_eq!(result.deleted_lines, vec![5, 6, 7]);
-        assert_eq!(result.added_lines.len(), 0);
+        assert_eq!(result.deleted, 5..8);
+        assert!(result.added.is_empty());
+    }
+
+    #[test]
+    fn test_parse_hunk_line_huge_hunk_keeps_ranges() {
+        let line = "@@ -1,4000000000 +1,4000000000 @@";
+        let result = parse_hunk_line(line, "generated.rs").unwrap().unwrap();
+        assert_eq!(result.added_lines().len(), 4_000_000_000);
+        assert_eq!(result.deleted_lines().last(), Some(4_000_000_000));
+
+        // A count running past u32::MAX is clamped instead of overflowing
+        let line = "@@ -4294967295,5 +4294967295,5 @@";
+        let result = parse_hunk_line(line, "generated.rs").unwrap().unwrap();
+        assert_eq!(result.added, 4294967295..u32::MAX);
+    }
+
+    #[test]
+    fn test_merge_line_ranges() {
+        let ranges = vec![5..7, 1..3, 3..3, 2..4, 8..9];
+        assert_eq!(merge_line_ranges(ranges), vec![(1, 3), (5, 6), (8, 8)]);
+
+        // A huge hunk stays a single range instead of one entry per line
+        let ranges = vec![1..4_000_000_001, 10..20];
+        assert_eq!(merge_line_ranges(ranges), vec![(1, 4_000_000_000)]);
+
+        assert_eq!(
+            collect_line_ranges([1, 2, 3, 5, 7, 8]),
+            vec![1..4, 5..6, 7..9]
+        );
     }
 
     #[test]
@@ -1091,44 +5284,708 @@ mod tests {
 
     #[test]
     fn test_format_attribution_ai() {
+        let glyphs = AnnotationGlyphs::default();
         let attr = Attribution::Ai("cursor".to_string());
-        assert_eq!(format_attribution(&attr), "🤖cursor");
+        assert_eq!(format_attribution(&attr, &glyphs), "🤖cursor");
 
         let attr = Attribution::Ai("claude".to_string());
-        assert_eq!(format_attribution(&attr), "🤖claude");
+        assert_eq!(format_attribution(&attr, &glyphs), "🤖claude");
     }
 
     #[test]
     fn test_format_attribution_human() {
+        let glyphs = AnnotationGlyphs::default();
         let attr = Attribution::Human("alice".to_string());
-        assert_eq!(format_attribution(&attr), "👤alice");
+        assert_eq!(format_attribution(&attr, &glyphs), "👤alice");
 
         let attr = Attribution::Human("bob@example.com".to_string());
-        assert_eq!(format_attribution(&attr), "👤bob@example.com");
+        assert_eq!(format_attribution(&attr, &glyphs), "👤bob@example.com");
     }
 
     #[test]
     fn test_format_attribution_no_data() {
+        let glyphs = AnnotationGlyphs::default();
         let attr = Attribution::NoData;
-        assert_eq!(format_attribution(&attr), "[no-data]");
+        assert_eq!(format_attribution(&attr, &glyphs), "[no-data]");
+    }
+
+    #[test]
+    fn test_format_attribution_custom_glyphs() {
+        let ai = Attribution::Ai("cursor".to_string());
+        let human = Attribution::Human("alice".to_string());
+
+        let ascii = AnnotationGlyphs::ascii();
+        assert_eq!(format_attribution(&ai, &ascii), "[AI:cursor]");
+        assert_eq!(format_attribution(&human, &ascii), "[human:alice]");
+        let no_data = Attribution::NoData;
+        assert_eq!(format_attribution(&no_data, &ascii), "[no-data]");
+
+        let prefixed = AnnotationGlyphs {
+            ai: "ai=".to_string(),
+            human: "<{}>".to_string(),
+        };
+        assert_eq!(format_attribution(&ai, &prefixed), "ai=cursor");
+        assert_eq!(format_attribution(&human, &prefixed), "<alice>");
+    }
+
+    #[test]
+    fn test_tool_color_is_stable_and_tints_only_ai() {
+        let glyphs = AnnotationGlyphs::default();
+        assert_eq!(tool_color("cursor"), tool_color("cursor"));
+        assert!(TOOL_PALETTE.contains(&tool_color("claude")));
+        assert_ne!(tool_color("claude"), tool_color("cursor"));
+
+        let ai = Attribution::Ai("cursor".to_string());
+        assert_eq!(
+            format_annotated_line("+x", LineType::Addition, true, Some(&ai), None, &glyphs),
+            format!(
+                "\x1b[32m+x\x1b[0m  {}🤖cursor\x1b[0m\n",
+                tool_color("cursor")
+            )
+        );
+        let human = Attribution::Human("alice".to_string());
+        assert_eq!(
+            format_annotated_line("-x", LineType::Deletion, true, Some(&human), None, &glyphs),
+            "\x1b[31m-x\x1b[0m  \x1b[2m👤alice\x1b[0m\n"
+        );
+        assert_eq!(
+            format_annotated_line("+x", LineType::Addition, false, Some(&ai), None, &glyphs),
+            "+x  🤖cursor\n"
+        );
+    }
+
+    #[test]
+    fn test_format_tool_legend() {
+        let glyphs = AnnotationGlyphs::default();
+        let mut attributions = HashMap::new();
+        let mut add = |line: u32, attribution: Attribution| {
+            attributions.insert(
+                DiffLineKey {
+                    file: "test.rs".into(),
+                    line,
+                    side: LineSide::New,
+                },
+                attribution,
+            );
+        };
+        add(1, Attribution::Human("alice".to_string()));
+        add(2, Attribution::NoData);
+        assert_eq!(format_tool_legend(&attributions, true, &glyphs), "");
+
+        add(3, Attribution::Ai("cursor".to_string()));
+        add(4, Attribution::Ai("claude".to_string()));
+        add(5, Attribution::Ai("cursor".to_string()));
+        assert_eq!(
+            format_tool_legend(&attributions, true, &glyphs),
+            format!(
+                "\x1b[2mAI tools:\x1b[0m {}🤖claude\x1b[0m  {}🤖cursor\x1b[0m\n",
+                tool_color("claude"),
+                tool_color("cursor")
+            )
+        );
+        assert_eq!(format_tool_legend(&attributions, false, &glyphs), "");
+    }
+
+    #[test]
+    fn test_line_prompts_record_first_user_message() {
+        use crate::authorship::working_log::AgentId;
+
+        let record = |messages: Vec<Message>| PromptRecord {
+            agent_id: AgentId {
+                tool: "cursor".to_string(),
+                id: "session".to_string(),
+                model: "model-x".to_string(),
+            },
+            human_author: None,
+            messages,
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            messages_url: None,
+            tool_version: None,
+        };
+        let key = |line: u32| DiffLineKey {
+            file: "a.rs".into(),
+            line,
+            side: LineSide::New,
+        };
+
+        let mut prompts = LinePrompts::default();
+        prompts.record(
+            key(1),
+            "p1",
+            &record(vec![
+                Message::assistant("Sure".to_string(), None),
+                Message::user("  Add a retry loop  ".to_string(), None),
+                Message::user("Also log failures".to_string(), None),
+            ]),
+        );
+        prompts.record(key(2), "p2", &record(vec![]));
+
+        assert_eq!(prompts.by_line.get(&key(1)).map(String::as_str), Some("p1"));
+        assert_eq!(
+            prompts.texts.get("p1").map(String::as_str),
+            Some("Add a retry loop")
+        );
+        assert!(!prompts.by_line.contains_key(&key(2)));
+    }
+
+    #[test]
+    fn test_label_tool_versions() {
+        use crate::authorship::working_log::AgentId;
+
+        let key = |line: u32| DiffLineKey {
+            file: "a.rs".into(),
+            line,
+            side: LineSide::New,
+        };
+        let record = PromptRecord {
+            agent_id: AgentId {
+                tool: "cursor".to_string(),
+                id: "session".to_string(),
+                model: "model-x".to_string(),
+            },
+            human_author: None,
+            messages: vec![],
+            total_additions: 0,
+            total_deletions: 0,
+            accepted_lines: 0,
+            overriden_lines: 0,
+            messages_url: None,
+            tool_version: Some("0.45.2".to_string()),
+        };
+
+        // The version is kept even for prompts without any text
+        let mut prompts = LinePrompts::default();
+        prompts.record(key(1), "p1", &record);
+        assert_eq!(
+            prompts.tool_versions.get(&key(1)).map(String::as_str),
+            Some("0.45.2")
+        );
+
+        let mut attributions = HashMap::from([
+            (key(1), Attribution::Ai("cursor".to_string())),
+            (key(2), Attribution::Ai("claude".to_string())),
+            (key(3), Attribution::Human("alice".to_string())),
+        ]);
+        prompts.tool_versions.insert(key(3), "1.0".to_string());
+        label_tool_versions(&mut attributions, &prompts);
+        assert_eq!(
+            attributions[&key(1)],
+            Attribution::Ai("cursor@0.45.2".to_string())
+        );
+        assert_eq!(attributions[&key(2)], Attribution::Ai("claude".to_string()));
+        assert_eq!(
+            attributions[&key(3)],
+            Attribution::Human("alice".to_string())
+        );
+    }
+
+    #[test]
+    fn test_summarize_prompt() {
+        assert_eq!(summarize_prompt("Fix the bug", 60), "Fix the bug");
+        assert_eq!(
+            summarize_prompt("Fix the bug\nin parser.rs", 60),
+            "Fix the bug…"
+        );
+        assert_eq!(summarize_prompt("Refactor everything now", 10), "Refactor…");
+    }
+
+    #[test]
+    fn test_prompt_notes_index_repeated_prompts() {
+        let glyphs = AnnotationGlyphs::default();
+        let key = |line: u32| DiffLineKey {
+            file: "a.rs".into(),
+            line,
+            side: LineSide::New,
+        };
+        let mut prompts = LinePrompts::default();
+        for (line, prompt_hash) in [(1, "once"), (2, "twice"), (3, "twice")] {
+            prompts.by_line.insert(key(line), prompt_hash.to_string());
+        }
+        prompts.texts.insert(
+            "once".to_string(),
+            "Rename the helper to something clearer".to_string(),
+        );
+        prompts.texts.insert(
+            "twice".to_string(),
+            "Add a retry loop\nwith backoff".to_string(),
+        );
+        let ai = Attribution::Ai("cursor".to_string());
+
+        let mut notes = PromptNotes::new(&prompts, None, &glyphs);
+        assert_eq!(notes.footer(), "");
+        assert_eq!(notes.note(&key(2), "+a", Some(&ai)).as_deref(), Some("[1]"));
+        assert_eq!(
+            notes.note(&key(1), "+b", Some(&ai)).as_deref(),
+            Some("\"Rename the helper to something clearer\"")
+        );
+        assert_eq!(notes.note(&key(3), "+c", Some(&ai)).as_deref(), Some("[1]"));
+        assert_eq!(notes.note(&key(4), "+d", Some(&ai)), None);
+        assert_eq!(
+            notes.footer(),
+            "\nPrompts:\n  [1] Add a retry loop\n      with backoff\n"
+        );
+
+        // A narrow terminal cuts the summary, but never below the minimum
+        let mut narrow = PromptNotes::new(&prompts, Some(40), &glyphs);
+        assert_eq!(
+            narrow.note(&key(1), "+b", Some(&ai)).as_deref(),
+            Some("\"Rename the helper to som…\"")
+        );
+        let mut tiny = PromptNotes::new(&prompts, Some(10), &glyphs);
+        assert_eq!(
+            tiny.note(&key(1), "+b", Some(&ai)).as_deref(),
+            Some("\"Rename the help…\"")
+        );
+    }
+
+    #[test]
+    fn test_format_attribution_summary() {
+        let mut attributions = HashMap::new();
+        let mut add = |line: u32, side: LineSide, attribution: Attribution| {
+            attributions.insert(
+                DiffLineKey {
+                    file: "test.rs".into(),
+                    line,
+                    side,
+                },
+                attribution,
+            );
+        };
+        add(1, LineSide::New, Attribution::Ai("claude".to_string()));
+        add(2, LineSide::New, Attribution::Ai("cursor".to_string()));
+        add(3, LineSide::New, Attribution::Ai("cursor".to_string()));
+        add(4, LineSide::New, Attribution::Human("alice".to_string()));
+        add(5, LineSide::New, Attribution::Human("alice".to_string()));
+        add(6, LineSide::New, Attribution::NoData);
+        add(1, LineSide::Old, Attribution::Human("bob".to_string()));
+
+        assert_eq!(
+            format_attribution_summary(&attributions),
+            "2 lines cursor, 1 line claude, 2 lines alice, 1 no-data"
+        );
+    }
+
+    #[test]
+    fn test_format_porcelain_diff() {
+        let hunks = vec![DiffHunk {
+            file_path: "new.rs".to_string(),
+            old_file_path: "old.rs".to_string(),
+            copied: false,
+            old_start: 3,
+            old_count: 1,
+            new_start: 3,
+            new_count: 3,
+            deleted: 3..4,
+            added: 3..6,
+        }];
+        let mut attributions = HashMap::new();
+        let mut add = |line: u32, attribution: Attribution| {
+            attributions.insert(
+                DiffLineKey {
+                    file: "new.rs".into(),
+                    line,
+                    side: LineSide::New,
+                },
+                attribution,
+            );
+        };
+        add(3, Attribution::Ai("cursor".to_string()));
+        add(4, Attribution::Human("alice".to_string()));
+        add(5, Attribution::NoData);
+
+        let prompts = LinePrompts::default();
+        let output = format_porcelain_diff(
+            &hunks,
+            &attributions,
+            &prompts,
+            &AnnotatedDiffOptions::default(),
+        );
+        assert_eq!(
+            output,
+            "old\told.rs\t3\tnodata\t\t\n\
+             new\tnew.rs\t3\tai\tcursor\tdirect\n\
+             new\tnew.rs\t4\thuman\talice\t\n\
+             new\tnew.rs\t5\tnodata\t\t\n"
+        );
+
+        let only_ai = AnnotatedDiffOptions {
+            attribution_filter: Some(AttributionFilter::OnlyAi),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_porcelain_diff(&hunks, &attributions, &prompts, &only_ai),
+            "new\tnew.rs\t3\tai\tcursor\tdirect\n"
+        );
+
+        let mut prompts = LinePrompts::default();
+        prompts.provenance.insert(
+            DiffLineKey {
+                file: "new.rs".into(),
+                line: 3,
+                side: LineSide::New,
+            },
+            AttributionProvenance::Merged,
+        );
+        assert_eq!(
+            format_porcelain_diff(&hunks, &attributions, &prompts, &only_ai),
+            "new\tnew.rs\t3\tai\tcursor\tmerged\n"
+        );
+    }
+
+    #[test]
+    fn test_format_porcelain_diff_relative() {
+        let hunks = vec![DiffHunk {
+            file_path: "src/lib.rs".to_string(),
+            old_file_path: "src/lib.rs".to_string(),
+            copied: false,
+            old_start: 1,
+            old_count: 1,
+            new_start: 1,
+            new_count: 1,
+            deleted: 1..2,
+            added: 1..2,
+        }];
+        let attributions = HashMap::from([(
+            DiffLineKey {
+                file: "src/lib.rs".into(),
+                line: 1,
+                side: LineSide::New,
+            },
+            Attribution::Ai("cursor".to_string()),
+        )]);
+        let options = AnnotatedDiffOptions {
+            relative: RelativeTo::Dir("src/".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            format_porcelain_diff(&hunks, &attributions, &LinePrompts::default(), &options),
+            "old\tlib.rs\t1\tnodata\t\t\n\
+             new\tlib.rs\t1\tai\tcursor\tdirect\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_context_lines_tracks_new_side_line_numbers() {
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                    index 1111111..2222222 100644\n\
+                    --- a/src/lib.rs\n\
+                    +++ b/src/lib.rs\n\
+                    @@ -1,4 +1,4 @@\n\
+                    \x20fn a() {}\n\
+                    -fn b() {}\n\
+                    +fn c() {}\n\
+                    +fn d() {}\n\
+                    \x20fn e() {}\n\
+                    diff --git a/gone.rs b/gone.rs\n\
+                    deleted file mode 100644\n\
+                    --- a/gone.rs\n\
+                    +++ /dev/null\n\
+                    @@ -1 +0,0 @@\n\
+                    -fn gone() {}\n";
+
+        let context_lines = parse_context_lines(&mut diff.as_bytes(), "sub/").unwrap();
+        assert_eq!(
+            context_lines,
+            BTreeMap::from([("sub/src/lib.rs".to_string(), vec![1..2, 4..5])])
+        );
+    }
+
+    #[test]
+    fn test_parse_diff_args_porcelain() {
+        let args = vec!["abc123".to_string(), "--porcelain".to_string()];
+        let (_spec, format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(format, DiffFormat::Porcelain));
+
+        let args = vec!["--json".to_string(), "--porcelain".to_string()];
+        assert!(parse_diff_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_args_no_data_only() {
+        let args = vec!["abc123".to_string(), "--no-data-only".to_string()];
+        let (_spec, format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(format, DiffFormat::NoDataOnly));
+
+        let args = vec!["--no-data-only".to_string(), "--stat".to_string()];
+        assert!(parse_diff_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_args_github_output() {
+        let args = vec!["abc123".to_string(), "--output=github".to_string()];
+        let (spec, format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(format, DiffFormat::GithubAnnotations));
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.output_path, None);
+
+        let args = vec!["--output=github".to_string(), "--json".to_string()];
+        assert!(parse_diff_args(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_output_file() {
+        let args: Vec<String> = ["--output", "diff.txt", "abc123", "--json"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (spec, format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+        assert!(matches!(format, DiffFormat::Json));
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.output_path.as_deref(), Some("diff.txt"));
+
+        // Only the file follows --output, so this still diffs the working tree
+        let args = vec!["--output".to_string(), "diff.txt".to_string()];
+        let (spec, _format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::WorkingTree));
+
+        let args = vec!["--output".to_string()];
+        assert!(parse_annotated_diff_options(&args).is_err());
+        let args = vec!["--output=".to_string()];
+        assert!(parse_annotated_diff_options(&args).is_err());
+    }
+
+    #[test]
+    fn test_parse_output_file_equals_form() {
+        let args: Vec<String> = ["abc123", "--output=diff.txt", "--porcelain"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (spec, format) = parse_diff_args(&args).unwrap();
+        assert!(matches!(spec, DiffSpec::SingleCommit(sha) if sha == "abc123"));
+        assert!(matches!(format, DiffFormat::Porcelain));
+        let options = parse_annotated_diff_options(&args).unwrap();
+        assert_eq!(options.output_path.as_deref(), Some("diff.txt"));
+    }
+
+    #[test]
+    fn test_format_diff_stat() {
+        let hunks = vec![
+            DiffHunk {
+                file_path: "src/main.rs".to_string(),
+                old_file_path: "src/main.rs".to_string(),
+                copied: false,
+                old_start: 2,
+                old_count: 1,
+                new_start: 2,
+                new_count: 3,
+                deleted: 2..3,
+                added: 2..5,
+            },
+            DiffHunk {
+                file_path: "README.md".to_string(),
+                old_file_path: "README.md".to_string(),
+                copied: false,
+                old_start: 0,
+                old_count: 0,
+                new_start: 1,
+                new_count: 1,
+                deleted: 0..0,
+                added: 1..2,
+            },
+        ];
+        let mut attributions = HashMap::new();
+        let mut add = |file: &str, line: u32, attribution: Attribution| {
+            attributions.insert(
+                DiffLineKey {
+                    file: file.into(),
+                    line,
+                    side: LineSide::New,
+                },
+                attribution,
+            );
+        };
+        // The deleted line has no attribution, as in a forward diff
+        add("src/main.rs", 2, Attribution::Ai("cursor".to_string()));
+        add("src/main.rs", 3, Attribution::Ai("cursor".to_string()));
+        add("src/main.rs", 4, Attribution::Human("alice".to_string()));
+        add("README.md", 1, Attribution::Human("alice".to_string()));
+
+        assert_eq!(
+            format_diff_stat(&hunks, &attributions, ""),
+            " src/main.rs | 4 (+3 -1)  2 ai, 1 human, 1 no-data\n\
+             \x20README.md   | 1 (+1 -0)  1 human\n\
+             \x202 files changed, 4 insertions(+), 1 deletion(-); 2 ai, 2 human, 1 no-data\n"
+        );
+        assert_eq!(format_diff_stat(&[], &attributions, ""), "");
+        assert!(format_diff_stat(&hunks[..1], &attributions, "src/").starts_with(" main.rs | 4"));
+    }
+
+    #[test]
+    fn test_format_github_annotations() {
+        let hunks = vec![
+            DiffHunk {
+                file_path: "src/main.rs".to_string(),
+                old_file_path: "src/main.rs".to_string(),
+                copied: false,
+                old_start: 0,
+                old_count: 0,
+                new_start: 1,
+                new_count: 6,
+                deleted: 0..0,
+                added: 1..7,
+            },
+            DiffHunk {
+                file_path: "a,b.rs".to_string(),
+                old_file_path: "a,b.rs".to_string(),
+                copied: false,
+                old_start: 0,
+                old_count: 0,
+                new_start: 1,
+                new_count: 1,
+                deleted: 0..0,
+                added: 1..2,
+            },
+        ];
+        let mut attributions = HashMap::new();
+        let mut add = |file: &str, line: u32, attribution: Attribution| {
+            attributions.insert(
+                DiffLineKey {
+                    file: file.into(),
+                    line,
+                    side: LineSide::New,
+                },
+                attribution,
+            );
+        };
+        add("src/main.rs", 1, Attribution::Ai("cursor".to_string()));
+        add("src/main.rs", 2, Attribution::Ai("cursor".to_string()));
+        add("src/main.rs", 3, Attribution::Ai("claude".to_string()));
+        add("src/main.rs", 4, Attribution::Human("alice".to_string()));
+        add("src/main.rs", 5, Attribution::Ai("cursor".to_string()));
+        add("src/main.rs", 6, Attribution::Ai("cursor".to_string()));
+        add("a,b.rs", 1, Attribution::Ai("cursor".to_string()));
+
+        assert_eq!(
+            format_github_annotations(&hunks, &attributions),
+            "::notice file=src/main.rs,line=1,endLine=2::AI-authored by cursor\n\
+             ::notice file=src/main.rs,line=3::AI-authored by claude\n\
+             ::notice file=src/main.rs,line=5,endLine=6::AI-authored by cursor\n\
+             ::notice file=a%2Cb.rs,line=1::AI-authored by cursor\n"
+        );
+    }
+
+    #[test]
+    fn test_word_diff_line_render() {
+        let glyphs = AnnotationGlyphs::default();
+        let mut line = WordDiffLine::default();
+        line.push(WordSpan::Common, "let x = ");
+        line.push(WordSpan::Removed, "1");
+        line.push(WordSpan::Added, "2");
+        line.push(WordSpan::Common, ";");
+
+        let attr = Attribution::Ai("cursor".to_string());
+        assert_eq!(
+            line.render(Some(&attr), false, None, &glyphs),
+            "let x = [-1-][+2+];  🤖cursor\n"
+        );
+        assert_eq!(
+            line.render(Some(&attr), true, None, &glyphs),
+            format!(
+                "let x = \x1b[31m[-1-]\x1b[0m\x1b[35m[+2+]\x1b[0m;  {}🤖cursor\x1b[0m\n",
+                tool_color("cursor")
+            )
+        );
+        assert!(line.has_changes());
+
+        let mut context = WordDiffLine::default();
+        context.push(WordSpan::Common, "unchanged");
+        assert!(!context.has_changes());
+        assert_eq!(context.render(None, true, None, &glyphs), "unchanged\n");
+    }
+
+    #[test]
+    fn test_parse_binary_numstat() {
+        let numstat = "3\t1\tsrc/main.rs\0-\t-\tlogo.png\0-\t-\t\0old.bin\0new.bin\0";
+        assert_eq!(
+            parse_binary_numstat(numstat),
+            vec!["logo.png".to_string(), "new.bin".to_string()]
+        );
+        assert!(parse_binary_numstat("").is_empty());
+    }
+
+    #[test]
+    fn test_binary_marker_attribution() {
+        let mut attributions = HashMap::new();
+        attributions.insert(
+            DiffLineKey::file_level("assets/logo.png"),
+            Attribution::Human("alice".to_string()),
+        );
+
+        let marker = "Binary files a/assets/logo.png and b/assets/logo.png differ";
+        assert!(matches!(
+            binary_marker_attribution(marker, "", &attributions),
+            Some(Attribution::Human(name)) if name == "alice"
+        ));
+        let added = "Binary files /dev/null and b/assets/logo.png differ";
+        assert!(binary_marker_attribution(added, "", &attributions).is_some());
+        let deleted = "Binary files a/assets/logo.png and /dev/null differ";
+        assert!(binary_marker_attribution(deleted, "", &attributions).is_none());
+
+        // File-level keys aren't lines, so they stay out of the summary
+        assert_eq!(format_attribution_summary(&attributions), "");
+    }
+
+    #[test]
+    fn test_diff_header_path_canonicalization() {
+        let path = |line: &str| diff_header_path(line, "+++ ", "b/");
+
+        assert_eq!(path("+++ b/src/main.rs"), Some("src/main.rs".to_string()));
+        assert_eq!(path("+++ b/my file.txt\t"), Some("my file.txt".to_string()));
+        assert_eq!(
+            path("+++ \"b/na\\303\\257ve.txt\""),
+            Some("naïve.txt".to_string())
+        );
+        assert_eq!(
+            path("+++ \"b/say \\\"hi\\\"\\t.txt\""),
+            Some("say \"hi\"\t.txt".to_string())
+        );
+        assert_eq!(path("+++ /dev/null"), None);
+        assert_eq!(
+            diff_header_path("--- a/Notes.md", "--- ", "a/"),
+            Some("Notes.md".to_string())
+        );
+
+        // Case is kept: paths differing only by case stay distinct keys
+        assert_ne!(path("+++ b/Notes.md"), path("+++ b/notes.md"));
+        assert_eq!(unquote_git_path("rename.rs"), "rename.rs");
+        assert_eq!(unquote_git_path("\"\\303\\251t\\303\\251.md\""), "été.md");
+    }
+
+    #[test]
+    fn test_binary_marker_attribution_quoted_path() {
+        let mut attributions = HashMap::new();
+        attributions.insert(
+            DiffLineKey::file_level("naïve.png"),
+            Attribution::Human("alice".to_string()),
+        );
+
+        let marker = "Binary files \"a/na\\303\\257ve.png\" and \"b/na\\303\\257ve.png\" differ";
+        assert!(binary_marker_attribution(marker, "", &attributions).is_some());
     }
 
     #[test]
     fn test_diff_line_key_equality() {
         let key1 = DiffLineKey {
-            file: "test.rs".to_string(),
+            file: "test.rs".into(),
             line: 10,
             side: LineSide::Old,
         };
 
         let key2 = DiffLineKey {
-            file: "test.rs".to_string(),
+            file: "test.rs".into(),
             line: 10,
             side: LineSide::Old,
         };
 
         let key3 = DiffLineKey {
-            file: "test.rs".to_string(),
+            file: "test.rs".into(),
             line: 10,
             side: LineSide::New,
         };
@@ -1157,10 +6014,175 @@ index 111222..333444 100644
         assert_eq!(result[1].file_path, "file2.rs");
     }
 
+    #[test]
+    fn test_parse_diff_hunks_deleted_file() {
+        let diff_text = r#"diff --git a/file1.rs b/file1.rs
+index abc123..def456 100644
+--- a/file1.rs
++++ b/file1.rs
+@@ -10,2 +10,3 @@ fn main() {
+diff --git a/gone.rs b/gone.rs
+deleted file mode 100644
+index 111222..0000000
+--- a/gone.rs
++++ /dev/null
+@@ -1,3 +0,0 @@
+diff --git a/file2.rs b/file2.rs
+index 555666..777888 100644
+--- a/file2.rs
++++ b/file2.rs
+@@ -5,1 +5,2 @@ fn test() {
+"#;
+
+        let result = parse_diff_hunks(diff_text).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[1].file_path, "gone.rs");
+        assert_eq!(result[1].old_file_path, "gone.rs");
+        assert_eq!(result[1].deleted, 1..4);
+        assert!(result[1].added.is_empty());
+        assert_eq!(result[2].file_path, "file2.rs");
+    }
+
+    #[test]
+    fn test_parse_diff_hunks_rename() {
+        let diff_text = r#"diff --git a/old_name.rs b/new_name.rs
+similarity index 90%
+rename from old_name.rs
+rename to new_name.rs
+index abc123..def456 100644
+--- a/old_name.rs
++++ b/new_name.rs
+@@ -3 +3 @@ fn main() {
+"#;
+
+        let result = parse_diff_hunks(diff_text).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].file_path, "new_name.rs");
+        assert_eq!(result[0].old_file_path, "old_name.rs");
+    }
+
+    #[test]
+    fn test_parse_diff_hunks_old_path_matches_new_path_without_rename() {
+        let diff_text = r#"diff --git a/file1.rs b/file1.rs
+index abc123..def456 100644
+--- a/file1.rs
++++ b/file1.rs
+@@ -10,2 +10,3 @@ fn main() {
+"#;
+
+        let result = parse_diff_hunks(diff_text).unwrap();
+        assert_eq!(result[0].file_path, "file1.rs");
+        assert_eq!(result[0].old_file_path, "file1.rs");
+    }
+
     #[test]
     fn test_parse_diff_hunks_empty() {
         let diff_text = "";
         let result = parse_diff_hunks(diff_text).unwrap();
         assert_eq!(result.len(), 0);
     }
+
+    /// Serves lines from an iterator as they're read, so the diff never exists in full.
+    /// `served` counts the lines handed out so far.
+    struct LineReader<I> {
+        lines: I,
+        current: Vec<u8>,
+        pos: usize,
+        served: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl<I: Iterator<Item = String>> std::io::Read for LineReader<I> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.pos == self.current.len() {
+                let Some(line) = self.lines.next() else {
+                    return Ok(0);
+                };
+                self.current = format!("{}\n", line).into_bytes();
+                self.pos = 0;
+                self.served.set(self.served.get() + 1);
+            }
+            let n = buf.len().min(self.current.len() - self.pos);
+            buf[..n].copy_from_slice(&self.current[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    /// Counts the lines written without keeping them, tracking how far they ever fell
+    /// behind the lines read, i.e. how many lines were held in memory at once
+    struct LagWriter {
+        served: std::rc::Rc<std::cell::Cell<usize>>,
+        written: usize,
+        max_held: usize,
+    }
+
+    impl Write for LagWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let held = self.served.get().saturating_sub(self.written);
+            self.max_held = self.max_held.max(held);
+            self.written += buf.iter().filter(|b| **b == b'\n').count();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_annotated_diff_streams_huge_hunk() {
+        const LINES: usize = 1_000_000;
+
+        let header = [
+            "diff --git a/big.txt b/big.txt".to_string(),
+            "new file mode 100644".to_string(),
+            "--- /dev/null".to_string(),
+            "+++ b/big.txt".to_string(),
+            format!("@@ -0,0 +1,{} @@", LINES),
+        ];
+        let lines = header
+            .into_iter()
+            .chain((1..=LINES).map(|line| format!("+line {}", line)));
+        let served = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut diff = std::io::BufReader::new(LineReader {
+            lines,
+            current: Vec::new(),
+            pos: 0,
+            served: served.clone(),
+        });
+
+        let mut attributions = HashMap::new();
+        attributions.insert(
+            DiffLineKey {
+                file: "big.txt".into(),
+                line: LINES as u32,
+                side: LineSide::New,
+            },
+            Attribution::Ai("cursor".to_string()),
+        );
+
+        let options = AnnotatedDiffOptions {
+            color: ColorMode::Never,
+            ..Default::default()
+        };
+
+        let mut out = LagWriter {
+            served,
+            written: 0,
+            max_held: 0,
+        };
+        write_annotated_diff(
+            &mut diff,
+            &mut out,
+            &attributions,
+            &HashMap::new(),
+            &LinePrompts::default(),
+            &options,
+        )
+        .unwrap();
+
+        // Without a filter the hunk goes out as it's read, never more than a line behind
+        assert!(out.written >= LINES, "{}", out.written);
+        assert!(out.max_held <= 1, "held {} lines", out.max_held);
+    }
 }
@@ -2,12 +2,13 @@ use serde_json::json;
 
 use crate::authorship::internal_db::InternalDatabase;
 use crate::authorship::range_authorship;
-use crate::authorship::stats::stats_command;
+use crate::authorship::repo_authorship;
+use crate::authorship::stats::{ai_ratio, stats_command};
 use crate::authorship::working_log::{AgentId, CheckpointKind};
 use crate::commands;
 use crate::commands::checkpoint_agent::agent_presets::{
     AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, AiTabPreset, ClaudePreset,
-    ContinueCliPreset, CursorPreset, GeminiPreset, GithubCopilotPreset,
+    ContinueCliPreset, CursorPreset, GeminiPreset, GithubCopilotPreset, WindsurfPreset,
 };
 use crate::commands::checkpoint_agent::agent_v1_preset::AgentV1Preset;
 use crate::config;
@@ -170,6 +171,24 @@ pub fn handle_git_ai(args: &[String]) {
         "show" => {
             commands::show::handle_show(&args[1..]);
         }
+        "log" => {
+            commands::log::handle_log(&args[1..]);
+        }
+        "shortlog" => {
+            commands::shortlog::handle_shortlog(&args[1..]);
+        }
+        "prompts" => {
+            commands::prompts::handle_prompts(&args[1..]);
+        }
+        "verify" => {
+            commands::verify::handle_verify(&args[1..]);
+        }
+        "backfill" => {
+            commands::backfill::handle_backfill(&args[1..]);
+        }
+        "sarif" => {
+            commands::sarif::handle_sarif(&args[1..]);
+        }
         "checkpoint" => {
             if !allowed_repository {
                 eprintln!(
@@ -260,7 +279,9 @@ fn print_help() {
     eprintln!("");
     eprintln!("Commands:");
     eprintln!("  checkpoint         Checkpoint working changes and attribute author");
-    eprintln!("    Presets: claude, continue-cli, cursor, gemini, github-copilot, ai_tab, mock_ai");
+    eprintln!(
+        "    Presets: claude, continue-cli, cursor, gemini, github-copilot, windsurf, ai_tab, mock_ai"
+    );
     eprintln!(
         "    --hook-input <json|stdin>   JSON payload required by presets, or 'stdin' to read from stdin"
     );
@@ -268,13 +289,112 @@ fn print_help() {
     eprintln!("    --reset                     Reset working log");
     eprintln!("    mock_ai [pathspecs...]      Test preset accepting optional file pathspecs");
     eprintln!("  blame <file>       Git blame with AI authorship overlay");
-    eprintln!("  diff <commit|range>  Show diff with AI authorship annotations");
-    eprintln!("    <commit>              Diff from commit's parent to commit");
+    eprintln!("    -L <start>,<end>      Only blame the given lines; also <start>,+<n>,");
+    eprintln!("                          <start>,-<n> and <start> (to the end), as in git blame");
+    eprintln!("  diff [<commit|range>] [-- <path>...]  Show diff with AI authorship annotations");
+    eprintln!("    (no commit)           Diff uncommitted changes against HEAD");
+    eprintln!("    --cached, --staged    Diff staged changes against HEAD");
+    eprintln!("    <commit>              Diff from commit's parent to commit (the first parent");
+    eprintln!("                          for merge commits)");
+    eprintln!("    -m -<n>, --parent=<n> Diff a merge commit against its <n>th parent instead");
     eprintln!("    <commit1>..<commit2>  Diff between two commits");
+    eprintln!("    <commit1> <commit2>   Same as <commit1>..<commit2>");
+    eprintln!("    <commit1>...<commit2> Diff from the merge-base of both commits to <commit2>");
+    eprintln!("    --json                Output in JSON format");
+    eprintln!("    --porcelain           One tab-separated record per changed line:");
+    eprintln!("                          <old|new> <file> <line> <ai|human|nodata> <tool|user>");
+    eprintln!("                          <provenance>, where AI lines are direct, merged (prompt");
+    eprintln!("                          from another commit's log), carried-over-rename (copied");
+    eprintln!("                          or renamed from another file) or carried-over-move");
+    eprintln!("                          (moved within the file, with --detect-moves)");
+    eprintln!("    --output=github       GitHub Actions ::notice annotations for AI-added lines");
+    eprintln!("    --stat                Per-file changed line counts with the AI/human split");
+    eprintln!("    --format=html         Standalone HTML page with inline styles, for sharing");
+    eprintln!("    --summary-json        JSON with AI/human/no-data counts of the added lines,");
+    eprintln!("                          per AI tool, and the --porcelain records as `lines`");
+    eprintln!("    --no-lines            Leave `lines` out of --summary-json output");
+    eprintln!("    --stdin               Read one <commit> or range per line and print one JSON");
+    eprintln!("                          result per line (--summary-json, or --json); failed");
+    eprintln!("                          specs are reported inline as {{spec, error}}");
+    eprintln!("    --no-data-only        List changed lines without authorship data, with a count");
+    eprintln!("    --only-ai             Only show changed lines attributed to AI");
+    eprintln!("    --only-human          Only show changed lines attributed to humans");
+    eprintln!("    -U<n>, --unified=<n>  Show <n> lines of context (default: 3)");
+    eprintln!("    --color=<when>        Colorize output: always, never or auto (default)");
+    eprintln!("    --word-diff           Show changed words inline, tinted by line attribution");
+    eprintln!("    -R, --reverse         Swap the two sides of the diff, like git diff -R");
+    eprintln!("    --no-pager            Print directly instead of through the pager");
+    eprintln!("    --output <file>       Write the diff (in any format) to <file>, uncolored");
+    eprintln!("                          unless --color=always is given; --output=<file> works");
+    eprintln!("                          too, except that --output=github picks that format");
+    eprintln!("    --show-prompt         Follow AI annotations with the prompt behind the line");
+    eprintln!("    --show-version        Name AI tools with their version where it was recorded,");
+    eprintln!("                          e.g. cursor@0.45.2 (JSON output has tool_version)");
+    eprintln!("    --find-copies-harder  Also detect copies of unmodified files; copied lines");
+    eprintln!("                          keep the attribution they have in the source");
+    eprintln!("    --detect-moves        Lines moved within a file keep the attribution they");
+    eprintln!("                          had before the move, like git diff --color-moved");
+    eprintln!("    --context-attribution Also annotate context lines, blamed at the new commit");
+    eprintln!("    --ascii               Annotate as [AI:tool] / [human:user] instead of emoji");
+    eprintln!("    --annotate-ai=<str>   Glyph before the tool in AI annotations; {{}} places the");
+    eprintln!("                          tool inside it (default: git-ai.ai-glyph, or 🤖)");
+    eprintln!("    --annotate-human=<str> Glyph for human annotations (git-ai.human-glyph, or 👤)");
+    eprintln!("    --relative[=<dir>]    Only show changes under <dir> (default: the current");
+    eprintln!("                          directory), with paths relative to it; --no-relative");
+    eprintln!("                          overrides diff.relative");
     eprintln!("  stats [commit]     Show AI authorship statistics for a commit");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!("    --repo                 Share of all current lines in [commit] (default: HEAD)");
+    eprintln!("                           that are AI-written, with a per-tool split");
+    eprintln!("    --format=<csv|json>    Output format (csv: one row per commit in a range)");
+    eprintln!("    --exclude <glob>       Leave matching paths out of stats (repeatable)");
+    eprintln!("                           Patterns in .gitaiignore at the repo root always apply");
+    eprintln!("    --no-cache             Recompute range stats instead of reading the cache");
+    eprintln!("    --timings              Print how long each range authorship step took");
+    eprintln!("    --fetch                Fetch the range's branch from its remote first");
+    eprintln!("    --fetch-dry-run        Print the remote and refspec --fetch would use, without");
+    eprintln!("                           fetching");
+    eprintln!("    --author <pattern>     Only count range commits whose author name or email");
+    eprintln!("                           contains <pattern>; totals sum those commits' own diffs");
+    eprintln!("    --merge-base <ref>     Use the commits on HEAD since its merge base with <ref>");
+    eprintln!("                           as the range, instead of <commit>..<commit>");
+    eprintln!("    --max-ai-percent <n>   Exit with 3 if over <n>% of a range's added lines");
+    eprintln!("                           are AI-written (for CI gating; errors exit with 1)");
+    eprintln!("    --exit-code            Exit with 2 if no commit in a range has git-ai");
+    eprintln!("                           authorship (errors exit with 1)");
+    eprintln!("    --stat-only            Print only a range's AI percentage (e.g. 37%), or with");
+    eprintln!("                           --json {{ai_percent, human_percent, total_lines}},");
+    eprintln!("                           for badges and scripts");
+    eprintln!("    --first-parent         Only list and count a range's first-parent commits, so");
+    eprintln!("                           merged-in branch commits aren't counted separately");
+    eprintln!(
+        "    --top <n>              Files to list for a <commit>..<commit> range (default: 10)"
+    );
+    eprintln!("  log [rev|range]    Show commits with their AI/human line split");
+    eprintln!("    --oneline             One line per commit: <sha>  <subject>  (+N ai, +M human)");
+    eprintln!("    --since=<date>        Only commits more recent than <date>");
+    eprintln!("    --until=<date>        Only commits older than <date>");
+    eprintln!("    -n, --max-count=<n>   Only the <n> most recent commits");
+    eprintln!("  shortlog [rev|range] Summarize commits per author with their AI share");
+    eprintln!("    --ai-only             Only list authors with AI-written lines");
+    eprintln!("  prompts            List every prompt recorded in authorship notes, with the");
+    eprintln!("                        number of commits and files that reference it");
+    eprintln!("    --json                Output in JSON format");
     eprintln!("  status             Show uncommitted AI authorship status (debug)");
     eprintln!("  show <rev|range>   Display authorship logs for a revision or range");
+    eprintln!(
+        "  verify [rev|range] Check authorship notes for missing prompts and bad line ranges"
+    );
+    eprintln!("                        Checks every annotated commit when no revision is given");
+    eprintln!(
+        "  backfill <commit>  Reconstruct and write the authorship log of a commit without one"
+    );
+    eprintln!(
+        "                        Lines with no resolvable AI prompt go to the commit's author"
+    );
+    eprintln!("  sarif <rev|range>  Export AI-authored line ranges as a SARIF 2.1.0 report");
+    eprintln!("                        Lines the range added, located in its end commit");
+    eprintln!("    -o, --output <file>   Write the report to <file> instead of stdout");
     eprintln!("  show-prompt <id>   Display a prompt record by its ID");
     eprintln!("    --commit <rev>        Look in a specific commit only");
     eprintln!(
@@ -295,7 +415,12 @@ fn print_help() {
     eprintln!("    --add <key> <value>   Add to array or upsert into object");
     eprintln!("    unset <key>           Remove config value (reverts to default)");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
+    eprintln!("    --project             Write hook configs into the current repository");
+    eprintln!("    --describe            Print each tool's hook command and config path, without");
+    eprintln!("                          installing anything");
     eprintln!("  uninstall-hooks    Remove git-ai hooks from all detected tools");
+    eprintln!("    --restore-backup      Restore configs from the backup taken before install");
+    eprintln!("    --project             Remove hook configs from the current repository");
     eprintln!("  ci                 Continuous integration utilities");
     eprintln!("    github                 GitHub CI helpers");
     eprintln!("  squash-authorship  Generate authorship log for squashed commits");
@@ -448,6 +573,19 @@ fn handle_checkpoint(args: &[String]) {
                     }
                 }
             }
+            "windsurf" => {
+                match WindsurfPreset.run(AgentCheckpointFlags {
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Windsurf preset error: {}", e);
+                        std::process::exit(0);
+                    }
+                }
+            }
             "ai_tab" => {
                 match AiTabPreset.run(AgentCheckpointFlags {
                     hook_input: hook_input.clone(),
@@ -844,6 +982,19 @@ fn handle_stats(args: &[String]) {
     let mut commit_sha = None;
     let mut commit_range: Option<CommitRange> = None;
     let mut ignore_patterns: Vec<String> = Vec::new();
+    let mut top_files: usize = 10;
+    let mut csv_output = false;
+    let mut use_cache = true;
+    let mut print_timings = false;
+    let mut author: Option<String> = None;
+    let mut max_ai_percent: Option<f64> = None;
+    let mut merge_base: Option<String> = None;
+    let mut pre_fetch = false;
+    let mut fetch_dry_run = false;
+    let mut exit_code = false;
+    let mut stat_only = false;
+    let mut first_parent = false;
+    let mut repo_wide = false;
 
     let mut i = 0;
     while i < args.len() {
@@ -852,6 +1003,109 @@ fn handle_stats(args: &[String]) {
                 json_output = true;
                 i += 1;
             }
+            "--exclude" => {
+                match args.get(i + 1) {
+                    Some(pattern) => ignore_patterns.push(pattern.clone()),
+                    None => {
+                        eprintln!("--exclude requires a glob pattern argument");
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            "--no-cache" => {
+                use_cache = false;
+                i += 1;
+            }
+            "--timings" => {
+                print_timings = true;
+                i += 1;
+            }
+            "--fetch" => {
+                pre_fetch = true;
+                i += 1;
+            }
+            "--exit-code" => {
+                exit_code = true;
+                i += 1;
+            }
+            "--fetch-dry-run" => {
+                pre_fetch = true;
+                fetch_dry_run = true;
+                i += 1;
+            }
+            "--author" => {
+                match args.get(i + 1) {
+                    Some(pattern) => author = Some(pattern.clone()),
+                    None => {
+                        eprintln!("--author requires a name or email pattern");
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            arg if arg.starts_with("--author=") => {
+                author = Some(arg["--author=".len()..].to_string());
+                i += 1;
+            }
+            "--merge-base" => {
+                match args.get(i + 1) {
+                    Some(base) => merge_base = Some(base.clone()),
+                    None => {
+                        eprintln!("--merge-base requires a branch or commit");
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            arg if arg.starts_with("--merge-base=") => {
+                merge_base = Some(arg["--merge-base=".len()..].to_string());
+                i += 1;
+            }
+            "--max-ai-percent" => {
+                max_ai_percent = match args
+                    .get(i + 1)
+                    .and_then(|n| n.parse::<f64>().ok())
+                    .filter(|n| (0.0..=100.0).contains(n))
+                {
+                    Some(n) => Some(n),
+                    None => {
+                        eprintln!("--max-ai-percent requires a number between 0 and 100");
+                        std::process::exit(1);
+                    }
+                };
+                i += 2;
+            }
+            "--stat-only" => {
+                stat_only = true;
+                i += 1;
+            }
+            "--first-parent" => {
+                first_parent = true;
+                i += 1;
+            }
+            "--repo" => {
+                repo_wide = true;
+                i += 1;
+            }
+            "--format=csv" => {
+                csv_output = true;
+                i += 1;
+            }
+            "--format=json" => {
+                json_output = true;
+                i += 1;
+            }
+            "--top" => {
+                top_files = match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+                    Some(n) => n,
+                    None => {
+                        eprintln!("--top requires a number argument");
+                        std::process::exit(1);
+                    }
+                };
+                i += 2;
+            }
             "--ignore" => {
                 // Collect all arguments after --ignore until we hit another flag or commit SHA
                 // This supports shell glob expansion: `--ignore *.lock` expands to `--ignore Cargo.lock package.lock`
@@ -918,15 +1172,71 @@ fn handle_stats(args: &[String]) {
         }
     }
 
+    // Patterns from .gitaiignore apply in addition to --ignore/--exclude
+    ignore_patterns.extend(range_authorship::load_gitaiignore_patterns(&repo));
+
+    if repo_wide && (commit_range.is_some() || merge_base.is_some()) {
+        eprintln!("--repo counts the lines of a single commit and cannot be used with a range");
+        std::process::exit(1);
+    }
+
+    if let Some(base) = &merge_base {
+        if commit_sha.is_some() || commit_range.is_some() {
+            eprintln!("--merge-base cannot be combined with a commit or range");
+            std::process::exit(1);
+        }
+        match range_authorship::merge_base_range(&repo, base) {
+            Ok(range) => commit_range = Some(range),
+            Err(e) => {
+                eprintln!("Failed to create commit range: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Handle commit range if detected
     if let Some(range) = commit_range {
-        match range_authorship::range_authorship(range, false, &ignore_patterns) {
+        let options = range_authorship::RangeAuthorshipOptions {
+            pre_fetch_contents: pre_fetch,
+            fetch_dry_run,
+            ignore_patterns,
+            use_cache,
+            author,
+            print_timings,
+        };
+        match range_authorship::range_authorship(range.with_first_parent(first_parent), &options) {
             Ok(stats) => {
-                if json_output {
+                if stat_only {
+                    print!(
+                        "{}",
+                        range_authorship::format_range_stat_summary(&stats, json_output)
+                    );
+                } else if csv_output {
+                    print!(
+                        "{}",
+                        range_authorship::range_authorship_stats_to_csv(&stats)
+                    );
+                } else if json_output {
                     let json_str = serde_json::to_string(&stats).unwrap();
                     println!("{}", json_str);
                 } else {
-                    range_authorship::print_range_authorship_stats(&stats);
+                    range_authorship::print_range_authorship_stats(&stats, top_files);
+                }
+
+                if let Some(max_percent) = max_ai_percent {
+                    let ai_percent = ai_ratio(&stats.range_stats) * 100.0;
+                    if ai_percent > max_percent {
+                        eprintln!(
+                            "AI-written lines are {:.1}% of additions, above the --max-ai-percent threshold of {}%",
+                            ai_percent, max_percent
+                        );
+                        // Distinct from errors (1) and --exit-code (2) so CI can tell them apart
+                        std::process::exit(3);
+                    }
+                }
+
+                if exit_code && !stats.has_ai_authorship {
+                    std::process::exit(2);
                 }
             }
             Err(e) => {
@@ -937,10 +1247,52 @@ fn handle_stats(args: &[String]) {
         return;
     }
 
+    if stat_only {
+        eprintln!("--stat-only requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+    if first_parent {
+        eprintln!("--first-parent requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+    if csv_output {
+        eprintln!("--format=csv requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+    if author.is_some() {
+        eprintln!("--author requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+    if max_ai_percent.is_some() {
+        eprintln!("--max-ai-percent requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+    if pre_fetch {
+        eprintln!("--fetch requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+    if exit_code {
+        eprintln!("--exit-code requires a <commit>..<commit> range");
+        std::process::exit(1);
+    }
+
+    if repo_wide {
+        let revision = commit_sha.as_deref().unwrap_or("HEAD");
+        match repo_authorship::repo_authorship(&repo, revision, &ignore_patterns) {
+            Ok(stats) if json_output => println!("{}", serde_json::to_string(&stats).unwrap()),
+            Ok(stats) => repo_authorship::print_repo_authorship_stats(&stats),
+            Err(e) => {
+                eprintln!("Repository stats failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     if let Err(e) = stats_command(&repo, commit_sha.as_deref(), json_output, &ignore_patterns) {
         match e {
-            crate::error::GitAiError::Generic(msg) if msg.starts_with("No commit found:") => {
-                eprintln!("{}", msg);
+            crate::error::GitAiError::CommitNotFound(_) => {
+                eprintln!("{}", e);
             }
             _ => {
                 eprintln!("Stats failed: {}", e);
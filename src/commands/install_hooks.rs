@@ -1,9 +1,15 @@
 use crate::error::GitAiError;
+use crate::git::repository::find_repository_in_path;
 use crate::mdm::agents::get_all_installers;
-use crate::mdm::hook_installer::HookInstallerParams;
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookInstaller, HookInstallerParams, HookVerification, InstallScope,
+};
 use crate::mdm::spinner::{print_diff, Spinner};
-use crate::mdm::utils::get_current_binary_path;
+use crate::mdm::utils::{
+    backup_config_file, find_latest_backup, get_current_binary_path, restore_latest_backup,
+};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Installation status for a tool
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,11 +41,25 @@ pub fn to_hashmap(statuses: HashMap<String, InstallStatus>) -> HashMap<String, S
         .collect()
 }
 
+/// Project scope targets the repository containing the current directory
+fn resolve_scope(project: bool) -> Result<InstallScope, GitAiError> {
+    if !project {
+        return Ok(InstallScope::Global);
+    }
+
+    let repo = find_repository_in_path(".").map_err(|_| {
+        GitAiError::Generic("--project must be run inside a git repository".to_string())
+    })?;
+    Ok(InstallScope::Project(repo.workdir()?))
+}
+
 /// Main entry point for install-hooks command
 pub fn run(args: &[String]) -> Result<HashMap<String, String>, GitAiError> {
     // Parse flags
     let mut dry_run = false;
     let mut verbose = false;
+    let mut project = false;
+    let mut describe = false;
     for arg in args {
         if arg == "--dry-run" || arg == "--dry-run=true" {
             dry_run = true;
@@ -47,22 +67,55 @@ pub fn run(args: &[String]) -> Result<HashMap<String, String>, GitAiError> {
         if arg == "--verbose" || arg == "-v" {
             verbose = true;
         }
+        if arg == "--project" {
+            project = true;
+        }
+        if arg == "--describe" {
+            describe = true;
+        }
     }
 
     // Get absolute path to the current binary
     let binary_path = get_current_binary_path()?;
-    let params = HookInstallerParams { binary_path };
+    let params = HookInstallerParams {
+        binary_path,
+        scope: resolve_scope(project)?,
+    };
+
+    if describe {
+        print_hook_descriptions(&params);
+        return Ok(HashMap::new());
+    }
 
     // Run async operations with smol and convert result
     let statuses = smol::block_on(async_run_install(&params, dry_run, verbose))?;
     Ok(to_hashmap(statuses))
 }
 
+/// Print the command each installer registers and the config it goes into, without
+/// checking for the tools or changing anything
+fn print_hook_descriptions(params: &HookInstallerParams) {
+    for installer in get_all_installers() {
+        match installer.describe(params) {
+            Some(description) => {
+                println!("{}: {}", installer.name(), description.command);
+                println!("  Config: {}", description.config_path.display());
+            }
+            None => println!(
+                "{}: no hook command (set up through its extension)",
+                installer.name()
+            ),
+        }
+    }
+}
+
 /// Main entry point for uninstall-hooks command
 pub fn run_uninstall(args: &[String]) -> Result<HashMap<String, String>, GitAiError> {
     // Parse flags
     let mut dry_run = false;
     let mut verbose = false;
+    let mut project = false;
+    let mut restore_backup = false;
     for arg in args {
         if arg == "--dry-run" || arg == "--dry-run=true" {
             dry_run = true;
@@ -70,14 +123,28 @@ pub fn run_uninstall(args: &[String]) -> Result<HashMap<String, String>, GitAiEr
         if arg == "--verbose" || arg == "-v" {
             verbose = true;
         }
+        if arg == "--project" {
+            project = true;
+        }
+        if arg == "--restore-backup" {
+            restore_backup = true;
+        }
     }
 
     // Get absolute path to the current binary
     let binary_path = get_current_binary_path()?;
-    let params = HookInstallerParams { binary_path };
+    let params = HookInstallerParams {
+        binary_path,
+        scope: resolve_scope(project)?,
+    };
 
     // Run async operations with smol and convert result
-    let statuses = smol::block_on(async_run_uninstall(&params, dry_run, verbose))?;
+    let statuses = smol::block_on(async_run_uninstall(
+        &params,
+        dry_run,
+        verbose,
+        restore_backup,
+    ))?;
     Ok(to_hashmap(statuses))
 }
 
@@ -110,12 +177,43 @@ async fn async_run_install(
                 let spinner = Spinner::new(&format!("{}: checking hooks", name));
                 spinner.start();
 
-                match installer.install_hooks(params, dry_run) {
-                    Ok(Some(diff)) => {
-                        if dry_run {
+                let install_result =
+                    backup_configs(installer.as_ref(), params, &check_result, dry_run).and_then(
+                        |backups| Ok((installer.install_hooks(params, dry_run)?, backups)),
+                    );
+                match install_result {
+                    Ok((Some(diff), backups)) => {
+                        // Re-read what was written, since a tool ignores a config it can't use
+                        let verification = if dry_run {
+                            None
+                        } else {
+                            installer.verify_hooks(params)
+                        };
+                        let verified = if verification == Some(HookVerification::Verified) {
+                            " and verified"
+                        } else {
+                            ""
+                        };
+
+                        if dry_run && check_result.is_outdated() {
+                            spinner
+                                .pending(&format!("{}: Pending upgrade of outdated hooks", name));
+                        } else if dry_run {
                             spinner.pending(&format!("{}: Pending updates", name));
+                        } else if let Some(HookVerification::Unverified(reason)) = &verification {
+                            spinner.pending(&format!(
+                                "{}: Hooks written but could not be verified",
+                                name
+                            ));
+                            eprintln!("  Reason: {}", reason);
+                        } else if check_result.is_outdated() {
+                            spinner
+                                .success(&format!("{}: Outdated hooks upgraded{}", name, verified));
                         } else {
-                            spinner.success(&format!("{}: Hooks updated", name));
+                            spinner.success(&format!("{}: Hooks updated{}", name, verified));
+                        }
+                        for backup_path in &backups {
+                            println!("  Backup saved to {}", backup_path.display());
                         }
                         if verbose {
                             println!();
@@ -124,7 +222,7 @@ async fn async_run_install(
                         has_changes = true;
                         statuses.insert(id.to_string(), InstallStatus::Installed);
                     }
-                    Ok(None) => {
+                    Ok((None, _)) => {
                         spinner.success(&format!("{}: Hooks already up to date", name));
                         statuses.insert(id.to_string(), InstallStatus::AlreadyInstalled);
                     }
@@ -159,6 +257,9 @@ async fn async_run_install(
                                 extra_spinner.start();
                                 extra_spinner.pending(&result.message);
                             }
+                            if let Some(backup_path) = &result.backup_path {
+                                println!("  Backup saved to {}", backup_path.display());
+                            }
                             if verbose {
                                 if let Some(diff) = result.diff {
                                     println!();
@@ -195,10 +296,62 @@ async fn async_run_install(
     Ok(statuses)
 }
 
+/// Back up the configs `installer` edits before it first adds hooks to them. Configs that
+/// already have git-ai hooks aren't backed up again, so upgrades don't add backups and the
+/// latest backup is always from before git-ai was installed. Returns the backups taken.
+fn backup_configs(
+    installer: &dyn HookInstaller,
+    params: &HookInstallerParams,
+    check_result: &HookCheckResult,
+    dry_run: bool,
+) -> Result<Vec<PathBuf>, GitAiError> {
+    let mut backups = Vec::new();
+    if dry_run || check_result.hooks_installed {
+        return Ok(backups);
+    }
+    for config_path in installer.config_paths(params) {
+        backups.extend(backup_config_file(&config_path)?);
+    }
+    Ok(backups)
+}
+
+/// Restore each config from its (config, backup) pair. Returns whether every restore
+/// succeeded (or, in dry-run mode, would be attempted).
+fn restore_backups(name: &str, backups: &[(PathBuf, PathBuf)], dry_run: bool) -> bool {
+    let spinner = Spinner::new(&format!("{}: restoring backup", name));
+    spinner.start();
+
+    let mut restore_errors = Vec::new();
+    for (config_path, _) in backups {
+        if let Err(e) = restore_latest_backup(config_path, dry_run) {
+            restore_errors.push(e);
+        }
+    }
+
+    if !restore_errors.is_empty() {
+        spinner.error(&format!("{}: Failed to restore backup", name));
+        for e in restore_errors {
+            eprintln!("  Error: {}", e);
+        }
+        return false;
+    }
+
+    if dry_run {
+        spinner.pending(&format!("{}: Pending backup restore", name));
+    } else {
+        spinner.success(&format!("{}: Restored config from backup", name));
+    }
+    for (config_path, backup_path) in backups {
+        println!("  {} <- {}", config_path.display(), backup_path.display());
+    }
+    true
+}
+
 async fn async_run_uninstall(
     params: &HookInstallerParams,
     dry_run: bool,
     verbose: bool,
+    restore_backup: bool,
 ) -> Result<HashMap<String, InstallStatus>, GitAiError> {
     let mut any_checked = false;
     let mut has_changes = false;
@@ -225,32 +378,63 @@ async fn async_run_uninstall(
 
                 any_checked = true;
 
-                // Uninstall hooks
-                let spinner = Spinner::new(&format!("{}: removing hooks", name));
-                spinner.start();
+                // Config backups taken by earlier installs, newest per config file
+                let backups: Vec<(PathBuf, PathBuf)> = installer
+                    .config_paths(params)
+                    .into_iter()
+                    .filter_map(|path| find_latest_backup(&path).map(|backup| (path, backup)))
+                    .collect();
 
-                match installer.uninstall_hooks(params, dry_run) {
-                    Ok(Some(diff)) => {
-                        if dry_run {
-                            spinner.pending(&format!("{}: Pending removal", name));
-                        } else {
-                            spinner.success(&format!("{}: Hooks removed", name));
+                let restored = restore_backup
+                    && !backups.is_empty()
+                    && restore_backups(name, &backups, dry_run);
+                if restored {
+                    has_changes = true;
+                    statuses.insert(id.to_string(), InstallStatus::Installed);
+                }
+
+                // A restored config can still have hooks, e.g. ones added by hand since the
+                // backup, so they're removed either way. A dry run can't know the restored
+                // contents, so it only reports the restore.
+                if !(restored && dry_run) {
+                    let spinner = Spinner::new(&format!("{}: removing hooks", name));
+                    spinner.start();
+
+                    match installer.uninstall_hooks(params, dry_run) {
+                        Ok(Some(diff)) => {
+                            if dry_run {
+                                spinner.pending(&format!("{}: Pending removal", name));
+                            } else {
+                                spinner.success(&format!("{}: Hooks removed", name));
+                            }
+                            if verbose {
+                                println!();
+                                print_diff(&diff);
+                            }
+                            has_changes = true;
+                            statuses.insert(id.to_string(), InstallStatus::Installed);
                         }
-                        if verbose {
-                            println!();
-                            print_diff(&diff);
+                        Ok(None) if restored => {
+                            spinner.success(&format!("{}: No hooks left after restore", name));
+                        }
+                        Ok(None) => {
+                            spinner.success(&format!("{}: No hooks to remove", name));
+                            statuses.insert(id.to_string(), InstallStatus::AlreadyInstalled);
+                        }
+                        Err(e) => {
+                            spinner.error(&format!("{}: Failed to remove hooks", name));
+                            eprintln!("  Error: {}", e);
+                            statuses.insert(id.to_string(), InstallStatus::NotFound);
                         }
-                        has_changes = true;
-                        statuses.insert(id.to_string(), InstallStatus::Installed);
-                    }
-                    Ok(None) => {
-                        spinner.success(&format!("{}: No hooks to remove", name));
-                        statuses.insert(id.to_string(), InstallStatus::AlreadyInstalled);
                     }
-                    Err(e) => {
-                        spinner.error(&format!("{}: Failed to remove hooks", name));
-                        eprintln!("  Error: {}", e);
-                        statuses.insert(id.to_string(), InstallStatus::NotFound);
+                }
+
+                if !restored {
+                    for (_, backup_path) in &backups {
+                        println!(
+                            "  Pre-install backup: {} (restore with --restore-backup)",
+                            backup_path.display()
+                        );
                     }
                 }
 
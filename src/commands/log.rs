@@ -0,0 +1,345 @@
+use crate::authorship::range_authorship::load_gitaiignore_patterns;
+use crate::authorship::stats::{CommitStats, stats_for_commit_stats};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{CommitRange, Repository, exec_git};
+
+/// Separates the fields of each commit in the `git log` output we parse
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// A commit in `git-ai log` output with its AI/human line split
+pub struct LogEntry {
+    pub sha: String,
+    pub short_sha: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+    pub stats: CommitStats,
+}
+
+/// Which commits `git-ai log` walks
+#[derive(Debug, Default)]
+pub struct LogOptions {
+    /// A revision (its history is walked) or a `<start>..<end>` range; HEAD when None
+    pub spec: Option<String>,
+    pub oneline: bool,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Stop after this many commits, like `git log -n`
+    pub max_count: Option<usize>,
+}
+
+pub fn handle_log(args: &[String]) {
+    let options = match parse_log_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let ignore_patterns = load_gitaiignore_patterns(&repo);
+    let entries = match log_entries(&repo, &options, &ignore_patterns) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to compute log: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    for (index, entry) in entries.iter().enumerate() {
+        if !options.oneline && index > 0 {
+            println!();
+        }
+        print!("{}", format_log_entry(entry, options.oneline));
+    }
+}
+
+pub fn parse_log_args(args: &[String]) -> Result<LogOptions, GitAiError> {
+    let mut options = LogOptions::default();
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--oneline" {
+            options.oneline = true;
+        } else if let Some(date) = arg.strip_prefix("--since=") {
+            options.since = Some(date.to_string());
+        } else if let Some(date) = arg.strip_prefix("--until=") {
+            options.until = Some(date.to_string());
+        } else if let Some(count) = arg
+            .strip_prefix("--max-count=")
+            .or_else(|| arg.strip_prefix("-n").filter(|count| !count.is_empty()))
+        {
+            options.max_count = Some(parse_max_count(count)?);
+        } else if arg == "-n" || arg == "--max-count" {
+            let Some(count) = args.get(i + 1) else {
+                return Err(GitAiError::Generic(format!("{} requires a count", arg)));
+            };
+            options.max_count = Some(parse_max_count(count)?);
+            i += 1;
+        } else if arg == "--since" || arg == "--until" {
+            let Some(date) = args.get(i + 1) else {
+                return Err(GitAiError::Generic(format!("{} requires a date", arg)));
+            };
+            if arg == "--since" {
+                options.since = Some(date.clone());
+            } else {
+                options.until = Some(date.clone());
+            }
+            i += 1;
+        } else if arg.starts_with('-') {
+            return Err(GitAiError::Generic(format!(
+                "Unknown log argument: {}",
+                arg
+            )));
+        } else if options.spec.is_none() {
+            options.spec = Some(arg.clone());
+        } else {
+            return Err(GitAiError::Generic(
+                "log accepts at most one revision or range".to_string(),
+            ));
+        }
+        i += 1;
+    }
+
+    Ok(options)
+}
+
+fn parse_max_count(count: &str) -> Result<usize, GitAiError> {
+    count
+        .parse()
+        .map_err(|_| GitAiError::Generic(format!("Invalid commit count: {}", count)))
+}
+
+/// List the commits selected by `options`, newest first, with the stats `git-ai stats`
+/// would report for each of them.
+pub fn log_entries(
+    repo: &Repository,
+    options: &LogOptions,
+    ignore_patterns: &[String],
+) -> Result<Vec<LogEntry>, GitAiError> {
+    let revisions = resolve_revisions(repo, options.spec.as_deref())?;
+
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--format=%H%x1f%h%x1f%an%x1f%ad%x1f%s".to_string());
+    if let Some(since) = &options.since {
+        args.push(format!("--since={}", since));
+    }
+    if let Some(until) = &options.until {
+        args.push(format!("--until={}", until));
+    }
+    if let Some(max_count) = options.max_count {
+        args.push(format!("--max-count={}", max_count));
+    }
+    args.push(revisions);
+    args.push("--".to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    let mut entries = Vec::new();
+    for line in stdout.lines() {
+        let fields: Vec<&str> = line.splitn(5, FIELD_SEPARATOR).collect();
+        if fields.len() < 5 {
+            continue;
+        }
+
+        let stats = stats_for_commit_stats(repo, fields[0], ignore_patterns)?;
+        entries.push(LogEntry {
+            sha: fields[0].to_string(),
+            short_sha: fields[1].to_string(),
+            author: fields[2].to_string(),
+            date: fields[3].to_string(),
+            subject: fields[4].to_string(),
+            stats,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Turn a revision (whose history is walked), a `<start>..<end>` range or a
+/// `<left>...<right>` symmetric difference into a `git log` revision argument. HEAD when
+/// `spec` is None.
+pub(crate) fn resolve_revisions(
+    repo: &Repository,
+    spec: Option<&str>,
+) -> Result<String, GitAiError> {
+    // Checked first, since `..` would split `A...B` into `A` and `.B`
+    if let Some((left, right)) = spec.and_then(|spec| spec.split_once("...")) {
+        if left.is_empty() || right.is_empty() || right.starts_with('.') {
+            return Err(GitAiError::Generic(
+                "Invalid symmetric difference format. Expected <left>...<right>".to_string(),
+            ));
+        }
+        // Commits reachable from either side but not both, as in `git log A...B`
+        return Ok(format!("{}...{}", left, right));
+    }
+
+    match spec {
+        Some(spec) => match spec.split_once("..") {
+            Some((start, end)) => {
+                if start.is_empty() || end.is_empty() {
+                    return Err(GitAiError::Generic(
+                        "Invalid commit range format. Expected <start>..<end>".to_string(),
+                    ));
+                }
+                let range =
+                    CommitRange::new_infer_refname(repo, start.to_string(), end.to_string(), None)?;
+                Ok(format!("{}..{}", range.start_oid, range.end_oid))
+            }
+            None => Ok(spec.to_string()),
+        },
+        None => Ok("HEAD".to_string()),
+    }
+}
+
+/// Render a commit either as a single line or in the spirit of `git log`'s default format
+pub fn format_log_entry(entry: &LogEntry, oneline: bool) -> String {
+    let split = format_line_split(&entry.stats);
+    if oneline {
+        return format!("{}  {}  ({})\n", entry.short_sha, entry.subject, split);
+    }
+
+    format!(
+        "commit {}\nAuthor: {}\nDate:   {}\n\n    {}\n\n    {}\n",
+        entry.sha, entry.author, entry.date, entry.subject, split
+    )
+}
+
+fn format_line_split(stats: &CommitStats) -> String {
+    format!(
+        "+{} ai, +{} human",
+        stats.ai_additions, stats.human_additions
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_log_args() {
+        let options = parse_log_args(&args(&[
+            "--oneline",
+            "--since=2 weeks ago",
+            "--until",
+            "yesterday",
+            "-n",
+            "5",
+            "main..feature",
+        ]))
+        .unwrap();
+        assert!(options.oneline);
+        assert_eq!(options.since.as_deref(), Some("2 weeks ago"));
+        assert_eq!(options.until.as_deref(), Some("yesterday"));
+        assert_eq!(options.spec.as_deref(), Some("main..feature"));
+        assert_eq!(options.max_count, Some(5));
+
+        for form in [&["-n3"][..], &["--max-count=3"], &["--max-count", "3"]] {
+            let options = parse_log_args(&args(form)).unwrap();
+            assert_eq!(options.max_count, Some(3));
+        }
+        assert!(parse_log_args(&args(&["-n"])).is_err());
+        assert!(parse_log_args(&args(&["--max-count=many"])).is_err());
+
+        assert!(parse_log_args(&args(&["--since"])).is_err());
+        assert!(parse_log_args(&args(&["--graph"])).is_err());
+        assert!(parse_log_args(&args(&["HEAD", "HEAD~1"])).is_err());
+    }
+
+    #[test]
+    fn test_log_entries_report_ai_and_human_split() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "human\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "human\nai one\nai two\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        let entries = log_entries(tmp_repo.gitai_repo(), &LogOptions::default(), &[]).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].subject, "AI commit");
+        assert_eq!(entries[0].stats.ai_additions, 2);
+        assert_eq!(entries[1].subject, "Human commit");
+        assert_eq!(entries[1].stats.ai_additions, 0);
+
+        let line = format_log_entry(&entries[0], true);
+        assert_eq!(
+            line,
+            format!("{}  AI commit  (+2 ai, +0 human)\n", entries[0].short_sha)
+        );
+
+        // Only the newest commit is inside the range
+        let range = LogOptions {
+            spec: Some("HEAD~1..HEAD".to_string()),
+            ..Default::default()
+        };
+        let entries = log_entries(tmp_repo.gitai_repo(), &range, &[]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].subject, "AI commit");
+
+        // On a linear history the symmetric difference is the same single commit
+        let symmetric = LogOptions {
+            spec: Some("HEAD~1...HEAD".to_string()),
+            ..Default::default()
+        };
+        let entries = log_entries(tmp_repo.gitai_repo(), &symmetric, &[]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].subject, "AI commit");
+
+        for spec in ["...HEAD", "HEAD...", "HEAD....HEAD~1"] {
+            let invalid = LogOptions {
+                spec: Some(spec.to_string()),
+                ..Default::default()
+            };
+            let err = log_entries(tmp_repo.gitai_repo(), &invalid, &[])
+                .err()
+                .expect("invalid range should be rejected");
+            assert!(
+                err.to_string().contains("Expected <left>...<right>"),
+                "{}: {}",
+                spec,
+                err
+            );
+        }
+
+        let newest = LogOptions {
+            max_count: Some(1),
+            ..Default::default()
+        };
+        let entries = log_entries(tmp_repo.gitai_repo(), &newest, &[]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].subject, "AI commit");
+
+        let until = LogOptions {
+            until: Some("2000-01-01".to_string()),
+            ..Default::default()
+        };
+        let entries = log_entries(tmp_repo.gitai_repo(), &until, &[]).unwrap();
+        assert!(entries.is_empty());
+    }
+}
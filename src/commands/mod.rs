@@ -1,3 +1,4 @@
+pub mod backfill;
 pub mod blame;
 pub mod checkpoint;
 pub mod checkpoint_agent;
@@ -10,12 +11,17 @@ pub mod git_ai_handlers;
 pub mod git_handlers;
 pub mod hooks;
 pub mod install_hooks;
+pub mod log;
 pub mod prompt_picker;
+pub mod prompts;
+pub mod sarif;
 pub mod share;
 pub mod share_tui;
+pub mod shortlog;
 pub mod show;
 pub mod show_prompt;
 pub mod status;
 pub mod squash_authorship;
 pub mod sync_prompts;
 pub mod upgrade;
+pub mod verify;
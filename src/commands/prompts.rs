@@ -0,0 +1,185 @@
+use serde::Serialize;
+
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::{RecordedPrompt, collect_prompts};
+use crate::git::find_repository;
+
+/// Options for `git-ai prompts`
+#[derive(Debug, Default)]
+pub struct PromptsOptions {
+    pub json: bool,
+}
+
+/// A prompt as printed by `git-ai prompts --json`
+#[derive(Debug, Serialize)]
+pub struct PromptSummary<'a> {
+    pub hash: &'a str,
+    pub tool: &'a str,
+    pub model: &'a str,
+    pub reference_count: usize,
+    pub commits: Vec<&'a str>,
+    pub files: Vec<&'a str>,
+}
+
+impl<'a> From<&'a RecordedPrompt> for PromptSummary<'a> {
+    fn from(prompt: &'a RecordedPrompt) -> Self {
+        PromptSummary {
+            hash: &prompt.hash,
+            tool: &prompt.record.agent_id.tool,
+            model: &prompt.record.agent_id.model,
+            reference_count: prompt.reference_count(),
+            commits: prompt.commits.iter().map(String::as_str).collect(),
+            files: prompt.files.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+pub fn handle_prompts(args: &[String]) {
+    let options = match parse_prompts_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let prompts = match collect_prompts(&repo) {
+        Ok(prompts) => prompts,
+        Err(e) => {
+            eprintln!("Failed to collect prompts: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if options.json {
+        let summaries: Vec<PromptSummary> = prompts.iter().map(PromptSummary::from).collect();
+        println!("{}", serde_json::to_string(&summaries).unwrap());
+    } else {
+        print!("{}", format_prompts_table(&prompts));
+    }
+}
+
+pub fn parse_prompts_args(args: &[String]) -> Result<PromptsOptions, GitAiError> {
+    let mut options = PromptsOptions::default();
+
+    for arg in args {
+        match arg.as_str() {
+            "--json" | "--format=json" => options.json = true,
+            _ => {
+                return Err(GitAiError::Generic(format!(
+                    "Unknown prompts argument: {}",
+                    arg
+                )));
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+/// One row per prompt: hash, tool, model, and how many commits and files reference it
+pub fn format_prompts_table(prompts: &[RecordedPrompt]) -> String {
+    if prompts.is_empty() {
+        return "No prompts recorded\n".to_string();
+    }
+
+    let tool_width = prompts
+        .iter()
+        .map(|prompt| prompt.record.agent_id.tool.len())
+        .chain(std::iter::once("TOOL".len()))
+        .max()
+        .unwrap_or(0);
+    let model_width = prompts
+        .iter()
+        .map(|prompt| prompt.record.agent_id.model.len())
+        .chain(std::iter::once("MODEL".len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut out = format!(
+        "{:<16}  {:<tool_width$}  {:<model_width$}  {:>7}  {:>5}\n",
+        "PROMPT", "TOOL", "MODEL", "COMMITS", "FILES"
+    );
+    for prompt in prompts {
+        out.push_str(&format!(
+            "{:<16}  {:<tool_width$}  {:<model_width$}  {:>7}  {:>5}\n",
+            prompt.hash,
+            prompt.record.agent_id.tool,
+            prompt.record.agent_id.model,
+            prompt.reference_count(),
+            prompt.files.len()
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_prompts_args() {
+        assert!(!parse_prompts_args(&[]).unwrap().json);
+        assert!(parse_prompts_args(&args(&["--json"])).unwrap().json);
+        assert!(parse_prompts_args(&args(&["--all"])).is_err());
+    }
+
+    #[test]
+    fn test_collect_prompts_dedupes_across_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "human\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "human\nai one\n", true)
+            .unwrap();
+        tmp_repo.write_file("b.txt", "ai two\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        // The same session keeps editing in the next commit
+        tmp_repo
+            .write_file("a.txt", "human\nai one\nai three\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("More AI").unwrap();
+
+        let prompts = collect_prompts(tmp_repo.gitai_repo()).unwrap();
+        assert_eq!(prompts.len(), 1);
+        let prompt = &prompts[0];
+        assert_eq!(prompt.record.agent_id.tool, "cursor");
+        assert_eq!(prompt.reference_count(), 2);
+        assert_eq!(
+            prompt.files.iter().collect::<Vec<_>>(),
+            vec!["a.txt", "b.txt"]
+        );
+
+        let table = format_prompts_table(&prompts);
+        assert!(table.starts_with("PROMPT"), "{}", table);
+        assert!(table.contains(&prompt.hash), "{}", table);
+
+        let json = serde_json::to_value(PromptSummary::from(prompt)).unwrap();
+        assert_eq!(json["tool"], "cursor");
+        assert_eq!(json["reference_count"], 2);
+    }
+}
@@ -0,0 +1,511 @@
+use crate::authorship::authorship_log_cache::AuthorshipLogCache;
+use crate::authorship::gitai_attributes::GitAiAttributes;
+use crate::authorship::tool_names::normalize_tool_name;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::diff::get_diff_with_line_numbers;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The git empty tree hash, diffed against for a root commit
+const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// The commits a report covers: lines added after `start_sha` up to `end_sha`, the commit
+/// code scanning places every result on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SarifRange {
+    /// None for a single root commit, whose lines were all added by it
+    pub start_sha: Option<String>,
+    pub end_sha: String,
+}
+
+impl SarifRange {
+    /// `<start>..<end>`, or the end commit alone for a root commit
+    fn label(&self) -> String {
+        match &self.start_sha {
+            Some(start_sha) => format!("{}..{}", start_sha, self.end_sha),
+            None => self.end_sha.clone(),
+        }
+    }
+}
+
+/// One AI-authored line range of a file as of the range's end commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AiRegion {
+    pub file_path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub tool: String,
+    pub model: String,
+}
+
+pub fn handle_sarif(args: &[String]) {
+    let mut spec: Option<&str> = None;
+    let mut output_path: Option<&str> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-o" | "--output" => {
+                match args.get(i + 1) {
+                    Some(path) => output_path = Some(path),
+                    None => {
+                        eprintln!("{} requires a file path", args[i]);
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            arg if arg.starts_with('-') => {
+                eprintln!("Unknown sarif argument: {}", arg);
+                std::process::exit(1);
+            }
+            arg => {
+                if spec.is_some() {
+                    eprintln!("Error: sarif accepts a single revision or range");
+                    std::process::exit(1);
+                }
+                spec = Some(arg);
+                i += 1;
+            }
+        }
+    }
+
+    let Some(spec) = spec else {
+        eprintln!("Error: sarif requires a revision or range");
+        eprintln!("Usage: git-ai sarif <rev|range> [-o <file>]");
+        std::process::exit(1);
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let range = match resolve_sarif_range(&repo, spec) {
+        Ok(range) => range,
+        Err(e) => {
+            eprintln!("Failed to resolve {}: {}", spec, e);
+            std::process::exit(1);
+        }
+    };
+
+    let regions = match collect_ai_regions(&repo, &range) {
+        Ok(regions) => regions,
+        Err(e) => {
+            eprintln!("Failed to collect AI-authored regions: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let repository_uri = default_remote_url(&repo);
+    let report = sarif_report(&regions, &range, repository_uri.as_deref());
+    let report = match serde_json::to_string_pretty(&report) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Failed to serialize SARIF report: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match output_path {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, format!("{}\n", report)) {
+                eprintln!("Failed to write {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        None => println!("{}", report),
+    }
+}
+
+/// The range a `<start>..<end>` spec covers, or for a single revision, the commit itself
+/// (from its first parent)
+pub fn resolve_sarif_range(repo: &Repository, spec: &str) -> Result<SarifRange, GitAiError> {
+    let resolve = |rev: &str| -> Result<String, GitAiError> {
+        Ok(repo.revparse_single(rev)?.peel_to_commit()?.id())
+    };
+
+    if let Some((start, end)) = spec.split_once("..") {
+        if start.is_empty() || end.is_empty() {
+            return Err(GitAiError::Generic(
+                "Invalid commit range format. Expected <start>..<end>".to_string(),
+            ));
+        }
+        return Ok(SarifRange {
+            start_sha: Some(resolve(start)?),
+            end_sha: resolve(end)?,
+        });
+    }
+
+    let commit = repo.revparse_single(spec)?.peel_to_commit()?;
+    let start_sha = match commit.parent_count()? {
+        0 => None,
+        _ => Some(commit.parent(0)?.id()),
+    };
+    Ok(SarifRange {
+        start_sha,
+        end_sha: commit.id(),
+    })
+}
+
+/// Every AI-attributed line the range added that is still there at its end commit, merged
+/// into regions of consecutive lines with the same tool and model.
+///
+/// Code scanning places all results on the analyzed (end) commit, so the lines are found the
+/// way `git-ai diff` finds them: the added lines of the start..end diff are blamed at the end
+/// commit, back to the start. Line numbers and paths are therefore those of the end commit,
+/// whatever commit in the range wrote them, and files deleted since aren't reported. Files
+/// marked `attribution=ignore` in `.gitai-attributes` are skipped.
+pub fn collect_ai_regions(
+    repo: &Repository,
+    range: &SarifRange,
+) -> Result<Vec<AiRegion>, GitAiError> {
+    let from = range.start_sha.as_deref().unwrap_or(EMPTY_TREE_HASH);
+    let hunks = get_diff_with_line_numbers(repo, from, &range.end_sha, &[], false)?;
+    let attributes = GitAiAttributes::load(repo);
+
+    let mut line_ranges_by_file: BTreeMap<String, Vec<(u32, u32)>> = BTreeMap::new();
+    for hunk in &hunks {
+        if hunk.added.is_empty() || attributes.is_ignored(&hunk.file_path) {
+            continue;
+        }
+        line_ranges_by_file
+            .entry(hunk.file_path.clone())
+            .or_default()
+            .push((hunk.added.start, hunk.added.end - 1));
+    }
+
+    let cache = Arc::new(AuthorshipLogCache::default());
+    let mut regions: Vec<AiRegion> = Vec::new();
+    for (file_path, line_ranges) in line_ranges_by_file {
+        let mut options = GitAiBlameOptions::default();
+        options.oldest_commit = range.start_sha.clone();
+        options.newest_commit = Some(range.end_sha.clone());
+        options.line_ranges = line_ranges;
+        options.no_output = true;
+        // AI lines are named by prompt hash, so the prompt record can be looked up
+        options.use_prompt_hashes_as_names = true;
+        options.authorship_log_cache = Some(Arc::clone(&cache));
+
+        let (line_authors, prompt_records) = match repo.blame(&file_path, &options) {
+            Ok(blame) => blame,
+            Err(e) => {
+                debug_log(&format!("Failed to blame {}: {}", file_path, e));
+                continue;
+            }
+        };
+
+        // Lines named by anything but a prompt hash are human
+        let mut ai_lines: Vec<(u32, String, String)> = line_authors
+            .iter()
+            .filter_map(|(line, author)| {
+                let record = prompt_records.get(author)?;
+                Some((
+                    *line,
+                    normalize_tool_name(&record.agent_id.tool),
+                    record.agent_id.model.clone(),
+                ))
+            })
+            .collect();
+        ai_lines.sort();
+
+        for (line, tool, model) in ai_lines {
+            match regions.last_mut() {
+                Some(region)
+                    if region.file_path == file_path
+                        && region.end_line + 1 == line
+                        && region.tool == tool
+                        && region.model == model =>
+                {
+                    region.end_line = line;
+                }
+                _ => regions.push(AiRegion {
+                    file_path: file_path.clone(),
+                    start_line: line,
+                    end_line: line,
+                    tool,
+                    model,
+                }),
+            }
+        }
+    }
+
+    Ok(regions)
+}
+
+/// URL of the default remote, to name the repository in the report
+fn default_remote_url(repo: &Repository) -> Option<String> {
+    let remote_name = repo.get_default_remote().ok().flatten()?;
+    repo.remotes_with_urls()
+        .ok()?
+        .into_iter()
+        .find(|(name, _)| *name == remote_name)
+        .map(|(_, url)| url)
+}
+
+/// A SARIF 2.1.0 log with one `note`-level result per AI region. Each tool is a rule, so
+/// code scanning can filter and group results by tool.
+///
+/// The run names the range's end commit as its revision, and its automation id (the code
+/// scanning category) names the whole range, so uploads for different ranges are kept apart
+/// instead of replacing each other.
+pub fn sarif_report(
+    regions: &[AiRegion],
+    range: &SarifRange,
+    repository_uri: Option<&str>,
+) -> Value {
+    let tools: Vec<&str> = regions
+        .iter()
+        .map(|region| region.tool.as_str())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let rules: Vec<Value> = tools
+        .iter()
+        .map(|tool| {
+            json!({
+                "id": tool,
+                "name": tool,
+                "shortDescription": { "text": format!("Code authored by {}", tool) },
+                "fullDescription": {
+                    "text": format!(
+                        "Lines attributed to {} by git-ai authorship notes",
+                        tool
+                    )
+                },
+                "defaultConfiguration": { "level": "note" },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = regions
+        .iter()
+        .map(|region| {
+            let rule_index = tools
+                .iter()
+                .position(|tool| *tool == region.tool)
+                .unwrap_or_default();
+            json!({
+                "ruleId": region.tool,
+                "ruleIndex": rule_index,
+                "level": "note",
+                "message": {
+                    "text": format!("AI-authored by {} ({})", region.tool, region.model)
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": encode_uri_path(&region.file_path),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": {
+                            "startLine": region.start_line,
+                            "endLine": region.end_line,
+                        },
+                    },
+                }],
+                "partialFingerprints": {
+                    "gitAiRegion/v1": format!(
+                        "{}:{}-{}:{}",
+                        region.file_path, region.start_line, region.end_line, region.tool
+                    ),
+                },
+                "properties": {
+                    "tool": region.tool,
+                    "model": region.model,
+                },
+            })
+        })
+        .collect();
+
+    let mut run = json!({
+        "tool": {
+            "driver": {
+                "name": "git-ai",
+                "version": env!("CARGO_PKG_VERSION"),
+                "informationUri": "https://github.com/acunniffe/git-ai",
+                "rules": rules,
+            },
+        },
+        "automationDetails": { "id": format!("git-ai/{}/", range.label()) },
+        "results": results,
+    });
+    // SARIF requires a repository URI for version control details
+    if let Some(repository_uri) = repository_uri {
+        run["versionControlProvenance"] = json!([{
+            "repositoryUri": repository_uri,
+            "revisionId": range.end_sha,
+        }]);
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [run],
+    })
+}
+
+/// Percent-encode a repo-relative path for use as a SARIF artifact URI, keeping `/`
+fn encode_uri_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_collect_ai_regions_from_range() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "one\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        // A later human commit moves the AI lines down
+        tmp_repo
+            .write_file("a.txt", "zero\none\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let range = resolve_sarif_range(repo, &format!("{}..{}", first_sha, head_sha)).unwrap();
+        assert_eq!(range.start_sha.as_deref(), Some(first_sha.as_str()));
+        assert_eq!(range.end_sha, head_sha);
+
+        // Regions are located in the end commit, not the commit that wrote them
+        let regions = collect_ai_regions(repo, &range).unwrap();
+        assert_eq!(
+            regions,
+            vec![AiRegion {
+                file_path: "a.txt".to_string(),
+                start_line: 3,
+                end_line: 4,
+                tool: "cursor".to_string(),
+                model: "claude-3-sonnet".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_resolve_sarif_range_single_revision() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let root = resolve_sarif_range(repo, &first_sha).unwrap();
+        assert_eq!(root.start_sha, None);
+        assert_eq!(root.label(), first_sha);
+
+        let head = resolve_sarif_range(repo, "HEAD").unwrap();
+        assert_eq!(head.start_sha.as_deref(), Some(first_sha.as_str()));
+        assert_eq!(head.end_sha, head_sha);
+        let regions = collect_ai_regions(repo, &head).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!((regions[0].start_line, regions[0].end_line), (2, 2));
+    }
+
+    #[test]
+    fn test_sarif_report_shape() {
+        let region = |file_path: &str, tool: &str, start_line: u32, end_line: u32| AiRegion {
+            file_path: file_path.to_string(),
+            start_line,
+            end_line,
+            tool: tool.to_string(),
+            model: "model-x".to_string(),
+        };
+        let range = SarifRange {
+            start_sha: Some("aaaa".to_string()),
+            end_sha: "bbbb".to_string(),
+        };
+        let report = sarif_report(
+            &[
+                region("src/main.rs", "cursor", 3, 5),
+                region("docs/my notes.md", "claude", 1, 1),
+            ],
+            &range,
+            Some("https://github.com/example/repo.git"),
+        );
+
+        assert_eq!(report["version"], "2.1.0");
+        let run = &report["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "git-ai");
+        assert_eq!(run["automationDetails"]["id"], "git-ai/aaaa..bbbb/");
+        assert_eq!(run["versionControlProvenance"][0]["revisionId"], "bbbb");
+        assert_eq!(
+            run["versionControlProvenance"][0]["repositoryUri"],
+            "https://github.com/example/repo.git"
+        );
+
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        let rule_ids: Vec<&str> = rules.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(rule_ids, vec!["claude", "cursor"]);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "cursor");
+        assert_eq!(results[0]["ruleIndex"], 1);
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/main.rs");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["endLine"], 5);
+        assert_eq!(
+            results[0]["message"]["text"],
+            "AI-authored by cursor (model-x)"
+        );
+
+        assert_eq!(results[1]["ruleIndex"], 0);
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "docs/my%20notes.md"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use crate::authorship::range_authorship::{
+    calculate_author_stats, calculate_commit_stats, load_gitaiignore_patterns,
+};
+use crate::authorship::stats::{CommitStats, ai_ratio};
+use crate::commands::log::resolve_revisions;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::get_commits_with_notes_from_list;
+use crate::git::repository::{Repository, exec_git};
+
+/// An author in `git-ai shortlog` output with their summed line split
+pub struct ShortlogEntry {
+    /// `Name <email>`, as recorded on the commits
+    pub author: String,
+    pub commits: usize,
+    pub stats: CommitStats,
+}
+
+/// Which commits `git-ai shortlog` summarizes
+#[derive(Debug, Default)]
+pub struct ShortlogOptions {
+    /// A revision (its history is walked) or a `<start>..<end>` range; HEAD when None
+    pub spec: Option<String>,
+    /// Only list authors with at least one AI-written added line
+    pub ai_only: bool,
+}
+
+pub fn handle_shortlog(args: &[String]) {
+    let options = match parse_shortlog_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let ignore_patterns = load_gitaiignore_patterns(&repo);
+    let entries = match shortlog_entries(&repo, &options, &ignore_patterns) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to compute shortlog: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    for entry in &entries {
+        print!("{}", format_shortlog_entry(entry));
+    }
+}
+
+pub fn parse_shortlog_args(args: &[String]) -> Result<ShortlogOptions, GitAiError> {
+    let mut options = ShortlogOptions::default();
+
+    for arg in args {
+        if arg == "--ai-only" {
+            options.ai_only = true;
+        } else if arg.starts_with('-') {
+            return Err(GitAiError::Generic(format!(
+                "Unknown shortlog argument: {}",
+                arg
+            )));
+        } else if options.spec.is_none() {
+            options.spec = Some(arg.clone());
+        } else {
+            return Err(GitAiError::Generic(
+                "shortlog accepts at most one revision or range".to_string(),
+            ));
+        }
+    }
+
+    Ok(options)
+}
+
+/// Group the commits selected by `options` by git author, most added lines first
+pub fn shortlog_entries(
+    repo: &Repository,
+    options: &ShortlogOptions,
+    ignore_patterns: &[String],
+) -> Result<Vec<ShortlogEntry>, GitAiError> {
+    let revisions = resolve_revisions(repo, options.spec.as_deref())?;
+
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push(revisions);
+    args.push("--".to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let shas: Vec<String> = stdout.lines().map(|line| line.to_string()).collect();
+
+    let commit_authorship = get_commits_with_notes_from_list(repo, &shas)?;
+    let commit_stats = calculate_commit_stats(repo, &commit_authorship, ignore_patterns)?;
+
+    let mut commit_counts: HashMap<&str, usize> = HashMap::new();
+    for commit in &commit_stats {
+        *commit_counts.entry(commit.git_author.as_str()).or_default() += 1;
+    }
+
+    let mut entries: Vec<ShortlogEntry> = calculate_author_stats(&commit_stats)
+        .into_iter()
+        .filter(|(_, stats)| !options.ai_only || stats.ai_additions > 0)
+        .map(|(author, stats)| ShortlogEntry {
+            commits: commit_counts.get(author.as_str()).copied().unwrap_or(0),
+            author,
+            stats,
+        })
+        .collect();
+
+    // Author order breaks ties since the stats map is already sorted by author
+    entries.sort_by(|a, b| {
+        b.stats
+            .git_diff_added_lines
+            .cmp(&a.stats.git_diff_added_lines)
+    });
+
+    Ok(entries)
+}
+
+/// Render an author in the spirit of `git shortlog -sn`
+pub fn format_shortlog_entry(entry: &ShortlogEntry) -> String {
+    format!(
+        "{:>6}\t{}  ({} lines, {:.1}% AI)\n",
+        entry.commits,
+        entry.author,
+        entry.stats.git_diff_added_lines,
+        ai_ratio(&entry.stats) * 100.0
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_shortlog_args() {
+        let options = parse_shortlog_args(&args(&["--ai-only", "main..feature"])).unwrap();
+        assert!(options.ai_only);
+        assert_eq!(options.spec.as_deref(), Some("main..feature"));
+
+        assert!(parse_shortlog_args(&args(&["-sn"])).is_err());
+        assert!(parse_shortlog_args(&args(&["HEAD", "HEAD~1"])).is_err());
+    }
+
+    #[test]
+    fn test_shortlog_entries_group_by_author() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "human\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human commit").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "human\nai one\nai two\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        let options = ShortlogOptions::default();
+        let entries = shortlog_entries(tmp_repo.gitai_repo(), &options, &[]).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].commits, 2);
+        assert_eq!(entries[0].stats.git_diff_added_lines, 3);
+        assert_eq!(entries[0].stats.ai_additions, 2);
+
+        let line = format_shortlog_entry(&entries[0]);
+        assert!(line.starts_with("     2\t"), "{}", line);
+        assert!(line.ends_with("(3 lines, 66.7% AI)\n"), "{}", line);
+
+        // The first commit alone has no AI lines
+        let human_only = ShortlogOptions {
+            spec: Some("HEAD~1".to_string()),
+            ai_only: true,
+        };
+        let entries = shortlog_entries(tmp_repo.gitai_repo(), &human_only, &[]).unwrap();
+        assert!(entries.is_empty());
+    }
+}
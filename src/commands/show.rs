@@ -71,7 +71,7 @@ fn show_authorship(repo: &Repository, spec: &str) -> Result<(), GitAiError> {
     Ok(())
 }
 
-fn resolve_commits(repo: &Repository, spec: &str) -> Result<Vec<String>, GitAiError> {
+pub fn resolve_commits(repo: &Repository, spec: &str) -> Result<Vec<String>, GitAiError> {
     if let Some((start, end)) = spec.split_once("..") {
         if start.is_empty() || end.is_empty() {
             return Err(GitAiError::Generic(
@@ -0,0 +1,240 @@
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{AuthorshipLog, find_foreign_prompt};
+use crate::commands::show::resolve_commits;
+use crate::error::GitAiError;
+use crate::git::authorship_traversal::list_annotated_commits;
+use crate::git::find_repository;
+use crate::git::refs::{get_reference_as_authorship_log_v3, show_authorship_note};
+use crate::git::repository::Repository;
+use std::collections::HashMap;
+
+/// Problems found in a single commit's authorship note
+pub struct CommitVerification {
+    pub sha: String,
+    pub problems: Vec<String>,
+}
+
+pub fn handle_verify(args: &[String]) {
+    if args.len() > 1 {
+        eprintln!("Error: verify accepts at most one revision or range");
+        std::process::exit(1);
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let results = match verify_authorship(&repo, args.first().map(String::as_str)) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("Failed to verify authorship: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let failed: Vec<&CommitVerification> =
+        results.iter().filter(|r| !r.problems.is_empty()).collect();
+
+    for result in &failed {
+        println!("{}", result.sha);
+        for problem in &result.problems {
+            println!("  - {}", problem);
+        }
+    }
+
+    if failed.is_empty() {
+        println!(
+            "Verified {} authorship notes, no problems found",
+            results.len()
+        );
+    } else {
+        println!(
+            "Verified {} authorship notes, {} with problems",
+            results.len(),
+            failed.len()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Verify the authorship notes of every commit in `spec` (a revision or range), or of every
+/// annotated commit when no spec is given. Commits in a range without a note are skipped.
+pub fn verify_authorship(
+    repo: &Repository,
+    spec: Option<&str>,
+) -> Result<Vec<CommitVerification>, GitAiError> {
+    let commits = match spec {
+        Some(spec) => resolve_commits(repo, spec)?
+            .into_iter()
+            .filter(|sha| show_authorship_note(repo, sha).is_some())
+            .collect(),
+        None => list_annotated_commits(repo)?,
+    };
+
+    let mut foreign_prompts_cache: HashMap<String, Option<PromptRecord>> = HashMap::new();
+    Ok(commits
+        .into_iter()
+        .map(|sha| {
+            let problems = verify_commit(repo, &sha, &mut foreign_prompts_cache);
+            CommitVerification { sha, problems }
+        })
+        .collect())
+}
+
+fn verify_commit(
+    repo: &Repository,
+    commit_sha: &str,
+    foreign_prompts_cache: &mut HashMap<String, Option<PromptRecord>>,
+) -> Vec<String> {
+    let authorship_log = match get_reference_as_authorship_log_v3(repo, commit_sha) {
+        Ok(log) => log,
+        Err(e) => return vec![format!("note could not be loaded: {}", e)],
+    };
+
+    check_authorship_log(
+        commit_sha,
+        &authorship_log,
+        |file_path| {
+            repo.get_file_content(file_path, commit_sha)
+                .ok()
+                .map(|content| String::from_utf8_lossy(&content).lines().count())
+        },
+        |hash| find_foreign_prompt(repo, hash, foreign_prompts_cache).is_some(),
+    )
+}
+
+/// Check a parsed note against the commit it's attached to. `line_count` returns the number
+/// of lines in a file at that commit (None if the file doesn't exist there), and
+/// `resolve_foreign_prompt` reports whether a prompt hash is recorded in another commit's note.
+fn check_authorship_log(
+    commit_sha: &str,
+    authorship_log: &AuthorshipLog,
+    mut line_count: impl FnMut(&str) -> Option<usize>,
+    mut resolve_foreign_prompt: impl FnMut(&str) -> bool,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let base_commit_sha = &authorship_log.metadata.base_commit_sha;
+    if base_commit_sha != commit_sha {
+        problems.push(format!(
+            "base_commit_sha is '{}' but the note is attached to {}; the note was likely \
+             copied from another commit without being rewritten",
+            base_commit_sha, commit_sha
+        ));
+    }
+
+    for attestation in &authorship_log.attestations {
+        let file_lines = line_count(&attestation.file_path);
+        if file_lines.is_none() {
+            problems.push(format!(
+                "{}: file is attested but doesn't exist in this commit",
+                attestation.file_path
+            ));
+        }
+
+        for entry in &attestation.entries {
+            if !authorship_log.metadata.prompts.contains_key(&entry.hash)
+                && !resolve_foreign_prompt(&entry.hash)
+            {
+                problems.push(format!(
+                    "{}: prompt {} is missing from metadata.prompts and no other note records \
+                     it, so its lines will be attributed to a human",
+                    attestation.file_path, entry.hash
+                ));
+            }
+
+            let Some(file_lines) = file_lines else {
+                continue;
+            };
+            for range in &entry.line_ranges {
+                let (start, end) = match range {
+                    LineRange::Single(line) => (*line, *line),
+                    LineRange::Range(start, end) => (*start, *end),
+                };
+                if start == 0 || start > end || end as usize > file_lines {
+                    problems.push(format!(
+                        "{}: lines {} for prompt {} are outside the file ({} lines)",
+                        attestation.file_path, range, entry.hash, file_lines
+                    ));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::notes_add;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_verify_accepts_freshly_committed_note() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        tmp_repo
+            .write_file("a.txt", "one\ntwo\nthree\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        let results = verify_authorship(tmp_repo.gitai_repo(), Some("HEAD")).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(
+            results[0].problems.is_empty(),
+            "unexpected problems: {:?}",
+            results[0].problems
+        );
+    }
+
+    #[test]
+    fn test_verify_reports_missing_prompt_and_out_of_bounds_lines() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        let mut authorship_log = tmp_repo.commit_with_message("AI commit").unwrap();
+        let head = tmp_repo.get_head_commit_sha().unwrap();
+
+        // Drop the prompt and stretch the attested range past the end of the file
+        authorship_log.metadata.prompts.clear();
+        authorship_log.attestations[0].entries[0].line_ranges = vec![LineRange::Range(1, 10)];
+        notes_add(
+            tmp_repo.gitai_repo(),
+            &head,
+            &authorship_log.serialize_to_string().unwrap(),
+        )
+        .unwrap();
+
+        let results = verify_authorship(tmp_repo.gitai_repo(), None).unwrap();
+        assert_eq!(results.len(), 1);
+        let problems = &results[0].problems;
+        assert_eq!(problems.len(), 2, "problems: {:?}", problems);
+        assert!(problems[0].contains("missing from metadata.prompts"));
+        assert!(problems[1].contains("outside the file (2 lines)"));
+    }
+
+    #[test]
+    fn test_check_authorship_log_flags_base_commit_mismatch() {
+        let mut authorship_log = AuthorshipLog::new();
+        authorship_log.metadata.base_commit_sha = "abc123".to_string();
+
+        let problems = check_authorship_log("def456", &authorship_log, |_| Some(0), |_| false);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("base_commit_sha is 'abc123'"));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -30,6 +31,7 @@ pub struct Config {
     api_base_url: String,
     prompt_storage: String,
     api_key: Option<String>,
+    tool_aliases: HashMap<String, String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -88,6 +90,8 @@ pub struct FileConfig {
     pub prompt_storage: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_aliases: Option<HashMap<String, String>>,
 }
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
@@ -257,6 +261,11 @@ impl Config {
         self.api_key.as_deref()
     }
 
+    /// Returns the tool name aliases from config, keyed by lowercased tool identifier
+    pub fn tool_aliases(&self) -> &HashMap<String, String> {
+        &self.tool_aliases
+    }
+
     /// Override feature flags for testing purposes.
     /// Only available when the `test-support` feature is enabled or in test mode.
     /// Must be `pub` to work with integration tests in the `tests/` directory.
@@ -417,6 +426,15 @@ fn build_config() -> Config {
                 .filter(|s| !s.is_empty())
         });
 
+    // Tool aliases are matched case-insensitively, so normalize the keys once here
+    let tool_aliases = file_cfg
+        .as_ref()
+        .and_then(|c| c.tool_aliases.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(tool, name)| (tool.trim().to_lowercase(), name))
+        .collect();
+
     #[cfg(any(test, feature = "test-support"))]
     {
         let mut config = Config {
@@ -433,6 +451,7 @@ fn build_config() -> Config {
             api_base_url,
             prompt_storage,
             api_key,
+            tool_aliases,
         };
         apply_test_config_patch(&mut config);
         config
@@ -453,6 +472,7 @@ fn build_config() -> Config {
         api_base_url,
         prompt_storage,
         api_key,
+        tool_aliases,
     }
 }
 
@@ -669,6 +689,7 @@ mod tests {
             api_base_url: DEFAULT_API_BASE_URL.to_string(),
             prompt_storage: "default".to_string(),
             api_key: None,
+            tool_aliases: HashMap::new(),
         }
     }
 
@@ -773,6 +794,7 @@ mod tests {
             api_base_url: DEFAULT_API_BASE_URL.to_string(),
             prompt_storage: "default".to_string(),
             api_key: None,
+            tool_aliases: HashMap::new(),
         }
     }
 
@@ -16,6 +16,16 @@ pub enum GitAiError {
     FromUtf8Error(std::string::FromUtf8Error),
     PresetError(String),
     SqliteError(rusqlite::Error),
+    /// A revision that doesn't resolve to a commit
+    CommitNotFound(String),
+    /// git itself couldn't be started, e.g. it isn't installed or isn't on PATH
+    GitExecFailed(std::io::Error),
+    /// The configured git is missing or older than git-ai supports, with what to do about it
+    GitUnavailable(String),
+    /// git diff output that couldn't be parsed
+    DiffParse(String),
+    /// A commit without an authorship note in refs/notes/ai
+    NotesMissing(String),
     Generic(String),
 }
 
@@ -40,6 +50,13 @@ impl fmt::Display for GitAiError {
             GitAiError::FromUtf8Error(e) => write!(f, "From UTF-8 error: {}", e),
             GitAiError::PresetError(e) => write!(f, "{}", e),
             GitAiError::SqliteError(e) => write!(f, "SQLite error: {}", e),
+            GitAiError::CommitNotFound(rev) => write!(f, "No commit found: {}", rev),
+            GitAiError::GitExecFailed(e) => write!(f, "Failed to run git: {}", e),
+            GitAiError::GitUnavailable(reason) => write!(f, "{}", reason),
+            GitAiError::DiffParse(e) => write!(f, "Failed to parse diff output: {}", e),
+            GitAiError::NotesMissing(commit) => {
+                write!(f, "No authorship note found for commit: {}", commit)
+            }
             GitAiError::Generic(e) => write!(f, "Generic error: {}", e),
         }
     }
@@ -102,6 +119,13 @@ impl Clone for GitAiError {
             GitAiError::FromUtf8Error(e) => GitAiError::FromUtf8Error(e.clone()),
             GitAiError::PresetError(s) => GitAiError::PresetError(s.clone()),
             GitAiError::SqliteError(e) => GitAiError::Generic(format!("SQLite error: {}", e)),
+            GitAiError::CommitNotFound(rev) => GitAiError::CommitNotFound(rev.clone()),
+            GitAiError::GitExecFailed(e) => {
+                GitAiError::GitExecFailed(std::io::Error::new(e.kind(), e.to_string()))
+            }
+            GitAiError::GitUnavailable(reason) => GitAiError::GitUnavailable(reason.clone()),
+            GitAiError::DiffParse(s) => GitAiError::DiffParse(s.clone()),
+            GitAiError::NotesMissing(commit) => GitAiError::NotesMissing(commit.clone()),
             GitAiError::Generic(s) => GitAiError::Generic(s.clone()),
         }
     }
@@ -1,53 +1,754 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
 
+use serde::{Deserialize, Serialize};
+
+use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::error::GitAiError;
-use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::git::refs::notes_add;
+use crate::git::repository::{CommitRange, Repository, exec_git, exec_git_stdin};
+use crate::utils::debug_log;
+
+/// Number of note blobs read per `cat-file --batch` call.
+const NOTE_BLOB_CHUNK_SIZE: usize = 500;
+
+/// Fewest note blobs worth giving a traversal worker of its own
+const MIN_BLOBS_PER_WORKER: usize = 100;
+
+/// Overrides the number of traversal workers, for tuning on very small or very large machines
+const TRAVERSAL_WORKERS_ENV: &str = "GIT_AI_TRAVERSAL_WORKERS";
+
+/// Cache of per-file touch counts across all of `refs/notes/ai`, in the `ai` directory of the
+/// common git directory, since every worktree of a repository shares the notes ref
+const AI_TOUCHED_FILES_CACHE: &str = "ai_touched_files_cache.json";
+
+/// Per-file attestation counts for every note in `refs/notes/ai` as of `notes_commit`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AiTouchedFilesCache {
+    notes_commit: String,
+    counts: HashMap<String, usize>,
+}
+
+/// Identifies a file written by [`export_authorship`].
+#[allow(dead_code)]
+pub const AUTHORSHIP_BUNDLE_FORMAT: &str = "git-ai-authorship-bundle";
+
+/// Current bundle layout version. Bundles with a newer version are rejected on import.
+#[allow(dead_code)]
+pub const AUTHORSHIP_BUNDLE_VERSION: u32 = 1;
+
+/// A self-contained copy of `refs/notes/ai`, mapping commit SHA to the raw authorship note.
+/// Notes are kept verbatim so a round trip doesn't depend on the log schema version.
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorshipBundle {
+    pub format: String,
+    pub version: u32,
+    pub notes: BTreeMap<String, String>,
+}
+
+#[allow(dead_code)]
+impl AuthorshipBundle {
+    pub fn serialize_to_string(&self) -> Result<String, GitAiError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn deserialize_from_string(content: &str) -> Result<Self, GitAiError> {
+        let bundle: AuthorshipBundle = serde_json::from_str(content)?;
+        if bundle.format != AUTHORSHIP_BUNDLE_FORMAT {
+            return Err(GitAiError::Generic(format!(
+                "Not an authorship bundle (format: {})",
+                bundle.format
+            )));
+        }
+        if bundle.version > AUTHORSHIP_BUNDLE_VERSION {
+            return Err(GitAiError::Generic(format!(
+                "Unsupported authorship bundle version: {} (expected at most {})",
+                bundle.version, AUTHORSHIP_BUNDLE_VERSION
+            )));
+        }
+        Ok(bundle)
+    }
+}
+
+/// Outcome of [`import_authorship`]
+#[allow(dead_code)]
+#[derive(Debug, Default, PartialEq)]
+pub struct AuthorshipImportSummary {
+    pub imported: usize,
+    /// Commits that already had a note, which is left untouched
+    pub already_annotated: usize,
+    /// Commits that don't exist in this repository
+    pub missing_commits: usize,
+}
 
 pub async fn load_ai_touched_files_for_commits(
     repo: &Repository,
     commit_shas: Vec<String>,
+    workers: Option<usize>,
 ) -> Result<HashSet<String>, GitAiError> {
+    let counts = count_ai_touches_per_file_for_commits(repo, commit_shas, workers).await?;
+    Ok(counts.into_keys().collect())
+}
+
+/// Load every file path with an AI attestation in `refs/notes/ai`. When `range` is
+/// given, only notes attached to commits inside it are read. Without a range the result is
+/// cached per notes commit and updated incrementally as notes change.
+///
+/// Note blobs are read by `workers` threads; with None the count comes from
+/// `GIT_AI_TRAVERSAL_WORKERS`, or is one per CPU when that isn't set. The result doesn't
+/// depend on the number of workers.
+pub async fn load_ai_touched_files(
+    repo: &Repository,
+    range: Option<&CommitRange<'_>>,
+    workers: Option<usize>,
+) -> Result<HashSet<String>, GitAiError> {
+    load_ai_touched_files_with_progress(repo, range, workers, |_, _| {}).await
+}
+
+/// Same as [`load_ai_touched_files`], but calls `progress(completed, total)` as note
+/// blobs are read so long traversals can drive a spinner or progress bar.
+#[allow(dead_code)]
+pub async fn load_ai_touched_files_with_progress<F>(
+    repo: &Repository,
+    range: Option<&CommitRange<'_>>,
+    workers: Option<usize>,
+    mut progress: F,
+) -> Result<HashSet<String>, GitAiError>
+where
+    F: FnMut(usize, usize) + Send + 'static,
+{
     let global_args = repo.global_args_for_exec();
+    let commit_filter: Option<HashSet<String>> =
+        range.map(|range| range.all_commits().into_iter().collect());
+    let cache_path = ai_touched_files_cache_path(repo);
+    let workers = traversal_workers(workers);
 
-    smol::unblock(move || {
-        if commit_shas.is_empty() {
-            return Ok(HashSet::new());
+    let counts = smol::unblock(move || match commit_filter {
+        Some(commit_filter) => {
+            count_touches_from_notes(&global_args, Some(&commit_filter), workers, &mut progress)
         }
+        None => count_all_touches_cached(&global_args, &cache_path, workers, &mut progress),
+    })
+    .await?;
+    Ok(counts.into_keys().collect())
+}
 
-        // Get all notes mappings (note_sha -> commit_sha) using git notes list
-        let note_mappings = get_notes_list(&global_args)?;
+/// Load the file paths AI-touched by commits committed at or after `since`, a date in any
+/// form git's `--since` accepts ("2024-01-01", "3 months ago"). Each note's commit header is
+/// read first so notes on older commits are skipped before their blobs are read. `workers`
+/// is as for [`load_ai_touched_files`].
+#[allow(dead_code)]
+pub async fn load_ai_touched_files_since(
+    repo: &Repository,
+    since: &str,
+    workers: Option<usize>,
+) -> Result<HashSet<String>, GitAiError> {
+    let global_args = repo.global_args_for_exec();
+    let cutoff = resolve_since_timestamp(&global_args, since)?;
+    let workers = traversal_workers(workers);
 
-        if note_mappings.is_empty() {
-            return Ok(HashSet::new());
-        }
+    let counts = smol::unblock(move || {
+        let note_mappings = get_notes_list(&global_args)?;
+        let commit_shas: Vec<String> = note_mappings
+            .iter()
+            .map(|(_, commit_sha)| commit_sha.clone())
+            .collect();
+        let committer_times = read_committer_times(&global_args, &commit_shas)?;
 
-        // Filter to only notes for commits we care about
-        let commit_set: HashSet<&str> = commit_shas.iter().map(|s| s.as_str()).collect();
-        let filtered_blob_shas: Vec<String> = note_mappings
+        // Notes on commits that aren't in this repository have no date and are skipped too
+        let blob_shas: Vec<String> = note_mappings
             .into_iter()
-            .filter(|(_, commit_sha)| commit_set.contains(commit_sha.as_str()))
+            .filter(|(_, commit_sha)| {
+                committer_times
+                    .get(commit_sha)
+                    .is_some_and(|time| *time >= cutoff)
+            })
             .map(|(note_sha, _)| note_sha)
             .collect();
 
-        if filtered_blob_shas.is_empty() {
-            return Ok(HashSet::new());
+        count_touches_in_blobs(&global_args, &blob_shas, workers, &mut |_, _| {})
+    })
+    .await?;
+    Ok(counts.into_keys().collect())
+}
+
+/// Turn a `--since` date into a unix timestamp using git's own date parsing
+fn resolve_since_timestamp(global_args: &[String], since: &str) -> Result<i64, GitAiError> {
+    let mut args = global_args.to_vec();
+    args.push("rev-parse".to_string());
+    args.push(format!("--since={}", since));
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    stdout
+        .trim()
+        .strip_prefix("--max-age=")
+        .and_then(|timestamp| timestamp.parse().ok())
+        .ok_or_else(|| GitAiError::Generic(format!("Invalid --since date: {}", since)))
+}
+
+/// Read the committer time (unix seconds) of each commit, using cat-file --batch in chunks.
+/// Commits that don't exist in this repository are absent from the map.
+fn read_committer_times(
+    global_args: &[String],
+    commit_shas: &[String],
+) -> Result<HashMap<String, i64>, GitAiError> {
+    let mut times = HashMap::new();
+
+    for chunk in commit_shas.chunks(NOTE_BLOB_CHUNK_SIZE) {
+        let mut args = global_args.to_vec();
+        args.push("cat-file".to_string());
+        args.push("--batch".to_string());
+
+        let stdin_data = chunk.join("\n") + "\n";
+        let output = exec_git_stdin(&args, stdin_data.as_bytes())?;
+
+        for (sha, object_type, content) in split_cat_file_batch_output(&output.stdout)? {
+            if object_type != "commit" {
+                continue;
+            }
+            if let Some(time) = parse_committer_time(&String::from_utf8_lossy(content)) {
+                times.insert(sha.to_string(), time);
+            }
         }
+    }
+
+    Ok(times)
+}
+
+/// The committer time from a raw commit object's `committer <ident> <time> <tz>` header
+fn parse_committer_time(commit: &str) -> Option<i64> {
+    commit
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| line.strip_prefix("committer "))
+        .and_then(|ident| ident.rsplit(' ').nth(1))
+        .and_then(|time| time.parse().ok())
+}
+
+fn ai_touched_files_cache_path(repo: &Repository) -> PathBuf {
+    let git_dir = repo.common_dir().unwrap_or_else(|e| {
+        debug_log(&format!("Failed to resolve the common git dir: {}", e));
+        repo.path().to_path_buf()
+    });
+    git_dir.join("ai").join(AI_TOUCHED_FILES_CACHE)
+}
 
-        // Use cat-file --batch to read the filtered blobs efficiently
-        let blob_contents = batch_read_blobs(&global_args, &filtered_blob_shas)?;
+/// Count attestations per file across every note, reusing the cache at `cache_path`. When
+/// `refs/notes/ai` still points at the cached commit the cached counts are returned as-is;
+/// otherwise only the note blobs that differ between the two notes trees are read. Falls
+/// back to a full scan when there's no usable cache.
+fn count_all_touches_cached(
+    global_args: &[String],
+    cache_path: &Path,
+    workers: usize,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<HashMap<String, usize>, GitAiError> {
+    let Some(notes_commit) = resolve_notes_commit(global_args)? else {
+        return Ok(HashMap::new());
+    };
 
-        // Extract file paths from all blob contents
-        let mut all_files = HashSet::new();
-        for content in blob_contents {
-            extract_file_paths_from_note(&content, &mut all_files);
+    let cached: Option<AiTouchedFilesCache> = fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    let counts = match cached {
+        Some(cache) if cache.notes_commit == notes_commit => {
+            debug_log(&format!("AI-touched files cache hit at {}", notes_commit));
+            return Ok(cache.counts);
+        }
+        Some(cache) => {
+            let old_commit = cache.notes_commit.clone();
+            match update_touch_counts(global_args, cache, &notes_commit, workers, progress) {
+                Ok(counts) => counts,
+                Err(e) => {
+                    debug_log(&format!(
+                        "Failed to update AI-touched files cache from {}: {}, rescanning",
+                        old_commit, e
+                    ));
+                    count_touches_in_blobs(
+                        global_args,
+                        &note_blobs_at(global_args, &notes_commit)?,
+                        workers,
+                        progress,
+                    )?
+                }
+            }
+        }
+        None => count_touches_in_blobs(
+            global_args,
+            &note_blobs_at(global_args, &notes_commit)?,
+            workers,
+            progress,
+        )?,
+    };
+
+    let cache = AiTouchedFilesCache {
+        notes_commit,
+        counts,
+    };
+    match serde_json::to_string(&cache) {
+        Ok(json) => {
+            // A linked worktree may be the first to use git-ai's directory in the common dir
+            let written = match cache_path.parent() {
+                Some(dir) => fs::create_dir_all(dir).and_then(|_| fs::write(cache_path, json)),
+                None => fs::write(cache_path, json),
+            };
+            if let Err(e) = written {
+                debug_log(&format!("Failed to write AI-touched files cache: {}", e));
+            }
+        }
+        Err(e) => debug_log(&format!(
+            "Failed to serialize AI-touched files cache: {}",
+            e
+        )),
+    }
+
+    Ok(cache.counts)
+}
+
+/// Apply the note changes between `cache.notes_commit` and `notes_commit` to the cached
+/// counts: attestations of removed or replaced note blobs are subtracted, and those of added
+/// or replacement blobs are added.
+fn update_touch_counts(
+    global_args: &[String],
+    cache: AiTouchedFilesCache,
+    notes_commit: &str,
+    workers: usize,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<HashMap<String, usize>, GitAiError> {
+    let (removed_blobs, added_blobs) =
+        diff_note_blobs(global_args, &cache.notes_commit, notes_commit)?;
+
+    let mut counts = cache.counts;
+    for (file_path, removed) in
+        count_touches_in_blobs(global_args, &removed_blobs, workers, &mut |_, _| {})?
+    {
+        match counts.get_mut(&file_path) {
+            Some(count) if *count > removed => *count -= removed,
+            _ => {
+                counts.remove(&file_path);
+            }
         }
+    }
+    for (file_path, added) in count_touches_in_blobs(global_args, &added_blobs, workers, progress)?
+    {
+        *counts.entry(file_path).or_insert(0) += added;
+    }
+
+    Ok(counts)
+}
 
-        Ok(all_files)
+/// The commit `refs/notes/ai` points at, or None if there are no notes yet
+fn resolve_notes_commit(global_args: &[String]) -> Result<Option<String>, GitAiError> {
+    let mut args = global_args.to_vec();
+    args.push("rev-parse".to_string());
+    args.push("--verify".to_string());
+    args.push("--quiet".to_string());
+    args.push("refs/notes/ai".to_string());
+
+    match exec_git(&args) {
+        Ok(output) => Ok(Some(String::from_utf8(output.stdout)?.trim().to_string())),
+        Err(GitAiError::GitCliError { code: Some(1), .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Every note blob in the notes tree of `notes_commit`
+fn note_blobs_at(global_args: &[String], notes_commit: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = global_args.to_vec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push("-z".to_string());
+    args.push(notes_commit.to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    // Each entry is "<mode> <type> <object>\t<path>"
+    Ok(stdout
+        .split('\0')
+        .filter_map(|entry| {
+            let (meta, _path) = entry.split_once('\t')?;
+            let mut parts = meta.split_whitespace();
+            let (_mode, object_type, object) = (parts.next()?, parts.next()?, parts.next()?);
+            (object_type == "blob").then(|| object.to_string())
+        })
+        .collect())
+}
+
+/// Note blobs removed and added between two notes commits, as (removed, added). A modified
+/// note appears in both: its old blob as removed and its new blob as added.
+fn diff_note_blobs(
+    global_args: &[String],
+    old_commit: &str,
+    new_commit: &str,
+) -> Result<(Vec<String>, Vec<String>), GitAiError> {
+    let mut args = global_args.to_vec();
+    args.push("diff-tree".to_string());
+    args.push("-r".to_string());
+    args.push("-z".to_string());
+    args.push("--no-renames".to_string());
+    args.push(old_commit.to_string());
+    args.push(new_commit.to_string());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(parse_diff_tree_blobs(&stdout))
+}
+
+/// Parse `git diff-tree -r -z` raw output into (removed, added) blob SHAs. Each change is a
+/// ":<old mode> <new mode> <old sha> <new sha> <status>" record followed by its path.
+fn parse_diff_tree_blobs(raw: &str) -> (Vec<String>, Vec<String>) {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    for record in raw.split('\0').filter(|record| record.starts_with(':')) {
+        let parts: Vec<&str> = record[1..].split_whitespace().collect();
+        let [old_mode, new_mode, old_sha, new_sha, _status] = parts[..] else {
+            continue;
+        };
+        // A zero mode means the side doesn't exist (added or deleted note)
+        if old_mode != "000000" {
+            removed.push(old_sha.to_string());
+        }
+        if new_mode != "000000" {
+            added.push(new_sha.to_string());
+        }
+    }
+
+    (removed, added)
+}
+
+/// Count, per file path, how many authorship notes in `refs/notes/ai` have an attestation
+/// for that file. Files no note touches are absent from the map. Like
+/// [`load_ai_touched_files`] without a range, the counts are cached per notes commit, and
+/// `workers` is chosen the same way.
+#[allow(dead_code)]
+pub async fn count_ai_touches_per_file(
+    repo: &Repository,
+    workers: Option<usize>,
+) -> Result<HashMap<String, usize>, GitAiError> {
+    let global_args = repo.global_args_for_exec();
+    let cache_path = ai_touched_files_cache_path(repo);
+    let workers = traversal_workers(workers);
+
+    smol::unblock(move || {
+        count_all_touches_cached(&global_args, &cache_path, workers, &mut |_, _| {})
     })
     .await
 }
 
+/// Same as [`count_ai_touches_per_file`], but only counts the notes of the given commits
+pub async fn count_ai_touches_per_file_for_commits(
+    repo: &Repository,
+    commit_shas: Vec<String>,
+    workers: Option<usize>,
+) -> Result<HashMap<String, usize>, GitAiError> {
+    let global_args = repo.global_args_for_exec();
+    let workers = traversal_workers(workers);
+
+    smol::unblock(move || {
+        if commit_shas.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let commit_set: HashSet<String> = commit_shas.into_iter().collect();
+        count_touches_from_notes(&global_args, Some(&commit_set), workers, &mut |_, _| {})
+    })
+    .await
+}
+
+/// Read the notes (optionally only those attached to `commit_filter`) and count
+/// attestations per file path. Notes are filtered before their blobs are read, and
+/// blobs are read in chunks so `progress` can report (completed, total).
+fn count_touches_from_notes(
+    global_args: &[String],
+    commit_filter: Option<&HashSet<String>>,
+    workers: usize,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<HashMap<String, usize>, GitAiError> {
+    // Get all notes mappings (note_sha -> commit_sha) using git notes list
+    let note_mappings = get_notes_list(global_args)?;
+
+    // Filter to only notes for commits we care about
+    let blob_shas: Vec<String> = note_mappings
+        .into_iter()
+        .filter(|(_, commit_sha)| commit_filter.is_none_or(|commits| commits.contains(commit_sha)))
+        .map(|(note_sha, _)| note_sha)
+        .collect();
+
+    count_touches_in_blobs(global_args, &blob_shas, workers, progress)
+}
+
+/// Number of workers note traversals read blobs with: `requested` when given, else
+/// `GIT_AI_TRAVERSAL_WORKERS` when it's set to a positive number, otherwise one per
+/// available CPU
+fn traversal_workers(requested: Option<usize>) -> usize {
+    if let Some(workers) = requested {
+        return workers.max(1);
+    }
+    std::env::var(TRAVERSAL_WORKERS_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|workers| *workers > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|cpus| cpus.get())
+                .unwrap_or(1)
+        })
+}
+
+/// Count attestations per file path in the given note blobs, reading them in chunks spread
+/// over up to `workers` threads so `progress` can report (completed, total). Small inputs use
+/// fewer workers, so each one gets at least `MIN_BLOBS_PER_WORKER` blobs. The counts don't
+/// depend on the number of workers.
+fn count_touches_in_blobs(
+    global_args: &[String],
+    blob_shas: &[String],
+    workers: usize,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<HashMap<String, usize>, GitAiError> {
+    if blob_shas.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let total = blob_shas.len();
+    let workers = workers.clamp(1, total.div_ceil(MIN_BLOBS_PER_WORKER));
+    let chunk_size = total.div_ceil(workers).min(NOTE_BLOB_CHUNK_SIZE);
+    let chunks: Vec<&[String]> = blob_shas.chunks(chunk_size).collect();
+    progress(0, total);
+
+    if workers == 1 {
+        let mut counts = HashMap::new();
+        let mut completed = 0;
+        for chunk in chunks {
+            add_note_touches(&mut counts, batch_read_blobs(global_args, chunk)?);
+            completed += chunk.len();
+            progress(completed, total);
+        }
+        return Ok(counts);
+    }
+
+    // Workers take the next unread chunk until none are left and report each finished chunk
+    // back here, so progress is only ever called from this thread
+    let next_chunk = AtomicUsize::new(0);
+    let (done_sender, done_receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let done_sender = done_sender.clone();
+                let (chunks, next_chunk) = (&chunks, &next_chunk);
+                scope.spawn(move || -> Result<HashMap<String, usize>, GitAiError> {
+                    let mut counts = HashMap::new();
+                    while let Some(chunk) = chunks.get(next_chunk.fetch_add(1, Ordering::Relaxed)) {
+                        match batch_read_blobs(global_args, chunk) {
+                            Ok(blob_contents) => add_note_touches(&mut counts, blob_contents),
+                            Err(e) => {
+                                // No point in the other workers carrying on
+                                next_chunk.store(chunks.len(), Ordering::Relaxed);
+                                return Err(e);
+                            }
+                        }
+                        let _ = done_sender.send(chunk.len());
+                    }
+                    Ok(counts)
+                })
+            })
+            .collect();
+        drop(done_sender);
+
+        let mut completed = 0;
+        for done in done_receiver {
+            completed += done;
+            progress(completed, total);
+        }
+
+        let mut counts = HashMap::new();
+        for handle in handles {
+            let worker_counts = handle
+                .join()
+                .map_err(|_| GitAiError::Generic("Note traversal worker panicked".to_string()))??;
+            for (file_path, count) in worker_counts {
+                *counts.entry(file_path).or_insert(0) += count;
+            }
+        }
+        Ok(counts)
+    })
+}
+
+/// Add one to the count of every file path each of the note blobs has an attestation for
+fn add_note_touches(counts: &mut HashMap<String, usize>, blob_contents: Vec<(String, String)>) {
+    for (_, content) in blob_contents {
+        for file_path in extract_file_paths_from_note(&content) {
+            *counts.entry(file_path).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Collect the authorship notes of every annotated commit (or only those inside `range`)
+/// into a bundle that can be written to disk and later restored with [`import_authorship`].
+#[allow(dead_code)]
+pub fn export_authorship(
+    repo: &Repository,
+    range: Option<&CommitRange<'_>>,
+) -> Result<AuthorshipBundle, GitAiError> {
+    let global_args = repo.global_args_for_exec();
+    let commit_filter: Option<HashSet<String>> =
+        range.map(|range| range.all_commits().into_iter().collect());
+
+    let note_mappings: Vec<(String, String)> = get_notes_list(&global_args)?
+        .into_iter()
+        .filter(|(_, commit_sha)| {
+            commit_filter
+                .as_ref()
+                .is_none_or(|commits| commits.contains(commit_sha))
+        })
+        .collect();
+
+    let mut notes = BTreeMap::new();
+    for chunk in note_mappings.chunks(NOTE_BLOB_CHUNK_SIZE) {
+        let blob_shas: Vec<String> = chunk.iter().map(|(note_sha, _)| note_sha.clone()).collect();
+        let blob_contents: HashMap<String, String> = batch_read_blobs(&global_args, &blob_shas)?
+            .into_iter()
+            .collect();
+
+        for (note_sha, commit_sha) in chunk {
+            if let Some(content) = blob_contents.get(note_sha) {
+                notes.insert(commit_sha.clone(), content.clone());
+            }
+        }
+    }
+
+    Ok(AuthorshipBundle {
+        format: AUTHORSHIP_BUNDLE_FORMAT.to_string(),
+        version: AUTHORSHIP_BUNDLE_VERSION,
+        notes,
+    })
+}
+
+/// Write the notes in `bundle` back to `refs/notes/ai`. Commits that already have a note keep
+/// it, and notes for commits that aren't in this repository are skipped.
+#[allow(dead_code)]
+pub fn import_authorship(
+    repo: &Repository,
+    bundle: &AuthorshipBundle,
+) -> Result<AuthorshipImportSummary, GitAiError> {
+    let annotated: HashSet<String> = list_annotated_commits(repo)?.into_iter().collect();
+
+    let mut summary = AuthorshipImportSummary::default();
+    for (commit_sha, content) in &bundle.notes {
+        if annotated.contains(commit_sha) {
+            summary.already_annotated += 1;
+            continue;
+        }
+        if repo.find_commit(commit_sha.clone()).is_err() {
+            summary.missing_commits += 1;
+            continue;
+        }
+
+        notes_add(repo, commit_sha, content)?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// A prompt recorded in `refs/notes/ai`, with the commits and files whose attestations
+/// point at it
+#[derive(Debug, Clone)]
+pub struct RecordedPrompt {
+    /// Short prompt hash, the key in each note's `prompts` metadata
+    pub hash: String,
+    /// The most complete copy of the record seen across notes
+    pub record: PromptRecord,
+    pub commits: BTreeSet<String>,
+    pub files: BTreeSet<String>,
+}
+
+impl RecordedPrompt {
+    /// Number of commits whose notes reference the prompt
+    pub fn reference_count(&self) -> usize {
+        self.commits.len()
+    }
+}
+
+/// Collect every distinct prompt across all authorship notes, most referenced first.
+/// A prompt carried forward into several notes is listed once, keeping its newest record.
+pub fn collect_prompts(repo: &Repository) -> Result<Vec<RecordedPrompt>, GitAiError> {
+    let global_args = repo.global_args_for_exec();
+    let note_mappings = get_notes_list(&global_args)?;
+
+    let mut prompts: BTreeMap<String, RecordedPrompt> = BTreeMap::new();
+    for chunk in note_mappings.chunks(NOTE_BLOB_CHUNK_SIZE) {
+        let blob_shas: Vec<String> = chunk.iter().map(|(note_sha, _)| note_sha.clone()).collect();
+        let blob_contents: HashMap<String, String> = batch_read_blobs(&global_args, &blob_shas)?
+            .into_iter()
+            .collect();
+
+        for (note_sha, commit_sha) in chunk {
+            let Some(log) = blob_contents
+                .get(note_sha)
+                .and_then(|content| AuthorshipLog::deserialize_from_string(content).ok())
+            else {
+                continue;
+            };
+            add_note_prompts(&mut prompts, commit_sha, log);
+        }
+    }
+
+    let mut prompts: Vec<RecordedPrompt> = prompts.into_values().collect();
+    // Hash order breaks ties since the map is already sorted by hash
+    prompts.sort_by_key(|prompt| std::cmp::Reverse(prompt.reference_count()));
+    Ok(prompts)
+}
+
+fn add_note_prompts(
+    prompts: &mut BTreeMap<String, RecordedPrompt>,
+    commit_sha: &str,
+    log: AuthorshipLog,
+) {
+    for (hash, record) in log.metadata.prompts {
+        let files = log
+            .attestations
+            .iter()
+            .filter(|file| file.entries.iter().any(|entry| entry.hash == hash))
+            .map(|file| file.file_path.clone());
+
+        match prompts.get_mut(&hash) {
+            Some(prompt) => {
+                if record > prompt.record {
+                    prompt.record = record;
+                }
+                prompt.commits.insert(commit_sha.to_string());
+                prompt.files.extend(files);
+            }
+            None => {
+                let prompt = RecordedPrompt {
+                    hash: hash.clone(),
+                    record,
+                    commits: BTreeSet::from([commit_sha.to_string()]),
+                    files: files.collect(),
+                };
+                prompts.insert(hash, prompt);
+            }
+        }
+    }
+}
+
+/// Get every commit that has an authorship note
+pub fn list_annotated_commits(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    Ok(get_notes_list(&repo.global_args_for_exec())?
+        .into_iter()
+        .map(|(_, commit_sha)| commit_sha)
+        .collect())
+}
+
 /// Get all notes as (note_blob_sha, commit_sha) pairs
 fn get_notes_list(global_args: &[String]) -> Result<Vec<(String, String)>, GitAiError> {
     let mut args = global_args.to_vec();
@@ -81,11 +782,11 @@ fn get_notes_list(global_args: &[String]) -> Result<Vec<(String, String)>, GitAi
     Ok(mappings)
 }
 
-/// Read multiple blobs efficiently using cat-file --batch
+/// Read multiple blobs efficiently using cat-file --batch, returning (blob_sha, content) pairs
 fn batch_read_blobs(
     global_args: &[String],
     blob_shas: &[String],
-) -> Result<Vec<String>, GitAiError> {
+) -> Result<Vec<(String, String)>, GitAiError> {
     if blob_shas.is_empty() {
         return Ok(Vec::new());
     }
@@ -106,13 +807,34 @@ fn batch_read_blobs(
     parse_cat_file_batch_output(&output.stdout)
 }
 
-/// Parse the output of git cat-file --batch
+/// Parse the output of git cat-file --batch into (sha, content) pairs of note blobs
+fn parse_cat_file_batch_output(data: &[u8]) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut results = Vec::new();
+
+    for (sha, object_type, content) in split_cat_file_batch_output(data)? {
+        // Notes are always blobs; anything else (a submodule commit, a tree) isn't a note
+        if object_type != "blob" {
+            debug_log(&format!(
+                "Skipping note object {} of type {}, expected a blob",
+                sha, object_type
+            ));
+        } else if let Ok(content) = std::str::from_utf8(content) {
+            results.push((sha.to_string(), content.to_string()));
+        } else {
+            debug_log(&format!("Skipping note blob {} with invalid UTF-8", sha));
+        }
+    }
+
+    Ok(results)
+}
+
+/// Split the output of git cat-file --batch into (sha, type, content) triples
 ///
 /// Format:
 /// <sha> <type> <size>\n
 /// <content bytes>\n
 /// (repeat for each object)
-fn parse_cat_file_batch_output(data: &[u8]) -> Result<Vec<String>, GitAiError> {
+fn split_cat_file_batch_output(data: &[u8]) -> Result<Vec<(&str, &str, &[u8])>, GitAiError> {
     let mut results = Vec::new();
     let mut pos = 0;
 
@@ -134,7 +856,9 @@ fn parse_cat_file_batch_output(data: &[u8]) -> Result<Vec<String>, GitAiError> {
         }
 
         if parts[1] == "missing" {
-            // Object doesn't exist, skip
+            // Object doesn't exist, e.g. a gitlink in the notes tree pointing at a
+            // commit from another repository
+            debug_log(&format!("Skipping missing object {}", parts[0]));
             pos = header_end + 1;
             continue;
         }
@@ -156,10 +880,7 @@ fn parse_cat_file_batch_output(data: &[u8]) -> Result<Vec<String>, GitAiError> {
             break;
         }
 
-        // Try to parse content as UTF-8
-        if let Ok(content) = std::str::from_utf8(&data[content_start..content_end]) {
-            results.push(content.to_string());
-        }
+        results.push((parts[0], parts[1], &data[content_start..content_end]));
 
         // Move past content and the trailing newline
         pos = content_end + 1;
@@ -169,7 +890,7 @@ fn parse_cat_file_batch_output(data: &[u8]) -> Result<Vec<String>, GitAiError> {
 }
 
 /// Extract file paths from a note blob content
-fn extract_file_paths_from_note(content: &str, files: &mut HashSet<String>) {
+fn extract_file_paths_from_note(content: &str) -> Vec<String> {
     // Find the divider and slice before it, then add minimal metadata to make it parseable
     if let Some(divider_pos) = content.find("\n---\n") {
         let attestation_section = &content[..divider_pos];
@@ -180,11 +901,15 @@ fn extract_file_paths_from_note(content: &str, files: &mut HashSet<String>) {
         );
 
         if let Ok(log) = AuthorshipLog::deserialize_from_string(&parseable) {
-            for attestation in log.attestations {
-                files.insert(attestation.file_path);
-            }
+            return log
+                .attestations
+                .into_iter()
+                .map(|attestation| attestation.file_path)
+                .collect();
         }
     }
+
+    Vec::new()
 }
 
 #[cfg(test)]
@@ -222,7 +947,7 @@ mod tests {
             println!("Testing with commits: {:?}", selected_commits);
 
             let start = Instant::now();
-            let files = load_ai_touched_files_for_commits(&repo, selected_commits.clone())
+            let files = load_ai_touched_files_for_commits(&repo, selected_commits.clone(), None)
                 .await
                 .unwrap();
             let elapsed = start.elapsed();
@@ -259,7 +984,7 @@ mod tests {
                 "1111111111111111111111111111111111111111".to_string(),
             ];
 
-            let files = load_ai_touched_files_for_commits(&repo, fake_commits)
+            let files = load_ai_touched_files_for_commits(&repo, fake_commits, None)
                 .await
                 .unwrap();
 
@@ -270,4 +995,454 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn test_load_ai_touched_files_with_packed_notes_ref() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("ai.txt", "AI Line 1\nAI Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        // Move refs/notes/ai into packed-refs, as `git gc` or a fresh clone would
+        tmp_repo.git_command(&["pack-refs", "--all"]).unwrap();
+        assert!(
+            !tmp_repo.path().join(".git/refs/notes/ai").exists(),
+            "notes ref should no longer be a loose file"
+        );
+
+        let files = smol::block_on(load_ai_touched_files_for_commits(
+            tmp_repo.gitai_repo(),
+            vec![head_sha],
+            None,
+        ))
+        .unwrap();
+
+        assert!(
+            files.contains("ai.txt"),
+            "Should find AI-touched files through packed refs, got {:?}",
+            files
+        );
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_from_linked_worktree() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("ai.txt", "AI Line 1\nAI Line 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let worktree_path = tmp_repo.path().with_extension("worktree");
+        tmp_repo
+            .git_command(&[
+                "worktree",
+                "add",
+                "--detach",
+                worktree_path.to_str().unwrap(),
+            ])
+            .unwrap();
+        let worktree_repo = find_repository_in_path(worktree_path.to_str().unwrap()).unwrap();
+
+        // The worktree has its own git dir, but shares the main one's objects and notes
+        let main_repo = tmp_repo.gitai_repo();
+        let main_git_dir = main_repo.path().canonicalize().unwrap();
+        assert_ne!(worktree_repo.path().canonicalize().unwrap(), main_git_dir);
+        assert_eq!(
+            worktree_repo.common_dir().unwrap().canonicalize().unwrap(),
+            main_git_dir
+        );
+        assert_eq!(
+            main_repo.common_dir().unwrap().canonicalize().unwrap(),
+            main_git_dir
+        );
+
+        let files = smol::block_on(load_ai_touched_files(&worktree_repo, None, None)).unwrap();
+        assert_eq!(files, HashSet::from(["ai.txt".to_string()]));
+        let files = smol::block_on(load_ai_touched_files_for_commits(
+            &worktree_repo,
+            vec![head_sha],
+            None,
+        ))
+        .unwrap();
+        assert_eq!(files, HashSet::from(["ai.txt".to_string()]));
+
+        // Both checkouts read and update the same cache, in the main git dir
+        let cache_path = ai_touched_files_cache_path(&worktree_repo).canonicalize();
+        assert_eq!(
+            cache_path.unwrap(),
+            main_git_dir.join("ai").join(AI_TOUCHED_FILES_CACHE)
+        );
+
+        let _ = fs::remove_dir_all(&worktree_path);
+    }
+
+    #[test]
+    fn test_count_ai_touches_per_file() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut shared = tmp_repo
+            .write_file("shared.txt", "AI Line 1\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("First AI commit").unwrap();
+
+        shared.append("AI Line 2\n").unwrap();
+        tmp_repo
+            .write_file("other.txt", "AI Other\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Second AI commit").unwrap();
+        let second_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let counts =
+            smol::block_on(count_ai_touches_per_file(tmp_repo.gitai_repo(), None)).unwrap();
+        assert_eq!(counts.get("shared.txt"), Some(&2));
+        assert_eq!(counts.get("other.txt"), Some(&1));
+
+        let counts = smol::block_on(count_ai_touches_per_file_for_commits(
+            tmp_repo.gitai_repo(),
+            vec![second_sha],
+            Some(2),
+        ))
+        .unwrap();
+        assert_eq!(counts.get("shared.txt"), Some(&1));
+        assert_eq!(counts.get("other.txt"), Some(&1));
+    }
+
+    #[test]
+    fn test_count_touches_in_blobs_same_for_any_worker_count() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut shared = tmp_repo
+            .write_file("shared.txt", "AI Line 1\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("First AI commit").unwrap();
+
+        shared.append("AI Line 2\n").unwrap();
+        tmp_repo
+            .write_file("other.txt", "AI Other\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Second AI commit").unwrap();
+
+        // Repeat the two note blobs so there's enough work to split between workers
+        let global_args = tmp_repo.gitai_repo().global_args_for_exec();
+        let note_blobs: Vec<String> = get_notes_list(&global_args)
+            .unwrap()
+            .into_iter()
+            .map(|(note_sha, _)| note_sha)
+            .collect();
+        let blob_shas = note_blobs.repeat(150);
+
+        let serial = count_touches_in_blobs(&global_args, &blob_shas, 1, &mut |_, _| {}).unwrap();
+        assert_eq!(serial.get("shared.txt"), Some(&300));
+        assert_eq!(serial.get("other.txt"), Some(&150));
+
+        for workers in [2, 3, 64] {
+            let mut reported = Vec::new();
+            let counts =
+                count_touches_in_blobs(&global_args, &blob_shas, workers, &mut |done, total| {
+                    reported.push((done, total))
+                })
+                .unwrap();
+            assert_eq!(counts, serial, "{} workers", workers);
+            assert_eq!(reported.first(), Some(&(0, 300)));
+            assert_eq!(reported.last(), Some(&(300, 300)));
+        }
+    }
+
+    #[test]
+    fn test_traversal_workers_prefers_requested_count() {
+        assert_eq!(traversal_workers(Some(3)), 3);
+        assert_eq!(traversal_workers(Some(0)), 1);
+        assert!(traversal_workers(None) >= 1);
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_filters_by_range() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("old.txt", "AI Old\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Old AI commit").unwrap();
+        let old_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo.write_file("new.txt", "AI New\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("New AI commit").unwrap();
+        let new_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+
+        let all = smol::block_on(load_ai_touched_files(repo, None, None)).unwrap();
+        assert!(all.contains("old.txt"));
+        assert!(all.contains("new.txt"));
+
+        let range = CommitRange::new(repo, old_sha, new_sha, "HEAD".to_string()).unwrap();
+        let in_range = smol::block_on(load_ai_touched_files(repo, Some(&range), None)).unwrap();
+        assert!(!in_range.contains("old.txt"));
+        assert!(in_range.contains("new.txt"));
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_since_skips_older_commits() {
+        use crate::git::test_utils::TmpRepo;
+        use std::process::Command;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("old.txt", "AI Old\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Old AI commit").unwrap();
+        let original_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        // Backdate the commit and carry its note over to the rewritten SHA
+        let output = Command::new(crate::config::Config::get().git_cmd())
+            .current_dir(tmp_repo.path())
+            .args(["commit", "--amend", "--no-edit", "--no-verify"])
+            .env("GIT_COMMITTER_DATE", "2020-01-01T00:00:00Z")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        tmp_repo
+            .git_command(&["notes", "--ref=ai", "copy", &original_sha, "HEAD"])
+            .unwrap();
+
+        tmp_repo.write_file("new.txt", "AI New\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("New AI commit").unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let recent = smol::block_on(load_ai_touched_files_since(repo, "2021-01-01", None)).unwrap();
+        assert!(!recent.contains("old.txt"));
+        assert!(recent.contains("new.txt"));
+
+        let all = smol::block_on(load_ai_touched_files_since(repo, "2019-01-01", None)).unwrap();
+        assert!(all.contains("old.txt"));
+        assert!(all.contains("new.txt"));
+    }
+
+    #[test]
+    fn test_parse_committer_time() {
+        let commit = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+                      author A U Thor <author@example.com> 1577836800 +0000\n\
+                      committer C O Mitter <committer@example.com> 1577923200 -0500\n\
+                      \n\
+                      committer 1 +0000 in the message\n";
+        assert_eq!(parse_committer_time(commit), Some(1577923200));
+        assert_eq!(parse_committer_time("tree abc\n\nno header\n"), None);
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_reports_progress() {
+        use crate::git::test_utils::TmpRepo;
+        use std::sync::{Arc, Mutex};
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("ai.txt", "AI Line\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let files = smol::block_on(load_ai_touched_files_with_progress(
+            tmp_repo.gitai_repo(),
+            None,
+            None,
+            move |completed, total| recorded.lock().unwrap().push((completed, total)),
+        ))
+        .unwrap();
+
+        assert!(files.contains("ai.txt"));
+        assert_eq!(*calls.lock().unwrap(), vec![(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_load_ai_touched_files_uses_and_updates_cache() {
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo
+            .write_file("first.txt", "AI First\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("First AI commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let cache_path = ai_touched_files_cache_path(repo);
+        let read_cache = || -> AiTouchedFilesCache {
+            serde_json::from_str(&fs::read_to_string(&cache_path).unwrap()).unwrap()
+        };
+
+        let files = smol::block_on(load_ai_touched_files(repo, None, None)).unwrap();
+        assert_eq!(files, HashSet::from(["first.txt".to_string()]));
+        let cache = read_cache();
+        assert_eq!(
+            Some(cache.notes_commit.clone()),
+            resolve_notes_commit(&repo.global_args_for_exec()).unwrap()
+        );
+
+        // While the notes ref is unchanged the cached counts are returned without a scan
+        let mut stale = cache;
+        stale.counts.insert("from-cache.txt".to_string(), 1);
+        fs::write(&cache_path, serde_json::to_string(&stale).unwrap()).unwrap();
+        let files = smol::block_on(load_ai_touched_files(repo, None, None)).unwrap();
+        assert!(files.contains("from-cache.txt"));
+        stale.counts.remove("from-cache.txt");
+        fs::write(&cache_path, serde_json::to_string(&stale).unwrap()).unwrap();
+
+        // A new note is merged into the cached counts
+        tmp_repo
+            .write_file("second.txt", "AI Second\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Second AI commit").unwrap();
+        let files = smol::block_on(load_ai_touched_files(repo, None, None)).unwrap();
+        assert_eq!(
+            files,
+            HashSet::from(["first.txt".to_string(), "second.txt".to_string()])
+        );
+
+        // A removed note takes its files out again
+        tmp_repo
+            .git_command(&["notes", "--ref=ai", "remove", &first_sha])
+            .unwrap();
+        let files = smol::block_on(load_ai_touched_files(repo, None, None)).unwrap();
+        assert_eq!(files, HashSet::from(["second.txt".to_string()]));
+        assert_eq!(read_cache().counts.get("first.txt"), None);
+    }
+
+    #[test]
+    fn test_parse_diff_tree_blobs() {
+        let raw = ":000000 100644 0000000 aaa1111 A\0ab/cdef\0\
+                   :100644 100644 bbb2222 ccc3333 M\0gh/ijkl\0\
+                   :100644 000000 ddd4444 0000000 D\0mn/opqr\0";
+
+        let (removed, added) = parse_diff_tree_blobs(raw);
+
+        assert_eq!(removed, vec!["bbb2222".to_string(), "ddd4444".to_string()]);
+        assert_eq!(added, vec!["aaa1111".to_string(), "ccc3333".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_cat_file_batch_output_skips_non_blob_objects() {
+        let data = b"aaa blob 5\nnote1\nbbb missing\nccc commit 4\ntree\nddd blob 5\nnote2\n";
+
+        let results = parse_cat_file_batch_output(data).unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                ("aaa".to_string(), "note1".to_string()),
+                ("ddd".to_string(), "note2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_import_authorship_round_trip() {
+        use crate::git::refs::show_authorship_note;
+        use crate::git::test_utils::TmpRepo;
+
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("ai.txt", "AI Line\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let original_note = show_authorship_note(repo, &head_sha).unwrap();
+
+        let exported = export_authorship(repo, None)
+            .unwrap()
+            .serialize_to_string()
+            .unwrap();
+        let mut bundle = AuthorshipBundle::deserialize_from_string(&exported).unwrap();
+        assert_eq!(bundle.version, AUTHORSHIP_BUNDLE_VERSION);
+        assert_eq!(bundle.notes.len(), 1);
+
+        tmp_repo
+            .git_command(&["notes", "--ref=ai", "remove", &head_sha])
+            .unwrap();
+        assert!(show_authorship_note(repo, &head_sha).is_none());
+
+        bundle.notes.insert(
+            "0000000000000000000000000000000000000000".to_string(),
+            bundle.notes[&head_sha].clone(),
+        );
+        let summary = import_authorship(repo, &bundle).unwrap();
+        assert_eq!(
+            summary,
+            AuthorshipImportSummary {
+                imported: 1,
+                already_annotated: 0,
+                missing_commits: 1,
+            }
+        );
+        assert_eq!(
+            show_authorship_note(repo, &head_sha).unwrap(),
+            original_note
+        );
+
+        // Importing again leaves the existing note alone
+        let summary = import_authorship(repo, &bundle).unwrap();
+        assert_eq!(summary.imported, 0);
+        assert_eq!(summary.already_annotated, 1);
+    }
+
+    #[test]
+    fn test_authorship_bundle_rejects_newer_version() {
+        let bundle = AuthorshipBundle {
+            format: AUTHORSHIP_BUNDLE_FORMAT.to_string(),
+            version: AUTHORSHIP_BUNDLE_VERSION + 1,
+            notes: BTreeMap::new(),
+        };
+        let content = bundle.serialize_to_string().unwrap();
+
+        let err = AuthorshipBundle::deserialize_from_string(&content).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unsupported authorship bundle version")
+        );
+    }
 }
@@ -9,6 +9,8 @@ use std::collections::{HashMap, HashSet};
 // Modern refspecs without force to enable proper merging
 pub const AI_AUTHORSHIP_REFNAME: &str = "ai";
 pub const AI_AUTHORSHIP_PUSH_REFSPEC: &str = "refs/notes/ai:refs/notes/ai";
+// Local-only cache of computed range stats, never pushed
+pub const AI_RANGE_CACHE_REFNAME: &str = "ai-range-cache";
 
 pub fn notes_add(
     repo: &Repository,
@@ -29,6 +31,41 @@ pub fn notes_add(
     Ok(())
 }
 
+// Show a range stats cache note attached to a commit, or None if it doesn't exist.
+pub fn show_range_cache_note(repo: &Repository, commit_sha: &str) -> Option<String> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", AI_RANGE_CACHE_REFNAME));
+    args.push("show".to_string());
+    args.push(commit_sha.to_string());
+
+    match exec_git(&args) {
+        Ok(output) => String::from_utf8(output.stdout)
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+        Err(_) => None,
+    }
+}
+
+pub fn range_cache_notes_add(
+    repo: &Repository,
+    commit_sha: &str,
+    note_content: &str,
+) -> Result<(), GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", AI_RANGE_CACHE_REFNAME));
+    args.push("add".to_string());
+    args.push("-f".to_string());
+    args.push("-F".to_string());
+    args.push("-".to_string());
+    args.push(commit_sha.to_string());
+
+    exec_git_stdin(&args, note_content.as_bytes())?;
+    Ok(())
+}
+
 // Check which commits from the given list have authorship notes.
 // Uses git cat-file --batch-check to efficiently check multiple commits in one invocation.
 // Returns a Vec of CommitAuthorship for each commit.
@@ -147,7 +184,7 @@ pub fn get_reference_as_working_log(
     commit_sha: &str,
 ) -> Result<Vec<Checkpoint>, GitAiError> {
     let content = show_authorship_note(repo, commit_sha)
-        .ok_or_else(|| GitAiError::Generic("No authorship note found".to_string()))?;
+        .ok_or_else(|| GitAiError::NotesMissing(commit_sha.to_string()))?;
     let working_log = serde_json::from_str(&content)?;
     Ok(working_log)
 }
@@ -157,7 +194,7 @@ pub fn get_reference_as_authorship_log_v3(
     commit_sha: &str,
 ) -> Result<AuthorshipLog, GitAiError> {
     let content = show_authorship_note(repo, commit_sha)
-        .ok_or_else(|| GitAiError::Generic("No authorship note found".to_string()))?;
+        .ok_or_else(|| GitAiError::NotesMissing(commit_sha.to_string()))?;
 
     // Try to deserialize as AuthorshipLog
     let authorship_log = match AuthorshipLog::deserialize_from_string(&content) {
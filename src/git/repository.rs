@@ -7,8 +7,16 @@ use crate::git::repo_storage::RepoStorage;
 use crate::git::rewrite_log::RewriteLogEvent;
 use crate::git::sync_authorship::{fetch_authorship_notes, push_authorship_notes};
 use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
+use std::sync::OnceLock;
+
+/// Oldest git release git-ai supports
+pub const MIN_GIT_VERSION: (u32, u32, u32) = (2, 20, 0);
+
+/// Outcome of the one-time check in [`ensure_git_available`]
+static GIT_AVAILABILITY: OnceLock<Result<(), String>> = OnceLock::new();
 
 pub struct Object<'a> {
     repo: &'a Repository,
@@ -43,6 +51,9 @@ pub struct CommitRange<'a> {
     pub start_oid: String,
     pub end_oid: String,
     pub refname: String,
+    /// Only walk first-parent history, like `git rev-list --first-parent`, leaving out
+    /// commits that came in through merges
+    pub first_parent: bool,
 }
 
 impl<'a> CommitRange<'a> {
@@ -53,6 +64,7 @@ impl<'a> CommitRange<'a> {
             start_oid: String::new(),
             end_oid: String::new(),
             refname: String::new(),
+            first_parent: false,
         }
     }
 
@@ -72,6 +84,7 @@ impl<'a> CommitRange<'a> {
             start_oid: resolved_start,
             end_oid: resolved_end,
             refname,
+            first_parent: false,
         })
     }
 
@@ -129,9 +142,16 @@ impl<'a> CommitRange<'a> {
             start_oid: resolved_start,
             end_oid: resolved_end,
             refname: inferred_refname,
+            first_parent: false,
         })
     }
 
+    /// Restrict the commits this range iterates over to first-parent history
+    pub fn with_first_parent(mut self, first_parent: bool) -> Self {
+        self.first_parent = first_parent;
+        self
+    }
+
     pub fn repo(&self) -> &'a Repository {
         self.repo
     }
@@ -253,6 +273,9 @@ impl<'a> IntoIterator for CommitRange<'a> {
         // Format: start_oid..end_oid means commits reachable from end_oid but not from start_oid
         let mut args = self.repo.global_args_for_exec();
         args.push("rev-list".to_string());
+        if self.first_parent {
+            args.push("--first-parent".to_string());
+        }
         args.push(format!("{}..{}", self.start_oid, self.end_oid));
 
         let commit_oids: Vec<String> = match exec_git(&args) {
@@ -942,6 +965,25 @@ impl Repository {
         self.git_dir.as_path()
     }
 
+    /// The git directory shared by all worktrees of this repository, which holds the object
+    /// database and refs such as `refs/notes/ai`. Same as [`Repository::path`] except in a
+    /// linked worktree, whose own git directory is `<common dir>/worktrees/<name>`.
+    pub fn common_dir(&self) -> Result<PathBuf, GitAiError> {
+        // Ask from inside the git directory, so a relative answer is relative to it
+        let args = vec![
+            "-C".to_string(),
+            self.git_dir.to_string_lossy().to_string(),
+            "rev-parse".to_string(),
+            "--git-common-dir".to_string(),
+        ];
+        let output = exec_git(&args)?;
+        let common_dir = String::from_utf8(output.stdout)?;
+        match common_dir.trim() {
+            "." => Ok(self.git_dir.clone()),
+            common_dir => Ok(self.git_dir.join(common_dir)),
+        }
+    }
+
     // Get the path of the working directory for this repository.
     // If this repository is bare, then None is returned.
     pub fn workdir(&self) -> Result<PathBuf, GitAiError> {
@@ -1572,7 +1614,6 @@ impl Repository {
 
     /// Get the content of a file at a specific commit
     /// Uses `git show <commit>:<path>` for efficient single-call retrieval
-    #[allow(dead_code)]
     pub fn get_file_content(
         &self,
         file_path: &str,
@@ -1914,13 +1955,58 @@ pub fn find_repository_in_path(path: &str) -> Result<Repository, GitAiError> {
     return find_repository(&global_args);
 }
 
+/// Check that the configured git can be run and is at least [`MIN_GIT_VERSION`], so a
+/// missing or outdated git is reported up front instead of as a failure to parse its output.
+/// git is only probed the first time; later calls return the cached outcome. A version that
+/// can't be parsed is assumed to be recent enough.
+pub fn ensure_git_available() -> Result<(), GitAiError> {
+    let git_cmd = config::Config::get().git_cmd();
+    GIT_AVAILABILITY
+        .get_or_init(|| probe_git(git_cmd))
+        .clone()
+        .map_err(GitAiError::GitUnavailable)
+}
+
+fn probe_git(git_cmd: &str) -> Result<(), String> {
+    let output = Command::new(git_cmd)
+        .arg("--version")
+        .output()
+        .map_err(|e| {
+            format!(
+                "Could not run git at {} ({}). Install git {} or newer, or set git_path in ~/.git-ai/config.json",
+                git_cmd,
+                e,
+                format_git_version(MIN_GIT_VERSION)
+            )
+        })?;
+    check_git_version(git_cmd, &String::from_utf8_lossy(&output.stdout))
+}
+
+/// Reject a `git --version` output older than [`MIN_GIT_VERSION`]
+fn check_git_version(git_cmd: &str, version_output: &str) -> Result<(), String> {
+    match parse_git_version(version_output) {
+        Some(version) if version < MIN_GIT_VERSION => Err(format!(
+            "git {} at {} is too old; git-ai needs git {} or newer. Upgrade git, or set git_path in ~/.git-ai/config.json to a newer one",
+            format_git_version(version),
+            git_cmd,
+            format_git_version(MIN_GIT_VERSION)
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn format_git_version((major, minor, patch): (u32, u32, u32)) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
 /// Helper to execute a git command
 pub fn exec_git(args: &[String]) -> Result<Output, GitAiError> {
+    ensure_git_available()?;
     // TODO Make sure to handle process signals, etc.
     let output = Command::new(config::Config::get().git_cmd())
         .args(args)
         .output()
-        .map_err(GitAiError::IoError)?;
+        .map_err(GitAiError::GitExecFailed)?;
 
     if !output.status.success() {
         let code = output.status.code();
@@ -1935,8 +2021,61 @@ pub fn exec_git(args: &[String]) -> Result<Output, GitAiError> {
     Ok(output)
 }
 
+/// Helper to execute a git command and consume its stdout while it runs, instead of
+/// collecting all of it first like `exec_git`. If `consume` fails, git is killed and that
+/// error is returned.
+pub fn exec_git_streaming<T>(
+    args: &[String],
+    consume: impl FnOnce(&mut dyn BufRead) -> Result<T, GitAiError>,
+) -> Result<T, GitAiError> {
+    ensure_git_available()?;
+    let mut child = Command::new(config::Config::get().git_cmd())
+        .args(args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(GitAiError::GitExecFailed)?;
+
+    let (Some(stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(GitAiError::Generic(
+            "Failed to capture git output".to_string(),
+        ));
+    };
+    // Drain stderr on its own thread so git never blocks on a full stderr pipe while
+    // stdout is still being consumed
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = std::io::Read::read_to_end(&mut stderr, &mut buf);
+        buf
+    });
+
+    let result = consume(&mut BufReader::new(stdout));
+    if result.is_err() {
+        let _ = child.kill();
+    }
+
+    let status = child.wait().map_err(GitAiError::IoError)?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    let value = result?;
+
+    if !status.success() {
+        let code = status.code();
+        let stderr = String::from_utf8_lossy(&stderr).to_string();
+        return Err(GitAiError::GitCliError {
+            code,
+            stderr,
+            args: args.to_vec(),
+        });
+    }
+
+    Ok(value)
+}
+
 /// Helper to execute a git command with data provided on stdin
 pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError> {
+    ensure_git_available()?;
     // TODO Make sure to handle process signals, etc.
     let mut child = Command::new(config::Config::get().git_cmd())
         .args(args)
@@ -1944,7 +2083,7 @@ pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitA
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
-        .map_err(GitAiError::IoError)?;
+        .map_err(GitAiError::GitExecFailed)?;
 
     if let Some(mut stdin) = child.stdin.take() {
         use std::io::Write;
@@ -1975,6 +2114,7 @@ pub fn exec_git_stdin_with_env(
     env: &Vec<(String, String)>,
     stdin_data: &[u8],
 ) -> Result<Output, GitAiError> {
+    ensure_git_available()?;
     // TODO Make sure to handle process signals, etc.
     let mut cmd = Command::new(config::Config::get().git_cmd());
     cmd.args(args)
@@ -1987,7 +2127,7 @@ pub fn exec_git_stdin_with_env(
         cmd.env(k, v);
     }
 
-    let mut child = cmd.spawn().map_err(GitAiError::IoError)?;
+    let mut child = cmd.spawn().map_err(GitAiError::GitExecFailed)?;
 
     if let Some(mut stdin) = child.stdin.take() {
         use std::io::Write;
@@ -2212,6 +2352,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_git_version_rejects_old_git() {
+        assert!(check_git_version("/usr/bin/git", "git version 2.39.3").is_ok());
+        assert!(check_git_version("/usr/bin/git", "git version 2.20.0").is_ok());
+        // Unparseable output isn't held against git
+        assert!(check_git_version("/usr/bin/git", "not git").is_ok());
+
+        let error = check_git_version("/usr/bin/git", "git version 1.8.5").unwrap_err();
+        assert!(
+            error.contains("git 1.8.5 at /usr/bin/git is too old"),
+            "{}",
+            error
+        );
+        assert!(error.contains("2.20.0 or newer"), "{}", error);
+    }
+
+    #[test]
+    fn test_probe_git_reports_missing_binary() {
+        let error = probe_git("/nonexistent/git").unwrap_err();
+        assert!(
+            error.contains("Could not run git at /nonexistent/git"),
+            "{}",
+            error
+        );
+        assert!(error.contains("git_path"), "{}", error);
+    }
+
     #[test]
     fn test_parse_git_version_apple_git() {
         // macOS Apple Git format
@@ -1,8 +1,12 @@
 use crate::error::GitAiError;
-use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookDescription, HookInstaller, HookInstallerParams, HookVerification,
+    InstallScope,
+};
 use crate::mdm::utils::{
-    binary_exists, generate_diff, get_binary_version, home_dir, is_git_ai_checkpoint_command,
-    parse_version, version_meets_requirement, write_atomic, MIN_CLAUDE_VERSION,
+    binary_exists, ensure_parent_dir, generate_diff, get_binary_version, home_dir,
+    is_git_ai_checkpoint_command, parse_version, verify_hook_config, version_meets_requirement,
+    write_atomic, MIN_CLAUDE_VERSION,
 };
 use serde_json::{json, Value};
 use std::fs;
@@ -15,8 +19,8 @@ const CLAUDE_POST_TOOL_CMD: &str = "checkpoint claude --hook-input stdin";
 pub struct ClaudeCodeInstaller;
 
 impl ClaudeCodeInstaller {
-    fn settings_path() -> PathBuf {
-        home_dir().join(".claude").join("settings.json")
+    fn settings_path(scope: &InstallScope) -> PathBuf {
+        scope.base_dir().join(".claude").join("settings.json")
     }
 }
 
@@ -29,7 +33,18 @@ impl HookInstaller for ClaudeCodeInstaller {
         "claude-code"
     }
 
-    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+    fn config_paths(&self, params: &HookInstallerParams) -> Vec<PathBuf> {
+        vec![Self::settings_path(&params.scope)]
+    }
+
+    fn describe(&self, params: &HookInstallerParams) -> Option<HookDescription> {
+        Some(HookDescription {
+            command: format!("git-ai {}", CLAUDE_PRE_TOOL_CMD),
+            config_path: Self::settings_path(&params.scope),
+        })
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
         let has_binary = binary_exists("claude");
         let has_dotfiles = home_dir().join(".claude").exists();
 
@@ -56,7 +71,7 @@ impl HookInstaller for ClaudeCodeInstaller {
         }
 
         // Check if hooks are installed
-        let settings_path = Self::settings_path();
+        let settings_path = Self::settings_path(&params.scope);
         if !settings_path.exists() {
             return Ok(HookCheckResult {
                 tool_installed: true,
@@ -93,21 +108,17 @@ impl HookInstaller for ClaudeCodeInstaller {
         Ok(HookCheckResult {
             tool_installed: true,
             hooks_installed: has_hooks,
-            hooks_up_to_date: has_hooks, // If installed, assume up to date for now
+            // Up to date when a dry-run install would leave the config unchanged
+            hooks_up_to_date: has_hooks && matches!(self.install_hooks(params, true), Ok(None)),
         })
     }
 
     fn install_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let settings_path = Self::settings_path();
-
-        // Ensure directory exists
-        if let Some(dir) = settings_path.parent() {
-            fs::create_dir_all(dir)?;
-        }
+        let settings_path = Self::settings_path(&params.scope);
 
         // Read existing content as string
         let existing_content = if settings_path.exists() {
@@ -265,18 +276,27 @@ impl HookInstaller for ClaudeCodeInstaller {
 
         // Write if not dry-run
         if !dry_run {
+            ensure_parent_dir(&settings_path)?;
             write_atomic(&settings_path, new_content.as_bytes())?;
         }
 
         Ok(Some(diff_output))
     }
 
+    fn verify_hooks(&self, params: &HookInstallerParams) -> Option<HookVerification> {
+        Some(verify_hook_config(
+            &Self::settings_path(&params.scope),
+            "git-ai",
+            CLAUDE_PRE_TOOL_CMD,
+        ))
+    }
+
     fn uninstall_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let settings_path = Self::settings_path();
+        let settings_path = Self::settings_path(&params.scope);
 
         if !settings_path.exists() {
             return Ok(None);
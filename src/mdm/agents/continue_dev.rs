@@ -0,0 +1,599 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookDescription, HookInstaller, HookInstallerParams, HookVerification,
+    InstallScope,
+};
+use crate::mdm::utils::{
+    binary_exists, ensure_parent_dir, generate_diff, home_dir, is_git_ai_checkpoint_command,
+    verify_hook_config, write_atomic,
+};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Command pattern for hooks
+const CONTINUE_CHECKPOINT_CMD: &str = "checkpoint continue-cli --hook-input stdin";
+
+const CONTINUE_HOOK_EVENTS: [&str; 2] = ["PreToolUse", "PostToolUse"];
+const CONTINUE_HOOK_MATCHER: &str = "Write|Edit|MultiEdit";
+
+pub struct ContinueInstaller;
+
+impl ContinueInstaller {
+    /// Continue reads `config.yaml`, falling back to the older `config.json`.
+    /// New installs go into `config.yaml`.
+    fn config_path(scope: &InstallScope) -> PathBuf {
+        let dir = scope.base_dir().join(".continue");
+        let yaml_path = dir.join("config.yaml");
+        let json_path = dir.join("config.json");
+        if !yaml_path.exists() && json_path.exists() {
+            json_path
+        } else {
+            yaml_path
+        }
+    }
+
+    fn is_json(path: &Path) -> bool {
+        path.extension().and_then(|e| e.to_str()) == Some("json")
+    }
+
+    fn desired_cmd() -> String {
+        format!("git-ai {}", CONTINUE_CHECKPOINT_CMD)
+    }
+}
+
+impl HookInstaller for ContinueInstaller {
+    fn name(&self) -> &str {
+        "Continue"
+    }
+
+    fn id(&self) -> &str {
+        "continue"
+    }
+
+    fn config_paths(&self, params: &HookInstallerParams) -> Vec<PathBuf> {
+        vec![Self::config_path(&params.scope)]
+    }
+
+    fn describe(&self, params: &HookInstallerParams) -> Option<HookDescription> {
+        Some(HookDescription {
+            command: Self::desired_cmd(),
+            config_path: Self::config_path(&params.scope),
+        })
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let has_binary = binary_exists("cn");
+        let has_dotfiles = home_dir().join(".continue").exists();
+
+        if !has_binary && !has_dotfiles {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let config_path = Self::config_path(&params.scope);
+        if !config_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&config_path)?;
+        let has_hooks = if Self::is_json(&config_path) {
+            let existing: Value = serde_json::from_str(&content).unwrap_or_else(|_| json!({}));
+            json_has_checkpoint_command(&existing)
+        } else {
+            yaml_command_lines(&content)
+                .iter()
+                .any(|(_, cmd)| is_git_ai_checkpoint_command(cmd))
+        };
+
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed: has_hooks,
+            // Up to date when a dry-run install would leave the config unchanged
+            hooks_up_to_date: has_hooks && matches!(self.install_hooks(params, true), Ok(None)),
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path(&params.scope);
+
+        let existing_content = if config_path.exists() {
+            fs::read_to_string(&config_path)?
+        } else {
+            String::new()
+        };
+
+        let desired_cmd = Self::desired_cmd();
+        let new_content = if Self::is_json(&config_path) {
+            let existing: Value = if existing_content.trim().is_empty() {
+                json!({})
+            } else {
+                serde_json::from_str(&existing_content)?
+            };
+            let merged = merge_json_hooks(&existing, &desired_cmd);
+            // Compare JSON values, not strings, so formatting alone isn't a change
+            if existing == merged {
+                return Ok(None);
+            }
+            serde_json::to_string_pretty(&merged)?
+        } else {
+            match merge_yaml_hooks(&existing_content, &desired_cmd)? {
+                Some(content) => content,
+                None => return Ok(None),
+            }
+        };
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+
+        if !dry_run {
+            ensure_parent_dir(&config_path)?;
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn verify_hooks(&self, params: &HookInstallerParams) -> Option<HookVerification> {
+        Some(verify_hook_config(
+            &Self::config_path(&params.scope),
+            "git-ai",
+            CONTINUE_CHECKPOINT_CMD,
+        ))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path(&params.scope);
+
+        if !config_path.exists() {
+            return Ok(None);
+        }
+
+        let existing_content = fs::read_to_string(&config_path)?;
+        let new_content = if Self::is_json(&config_path) {
+            let existing: Value = serde_json::from_str(&existing_content)?;
+            let mut merged = existing.clone();
+            remove_json_hooks(&mut merged);
+            if existing == merged {
+                return Ok(None);
+            }
+            serde_json::to_string_pretty(&merged)?
+        } else {
+            match remove_yaml_hooks(&existing_content)? {
+                Some(content) => content,
+                None => return Ok(None),
+            }
+        };
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+}
+
+fn json_has_checkpoint_command(config: &Value) -> bool {
+    CONTINUE_HOOK_EVENTS.iter().any(|event| {
+        config
+            .get("hooks")
+            .and_then(|h| h.get(*event))
+            .and_then(|v| v.as_array())
+            .map(|blocks| {
+                blocks.iter().any(|block| {
+                    block
+                        .get("hooks")
+                        .and_then(|h| h.as_array())
+                        .map(|hooks| {
+                            hooks.iter().any(|hook| {
+                                hook.get("command")
+                                    .and_then(|c| c.as_str())
+                                    .map(is_git_ai_checkpoint_command)
+                                    .unwrap_or(false)
+                            })
+                        })
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// Ensure each hook event has exactly one up-to-date git-ai command in the
+/// `Write|Edit|MultiEdit` matcher block, leaving other hooks untouched.
+fn merge_json_hooks(existing: &Value, desired_cmd: &str) -> Value {
+    let mut merged = existing.clone();
+    let mut hooks_obj = merged.get("hooks").cloned().unwrap_or_else(|| json!({}));
+
+    for event in CONTINUE_HOOK_EVENTS {
+        let mut blocks = hooks_obj
+            .get(event)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let matcher_idx = match blocks.iter().position(|block| {
+            block.get("matcher").and_then(|m| m.as_str()) == Some(CONTINUE_HOOK_MATCHER)
+        }) {
+            Some(idx) => idx,
+            None => {
+                blocks.push(json!({
+                    "matcher": CONTINUE_HOOK_MATCHER,
+                    "hooks": []
+                }));
+                blocks.len() - 1
+            }
+        };
+
+        let hooks = blocks[matcher_idx]
+            .get("hooks")
+            .and_then(|h| h.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        // Keep the first git-ai command (updated if outdated) and drop any duplicates
+        let mut kept = false;
+        let mut new_hooks = Vec::with_capacity(hooks.len() + 1);
+        for hook in hooks {
+            let is_ours = hook
+                .get("command")
+                .and_then(|c| c.as_str())
+                .map(is_git_ai_checkpoint_command)
+                .unwrap_or(false);
+            if !is_ours {
+                new_hooks.push(hook);
+            } else if !kept {
+                kept = true;
+                if hook.get("command").and_then(|c| c.as_str()) == Some(desired_cmd) {
+                    new_hooks.push(hook);
+                } else {
+                    new_hooks.push(json!({
+                        "type": "command",
+                        "command": desired_cmd
+                    }));
+                }
+            }
+        }
+        if !kept {
+            new_hooks.push(json!({
+                "type": "command",
+                "command": desired_cmd
+            }));
+        }
+
+        if let Some(block) = blocks[matcher_idx].as_object_mut() {
+            block.insert("hooks".to_string(), Value::Array(new_hooks));
+        }
+        if let Some(obj) = hooks_obj.as_object_mut() {
+            obj.insert(event.to_string(), Value::Array(blocks));
+        }
+    }
+
+    if let Some(root) = merged.as_object_mut() {
+        root.insert("hooks".to_string(), hooks_obj);
+    }
+    merged
+}
+
+fn remove_json_hooks(config: &mut Value) {
+    for event in CONTINUE_HOOK_EVENTS {
+        if let Some(blocks) = config
+            .get_mut("hooks")
+            .and_then(|h| h.get_mut(event))
+            .and_then(|v| v.as_array_mut())
+        {
+            for block in blocks.iter_mut() {
+                if let Some(hooks) = block.get_mut("hooks").and_then(|h| h.as_array_mut()) {
+                    hooks.retain(|hook| {
+                        !hook
+                            .get("command")
+                            .and_then(|c| c.as_str())
+                            .map(is_git_ai_checkpoint_command)
+                            .unwrap_or(false)
+                    });
+                }
+            }
+        }
+    }
+}
+
+// There's no YAML parser in the dependency tree, so the YAML config is edited as
+// text: git-ai only ever appends its own top-level `hooks:` block or rewrites the
+// `command:` lines it wrote, which keeps comments and formatting intact.
+
+fn yaml_hooks_block(desired_cmd: &str) -> String {
+    let mut block = String::from("hooks:\n");
+    for event in CONTINUE_HOOK_EVENTS {
+        block.push_str(&format!(
+            "  {}:\n    - matcher: \"{}\"\n      hooks:\n        - type: command\n          command: \"{}\"\n",
+            event, CONTINUE_HOOK_MATCHER, desired_cmd
+        ));
+    }
+    block
+}
+
+/// `command:` lines in a YAML document, as (line index, unquoted value)
+fn yaml_command_lines(content: &str) -> Vec<(usize, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let trimmed = line.trim_start();
+            let trimmed = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+            let value = trimmed.strip_prefix("command:")?.trim();
+            Some((
+                idx,
+                value.trim_matches(|c| c == '"' || c == '\'').to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Line range of the top-level `hooks:` section, if the document has one
+fn yaml_hooks_section(lines: &[&str]) -> Option<(usize, usize)> {
+    let is_top_level = |line: &str| {
+        !line.is_empty() && !line.starts_with(char::is_whitespace) && !line.starts_with('#')
+    };
+    let start = lines
+        .iter()
+        .position(|line| is_top_level(*line) && line.trim_end().starts_with("hooks:"))?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| is_top_level(*line))
+        .map(|offset| start + 1 + offset)
+        .unwrap_or(lines.len());
+    Some((start, end))
+}
+
+fn join_yaml_lines(lines: &[String], trailing_newline: bool) -> String {
+    let mut content = lines.join("\n");
+    if trailing_newline && !content.is_empty() {
+        content.push('\n');
+    }
+    content
+}
+
+fn merge_yaml_hooks(content: &str, desired_cmd: &str) -> Result<Option<String>, GitAiError> {
+    let ours: Vec<(usize, String)> = yaml_command_lines(content)
+        .into_iter()
+        .filter(|(_, cmd)| is_git_ai_checkpoint_command(cmd))
+        .collect();
+
+    if !ours.is_empty() {
+        if ours.iter().all(|(_, cmd)| cmd == desired_cmd) {
+            return Ok(None);
+        }
+        // Rewrite outdated commands in place, keeping indentation and list markers
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        for (idx, cmd) in &ours {
+            if cmd != desired_cmd {
+                let prefix_len = lines[*idx].find("command:").unwrap();
+                lines[*idx] = format!("{}command: \"{}\"", &lines[*idx][..prefix_len], desired_cmd);
+            }
+        }
+        return Ok(Some(join_yaml_lines(&lines, content.ends_with('\n'))));
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    if yaml_hooks_section(&lines).is_some() {
+        return Err(GitAiError::Generic(format!(
+            "Continue config already has a hooks section; add git-ai's hooks to it manually:\n{}",
+            yaml_hooks_block(desired_cmd)
+        )));
+    }
+
+    let mut new_content = content.to_string();
+    if !new_content.is_empty() {
+        if !new_content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        new_content.push('\n');
+    }
+    new_content.push_str(&yaml_hooks_block(desired_cmd));
+    Ok(Some(new_content))
+}
+
+fn remove_yaml_hooks(content: &str) -> Result<Option<String>, GitAiError> {
+    let commands = yaml_command_lines(content);
+    if !commands
+        .iter()
+        .any(|(_, cmd)| is_git_ai_checkpoint_command(cmd))
+    {
+        return Ok(None);
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let section = yaml_hooks_section(&lines);
+    let owns_section = section.is_some_and(|(start, end)| {
+        commands
+            .iter()
+            .filter(|(idx, _)| *idx > start && *idx < end)
+            .all(|(_, cmd)| is_git_ai_checkpoint_command(cmd))
+    });
+
+    // Only remove a hooks section made up entirely of git-ai commands; anything
+    // else was edited by the user and is safer to clean up by hand
+    let (start, end) = match section {
+        Some(range) if owns_section => range,
+        _ => {
+            return Err(GitAiError::Generic(
+                "Continue config has other hooks alongside git-ai's; remove the git-ai commands manually"
+                    .to_string(),
+            ));
+        }
+    };
+
+    let mut kept: Vec<String> = lines[..start].iter().map(|l| l.to_string()).collect();
+    // Drop the blank separator added on install
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+    kept.extend(lines[end..].iter().map(|l| l.to_string()));
+    Ok(Some(join_yaml_lines(&kept, true)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project_params(temp_dir: &TempDir) -> HookInstallerParams {
+        HookInstallerParams {
+            binary_path: PathBuf::from("git-ai"),
+            scope: InstallScope::Project(temp_dir.path().to_path_buf()),
+        }
+    }
+
+    #[test]
+    fn test_continue_config_path_prefers_yaml() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = project_params(&temp_dir);
+        let dir = temp_dir.path().join(".continue");
+
+        assert_eq!(
+            ContinueInstaller::config_path(&params.scope),
+            dir.join("config.yaml")
+        );
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), "{}").unwrap();
+        assert_eq!(
+            ContinueInstaller::config_path(&params.scope),
+            dir.join("config.json")
+        );
+
+        fs::write(dir.join("config.yaml"), "name: test\n").unwrap();
+        assert_eq!(
+            ContinueInstaller::config_path(&params.scope),
+            dir.join("config.yaml")
+        );
+    }
+
+    #[test]
+    fn test_continue_json_install_is_idempotent_and_preserves_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = project_params(&temp_dir);
+        let config_path = temp_dir.path().join(".continue").join("config.json");
+        fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        fs::write(&config_path, r#"{"models": [{"title": "GPT-4"}]}"#).unwrap();
+
+        let installer = ContinueInstaller;
+        assert!(installer.install_hooks(&params, false).unwrap().is_some());
+        assert!(installer.install_hooks(&params, false).unwrap().is_none());
+
+        let config: Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(config["models"][0]["title"], "GPT-4");
+        for event in CONTINUE_HOOK_EVENTS {
+            let blocks = config["hooks"][event].as_array().unwrap();
+            assert_eq!(blocks.len(), 1);
+            assert_eq!(blocks[0]["matcher"], CONTINUE_HOOK_MATCHER);
+            assert_eq!(
+                blocks[0]["hooks"][0]["command"],
+                ContinueInstaller::desired_cmd()
+            );
+        }
+    }
+
+    #[test]
+    fn test_continue_json_updates_outdated_command_and_uninstalls() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = project_params(&temp_dir);
+        let config_path = temp_dir.path().join(".continue").join("config.json");
+        fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        let outdated = json!({
+            "hooks": {
+                "PreToolUse": [{
+                    "matcher": CONTINUE_HOOK_MATCHER,
+                    "hooks": [
+                        {"type": "command", "command": "echo pre"},
+                        {"type": "command", "command": "/old/git-ai checkpoint continue-cli"},
+                        {"type": "command", "command": "git-ai checkpoint continue-cli"}
+                    ]
+                }]
+            }
+        });
+        fs::write(&config_path, outdated.to_string()).unwrap();
+
+        let installer = ContinueInstaller;
+        installer.install_hooks(&params, false).unwrap();
+        let config: Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        let pre_hooks = config["hooks"]["PreToolUse"][0]["hooks"]
+            .as_array()
+            .unwrap();
+        assert_eq!(pre_hooks.len(), 2);
+        assert_eq!(pre_hooks[0]["command"], "echo pre");
+        assert_eq!(pre_hooks[1]["command"], ContinueInstaller::desired_cmd());
+
+        assert!(installer.uninstall_hooks(&params, false).unwrap().is_some());
+        let config: Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert!(!json_has_checkpoint_command(&config));
+        assert_eq!(
+            config["hooks"]["PreToolUse"][0]["hooks"][0]["command"],
+            "echo pre"
+        );
+        assert!(installer.uninstall_hooks(&params, false).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_continue_yaml_install_update_and_uninstall() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = project_params(&temp_dir);
+        let config_path = temp_dir.path().join(".continue").join("config.yaml");
+        fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        let original =
+            "name: My Assistant\nversion: 1.0.0\n# models below\nmodels:\n  - name: gpt-4\n";
+        fs::write(&config_path, original).unwrap();
+
+        let installer = ContinueInstaller;
+        assert!(installer.install_hooks(&params, false).unwrap().is_some());
+        assert!(installer.install_hooks(&params, false).unwrap().is_none());
+
+        let installed = fs::read_to_string(&config_path).unwrap();
+        assert!(installed.starts_with(original));
+        let commands = yaml_command_lines(&installed);
+        assert_eq!(commands.len(), 2);
+        assert!(commands
+            .iter()
+            .all(|(_, cmd)| *cmd == ContinueInstaller::desired_cmd()));
+
+        // An outdated command is rewritten in place
+        fs::write(
+            &config_path,
+            installed.replacen("git-ai checkpoint", "/old/git-ai checkpoint", 1),
+        )
+        .unwrap();
+        assert!(installer.install_hooks(&params, false).unwrap().is_some());
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), installed);
+
+        assert!(installer.uninstall_hooks(&params, false).unwrap().is_some());
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), original);
+        assert!(installer.uninstall_hooks(&params, false).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_continue_yaml_with_user_hooks_section_is_left_alone() {
+        let original = "name: test\nhooks:\n  PostToolUse:\n    - hooks:\n        - type: command\n          command: \"echo done\"\n";
+
+        assert!(merge_yaml_hooks(original, &ContinueInstaller::desired_cmd()).is_err());
+        assert!(remove_yaml_hooks(original).unwrap().is_none());
+    }
+}
@@ -1,9 +1,13 @@
 use crate::error::GitAiError;
-use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams, InstallResult};
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookDescription, HookInstaller, HookInstallerParams, HookVerification,
+    InstallResult, InstallScope,
+};
 use crate::mdm::utils::{
-    binary_exists, generate_diff, get_binary_version, home_dir, install_vsc_editor_extension,
-    is_vsc_editor_extension_installed, parse_version, settings_paths_for_products,
-    should_process_settings_target, version_meets_requirement, write_atomic, MIN_CURSOR_VERSION,
+    binary_exists, ensure_parent_dir, generate_diff, get_binary_version, home_dir,
+    install_vsc_editor_extension, is_vsc_editor_extension_installed, parse_version,
+    settings_paths_for_products, should_process_settings_target, verify_hook_config,
+    version_meets_requirement, write_atomic, MIN_CURSOR_VERSION,
 };
 use crate::utils::debug_log;
 use serde_json::{json, Value};
@@ -17,8 +21,8 @@ const CURSOR_AFTER_EDIT_CMD: &str = "checkpoint cursor --hook-input stdin";
 pub struct CursorInstaller;
 
 impl CursorInstaller {
-    fn hooks_path() -> PathBuf {
-        home_dir().join(".cursor").join("hooks.json")
+    fn hooks_path(scope: &InstallScope) -> PathBuf {
+        scope.base_dir().join(".cursor").join("hooks.json")
     }
 
     fn settings_targets() -> Vec<PathBuf> {
@@ -40,7 +44,18 @@ impl HookInstaller for CursorInstaller {
         "cursor"
     }
 
-    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+    fn config_paths(&self, params: &HookInstallerParams) -> Vec<PathBuf> {
+        vec![Self::hooks_path(&params.scope)]
+    }
+
+    fn describe(&self, params: &HookInstallerParams) -> Option<HookDescription> {
+        Some(HookDescription {
+            command: format!("{} {}", params.hook_binary(), CURSOR_BEFORE_SUBMIT_CMD),
+            config_path: Self::hooks_path(&params.scope),
+        })
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
         let has_binary = binary_exists("cursor");
         let has_dotfiles = home_dir().join(".cursor").exists();
         let has_settings_targets = Self::settings_targets()
@@ -70,7 +85,7 @@ impl HookInstaller for CursorInstaller {
         }
 
         // Check if hooks are installed
-        let hooks_path = Self::hooks_path();
+        let hooks_path = Self::hooks_path(&params.scope);
         if !hooks_path.exists() {
             return Ok(HookCheckResult {
                 tool_installed: true,
@@ -99,7 +114,8 @@ impl HookInstaller for CursorInstaller {
         Ok(HookCheckResult {
             tool_installed: true,
             hooks_installed: has_hooks,
-            hooks_up_to_date: has_hooks,
+            // Up to date when a dry-run install would leave the config unchanged
+            hooks_up_to_date: has_hooks && matches!(self.install_hooks(params, true), Ok(None)),
         })
     }
 
@@ -108,12 +124,7 @@ impl HookInstaller for CursorInstaller {
         params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let hooks_path = Self::hooks_path();
-
-        // Ensure directory exists
-        if let Some(dir) = hooks_path.parent() {
-            fs::create_dir_all(dir)?;
-        }
+        let hooks_path = Self::hooks_path(&params.scope);
 
         // Read existing content as string
         let existing_content = if hooks_path.exists() {
@@ -130,8 +141,8 @@ impl HookInstaller for CursorInstaller {
         };
 
         // Build commands with absolute path
-        let before_submit_cmd = format!("{} {}", params.binary_path.display(), CURSOR_BEFORE_SUBMIT_CMD);
-        let after_edit_cmd = format!("{} {}", params.binary_path.display(), CURSOR_AFTER_EDIT_CMD);
+        let before_submit_cmd = format!("{} {}", params.hook_binary(), CURSOR_BEFORE_SUBMIT_CMD);
+        let after_edit_cmd = format!("{} {}", params.hook_binary(), CURSOR_AFTER_EDIT_CMD);
 
         // Desired hooks payload for Cursor
         let desired: Value = json!({
@@ -240,18 +251,27 @@ impl HookInstaller for CursorInstaller {
 
         // Write if not dry-run
         if !dry_run {
+            ensure_parent_dir(&hooks_path)?;
             write_atomic(&hooks_path, new_content.as_bytes())?;
         }
 
         Ok(Some(diff_output))
     }
 
+    fn verify_hooks(&self, params: &HookInstallerParams) -> Option<HookVerification> {
+        Some(verify_hook_config(
+            &Self::hooks_path(&params.scope),
+            &params.hook_binary(),
+            CURSOR_BEFORE_SUBMIT_CMD,
+        ))
+    }
+
     fn uninstall_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let hooks_path = Self::hooks_path();
+        let hooks_path = Self::hooks_path(&params.scope);
 
         if !hooks_path.exists() {
             return Ok(None);
@@ -318,6 +338,7 @@ impl HookInstaller for CursorInstaller {
                     results.push(InstallResult {
                         changed: false,
                         diff: None,
+                        backup_path: None,
                         message: "Cursor: Extension already installed".to_string(),
                     });
                 }
@@ -326,6 +347,7 @@ impl HookInstaller for CursorInstaller {
                         results.push(InstallResult {
                             changed: true,
                             diff: None,
+                            backup_path: None,
                             message: "Cursor: Pending extension install".to_string(),
                         });
                     } else {
@@ -336,6 +358,7 @@ impl HookInstaller for CursorInstaller {
                                 results.push(InstallResult {
                                     changed: true,
                                     diff: None,
+                                    backup_path: None,
                                     message: "\tExtension 'git-ai.git-ai-vscode' was successfully installed.".to_string(),
                                 });
                             }
@@ -347,6 +370,7 @@ impl HookInstaller for CursorInstaller {
                                 results.push(InstallResult {
                                     changed: false,
                                     diff: None,
+                                    backup_path: None,
                                     message: "Cursor: Unable to automatically install extension. Please cmd+click on the following link to install: cursor:extension/git-ai.git-ai-vscode (or search for 'git-ai-vscode' in the Cursor extensions tab)".to_string(),
                                 });
                             }
@@ -357,6 +381,7 @@ impl HookInstaller for CursorInstaller {
                     results.push(InstallResult {
                         changed: false,
                         diff: None,
+                        backup_path: None,
                         message: format!("Cursor: Failed to check extension: {}", e),
                     });
                 }
@@ -365,6 +390,7 @@ impl HookInstaller for CursorInstaller {
             results.push(InstallResult {
                 changed: false,
                 diff: None,
+                backup_path: None,
                 message: "Cursor: Unable to automatically install extension. Please cmd+click on the following link to install: cursor:extension/git-ai.git-ai-vscode (or search for 'git-ai-vscode' in the Cursor extensions tab)".to_string(),
             });
         }
@@ -372,7 +398,9 @@ impl HookInstaller for CursorInstaller {
         // Configure git.path on Windows
         #[cfg(windows)]
         {
-            use crate::mdm::utils::{git_shim_path_string, update_git_path_setting};
+            use crate::mdm::utils::{
+                find_latest_backup, git_shim_path_string, update_git_path_setting,
+            };
 
             let git_path = git_shim_path_string();
             for settings_path in Self::settings_targets() {
@@ -385,6 +413,11 @@ impl HookInstaller for CursorInstaller {
                         results.push(InstallResult {
                             changed: true,
                             diff: Some(diff),
+                            backup_path: if dry_run {
+                                None
+                            } else {
+                                find_latest_backup(&settings_path)
+                            },
                             message: format!("Cursor: git.path updated in {}", settings_path.display()),
                         });
                     }
@@ -392,6 +425,7 @@ impl HookInstaller for CursorInstaller {
                         results.push(InstallResult {
                             changed: false,
                             diff: None,
+                            backup_path: None,
                             message: format!("Cursor: git.path already configured in {}", settings_path.display()),
                         });
                     }
@@ -399,6 +433,7 @@ impl HookInstaller for CursorInstaller {
                         results.push(InstallResult {
                             changed: false,
                             diff: None,
+                            backup_path: None,
                             message: format!("Cursor: Failed to configure git.path: {}", e),
                         });
                     }
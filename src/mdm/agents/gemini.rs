@@ -1,7 +1,11 @@
 use crate::error::GitAiError;
-use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookDescription, HookInstaller, HookInstallerParams, HookVerification,
+    InstallScope,
+};
 use crate::mdm::utils::{
-    binary_exists, generate_diff, home_dir, is_git_ai_checkpoint_command, write_atomic,
+    binary_exists, ensure_parent_dir, generate_diff, home_dir, is_git_ai_checkpoint_command,
+    verify_hook_config, write_atomic,
 };
 use serde_json::{json, Value};
 use std::fs;
@@ -11,50 +15,45 @@ use std::path::PathBuf;
 const GEMINI_BEFORE_TOOL_CMD: &str = "checkpoint gemini --hook-input stdin";
 const GEMINI_AFTER_TOOL_CMD: &str = "checkpoint gemini --hook-input stdin";
 
-pub struct GeminiInstaller;
-
-impl GeminiInstaller {
-    fn settings_path() -> PathBuf {
-        home_dir().join(".gemini").join("settings.json")
-    }
+/// Where `settings.json` keeps its hooks; newer Gemini CLI releases moved them under
+/// `agentHooks`, which carries its own `enabled` flag instead of `tools.enableHooks`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GeminiLayout {
+    Legacy,
+    AgentHooks,
 }
 
-impl HookInstaller for GeminiInstaller {
-    fn name(&self) -> &str {
-        "Gemini"
-    }
+impl GeminiLayout {
+    const ALL: [GeminiLayout; 2] = [GeminiLayout::AgentHooks, GeminiLayout::Legacy];
 
-    fn id(&self) -> &str {
-        "gemini"
+    fn hooks_key(self) -> &'static str {
+        match self {
+            GeminiLayout::Legacy => "hooks",
+            GeminiLayout::AgentHooks => "agentHooks",
+        }
     }
 
-    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
-        let has_binary = binary_exists("gemini");
-        let has_dotfiles = home_dir().join(".gemini").exists();
-
-        if !has_binary && !has_dotfiles {
-            return Ok(HookCheckResult {
-                tool_installed: false,
-                hooks_installed: false,
-                hooks_up_to_date: false,
-            });
+    /// Layout to install into: agent hooks when the settings already use them
+    fn detect(settings: &Value) -> Self {
+        if settings.get(GeminiLayout::AgentHooks.hooks_key()).is_some() {
+            GeminiLayout::AgentHooks
+        } else {
+            GeminiLayout::Legacy
         }
+    }
+}
 
-        // Check if hooks are installed
-        let settings_path = Self::settings_path();
-        if !settings_path.exists() {
-            return Ok(HookCheckResult {
-                tool_installed: true,
-                hooks_installed: false,
-                hooks_up_to_date: false,
-            });
-        }
+pub struct GeminiInstaller;
 
-        let content = fs::read_to_string(&settings_path)?;
-        let existing: Value = serde_json::from_str(&content).unwrap_or_else(|_| json!({}));
+impl GeminiInstaller {
+    fn settings_path(scope: &InstallScope) -> PathBuf {
+        scope.base_dir().join(".gemini").join("settings.json")
+    }
 
-        let has_hooks = existing
-            .get("hooks")
+    /// Whether a git-ai checkpoint runs before tools in the given layout
+    fn has_git_ai_hooks(settings: &Value, layout: GeminiLayout) -> bool {
+        settings
+            .get(layout.hooks_key())
             .and_then(|h| h.get("BeforeTool"))
             .and_then(|v| v.as_array())
             .map(|arr| {
@@ -72,26 +71,77 @@ impl HookInstaller for GeminiInstaller {
                         .unwrap_or(false)
                 })
             })
-            .unwrap_or(false);
+            .unwrap_or(false)
+    }
+
+    /// Hook state of the settings file, once the Gemini CLI is known to be installed
+    fn check_settings(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let settings_path = Self::settings_path(&params.scope);
+        if !settings_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&settings_path)?;
+        let existing: Value = serde_json::from_str(&content).unwrap_or_else(|_| json!({}));
+
+        let has_hooks = GeminiLayout::ALL
+            .iter()
+            .any(|layout| Self::has_git_ai_hooks(&existing, *layout));
 
         Ok(HookCheckResult {
             tool_installed: true,
             hooks_installed: has_hooks,
-            hooks_up_to_date: has_hooks,
+            // Up to date when a dry-run install would leave the config unchanged
+            hooks_up_to_date: has_hooks && matches!(self.install_hooks(params, true), Ok(None)),
         })
     }
+}
+
+impl HookInstaller for GeminiInstaller {
+    fn name(&self) -> &str {
+        "Gemini"
+    }
+
+    fn id(&self) -> &str {
+        "gemini"
+    }
+
+    fn config_paths(&self, params: &HookInstallerParams) -> Vec<PathBuf> {
+        vec![Self::settings_path(&params.scope)]
+    }
+
+    fn describe(&self, params: &HookInstallerParams) -> Option<HookDescription> {
+        Some(HookDescription {
+            command: format!("git-ai {}", GEMINI_BEFORE_TOOL_CMD),
+            config_path: Self::settings_path(&params.scope),
+        })
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let has_binary = binary_exists("gemini");
+        let has_dotfiles = home_dir().join(".gemini").exists();
+
+        if !has_binary && !has_dotfiles {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        self.check_settings(params)
+    }
 
     fn install_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let settings_path = Self::settings_path();
-
-        // Ensure directory exists
-        if let Some(dir) = settings_path.parent() {
-            fs::create_dir_all(dir)?;
-        }
+        let settings_path = Self::settings_path(&params.scope);
 
         // Read existing content as string
         let existing_content = if settings_path.exists() {
@@ -125,17 +175,30 @@ impl HookInstaller for GeminiInstaller {
         // Merge desired into existing
         let mut merged = existing.clone();
 
-        // Ensure tools.enableHooks is set to true
-        if let Some(tools_obj) = merged.get_mut("tools").and_then(|t| t.as_object_mut()) {
-            if tools_obj.get("enableHooks") != Some(&json!(true)) {
-                tools_obj.insert("enableHooks".to_string(), json!(true));
+        let layout = GeminiLayout::detect(&existing);
+        let mut hooks_obj = merged
+            .get(layout.hooks_key())
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        // Ensure hooks are enabled: tools.enableHooks, or agentHooks.enabled
+        match layout {
+            GeminiLayout::Legacy => {
+                if let Some(tools_obj) = merged.get_mut("tools").and_then(|t| t.as_object_mut()) {
+                    if tools_obj.get("enableHooks") != Some(&json!(true)) {
+                        tools_obj.insert("enableHooks".to_string(), json!(true));
+                    }
+                } else if let Some(root) = merged.as_object_mut() {
+                    root.insert("tools".to_string(), json!({ "enableHooks": true }));
+                }
+            }
+            GeminiLayout::AgentHooks => {
+                if let Some(agent_hooks) = hooks_obj.as_object_mut() {
+                    agent_hooks.insert("enabled".to_string(), json!(true));
+                }
             }
-        } else if let Some(root) = merged.as_object_mut() {
-            root.insert("tools".to_string(), json!({ "enableHooks": true }));
         }
 
-        let mut hooks_obj = merged.get("hooks").cloned().unwrap_or_else(|| json!({}));
-
         // Process both BeforeTool and AfterTool
         for hook_type in &["BeforeTool", "AfterTool"] {
             let desired_matcher = desired_hooks[hook_type]["matcher"].as_str().unwrap();
@@ -241,7 +304,7 @@ impl HookInstaller for GeminiInstaller {
 
         // Write back hooks to merged
         if let Some(root) = merged.as_object_mut() {
-            root.insert("hooks".to_string(), hooks_obj);
+            root.insert(layout.hooks_key().to_string(), hooks_obj);
         }
 
         // Check if there are semantic changes (compare JSON values, not strings)
@@ -257,18 +320,27 @@ impl HookInstaller for GeminiInstaller {
 
         // Write if not dry-run
         if !dry_run {
+            ensure_parent_dir(&settings_path)?;
             write_atomic(&settings_path, new_content.as_bytes())?;
         }
 
         Ok(Some(diff_output))
     }
 
+    fn verify_hooks(&self, params: &HookInstallerParams) -> Option<HookVerification> {
+        Some(verify_hook_config(
+            &Self::settings_path(&params.scope),
+            "git-ai",
+            GEMINI_BEFORE_TOOL_CMD,
+        ))
+    }
+
     fn uninstall_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let settings_path = Self::settings_path();
+        let settings_path = Self::settings_path(&params.scope);
 
         if !settings_path.exists() {
             return Ok(None);
@@ -278,28 +350,34 @@ impl HookInstaller for GeminiInstaller {
         let existing: Value = serde_json::from_str(&existing_content)?;
 
         let mut merged = existing.clone();
-        let mut hooks_obj = match merged.get("hooks").cloned() {
-            Some(h) => h,
-            None => return Ok(None),
-        };
-
         let mut changed = false;
 
-        // Remove git-ai checkpoint commands from both BeforeTool and AfterTool
-        for hook_type in &["BeforeTool", "AfterTool"] {
-            if let Some(hook_type_array) = hooks_obj.get_mut(*hook_type).and_then(|v| v.as_array_mut()) {
-                for matcher_block in hook_type_array.iter_mut() {
-                    if let Some(hooks_array) = matcher_block.get_mut("hooks").and_then(|h| h.as_array_mut()) {
-                        let original_len = hooks_array.len();
-                        hooks_array.retain(|hook| {
-                            if let Some(cmd) = hook.get("command").and_then(|c| c.as_str()) {
-                                !is_git_ai_checkpoint_command(cmd)
-                            } else {
-                                true
+        // Remove git-ai checkpoint commands from both layouts, as either may be in use
+        for layout in GeminiLayout::ALL {
+            let Some(hooks_obj) = merged.get_mut(layout.hooks_key()) else {
+                continue;
+            };
+
+            for hook_type in &["BeforeTool", "AfterTool"] {
+                if let Some(hook_type_array) =
+                    hooks_obj.get_mut(*hook_type).and_then(|v| v.as_array_mut())
+                {
+                    for matcher_block in hook_type_array.iter_mut() {
+                        if let Some(hooks_array) = matcher_block
+                            .get_mut("hooks")
+                            .and_then(|h| h.as_array_mut())
+                        {
+                            let original_len = hooks_array.len();
+                            hooks_array.retain(|hook| {
+                                if let Some(cmd) = hook.get("command").and_then(|c| c.as_str()) {
+                                    !is_git_ai_checkpoint_command(cmd)
+                                } else {
+                                    true
+                                }
+                            });
+                            if hooks_array.len() != original_len {
+                                changed = true;
                             }
-                        });
-                        if hooks_array.len() != original_len {
-                            changed = true;
                         }
                     }
                 }
@@ -310,11 +388,6 @@ impl HookInstaller for GeminiInstaller {
             return Ok(None);
         }
 
-        // Write back hooks to merged
-        if let Some(root) = merged.as_object_mut() {
-            root.insert("hooks".to_string(), hooks_obj);
-        }
-
         let new_content = serde_json::to_string_pretty(&merged)?;
         let diff_output = generate_diff(&settings_path, &existing_content, &new_content);
 
@@ -338,6 +411,27 @@ mod tests {
         (temp_dir, settings_path)
     }
 
+    const LEGACY_SETTINGS: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/gemini-settings-legacy.json"
+    ));
+    const AGENT_HOOKS_SETTINGS: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/gemini-settings-agent-hooks.json"
+    ));
+
+    fn project_params(temp_dir: &TempDir) -> HookInstallerParams {
+        HookInstallerParams {
+            binary_path: PathBuf::from("git-ai"),
+            scope: InstallScope::Project(temp_dir.path().to_path_buf()),
+        }
+    }
+
+    fn write_settings(settings_path: &PathBuf, content: &str) {
+        fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+        fs::write(settings_path, content).unwrap();
+    }
+
     #[test]
     fn test_gemini_install_hooks_creates_file_from_scratch() {
         let (_temp_dir, settings_path) = setup_test_env();
@@ -522,4 +616,92 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_gemini_check_recognizes_both_layouts() {
+        for fixture in [LEGACY_SETTINGS, AGENT_HOOKS_SETTINGS] {
+            let (temp_dir, settings_path) = setup_test_env();
+            write_settings(&settings_path, fixture);
+
+            let result = GeminiInstaller
+                .check_settings(&project_params(&temp_dir))
+                .unwrap();
+            assert!(result.hooks_installed);
+            assert!(result.hooks_up_to_date);
+        }
+    }
+
+    #[test]
+    fn test_gemini_check_reports_missing_hooks() {
+        let (temp_dir, settings_path) = setup_test_env();
+        write_settings(&settings_path, r#"{"agentHooks": {"enabled": true}}"#);
+
+        let result = GeminiInstaller
+            .check_settings(&project_params(&temp_dir))
+            .unwrap();
+        assert!(result.tool_installed);
+        assert!(!result.hooks_installed);
+        assert!(!result.hooks_up_to_date);
+    }
+
+    #[test]
+    fn test_gemini_install_prefers_agent_hooks_layout() {
+        let (temp_dir, settings_path) = setup_test_env();
+        let params = project_params(&temp_dir);
+        write_settings(
+            &settings_path,
+            r#"{"tools": {"enableHooks": true}, "agentHooks": {}}"#,
+        );
+
+        let diff = GeminiInstaller.install_hooks(&params, false).unwrap();
+        assert!(diff.is_some());
+
+        let content: Value =
+            serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert_eq!(content["agentHooks"]["enabled"], json!(true));
+        let layout = GeminiLayout::AgentHooks;
+        assert!(GeminiInstaller::has_git_ai_hooks(&content, layout));
+        assert!(content.get("hooks").is_none());
+        let result = GeminiInstaller.check_settings(&params).unwrap();
+        assert!(result.hooks_up_to_date);
+
+        // Without agent hooks the legacy layout is used
+        let (temp_dir, settings_path) = setup_test_env();
+        write_settings(&settings_path, "{}");
+        GeminiInstaller
+            .install_hooks(&project_params(&temp_dir), false)
+            .unwrap();
+
+        let content: Value =
+            serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert_eq!(content["tools"]["enableHooks"], json!(true));
+        let layout = GeminiLayout::Legacy;
+        assert!(GeminiInstaller::has_git_ai_hooks(&content, layout));
+        assert!(content.get("agentHooks").is_none());
+    }
+
+    #[test]
+    fn test_gemini_uninstall_removes_hooks_from_both_layouts() {
+        let legacy: Value = serde_json::from_str(LEGACY_SETTINGS).unwrap();
+        let mut settings: Value = serde_json::from_str(AGENT_HOOKS_SETTINGS).unwrap();
+        settings["hooks"] = legacy["hooks"].clone();
+
+        let (temp_dir, settings_path) = setup_test_env();
+        let params = project_params(&temp_dir);
+        write_settings(&settings_path, &settings.to_string());
+
+        let diff = GeminiInstaller.uninstall_hooks(&params, false).unwrap();
+        assert!(diff.is_some());
+
+        let content: Value =
+            serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        for layout in GeminiLayout::ALL {
+            assert!(!GeminiInstaller::has_git_ai_hooks(&content, layout));
+            let after_tool = &content[layout.hooks_key()]["AfterTool"][0]["hooks"];
+            assert_eq!(after_tool.as_array().unwrap().len(), 1);
+        }
+        assert_eq!(content["theme"], json!("GitHub"));
+        let result = GeminiInstaller.check_settings(&params).unwrap();
+        assert!(!result.hooks_installed);
+    }
 }
@@ -1,16 +1,23 @@
 mod claude_code;
+mod continue_dev;
 mod cursor;
 mod gemini;
 mod opencode;
 mod vscode;
+mod windsurf;
 
 pub use claude_code::ClaudeCodeInstaller;
+pub use continue_dev::ContinueInstaller;
 pub use cursor::CursorInstaller;
 pub use gemini::GeminiInstaller;
 pub use opencode::OpenCodeInstaller;
 pub use vscode::VSCodeInstaller;
+pub use windsurf::WindsurfInstaller;
 
-use super::hook_installer::HookInstaller;
+use super::hook_installer::{
+    HookCheckResult, HookInstaller, HookInstallerParams, InstallScope, UninstallResult,
+};
+use crate::error::GitAiError;
 
 /// Get all available hook installers
 pub fn get_all_installers() -> Vec<Box<dyn HookInstaller>> {
@@ -20,5 +27,256 @@ pub fn get_all_installers() -> Vec<Box<dyn HookInstaller>> {
         Box::new(VSCodeInstaller),
         Box::new(OpenCodeInstaller),
         Box::new(GeminiInstaller),
+        Box::new(WindsurfInstaller),
+        Box::new(ContinueInstaller),
     ]
 }
+
+/// Check hook status for every installer in each of the given scopes, labeled by
+/// installer name. A failed check is kept as an error so it still shows up in the listing.
+#[allow(dead_code)]
+pub fn hooks_status_all(
+    params: &HookInstallerParams,
+    scopes: &[InstallScope],
+) -> Vec<(String, InstallScope, Result<HookCheckResult, GitAiError>)> {
+    check_installers(&get_all_installers(), params, scopes)
+}
+
+fn check_installers(
+    installers: &[Box<dyn HookInstaller>],
+    params: &HookInstallerParams,
+    scopes: &[InstallScope],
+) -> Vec<(String, InstallScope, Result<HookCheckResult, GitAiError>)> {
+    let mut statuses = Vec::new();
+    for installer in installers {
+        for scope in scopes {
+            let scoped_params = HookInstallerParams {
+                scope: scope.clone(),
+                ..params.clone()
+            };
+            statuses.push((
+                installer.name().to_string(),
+                scope.clone(),
+                installer.check_hooks(&scoped_params),
+            ));
+        }
+    }
+    statuses
+}
+
+/// Outcome of uninstalling hooks from every installer
+#[allow(dead_code)]
+pub struct UninstallAllReport {
+    /// Results from installers whose hooks were removed (or would be, in dry-run mode)
+    pub results: Vec<UninstallResult>,
+    /// Installers that failed to check or uninstall, by name
+    pub errors: Vec<(String, GitAiError)>,
+}
+
+/// Uninstall hooks from every installer that currently has them installed.
+/// One installer failing doesn't stop the others; its error is collected instead.
+#[allow(dead_code)]
+pub fn uninstall_all(params: &HookInstallerParams, dry_run: bool) -> UninstallAllReport {
+    uninstall_installers(&get_all_installers(), params, dry_run)
+}
+
+fn uninstall_installers(
+    installers: &[Box<dyn HookInstaller>],
+    params: &HookInstallerParams,
+    dry_run: bool,
+) -> UninstallAllReport {
+    let mut report = UninstallAllReport {
+        results: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for installer in installers {
+        let name = installer.name();
+
+        match installer.check_hooks(params) {
+            Ok(check_result) if check_result.hooks_installed => {}
+            Ok(_) => continue,
+            Err(e) => {
+                report.errors.push((name.to_string(), e));
+                continue;
+            }
+        }
+
+        match installer.uninstall_hooks(params, dry_run) {
+            Ok(diff) => report.results.push(UninstallResult {
+                changed: diff.is_some(),
+                message: if diff.is_some() {
+                    format!("{}: Hooks removed", name)
+                } else {
+                    format!("{}: No hooks to remove", name)
+                },
+                diff,
+            }),
+            Err(e) => {
+                report.errors.push((name.to_string(), e));
+                continue;
+            }
+        }
+
+        match installer.uninstall_extras(params, dry_run) {
+            Ok(results) => report.results.extend(results),
+            Err(e) => report.errors.push((name.to_string(), e)),
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    struct FakeInstaller {
+        name: &'static str,
+        hooks_installed: bool,
+        fail_uninstall: bool,
+    }
+
+    impl HookInstaller for FakeInstaller {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn id(&self) -> &str {
+            self.name
+        }
+
+        fn check_hooks(
+            &self,
+            _params: &HookInstallerParams,
+        ) -> Result<HookCheckResult, GitAiError> {
+            Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: self.hooks_installed,
+                hooks_up_to_date: self.hooks_installed,
+            })
+        }
+
+        fn install_hooks(
+            &self,
+            _params: &HookInstallerParams,
+            _dry_run: bool,
+        ) -> Result<Option<String>, GitAiError> {
+            Ok(None)
+        }
+
+        fn uninstall_hooks(
+            &self,
+            _params: &HookInstallerParams,
+            _dry_run: bool,
+        ) -> Result<Option<String>, GitAiError> {
+            if self.fail_uninstall {
+                Err(GitAiError::Generic("permission denied".to_string()))
+            } else {
+                Ok(Some(format!("-{} hook", self.name)))
+            }
+        }
+    }
+
+    fn fake(
+        name: &'static str,
+        hooks_installed: bool,
+        fail_uninstall: bool,
+    ) -> Box<dyn HookInstaller> {
+        Box::new(FakeInstaller {
+            name,
+            hooks_installed,
+            fail_uninstall,
+        })
+    }
+
+    fn test_params() -> HookInstallerParams {
+        HookInstallerParams {
+            binary_path: PathBuf::from("/usr/local/bin/git-ai"),
+            scope: InstallScope::Global,
+        }
+    }
+
+    #[test]
+    fn test_check_installers_labels_results_by_name() {
+        let installers = vec![fake("Installed", true, false), fake("NotInstalled", false, false)];
+        let scopes = [
+            InstallScope::Global,
+            InstallScope::Project(PathBuf::from("/work/repo")),
+        ];
+
+        let statuses = check_installers(&installers, &test_params(), &scopes);
+
+        let summary: Vec<(&str, &str, bool)> = statuses
+            .iter()
+            .map(|(name, scope, result)| {
+                (
+                    name.as_str(),
+                    scope.as_str(),
+                    result.as_ref().unwrap().hooks_installed,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Installed", "global", true),
+                ("Installed", "project", true),
+                ("NotInstalled", "global", false),
+                ("NotInstalled", "project", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe_reports_project_hook_commands() {
+        let root = PathBuf::from("/work/repo");
+        let params = HookInstallerParams {
+            scope: InstallScope::Project(root.clone()),
+            ..test_params()
+        };
+
+        for installer in get_all_installers() {
+            let Some(description) = installer.describe(&params) else {
+                // VS Code is hooked up through its extension
+                assert_eq!(installer.id(), "vscode");
+                continue;
+            };
+            assert!(
+                description.command.starts_with("git-ai checkpoint "),
+                "{}: {}",
+                installer.name(),
+                description.command
+            );
+            assert!(
+                description.config_path.starts_with(&root),
+                "{}: {}",
+                installer.name(),
+                description.config_path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_uninstall_installers_skips_missing_and_collects_errors() {
+        let installers = vec![
+            fake("Installed", true, false),
+            fake("NotInstalled", false, false),
+            fake("Broken", true, true),
+            fake("AlsoInstalled", true, false),
+        ];
+
+        let report = uninstall_installers(&installers, &test_params(), false);
+
+        let messages: Vec<&str> = report.results.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["Installed: Hooks removed", "AlsoInstalled: Hooks removed"]
+        );
+        assert!(report.results.iter().all(|r| r.changed));
+
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, "Broken");
+    }
+}
@@ -1,9 +1,15 @@
 use crate::error::GitAiError;
-use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
-use crate::mdm::utils::{binary_exists, generate_diff, home_dir, write_atomic};
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookDescription, HookInstaller, HookInstallerParams, HookVerification,
+    InstallScope,
+};
+use crate::mdm::utils::{binary_exists, generate_diff, home_dir, verify_hook_config, write_atomic};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+// Command the plugin runs for each checkpoint; git-ai is looked up on PATH
+const OPENCODE_CHECKPOINT_CMD: &str = "checkpoint agent-v1";
+
 // OpenCode plugin content (TypeScript), embedded from the source file
 const OPENCODE_PLUGIN_CONTENT: &str = include_str!(concat!(
     env!("CARGO_MANIFEST_DIR"),
@@ -13,12 +19,12 @@ const OPENCODE_PLUGIN_CONTENT: &str = include_str!(concat!(
 pub struct OpenCodeInstaller;
 
 impl OpenCodeInstaller {
-    fn plugin_path() -> PathBuf {
-        home_dir()
-            .join(".config")
-            .join("opencode")
-            .join("plugin")
-            .join("git-ai.ts")
+    fn plugin_path(scope: &InstallScope) -> PathBuf {
+        let plugin_dir = match scope {
+            InstallScope::Global => home_dir().join(".config").join("opencode"),
+            InstallScope::Project(root) => root.join(".opencode"),
+        };
+        plugin_dir.join("plugin").join("git-ai.ts")
     }
 }
 
@@ -31,7 +37,14 @@ impl HookInstaller for OpenCodeInstaller {
         "opencode"
     }
 
-    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+    fn describe(&self, params: &HookInstallerParams) -> Option<HookDescription> {
+        Some(HookDescription {
+            command: format!("git-ai {}", OPENCODE_CHECKPOINT_CMD),
+            config_path: Self::plugin_path(&params.scope),
+        })
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
         let has_binary = binary_exists("opencode");
         let has_global_config = home_dir().join(".config").join("opencode").exists();
         let has_local_config = Path::new(".opencode").exists();
@@ -45,7 +58,7 @@ impl HookInstaller for OpenCodeInstaller {
         }
 
         // Check if plugin is installed
-        let plugin_path = Self::plugin_path();
+        let plugin_path = Self::plugin_path(&params.scope);
         if !plugin_path.exists() {
             return Ok(HookCheckResult {
                 tool_installed: true,
@@ -67,10 +80,10 @@ impl HookInstaller for OpenCodeInstaller {
 
     fn install_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let plugin_path = Self::plugin_path();
+        let plugin_path = Self::plugin_path(&params.scope);
 
         // Ensure directory exists
         if let Some(dir) = plugin_path.parent() {
@@ -108,12 +121,31 @@ impl HookInstaller for OpenCodeInstaller {
         Ok(Some(diff_output))
     }
 
+    fn verify_hooks(&self, params: &HookInstallerParams) -> Option<HookVerification> {
+        let plugin_path = Self::plugin_path(&params.scope);
+        let verification = verify_hook_config(&plugin_path, "git-ai", OPENCODE_CHECKPOINT_CMD);
+        if verification != HookVerification::Verified {
+            return Some(verification);
+        }
+
+        // OpenCode only loads plugins it can parse, so a partial write fails silently
+        let content = fs::read_to_string(&plugin_path).unwrap_or_default();
+        if content.trim() != OPENCODE_PLUGIN_CONTENT.trim() {
+            return Some(HookVerification::Unverified(format!(
+                "{} does not match the bundled plugin",
+                plugin_path.display()
+            )));
+        }
+
+        Some(HookVerification::Verified)
+    }
+
     fn uninstall_hooks(
         &self,
-        _params: &HookInstallerParams,
+        params: &HookInstallerParams,
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError> {
-        let plugin_path = Self::plugin_path();
+        let plugin_path = Self::plugin_path(&params.scope);
 
         if !plugin_path.exists() {
             return Ok(None);
@@ -243,4 +275,26 @@ mod tests {
         let content = fs::read_to_string(&plugin_path).unwrap();
         assert!(content.contains("GitAiPlugin"));
     }
+
+    #[test]
+    fn test_opencode_verify_hooks_reports_outdated_plugin() {
+        let temp_dir = TempDir::new().unwrap();
+        let params = HookInstallerParams {
+            binary_path: PathBuf::from("git-ai"),
+            scope: InstallScope::Project(temp_dir.path().to_path_buf()),
+        };
+        let plugin_path = OpenCodeInstaller::plugin_path(&params.scope);
+
+        assert!(matches!(
+            OpenCodeInstaller.verify_hooks(&params),
+            Some(HookVerification::Unverified(reason)) if reason.contains("could not re-read")
+        ));
+
+        fs::create_dir_all(plugin_path.parent().unwrap()).unwrap();
+        fs::write(&plugin_path, "export const OldPlugin = {}").unwrap();
+        assert!(matches!(
+            OpenCodeInstaller.verify_hooks(&params),
+            Some(HookVerification::Unverified(reason)) if reason.contains("does not contain")
+        ));
+    }
 }
@@ -116,6 +116,7 @@ impl HookInstaller for VSCodeInstaller {
                     results.push(InstallResult {
                         changed: false,
                         diff: None,
+                        backup_path: None,
                         message: "VS Code: Extension already installed".to_string(),
                     });
                 }
@@ -124,6 +125,7 @@ impl HookInstaller for VSCodeInstaller {
                         results.push(InstallResult {
                             changed: true,
                             diff: None,
+                            backup_path: None,
                             message: "VS Code: Pending extension install".to_string(),
                         });
                     } else {
@@ -132,6 +134,7 @@ impl HookInstaller for VSCodeInstaller {
                                 results.push(InstallResult {
                                     changed: true,
                                     diff: None,
+                                    backup_path: None,
                                     message: "VS Code: Extension installed".to_string(),
                                 });
                             }
@@ -143,6 +146,7 @@ impl HookInstaller for VSCodeInstaller {
                                 results.push(InstallResult {
                                     changed: false,
                                     diff: None,
+                                    backup_path: None,
                                     message: "VS Code: Unable to automatically install extension. Please cmd+click on the following link to install: vscode:extension/git-ai.git-ai-vscode (or navigate to https://marketplace.visualstudio.com/items?itemName=git-ai.git-ai-vscode in your browser)".to_string(),
                                 });
                             }
@@ -153,6 +157,7 @@ impl HookInstaller for VSCodeInstaller {
                     results.push(InstallResult {
                         changed: false,
                         diff: None,
+                        backup_path: None,
                         message: format!("VS Code: Failed to check extension: {}", e),
                     });
                 }
@@ -161,6 +166,7 @@ impl HookInstaller for VSCodeInstaller {
             results.push(InstallResult {
                 changed: false,
                 diff: None,
+                backup_path: None,
                 message: "VS Code: Unable to automatically install extension. Please cmd+click on the following link to install: vscode:extension/git-ai.git-ai-vscode (or navigate to https://marketplace.visualstudio.com/items?itemName=git-ai.git-ai-vscode in your browser)".to_string(),
             });
         }
@@ -168,7 +174,9 @@ impl HookInstaller for VSCodeInstaller {
         // Configure git.path on Windows
         #[cfg(windows)]
         {
-            use crate::mdm::utils::{git_shim_path_string, update_git_path_setting};
+            use crate::mdm::utils::{
+                find_latest_backup, git_shim_path_string, update_git_path_setting,
+            };
 
             let git_path = git_shim_path_string();
             for settings_path in Self::settings_targets() {
@@ -181,6 +189,11 @@ impl HookInstaller for VSCodeInstaller {
                         results.push(InstallResult {
                             changed: true,
                             diff: Some(diff),
+                            backup_path: if dry_run {
+                                None
+                            } else {
+                                find_latest_backup(&settings_path)
+                            },
                             message: format!("VS Code: git.path updated in {}", settings_path.display()),
                         });
                     }
@@ -188,6 +201,7 @@ impl HookInstaller for VSCodeInstaller {
                         results.push(InstallResult {
                             changed: false,
                             diff: None,
+                            backup_path: None,
                             message: format!("VS Code: git.path already configured in {}", settings_path.display()),
                         });
                     }
@@ -195,6 +209,7 @@ impl HookInstaller for VSCodeInstaller {
                         results.push(InstallResult {
                             changed: false,
                             diff: None,
+                            backup_path: None,
                             message: format!("VS Code: Failed to configure git.path: {}", e),
                         });
                     }
@@ -0,0 +1,431 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{
+    HookCheckResult, HookDescription, HookInstaller, HookInstallerParams, HookVerification,
+    InstallScope,
+};
+use crate::mdm::utils::{binary_exists, generate_diff, home_dir, verify_hook_config, write_atomic};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Command pattern for hooks
+const WINDSURF_CHECKPOINT_CMD: &str = "checkpoint windsurf --hook-input stdin";
+
+// Cascade hook events git-ai listens to
+const WINDSURF_HOOK_EVENTS: &[&str] = &["pre_write_code", "post_write_code"];
+
+pub struct WindsurfInstaller;
+
+impl WindsurfInstaller {
+    fn config_dir() -> PathBuf {
+        home_dir().join(".codeium").join("windsurf")
+    }
+
+    fn hooks_path(scope: &InstallScope) -> PathBuf {
+        match scope {
+            InstallScope::Global => Self::config_dir().join("hooks.json"),
+            // Workspace hooks live in the repo's .windsurf directory
+            InstallScope::Project(root) => root.join(".windsurf").join("hooks.json"),
+        }
+    }
+
+    fn is_windsurf_checkpoint_command(cmd: &str) -> bool {
+        cmd.contains("git-ai checkpoint windsurf")
+            || (cmd.contains("git-ai") && cmd.contains("checkpoint") && cmd.contains("windsurf"))
+    }
+
+    fn hook_has_checkpoint_command(hook: &Value) -> bool {
+        hook.get("command")
+            .and_then(|c| c.as_str())
+            .map(Self::is_windsurf_checkpoint_command)
+            .unwrap_or(false)
+    }
+
+    /// Whether every hook event has a git-ai command, and whether they all match `desired_cmd`
+    fn hooks_status(config: &Value, desired_cmd: &str) -> (bool, bool) {
+        let mut installed = true;
+        let mut up_to_date = true;
+
+        for event in WINDSURF_HOOK_EVENTS {
+            let command = config
+                .get("hooks")
+                .and_then(|h| h.get(*event))
+                .and_then(|v| v.as_array())
+                .and_then(|arr| {
+                    arr.iter()
+                        .find(|hook| Self::hook_has_checkpoint_command(hook))
+                })
+                .and_then(|hook| hook.get("command"))
+                .and_then(|c| c.as_str());
+
+            match command {
+                Some(cmd) => up_to_date &= cmd == desired_cmd,
+                None => {
+                    installed = false;
+                    up_to_date = false;
+                }
+            }
+        }
+
+        (installed, up_to_date)
+    }
+
+    fn install_hooks_at(
+        hooks_path: &Path,
+        binary_path: &Path,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let existing_content = if hooks_path.exists() {
+            fs::read_to_string(hooks_path)?
+        } else {
+            String::new()
+        };
+
+        let existing: Value = if existing_content.trim().is_empty() {
+            json!({})
+        } else {
+            serde_json::from_str(&existing_content)?
+        };
+
+        let desired_cmd = format!("{} {}", binary_path.display(), WINDSURF_CHECKPOINT_CMD);
+
+        let mut merged = existing.clone();
+        let mut hooks_obj = merged.get("hooks").cloned().unwrap_or_else(|| json!({}));
+
+        for event in WINDSURF_HOOK_EVENTS {
+            let mut event_hooks = hooks_obj
+                .get(*event)
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+
+            // Update an outdated git-ai checkpoint command, or add one if missing
+            match event_hooks
+                .iter()
+                .position(|hook| Self::hook_has_checkpoint_command(hook))
+            {
+                Some(idx) => {
+                    if event_hooks[idx].get("command").and_then(|c| c.as_str())
+                        != Some(desired_cmd.as_str())
+                    {
+                        event_hooks[idx] = json!({ "command": desired_cmd });
+                    }
+                }
+                None => event_hooks.push(json!({ "command": desired_cmd })),
+            }
+
+            if let Some(obj) = hooks_obj.as_object_mut() {
+                obj.insert(event.to_string(), Value::Array(event_hooks));
+            }
+        }
+
+        if let Some(root) = merged.as_object_mut() {
+            root.insert("hooks".to_string(), hooks_obj);
+        }
+
+        // Check if there are semantic changes (compare JSON values, not strings)
+        if existing == merged {
+            return Ok(None);
+        }
+
+        let new_content = serde_json::to_string_pretty(&merged)?;
+        let diff_output = generate_diff(hooks_path, &existing_content, &new_content);
+
+        if !dry_run {
+            if let Some(dir) = hooks_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            write_atomic(hooks_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks_at(hooks_path: &Path, dry_run: bool) -> Result<Option<String>, GitAiError> {
+        if !hooks_path.exists() {
+            return Ok(None);
+        }
+
+        let existing_content = fs::read_to_string(hooks_path)?;
+        let existing: Value = serde_json::from_str(&existing_content)?;
+
+        let mut merged = existing.clone();
+        let mut hooks_obj = match merged.get("hooks").cloned() {
+            Some(h) => h,
+            None => return Ok(None),
+        };
+
+        let mut changed = false;
+
+        for event in WINDSURF_HOOK_EVENTS {
+            if let Some(event_hooks) = hooks_obj.get_mut(*event).and_then(|v| v.as_array_mut()) {
+                let original_len = event_hooks.len();
+                event_hooks.retain(|hook| !Self::hook_has_checkpoint_command(hook));
+                if event_hooks.len() != original_len {
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            return Ok(None);
+        }
+
+        if let Some(root) = merged.as_object_mut() {
+            root.insert("hooks".to_string(), hooks_obj);
+        }
+
+        let new_content = serde_json::to_string_pretty(&merged)?;
+        let diff_output = generate_diff(hooks_path, &existing_content, &new_content);
+
+        if !dry_run {
+            write_atomic(hooks_path, new_content.as_bytes())?;
+        }
+
+        Ok(Some(diff_output))
+    }
+}
+
+impl HookInstaller for WindsurfInstaller {
+    fn name(&self) -> &str {
+        "Windsurf"
+    }
+
+    fn id(&self) -> &str {
+        "windsurf"
+    }
+
+    fn config_paths(&self, params: &HookInstallerParams) -> Vec<PathBuf> {
+        vec![Self::hooks_path(&params.scope)]
+    }
+
+    fn describe(&self, params: &HookInstallerParams) -> Option<HookDescription> {
+        Some(HookDescription {
+            command: format!("{} {}", params.hook_binary(), WINDSURF_CHECKPOINT_CMD),
+            config_path: Self::hooks_path(&params.scope),
+        })
+    }
+
+    fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let has_binary = binary_exists("windsurf");
+        let has_dotfiles = Self::config_dir().exists();
+
+        if !has_binary && !has_dotfiles {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let hooks_path = Self::hooks_path(&params.scope);
+        if !hooks_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: true,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&hooks_path)?;
+        let existing: Value = serde_json::from_str(&content).unwrap_or_else(|_| json!({}));
+        let desired_cmd = format!("{} {}", params.hook_binary(), WINDSURF_CHECKPOINT_CMD);
+        let (hooks_installed, hooks_up_to_date) = Self::hooks_status(&existing, &desired_cmd);
+
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed,
+            hooks_up_to_date,
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Self::install_hooks_at(
+            &Self::hooks_path(&params.scope),
+            Path::new(&params.hook_binary()),
+            dry_run,
+        )
+    }
+
+    fn verify_hooks(&self, params: &HookInstallerParams) -> Option<HookVerification> {
+        Some(verify_hook_config(
+            &Self::hooks_path(&params.scope),
+            &params.hook_binary(),
+            WINDSURF_CHECKPOINT_CMD,
+        ))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        Self::uninstall_hooks_at(&Self::hooks_path(&params.scope), dry_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let hooks_path = temp_dir
+            .path()
+            .join(".codeium")
+            .join("windsurf")
+            .join("hooks.json");
+        (temp_dir, hooks_path)
+    }
+
+    fn create_test_binary_path() -> PathBuf {
+        PathBuf::from("/usr/local/bin/git-ai")
+    }
+
+    fn read_hooks(hooks_path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(hooks_path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_install_hooks_creates_file_from_scratch() {
+        let (_temp_dir, hooks_path) = setup_test_env();
+        let binary_path = create_test_binary_path();
+
+        let diff = WindsurfInstaller::install_hooks_at(&hooks_path, &binary_path, false).unwrap();
+        assert!(diff.is_some());
+
+        let content = read_hooks(&hooks_path);
+        let git_ai_cmd = format!("{} {}", binary_path.display(), WINDSURF_CHECKPOINT_CMD);
+        for event in WINDSURF_HOOK_EVENTS {
+            let hooks = content["hooks"][*event].as_array().unwrap();
+            assert_eq!(hooks.len(), 1);
+            assert_eq!(hooks[0]["command"], json!(git_ai_cmd));
+        }
+
+        assert_eq!(
+            WindsurfInstaller::hooks_status(&content, &git_ai_cmd),
+            (true, true)
+        );
+
+        // Installing again is a no-op
+        let diff = WindsurfInstaller::install_hooks_at(&hooks_path, &binary_path, false).unwrap();
+        assert!(diff.is_none());
+    }
+
+    #[test]
+    fn test_install_hooks_preserves_existing_and_updates_outdated() {
+        let (_temp_dir, hooks_path) = setup_test_env();
+        let binary_path = create_test_binary_path();
+
+        fs::create_dir_all(hooks_path.parent().unwrap()).unwrap();
+        let existing = json!({
+            "hooks": {
+                "pre_write_code": [
+                    { "command": "echo 'before'" },
+                    { "command": "/old/path/git-ai checkpoint windsurf --hook-input stdin" }
+                ],
+                "pre_run_command": [
+                    { "command": "echo 'run'" }
+                ]
+            }
+        });
+        fs::write(
+            &hooks_path,
+            serde_json::to_string_pretty(&existing).unwrap(),
+        )
+        .unwrap();
+
+        let git_ai_cmd = format!("{} {}", binary_path.display(), WINDSURF_CHECKPOINT_CMD);
+        assert_eq!(
+            WindsurfInstaller::hooks_status(&existing, &git_ai_cmd),
+            (false, false)
+        );
+
+        WindsurfInstaller::install_hooks_at(&hooks_path, &binary_path, false).unwrap();
+
+        let content = read_hooks(&hooks_path);
+        let pre_write = content["hooks"]["pre_write_code"].as_array().unwrap();
+        assert_eq!(pre_write.len(), 2);
+        assert_eq!(pre_write[0]["command"], json!("echo 'before'"));
+        assert_eq!(pre_write[1]["command"], json!(git_ai_cmd));
+        assert_eq!(
+            content["hooks"]["post_write_code"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            content["hooks"]["pre_run_command"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_install_hooks_dry_run_does_not_write() {
+        let (_temp_dir, hooks_path) = setup_test_env();
+
+        let diff =
+            WindsurfInstaller::install_hooks_at(&hooks_path, &create_test_binary_path(), true)
+                .unwrap();
+        assert!(diff.is_some());
+        assert!(!hooks_path.exists());
+    }
+
+    #[test]
+    fn test_uninstall_hooks_removes_only_git_ai_commands() {
+        let (_temp_dir, hooks_path) = setup_test_env();
+        let binary_path = create_test_binary_path();
+
+        fs::create_dir_all(hooks_path.parent().unwrap()).unwrap();
+        let existing = json!({
+            "hooks": {
+                "pre_write_code": [{ "command": "echo 'before'" }]
+            }
+        });
+        fs::write(
+            &hooks_path,
+            serde_json::to_string_pretty(&existing).unwrap(),
+        )
+        .unwrap();
+        WindsurfInstaller::install_hooks_at(&hooks_path, &binary_path, false).unwrap();
+
+        let diff = WindsurfInstaller::uninstall_hooks_at(&hooks_path, false).unwrap();
+        assert!(diff.is_some());
+
+        let content = read_hooks(&hooks_path);
+        let pre_write = content["hooks"]["pre_write_code"].as_array().unwrap();
+        assert_eq!(pre_write.len(), 1);
+        assert_eq!(pre_write[0]["command"], json!("echo 'before'"));
+        assert!(content["hooks"]["post_write_code"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+
+        // Nothing left to uninstall
+        let diff = WindsurfInstaller::uninstall_hooks_at(&hooks_path, false).unwrap();
+        assert!(diff.is_none());
+    }
+
+    #[test]
+    fn test_project_scope_uses_workspace_hooks_and_portable_command() {
+        let params = HookInstallerParams {
+            binary_path: create_test_binary_path(),
+            scope: InstallScope::Project(PathBuf::from("/work/repo")),
+        };
+
+        assert_eq!(
+            WindsurfInstaller.config_paths(&params),
+            vec![PathBuf::from("/work/repo/.windsurf/hooks.json")]
+        );
+        assert_eq!(params.hook_binary(), "git-ai");
+    }
+}
@@ -1,11 +1,53 @@
 use crate::error::GitAiError;
+use crate::mdm::utils::home_dir;
 use std::path::PathBuf;
 
+/// Where hook configs are installed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InstallScope {
+    /// The user's own config under their home directory
+    #[default]
+    Global,
+    /// Config inside a repository, meant to be committed alongside the code
+    Project(PathBuf),
+}
+
+impl InstallScope {
+    /// Directory that per-tool config dirs (e.g. `.cursor`) live under
+    pub fn base_dir(&self) -> PathBuf {
+        match self {
+            InstallScope::Global => home_dir(),
+            InstallScope::Project(root) => root.clone(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InstallScope::Global => "global",
+            InstallScope::Project(_) => "project",
+        }
+    }
+}
+
 /// Parameters passed to hook installers
 #[derive(Clone)]
 pub struct HookInstallerParams {
     /// Path to the git-ai binary
     pub binary_path: PathBuf,
+    /// Whether to target the user's config or the repository's
+    pub scope: InstallScope,
+}
+
+impl HookInstallerParams {
+    /// Binary to invoke from hook commands. Project configs are shared between
+    /// machines, so they rely on `git-ai` being on PATH instead of a local path.
+    pub fn hook_binary(&self) -> String {
+        match self.scope {
+            InstallScope::Global => self.binary_path.display().to_string(),
+            InstallScope::Project(_) => "git-ai".to_string(),
+        }
+    }
 }
 
 /// Result of checking hook status
@@ -18,16 +60,43 @@ pub struct HookCheckResult {
     pub hooks_up_to_date: bool,
 }
 
+impl HookCheckResult {
+    /// Hooks are installed but point at an older git-ai invocation
+    pub fn is_outdated(&self) -> bool {
+        self.hooks_installed && !self.hooks_up_to_date
+    }
+}
+
+/// The hook an installer registers, as reported by [`HookInstaller::describe`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookDescription {
+    /// Command line the tool runs for each hook event
+    pub command: String,
+    /// Config file the hook is written to
+    pub config_path: PathBuf,
+}
+
 /// Result of an install operation
 pub struct InstallResult {
     /// Whether changes were made
     pub changed: bool,
     /// Diff output if changes were made
     pub diff: Option<String>,
+    /// Backup of the original config, if one was taken before editing it
+    pub backup_path: Option<PathBuf>,
     /// Human-readable message
     pub message: String,
 }
 
+/// Outcome of the self-test run after hooks are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookVerification {
+    /// The config was re-read, has the hook command, and the command's program is executable
+    Verified,
+    /// The config was written but the hook could not be confirmed, for the given reason
+    Unverified(String),
+}
+
 /// Result of an uninstall operation
 pub struct UninstallResult {
     /// Whether changes were made
@@ -49,6 +118,20 @@ pub trait HookInstaller: Send + Sync {
     /// Check if the tool is installed and hook status
     fn check_hooks(&self, params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError>;
 
+    /// Config files that install_hooks edits for the given scope, backed up by install-hooks
+    /// before hooks are first added to them
+    /// Default implementation has none
+    fn config_paths(&self, _params: &HookInstallerParams) -> Vec<PathBuf> {
+        vec![]
+    }
+
+    /// The hook command install_hooks would register and the config it goes into, without
+    /// touching anything. Default implementation returns None, for tools hooked up some
+    /// other way (e.g. an editor extension)
+    fn describe(&self, _params: &HookInstallerParams) -> Option<HookDescription> {
+        None
+    }
+
     /// Install or update hooks
     /// Returns Ok(Some(diff)) if changes were made, Ok(None) if already up to date
     fn install_hooks(
@@ -57,6 +140,12 @@ pub trait HookInstaller: Send + Sync {
         dry_run: bool,
     ) -> Result<Option<String>, GitAiError>;
 
+    /// Check that hooks written by install_hooks will actually be invoked by the tool
+    /// Default implementation has no self-test and returns None
+    fn verify_hooks(&self, _params: &HookInstallerParams) -> Option<HookVerification> {
+        None
+    }
+
     /// Uninstall hooks
     /// Returns Ok(Some(diff)) if changes were made, Ok(None) if nothing to uninstall
     fn uninstall_hooks(
@@ -4,4 +4,7 @@ pub mod spinner;
 pub mod utils;
 
 pub use agents::get_all_installers;
-pub use hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams, InstallResult, UninstallResult};
+pub use hook_installer::{
+    HookCheckResult, HookInstaller, HookInstallerParams, InstallResult, InstallScope,
+    UninstallResult,
+};
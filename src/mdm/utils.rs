@@ -1,5 +1,7 @@
 use crate::authorship::imara_diff_utils::{compute_line_changes, LineChangeTag};
 use crate::error::GitAiError;
+use crate::mdm::hook_installer::HookVerification;
+use crate::utils::debug_log;
 use jsonc_parser::cst::CstRootNode;
 use jsonc_parser::ParseOptions;
 use std::fs;
@@ -113,6 +115,88 @@ pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), GitAiError> {
     Ok(())
 }
 
+/// Suffix for config backups taken before git-ai edits a file
+pub const BACKUP_SUFFIX: &str = ".git-ai.bak";
+
+/// Backups kept per config file. Taking another one deletes the oldest.
+const MAX_CONFIG_BACKUPS: usize = 5;
+
+/// Copy an existing config file to a timestamped `<name>.<timestamp>.git-ai.bak`
+/// next to it, pruning all but the newest `MAX_CONFIG_BACKUPS` backups. Returns the
+/// backup path, or None if there was nothing to back up.
+pub fn backup_config_file(path: &Path) -> Result<Option<PathBuf>, GitAiError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let file_name = match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => return Ok(None),
+    };
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3f");
+    let backup_path = path.with_file_name(format!("{}.{}{}", file_name, timestamp, BACKUP_SUFFIX));
+
+    fs::copy(path, &backup_path)?;
+
+    let backups = list_backups(path);
+    let excess = backups.len().saturating_sub(MAX_CONFIG_BACKUPS);
+    for old_backup in &backups[..excess] {
+        if let Err(e) = fs::remove_file(old_backup) {
+            debug_log(&format!(
+                "Failed to prune backup {}: {}",
+                old_backup.display(),
+                e
+            ));
+        }
+    }
+
+    Ok(Some(backup_path))
+}
+
+/// Every git-ai backup of a config file, oldest first
+fn list_backups(path: &Path) -> Vec<PathBuf> {
+    let Some(file_name) = path.file_name() else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", file_name.to_string_lossy());
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    // Timestamps are fixed-width, so names sort from oldest to newest
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(&prefix) && name.ends_with(BACKUP_SUFFIX))
+        .collect();
+    names.sort();
+    names.into_iter().map(|name| dir.join(name)).collect()
+}
+
+/// Find the most recent git-ai backup of a config file, if any
+pub fn find_latest_backup(path: &Path) -> Option<PathBuf> {
+    list_backups(path).pop()
+}
+
+/// Restore a config file from its most recent git-ai backup, consuming the backup.
+/// Returns the backup that was (or, in dry-run mode, would be) restored.
+pub fn restore_latest_backup(path: &Path, dry_run: bool) -> Result<Option<PathBuf>, GitAiError> {
+    let backup_path = match find_latest_backup(path) {
+        Some(backup_path) => backup_path,
+        None => return Ok(None),
+    };
+
+    if !dry_run {
+        fs::rename(&backup_path, path)?;
+    }
+
+    Ok(Some(backup_path))
+}
+
 /// Ensure parent directory exists
 pub fn ensure_parent_dir(path: &Path) -> Result<(), GitAiError> {
     if let Some(parent) = path.parent() {
@@ -127,6 +211,64 @@ pub fn is_git_ai_checkpoint_command(cmd: &str) -> bool {
     cmd.contains("git-ai") && cmd.contains("checkpoint")
 }
 
+/// Re-read a hook config after writing it and confirm it will run git-ai: the file must
+/// contain the `<program> <args>` hook command, and `program` must be an executable file
+/// (when it's a path) or found on PATH (when it's a bare name).
+pub fn verify_hook_config(config_path: &Path, program: &str, args: &str) -> HookVerification {
+    let content = match fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            return HookVerification::Unverified(format!(
+                "could not re-read {}: {}",
+                config_path.display(),
+                e
+            ));
+        }
+    };
+
+    // JSON configs escape the command, e.g. backslashes in Windows paths
+    let command = format!("{} {}", program, args);
+    let json_command = serde_json::to_string(&command).unwrap_or_default();
+    if !content.contains(&command) && !content.contains(json_command.trim_matches('"')) {
+        return HookVerification::Unverified(format!(
+            "{} does not contain the hook command `{}`",
+            config_path.display(),
+            command
+        ));
+    }
+
+    let program_path = Path::new(program);
+    if program_path.components().count() > 1 {
+        if !is_executable_file(program_path) {
+            return HookVerification::Unverified(format!("{} is not an executable file", program));
+        }
+    } else if !binary_exists(program) {
+        return HookVerification::Unverified(format!("{} was not found on PATH", program));
+    }
+
+    HookVerification::Verified
+}
+
+/// Whether `path` is a file the current user could execute
+fn is_executable_file(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
 /// Generate a diff between old and new content
 pub fn generate_diff(path: &Path, old_content: &str, new_content: &str) -> String {
     let changes = compute_line_changes(old_content, new_content);
@@ -381,6 +523,7 @@ pub fn update_git_path_setting(
                 fs::create_dir_all(parent)?;
             }
         }
+        backup_config_file(settings_path)?;
         write_atomic(settings_path, new_content.as_bytes())?;
     }
 
@@ -546,4 +689,102 @@ mod tests {
         let final_content = fs::read_to_string(&settings_path).unwrap();
         assert_eq!(final_content, initial);
     }
+
+    #[test]
+    fn test_backup_and_restore_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+
+        // Nothing to back up yet
+        assert!(backup_config_file(&settings_path).unwrap().is_none());
+        assert!(find_latest_backup(&settings_path).is_none());
+
+        fs::write(&settings_path, "{\"first\": true}").unwrap();
+        let first_backup = backup_config_file(&settings_path).unwrap().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        fs::write(&settings_path, "{\"second\": true}").unwrap();
+        let second_backup = backup_config_file(&settings_path).unwrap().unwrap();
+
+        assert_ne!(first_backup, second_backup);
+        assert!(second_backup.to_string_lossy().ends_with(BACKUP_SUFFIX));
+        assert_eq!(
+            find_latest_backup(&settings_path),
+            Some(second_backup.clone())
+        );
+
+        fs::write(&settings_path, "{\"broken\": true}").unwrap();
+
+        // Dry run reports the backup without touching anything
+        let restored = restore_latest_backup(&settings_path, true).unwrap();
+        assert_eq!(restored, Some(second_backup.clone()));
+        assert!(second_backup.exists());
+
+        let restored = restore_latest_backup(&settings_path, false).unwrap();
+        assert_eq!(restored, Some(second_backup.clone()));
+        assert_eq!(
+            fs::read_to_string(&settings_path).unwrap(),
+            "{\"second\": true}"
+        );
+        assert!(!second_backup.exists());
+        assert_eq!(find_latest_backup(&settings_path), Some(first_backup));
+    }
+
+    #[test]
+    fn test_backup_config_file_prunes_old_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings_path = temp_dir.path().join("settings.json");
+        fs::write(&settings_path, "{}").unwrap();
+
+        let mut backups = Vec::new();
+        for _ in 0..MAX_CONFIG_BACKUPS + 2 {
+            backups.push(backup_config_file(&settings_path).unwrap().unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        assert_eq!(list_backups(&settings_path), backups[2..]);
+        assert!(!backups[0].exists());
+        assert!(!backups[1].exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_hook_config() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let binary = temp_dir.path().join("git-ai");
+        fs::write(&binary, "#!/bin/sh\n").unwrap();
+        let program = binary.display().to_string();
+        let hooks_path = temp_dir.path().join("hooks.json");
+        let hooks = serde_json::json!({
+            "hooks": { "afterFileEdit": [{ "command": format!("{} checkpoint cursor", program) }] }
+        });
+        fs::write(&hooks_path, hooks.to_string()).unwrap();
+
+        // Missing config
+        let missing = temp_dir.path().join("missing.json");
+        assert!(matches!(
+            verify_hook_config(&missing, &program, "checkpoint cursor"),
+            HookVerification::Unverified(reason) if reason.contains("could not re-read")
+        ));
+
+        // Config without the hook command
+        assert!(matches!(
+            verify_hook_config(&hooks_path, &program, "checkpoint claude"),
+            HookVerification::Unverified(reason) if reason.contains("does not contain")
+        ));
+
+        // Binary isn't executable yet
+        fs::set_permissions(&binary, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(matches!(
+            verify_hook_config(&hooks_path, &program, "checkpoint cursor"),
+            HookVerification::Unverified(reason) if reason.contains("not an executable file")
+        ));
+
+        fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(
+            verify_hook_config(&hooks_path, &program, "checkpoint cursor"),
+            HookVerification::Verified
+        );
+    }
 }
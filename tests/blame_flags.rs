@@ -164,6 +164,47 @@ fn test_blame_line_range() {
     );
 }
 
+#[test]
+fn test_blame_line_range_forms() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+
+    file.set_contents(lines![
+        "Line 1", "Line 2", "Line 3", "Line 4", "Line 5", "Line 6"
+    ]);
+
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // Relative counts, open ends, reversed ends and ends past the last line all follow git
+    for range in ["2,+2", "4,-2", "5", "5,", ",3", "4,2", "2,9"] {
+        let git_output = repo.git(&["blame", "-L", range, "test.txt"]).unwrap();
+        let git_ai_output = repo.git_ai(&["blame", "-L", range, "test.txt"]).unwrap();
+        assert_eq!(
+            normalize_for_snapshot(&git_output),
+            normalize_for_snapshot(&git_ai_output),
+            "blame -L {} should match git",
+            range
+        );
+    }
+
+    let git_output = repo.git(&["blame", "-L2,+2", "test.txt"]).unwrap();
+    let git_ai_output = repo.git_ai(&["blame", "-L2,+2", "test.txt"]).unwrap();
+    assert_eq!(
+        normalize_for_snapshot(&git_output),
+        normalize_for_snapshot(&git_ai_output)
+    );
+
+    let err = repo
+        .git_ai(&["blame", "-L", "7,+2", "test.txt"])
+        .expect_err("a range starting past the end should fail");
+    assert!(err.contains("has only 6 lines"), "{}", err);
+
+    let err = repo
+        .git_ai(&["blame", "-L", "2,+x", "test.txt"])
+        .expect_err("a malformed range should fail");
+    assert!(err.contains("Invalid line range"), "{}", err);
+}
+
 #[test]
 fn test_blame_porcelain_format() {
     let repo = TestRepo::new();
@@ -1128,6 +1169,7 @@ fn test_blame_ai_human_author() {
             accepted_lines: 1,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
@@ -1149,6 +1191,7 @@ fn test_blame_ai_human_author() {
             accepted_lines: 1,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
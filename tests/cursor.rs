@@ -423,6 +423,67 @@ fn test_cursor_e2e_with_attribution() {
     );
 }
 
+#[test]
+fn test_cursor_e2e_records_tool_version() {
+    use std::fs;
+
+    let repo = TestRepo::new();
+    let db_path = fixture_path("cursor_test.vscdb");
+    let db_path_str = db_path.to_string_lossy().to_string();
+
+    let file_path = repo.path().join("main.rs");
+    fs::write(&file_path, "fn main() {}\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(&file_path, "fn main() {}\nfn from_cursor() {}\n").unwrap();
+    let hook_input = serde_json::json!({
+        "conversation_id": TEST_CONVERSATION_ID,
+        "workspace_roots": [repo.canonical_path().to_string_lossy().to_string()],
+        "hook_event_name": "afterFileEdit",
+        "file_path": file_path.to_string_lossy().to_string(),
+        "model": "model-name-from-hook-test",
+        "cursor_version": "0.45.2"
+    })
+    .to_string();
+    repo.git_ai_with_env(
+        &["checkpoint", "cursor", "--hook-input", &hook_input],
+        &[("GIT_AI_CURSOR_GLOBAL_DB_PATH", &db_path_str)],
+    )
+    .unwrap();
+    let commit = repo.stage_all_and_commit("Add cursor edits").unwrap();
+
+    let prompt_record = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("Should have a prompt record");
+    assert_eq!(prompt_record.tool_version.as_deref(), Some("0.45.2"));
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--show-version"])
+        .expect("git-ai diff --show-version should succeed");
+    assert!(output.contains("cursor@0.45.2"), "Got: {}", output);
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+    assert!(!output.contains("@0.45.2"), "Got: {}", output);
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--summary-json"])
+        .expect("git-ai diff --summary-json should succeed");
+    let json: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    let ai_line = json["lines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|line| line["kind"] == "ai")
+        .expect("Should have an AI line");
+    assert_eq!(ai_line["author"], "cursor");
+    assert_eq!(ai_line["tool_version"], "0.45.2");
+}
+
 #[test]
 fn test_cursor_e2e_with_resync() {
     use rusqlite::Connection;
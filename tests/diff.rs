@@ -313,6 +313,39 @@ fn test_diff_commit_range() {
     );
 }
 
+#[test]
+fn test_diff_two_positional_commits_with_path() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("tracked.txt");
+    file.set_contents(lines!["Line 1".human()]);
+    let mut other = repo.filename("other.txt");
+    other.set_contents(lines!["Other 1".human()]);
+    let first = repo.stage_all_and_commit("First commit").unwrap();
+
+    file.set_contents(lines!["Line 1".human(), "Line 2".ai()]);
+    other.set_contents(lines!["Other 1".human(), "Other 2".human()]);
+    let second = repo.stage_all_and_commit("Second commit").unwrap();
+
+    let joined = format!("{}..{}", first.commit_sha, second.commit_sha);
+    let joined_output = repo
+        .git_ai(&["diff", &joined, "--", "tracked.txt"])
+        .expect("git-ai diff range should succeed");
+    let output = repo
+        .git_ai(&[
+            "diff",
+            &first.commit_sha,
+            &second.commit_sha,
+            "--",
+            "tracked.txt",
+        ])
+        .expect("git-ai diff with two commits should succeed");
+
+    assert_eq!(output, joined_output);
+    assert!(output.contains("+Line 2"), "Should show the added line");
+    assert!(!output.contains("other.txt"), "Should only diff the path");
+}
+
 #[test]
 fn test_diff_shows_ai_attribution() {
     let repo = TestRepo::new();
@@ -615,11 +648,44 @@ fn test_diff_output_format() {
 fn test_diff_error_on_no_args() {
     let repo = TestRepo::new();
 
-    // Try to run diff without arguments
+    // Without arguments diff compares the working tree to HEAD, which doesn't exist yet
     let result = repo.git_ai(&["diff"]);
 
     // Should fail with error
-    assert!(result.is_err(), "git-ai diff without arguments should fail");
+    assert!(
+        result.is_err(),
+        "git-ai diff without arguments should fail when there is no HEAD"
+    );
+}
+
+#[test]
+fn test_diff_working_tree_against_head() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("wip.rs");
+    file.set_contents(lines!["fn committed() {}".human(), "// end".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    // Uncommitted AI change
+    file.set_contents(lines![
+        "fn committed() {}".human(),
+        "fn uncommitted() {}".ai(),
+        "// end".human()
+    ]);
+
+    let output = repo
+        .git_ai(&["diff"])
+        .expect("git-ai diff without arguments should diff the working tree");
+
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(
+        &lines,
+        &[
+            (" ", "fn committed()", None),
+            ("+", "fn uncommitted()", Some("ai")),
+            (" ", "// end", None),
+        ],
+    );
 }
 
 #[test]
@@ -794,3 +860,1006 @@ fn test_diff_range_multiple_commits() {
         "Should have attribution markers"
     );
 }
+
+#[test]
+fn test_diff_html_format() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("page.html");
+    file.set_contents(lines!["<p>old</p>".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines![
+        "<p>old</p>".human(),
+        "<b>\"AI\" & co</b>".ai(),
+        "<i>human</i>".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Add lines").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--format=html"])
+        .expect("git-ai diff --format=html should succeed");
+
+    assert!(output.starts_with("<!DOCTYPE html>"), "{}", output);
+    assert!(output.trim_end().ends_with("</html>"), "{}", output);
+    assert!(output.contains("50% AI"), "Should summarize the AI share");
+    assert!(output.contains("page.html"), "Should name the file");
+    assert!(
+        output.contains("+&lt;b&gt;&quot;AI&quot; &amp; co&lt;/b&gt;"),
+        "Should escape file contents"
+    );
+    assert!(!output.contains("<b>"), "Raw markup leaked into the page");
+    assert!(!output.contains("<link"), "Styles should be inline");
+    assert!(!output.contains("\x1b["), "Should not contain ANSI colors");
+}
+
+#[test]
+fn test_diff_merge_commit_parent_selection() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("base.txt");
+    file.set_contents(lines!["Base line".human()]);
+    repo.stage_all_and_commit("Base").unwrap();
+    let main_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut feature_file = repo.filename("feature.txt");
+    feature_file.set_contents(lines!["Feature line".ai()]);
+    repo.stage_all_and_commit("Feature work").unwrap();
+
+    repo.git(&["checkout", &main_branch]).unwrap();
+    let mut main_file = repo.filename("main_only.txt");
+    main_file.set_contents(lines!["Main only line".human()]);
+    repo.stage_all_and_commit("Main work").unwrap();
+    repo.git(&["merge", "--no-ff", "-m", "Merge feature", "feature"])
+        .unwrap();
+
+    // By default a merge is diffed against its first parent, showing what the merge brought in
+    let output = repo
+        .git_ai(&["diff", "HEAD"])
+        .expect("git-ai diff of a merge should succeed");
+    assert!(output.contains("feature.txt"), "Should include merged file");
+    assert!(!output.contains("main_only.txt"), "{}", output);
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(&lines, &[("+", "Feature line", Some("ai"))]);
+
+    // -m -2 diffs against the second parent instead, like HEAD^2..HEAD
+    let second_parent = repo
+        .git_ai(&["diff", "-m", "-2", "HEAD"])
+        .expect("git-ai diff -m -2 should succeed");
+    let range_output = repo
+        .git_ai(&["diff", "HEAD^2..HEAD"])
+        .expect("git-ai diff range should succeed");
+    assert_eq!(second_parent, range_output);
+    assert!(second_parent.contains("main_only.txt"), "{}", second_parent);
+    assert!(!second_parent.contains("feature.txt"), "{}", second_parent);
+
+    let missing = repo.git_ai(&["diff", "--parent=3", "HEAD"]);
+    assert!(missing.is_err(), "A merge with two parents has no third");
+}
+
+#[test]
+fn test_diff_three_dot_range_uses_merge_base() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("base.txt");
+    file.set_contents(lines!["Base line".human()]);
+    repo.stage_all_and_commit("Base").unwrap();
+    let main_branch = repo.current_branch();
+
+    // Feature branch adds an AI line
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut feature_file = repo.filename("feature.txt");
+    feature_file.set_contents(lines!["Feature line".ai()]);
+    repo.stage_all_and_commit("Feature work").unwrap();
+
+    // Main moves on independently
+    repo.git(&["checkout", &main_branch]).unwrap();
+    let mut main_file = repo.filename("main_only.txt");
+    main_file.set_contents(lines!["Main only line".human()]);
+    repo.stage_all_and_commit("Main work").unwrap();
+
+    let range = format!("{}...feature", main_branch);
+    let output = repo
+        .git_ai(&["diff", &range])
+        .expect("git-ai diff three-dot range should succeed");
+
+    // Only the feature branch changes since the merge-base should appear
+    assert!(
+        output.contains("feature.txt"),
+        "Should include feature file"
+    );
+    assert!(
+        !output.contains("main_only.txt"),
+        "Should not include changes made on main after the merge-base"
+    );
+
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(&lines, &[("+", "Feature line", Some("ai"))]);
+}
+
+#[test]
+fn test_diff_only_ai_filters_human_lines() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("filter.txt");
+    file.set_contents(lines!["Base".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["Base".human(), "AI line".ai(), "Human line".human()]);
+    let commit = repo.stage_all_and_commit("Mixed").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--only-ai"])
+        .expect("git-ai diff --only-ai should succeed");
+
+    assert!(output.contains("@@"), "Should keep hunk headers");
+    let lines = parse_diff_output(&output);
+    assert!(
+        lines
+            .iter()
+            .filter(|l| l.prefix == "+" || l.prefix == "-")
+            .all(|l| matches!(l.attribution.as_deref(), Some(a) if a.starts_with("ai:"))),
+        "Only AI lines should be shown: {:?}",
+        lines
+    );
+    assert!(lines.iter().any(|l| l.content.contains("AI line")));
+    assert!(!lines.iter().any(|l| l.content.contains("Human line")));
+}
+
+#[test]
+fn test_diff_only_human_drops_context_without_matches() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("filter.txt");
+    file.set_contents(lines!["Context".human(), "End".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["Context".human(), "AI only".ai(), "End".human()]);
+    let commit = repo.stage_all_and_commit("AI change").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--only-human"])
+        .expect("git-ai diff --only-human should succeed");
+
+    assert!(output.contains("@@"), "Should keep hunk headers");
+    let lines = parse_diff_output(&output);
+    assert!(
+        lines.is_empty(),
+        "Hunk without human lines should print no body: {:?}",
+        lines
+    );
+}
+
+#[test]
+fn test_diff_rename_keeps_attribution() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("original.rs");
+    file.set_contents(lines![
+        "fn one() {}".human(),
+        "fn two() {}".human(),
+        "fn three() {}".human(),
+        "fn four() {}".human(),
+        "fn five() {}".human()
+    ]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    // Rename the file and have AI append a line
+    repo.git(&["mv", "original.rs", "renamed.rs"]).unwrap();
+    let mut renamed = repo.filename("renamed.rs");
+    renamed.set_contents(lines![
+        "fn one() {}".human(),
+        "fn two() {}".human(),
+        "fn three() {}".human(),
+        "fn four() {}".human(),
+        "fn six() {}".ai(),
+        "fn five() {}".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Rename and extend").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+
+    assert!(
+        output.contains("rename from original.rs"),
+        "Rename should be detected: {}",
+        output
+    );
+
+    // Only the appended line is a change; untouched lines are not re-added
+    let lines = parse_diff_output(&output);
+    let changed: Vec<&DiffLine> = lines.iter().filter(|l| l.prefix != " ").collect();
+    assert_eq!(
+        changed.len(),
+        1,
+        "Only the new line should change: {:?}",
+        lines
+    );
+    assert_diff_line(changed[0], "+", "fn six()", Some("ai"));
+}
+
+#[test]
+fn test_diff_custom_context_lines() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("wide.txt");
+    file.set_contents(lines![
+        "Context 1".human(),
+        "Context 2".human(),
+        "Context 3".human(),
+        "Context 4".human(),
+        "Old line".human(),
+        "Context 5".human(),
+        "Context 6".human(),
+        "Context 7".human(),
+        "Context 8".human()
+    ]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines![
+        "Context 1".human(),
+        "Context 2".human(),
+        "Context 3".human(),
+        "Context 4".human(),
+        "New line".ai(),
+        "Context 5".human(),
+        "Context 6".human(),
+        "Context 7".human(),
+        "Context 8".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Change middle").unwrap();
+
+    let narrow = repo
+        .git_ai(&["diff", &commit.commit_sha, "-U0"])
+        .expect("git-ai diff -U0 should succeed");
+    let narrow_lines = parse_diff_output(&narrow);
+    assert_diff_lines_exact(
+        &narrow_lines,
+        &[("-", "Old line", None), ("+", "New line", Some("ai"))],
+    );
+
+    let wide = repo
+        .git_ai(&["diff", &commit.commit_sha, "--unified=4"])
+        .expect("git-ai diff --unified=4 should succeed");
+    let wide_lines = parse_diff_output(&wide);
+    let context_count = wide_lines.iter().filter(|l| l.prefix == " ").count();
+    assert_eq!(context_count, 8, "Should show 4 context lines on each side");
+    let added = wide_lines.iter().find(|l| l.prefix == "+").unwrap();
+    assert_diff_line(added, "+", "New line", Some("ai"));
+}
+
+#[test]
+fn test_diff_pathspec_filters_files() {
+    let repo = TestRepo::new();
+
+    let mut src_file = repo.filename("src/lib.rs");
+    src_file.set_contents(lines!["fn lib() {}".human(), "// end".human()]);
+    let mut docs_file = repo.filename("docs/readme.md");
+    docs_file.set_contents(lines!["# Docs".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    src_file.set_contents(lines![
+        "fn lib() {}".human(),
+        "fn ai() {}".ai(),
+        "// end".human()
+    ]);
+    docs_file.set_contents(lines!["# Docs".human(), "More docs".human()]);
+    let commit = repo.stage_all_and_commit("Touch both").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--", "src"])
+        .expect("git-ai diff with pathspec should succeed");
+
+    assert!(output.contains("src/lib.rs"), "Should include src file");
+    assert!(!output.contains("docs/readme.md"), "Should exclude docs");
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(
+        &lines,
+        &[
+            (" ", "fn lib()", None),
+            ("+", "fn ai()", Some("ai")),
+            (" ", "// end", None),
+        ],
+    );
+
+    // A pathspec matching nothing prints nothing
+    let empty = repo
+        .git_ai(&["diff", &commit.commit_sha, "--", "does-not-exist"])
+        .expect("git-ai diff with non-matching pathspec should succeed");
+    assert!(
+        empty.trim().is_empty(),
+        "Expected empty output, got: {}",
+        empty
+    );
+}
+
+#[test]
+fn test_diff_relative_shows_paths_under_directory() {
+    let repo = TestRepo::new();
+
+    let mut src_file = repo.filename("src/lib.rs");
+    src_file.set_contents(lines!["fn lib() {}".human(), "// end".human()]);
+    let mut docs_file = repo.filename("docs/readme.md");
+    docs_file.set_contents(lines!["# Docs".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    src_file.set_contents(lines![
+        "fn lib() {}".human(),
+        "fn ai() {}".ai(),
+        "// end".human()
+    ]);
+    docs_file.set_contents(lines!["# Docs".human(), "More docs".human()]);
+    let commit = repo.stage_all_and_commit("Touch both").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--relative=src"])
+        .expect("git-ai diff --relative should succeed");
+
+    assert!(output.contains("+++ b/lib.rs"), "Unexpected: {}", output);
+    assert!(!output.contains("src/lib.rs"), "Unexpected: {}", output);
+    assert!(!output.contains("readme.md"), "Should exclude docs");
+    // Attributions are still found under the repository path
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(
+        &lines,
+        &[
+            (" ", "fn lib()", None),
+            ("+", "fn ai()", Some("ai")),
+            (" ", "// end", None),
+        ],
+    );
+
+    let stat = repo
+        .git_ai(&["diff", &commit.commit_sha, "--relative=src/", "--stat"])
+        .expect("git-ai diff --relative --stat should succeed");
+    assert!(stat.contains(" lib.rs |"), "Unexpected stat: {}", stat);
+    assert!(stat.contains("1 file changed"), "Unexpected stat: {}", stat);
+}
+
+#[test]
+fn test_diff_find_copies_harder_carries_source_attribution() {
+    let repo = TestRepo::new();
+
+    let mut source = repo.filename("source.rs");
+    source.set_contents(lines![
+        "fn one() {}".ai(),
+        "fn two() {}".ai(),
+        "fn three() {}".ai(),
+        "fn four() {}".ai(),
+        "fn five() {}".ai(),
+        "fn six() {}".ai()
+    ]);
+    repo.stage_all_and_commit("AI source").unwrap();
+
+    // A human copies the file, moving one function within the copy
+    let mut copy = repo.filename("copy.rs");
+    copy.set_contents(lines![
+        "fn two() {}".human(),
+        "fn three() {}".human(),
+        "fn one() {}".human(),
+        "fn four() {}".human(),
+        "fn five() {}".human(),
+        "fn six() {}".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Copy source").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--find-copies-harder"])
+        .expect("git-ai diff --find-copies-harder should succeed");
+    assert!(output.contains("copy from source.rs"), "{}", output);
+    let lines = parse_diff_output(&output);
+    let moved = lines
+        .iter()
+        .find(|line| line.prefix == "+" && line.content.contains("fn one()"))
+        .expect("the moved line should be shown as added");
+    assert_diff_line(moved, "+", "fn one()", Some("ai"));
+
+    // Without copy detection the copy is a new file written by the human
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+    let lines = parse_diff_output(&output);
+    let copied = lines
+        .iter()
+        .find(|line| line.content.contains("fn one()"))
+        .expect("the copy should be shown as added");
+    assert_diff_line(copied, "+", "fn one()", Some("human"));
+}
+
+#[test]
+fn test_diff_detect_moves_keeps_attribution_of_moved_block() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines![
+        "fn first() {}".human(),
+        "fn generated_helper(input: u32) -> u32 {".ai(),
+        "    input.wrapping_mul(31)".ai(),
+        "}".ai(),
+        "fn second() {}".human(),
+        "fn third() {}".human(),
+        "fn fourth() {}".human(),
+        "fn fifth() {}".human(),
+        "fn last() {}".human()
+    ]);
+    repo.stage_all_and_commit("AI helper").unwrap();
+
+    // A human moves the AI helper to the end of the file
+    file.set_contents(lines![
+        "fn first() {}".human(),
+        "fn second() {}".human(),
+        "fn third() {}".human(),
+        "fn fourth() {}".human(),
+        "fn fifth() {}".human(),
+        "fn last() {}".human(),
+        "fn generated_helper(input: u32) -> u32 {".human(),
+        "    input.wrapping_mul(31)".human(),
+        "}".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Move helper").unwrap();
+
+    let moved_line = |output: &str| {
+        let lines = parse_diff_output(output);
+        lines
+            .into_iter()
+            .find(|line| line.prefix == "+" && line.content.contains("wrapping_mul"))
+            .expect("the moved line should be shown as added")
+    };
+
+    // Without move detection the mover gets the credit
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+    assert_diff_line(&moved_line(&output), "+", "wrapping_mul", Some("human"));
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--detect-moves"])
+        .expect("git-ai diff --detect-moves should succeed");
+    assert_diff_line(&moved_line(&output), "+", "wrapping_mul", Some("ai"));
+
+    // The moved lines' attribution is marked as carried over
+    let output = repo
+        .git_ai(&[
+            "diff",
+            &commit.commit_sha,
+            "--detect-moves",
+            "--porcelain",
+            "--only-ai",
+        ])
+        .expect("git-ai diff --detect-moves --porcelain should succeed");
+    let provenances: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split('\t').nth(5))
+        .collect();
+    assert_eq!(provenances.len(), 3, "Got: {}", output);
+    assert!(
+        provenances.iter().all(|p| *p == "carried-over-move"),
+        "Got: {}",
+        output
+    );
+
+    let result = repo.git_ai(&["diff", "--detect-moves"]);
+    assert!(result.is_err(), "--detect-moves needs a commit to compare");
+}
+
+#[test]
+fn test_diff_context_attribution_annotates_context_lines() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("context.rs");
+    file.set_contents(lines![
+        "fn generated_one() {}".ai(),
+        "fn generated_two() {}".ai(),
+        "fn original() {}".human()
+    ]);
+    repo.stage_all_and_commit("AI functions").unwrap();
+
+    file.set_contents(lines![
+        "fn generated_one() {}".ai(),
+        "fn generated_two() {}".ai(),
+        "fn edited() {}".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Human edit").unwrap();
+
+    let context_line = |output: &str| {
+        parse_diff_output(output)
+            .into_iter()
+            .find(|line| line.prefix == " " && line.content.contains("generated_two"))
+            .expect("generated_two should be shown as context")
+    };
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+    assert_eq!(context_line(&output).attribution, None);
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--context-attribution"])
+        .expect("git-ai diff --context-attribution should succeed");
+    assert_diff_line(&context_line(&output), " ", "generated_two", Some("ai"));
+    let edited = parse_diff_output(&output)
+        .into_iter()
+        .find(|line| line.prefix == "+")
+        .expect("the edited line should be shown as added");
+    assert_diff_line(&edited, "+", "edited", Some("human"));
+
+    let result = repo.git_ai(&["diff", "--context-attribution"]);
+    assert!(result.is_err(), "--context-attribution needs a commit");
+    let result = repo.git_ai(&[
+        "diff",
+        &commit.commit_sha,
+        "--context-attribution",
+        "--stat",
+    ]);
+    assert!(
+        result.is_err(),
+        "--context-attribution only applies to the terminal diff"
+    );
+}
+
+#[test]
+fn test_diff_no_data_only_lists_unattributed_lines() {
+    let repo = TestRepo::new();
+
+    let mut attributes = repo.filename(".gitai-attributes");
+    attributes.set_contents(lines!["vendor/ attribution=ignore".human()]);
+    repo.stage_all_and_commit("Add attributes").unwrap();
+
+    let mut vendored = repo.filename("vendor/lib.rs");
+    vendored.set_contents(lines!["fn vendored() {}".human()]);
+    let mut source = repo.filename("src/main.rs");
+    source.set_contents(lines!["fn main() {}".ai()]);
+    let commit = repo.stage_all_and_commit("Add code").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--no-data-only"])
+        .expect("git-ai diff --no-data-only should succeed");
+    assert!(
+        output.contains("vendor/lib.rs:1: +fn vendored() {}\n"),
+        "Unexpected: {}",
+        output
+    );
+    assert!(!output.contains("src/main.rs"), "Unexpected: {}", output);
+    assert!(output.contains("1 changed line without authorship data"));
+
+    let result = repo.git_ai(&["diff", &commit.commit_sha, "--no-data-only", "--only-ai"]);
+    assert!(result.is_err(), "Filters should conflict");
+}
+
+#[test]
+fn test_diff_ascii_and_configured_glyphs() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+    let commit = repo.stage_all_and_commit("Add code").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--ascii"])
+        .expect("git-ai diff --ascii should succeed");
+    assert!(output.contains("+fn human() {}  [human:"), "{}", output);
+    assert!(output.contains("+fn ai() {}  [AI:"), "{}", output);
+    assert!(!output.contains(['🤖', '👤']), "{}", output);
+
+    // Glyphs from git config apply by default, and flags override them
+    repo.git(&["config", "git-ai.human-glyph", "H:"]).unwrap();
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--annotate-ai=<{}>"])
+        .expect("git-ai diff with configured glyphs should succeed");
+    assert!(output.contains("+fn human() {}  H:"), "{}", output);
+    assert!(output.contains("+fn ai() {}  <"), "{}", output);
+    assert!(!output.contains(['🤖', '👤']), "{}", output);
+}
+
+#[test]
+fn test_diff_gitai_attributes_ignore_shows_no_data() {
+    let repo = TestRepo::new();
+
+    let mut attributes = repo.filename(".gitai-attributes");
+    attributes.set_contents(lines!["fixtures/ attribution=ignore".human()]);
+    repo.stage_all_and_commit("Add attributes").unwrap();
+
+    let mut fixture = repo.filename("tests/fixtures/input.txt");
+    fixture.set_contents(lines!["fixture line".ai()]);
+    let mut source = repo.filename("src/lib.rs");
+    source.set_contents(lines!["fn ai() {}".ai()]);
+    let commit = repo.stage_all_and_commit("Add fixture and source").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(
+        &lines,
+        &[
+            ("+", "fn ai()", Some("ai")),
+            ("+", "fixture line", Some("no-data")),
+        ],
+    );
+}
+
+#[test]
+fn test_diff_color_always_when_piped() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("color.txt");
+    file.set_contents(lines!["Line 1".human(), "// end".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["Line 1".human(), "Line 2".ai(), "// end".human()]);
+    let commit = repo.stage_all_and_commit("Add line").unwrap();
+
+    let colored = repo
+        .git_ai(&["diff", &commit.commit_sha, "--color=always"])
+        .expect("git-ai diff --color=always should succeed");
+    assert!(
+        colored.contains("\x1b[32m+Line 2"),
+        "Expected green addition"
+    );
+
+    // Output is piped in tests, so auto means no color
+    let plain = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+    assert!(!plain.contains('\x1b'), "Expected no ANSI escapes");
+}
+
+#[test]
+fn test_diff_reverse_swaps_sides_and_keeps_attribution() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("reverse.rs");
+    file.set_contents(lines!["fn old() {}".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["fn new() {}".ai(), "fn another() {}".ai()]);
+    let commit = repo.stage_all_and_commit("AI changes").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--reverse"])
+        .expect("git-ai diff --reverse should succeed");
+
+    // The AI lines are now deletions and still carry their attribution, while the
+    // restored line is attributed from the parent commit
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(
+        &lines,
+        &[
+            ("-", "fn new()", Some("ai")),
+            ("-", "fn another()", Some("ai")),
+            ("+", "fn old()", Some("human")),
+        ],
+    );
+
+    let result = repo.git_ai(&["diff", "-R"]);
+    assert!(result.is_err(), "--reverse needs a commit to diff against");
+}
+
+#[test]
+fn test_diff_porcelain_emits_one_record_per_changed_line() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("porcelain.rs");
+    file.set_contents(lines!["fn old() {}".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["fn new() {}".ai(), "fn another() {}".ai()]);
+    let commit = repo.stage_all_and_commit("AI changes").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--porcelain"])
+        .expect("git-ai diff --porcelain should succeed");
+
+    let records: Vec<Vec<&str>> = output.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(records.len(), 3, "Got: {}", output);
+    assert_eq!(records[0][..4], ["old", "porcelain.rs", "1", "nodata"]);
+    assert_eq!(records[1][..4], ["new", "porcelain.rs", "1", "ai"]);
+    assert_eq!(records[2][..4], ["new", "porcelain.rs", "2", "ai"]);
+    assert!(records.iter().all(|r| r.len() == 6), "Got: {}", output);
+    assert_eq!(records[0][5], "", "Only AI lines have a provenance");
+    assert_eq!(records[1][5], "direct", "Got: {}", output);
+}
+
+#[test]
+fn test_diff_dense_file_attributes_every_line() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("dense.rs");
+    file.set_contents(lines!["fn header() {}".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    // Enough added lines that blame resolves attributions for the whole file at once
+    let mut contents = vec!["fn header() {}".human()];
+    contents.extend((0..250).map(|i| format!("fn generated_{}() {{}}", i).ai()));
+    contents.push("fn footer() {}".human());
+    file.set_contents(contents);
+    let commit = repo.stage_all_and_commit("Generated code").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--porcelain"])
+        .expect("git-ai diff --porcelain should succeed");
+    let kinds: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split('\t').nth(3))
+        .collect();
+    assert_eq!(kinds.len(), 251, "Got: {}", output);
+    assert_eq!(kinds.iter().filter(|kind| **kind == "ai").count(), 250);
+    assert_eq!(kinds[250], "human");
+}
+
+#[test]
+fn test_diff_output_writes_file_without_color() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("artifact.rs");
+    file.set_contents(lines!["fn old() {}".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["fn old() {}".human(), "fn new() {}".ai()]);
+    let commit = repo.stage_all_and_commit("AI change").unwrap();
+
+    let output_path = repo.path().join(".git").join("diff-artifact.txt");
+    let output_arg = output_path.to_str().unwrap();
+
+    let stdout = repo
+        .git_ai(&["diff", &commit.commit_sha, "--output", output_arg])
+        .expect("git-ai diff --output should succeed");
+    assert!(stdout.trim().is_empty(), "Got: {}", stdout);
+    let written = std::fs::read_to_string(&output_path).unwrap();
+    assert!(written.contains("+fn new() {}"), "Got: {}", written);
+    assert!(!written.contains('\x1b'), "Got: {}", written);
+
+    repo.git_ai(&[
+        "diff",
+        &commit.commit_sha,
+        "--output",
+        output_arg,
+        "--color=always",
+    ])
+    .expect("git-ai diff --output --color=always should succeed");
+    let written = std::fs::read_to_string(&output_path).unwrap();
+    assert!(written.contains('\x1b'), "Got: {}", written);
+
+    repo.git_ai(&["diff", &commit.commit_sha, "--json", "--output", output_arg])
+        .expect("git-ai diff --json --output should succeed");
+    let written = std::fs::read_to_string(&output_path).unwrap();
+    let json: serde_json::Value = serde_json::from_str(written.trim()).unwrap();
+    assert!(json.is_object(), "Got: {}", written);
+}
+
+#[test]
+fn test_diff_summary_json_combines_totals_and_lines() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("summary.rs");
+    file.set_contents(lines!["fn old() {}".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines![
+        "fn new() {}".ai(),
+        "fn another() {}".ai(),
+        "fn mine() {}".human()
+    ]);
+    let commit = repo.stage_all_and_commit("Mixed changes").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--summary-json"])
+        .expect("git-ai diff --summary-json should succeed");
+    let json: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+
+    let summary = &json["summary"];
+    assert_eq!(summary["ai_lines"], 2, "Got: {}", output);
+    assert_eq!(summary["human_lines"], 1, "Got: {}", output);
+    assert_eq!(summary["no_data_lines"], 0, "Got: {}", output);
+    let tools = summary["tools"].as_object().unwrap();
+    assert_eq!(tools.values().filter_map(|v| v.as_u64()).sum::<u64>(), 2);
+
+    // Same records as --porcelain: the deleted line, then the three added ones
+    let lines = json["lines"].as_array().expect("Should include lines");
+    assert_eq!(lines.len(), 4, "Got: {}", output);
+    assert_eq!(lines[0]["side"], "old");
+    assert_eq!(lines[1]["side"], "new");
+    assert_eq!(lines[1]["kind"], "ai");
+    assert_eq!(lines[3]["kind"], "human");
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--summary-json", "--no-lines"])
+        .expect("git-ai diff --summary-json --no-lines should succeed");
+    let json: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(json["summary"]["ai_lines"], 2);
+    assert!(json.get("lines").is_none(), "Got: {}", output);
+
+    let result = repo.git_ai(&["diff", &commit.commit_sha, "--no-lines"]);
+    assert!(result.is_err(), "--no-lines needs --summary-json");
+}
+
+#[test]
+fn test_diff_stdin_emits_one_result_per_spec() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("batch.rs");
+    file.set_contents(lines!["fn old() {}".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["fn old() {}".human(), "fn new() {}".ai()]);
+    repo.stage_all_and_commit("AI change").unwrap();
+
+    file.set_contents(lines![
+        "fn old() {}".human(),
+        "fn new() {}".ai(),
+        "fn mine() {}".human()
+    ]);
+    repo.stage_all_and_commit("Human change").unwrap();
+
+    let output = repo
+        .git_ai_with_stdin(
+            &["diff", "--stdin", "--no-lines"],
+            b"HEAD~1\n\nHEAD~2..HEAD\nno-such-commit\nHEAD~2 HEAD~1\n",
+        )
+        .expect("git-ai diff --stdin should succeed");
+
+    let results: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(results.len(), 4, "Blank lines are skipped. Got: {}", output);
+
+    assert_eq!(results[0]["spec"], "HEAD~1");
+    assert_eq!(results[0]["result"]["summary"]["ai_lines"], 1);
+    assert_eq!(results[0]["result"]["summary"]["human_lines"], 0);
+
+    assert_eq!(results[1]["spec"], "HEAD~2..HEAD");
+    assert_eq!(results[1]["result"]["summary"]["ai_lines"], 1);
+    assert_eq!(results[1]["result"]["summary"]["human_lines"], 1);
+
+    // A bad spec is reported without stopping the batch
+    assert_eq!(results[2]["spec"], "no-such-commit");
+    assert!(results[2]["error"].is_string(), "Got: {}", output);
+    assert!(results[2].get("result").is_none());
+
+    assert_eq!(results[3]["result"]["summary"]["ai_lines"], 1);
+
+    let result = repo.git_ai_with_stdin(&["diff", "--stdin", "--stat"], b"HEAD\n");
+    assert!(result.is_err(), "--stdin only supports JSON output");
+}
+
+#[test]
+fn test_diff_word_diff_marks_changed_spans() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("words.rs");
+    file.set_contents(lines!["let value = 1;".human(), "// end".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines!["let value = 2;".ai(), "// end".human()]);
+    let commit = repo.stage_all_and_commit("Tweak value").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha, "--word-diff"])
+        .expect("git-ai diff --word-diff should succeed");
+
+    let changed = output
+        .lines()
+        .find(|l| l.contains("[+"))
+        .expect("Should have an inline addition");
+    assert!(changed.contains("[-1;-]"), "Got: {}", changed);
+    assert!(changed.contains("[+2;+]"), "Got: {}", changed);
+    assert!(
+        changed.contains("🤖"),
+        "Should carry AI attribution: {}",
+        changed
+    );
+    assert!(output.contains("// end"), "Should keep context lines");
+}
+
+#[test]
+fn test_diff_annotates_binary_file_marker() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("README.md");
+    file.set_contents(lines!["# Project".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    std::fs::write(
+        repo.path().join("logo.png"),
+        [0x89, b'P', b'N', b'G', 0, 0, 1, 2],
+    )
+    .expect("failed to write binary file");
+    let commit = repo.stage_all_and_commit("Add logo").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+
+    let marker = output
+        .lines()
+        .find(|l| l.starts_with("Binary files"))
+        .expect("Should have a binary marker");
+    assert!(
+        marker.ends_with("👤Test User"),
+        "Binary marker should carry the commit author: {}",
+        marker
+    );
+}
+
+#[test]
+fn test_diff_keeps_files_differing_only_by_case_apart() {
+    let repo = TestRepo::new();
+
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    // The non-ASCII pair is C-quoted in diff headers, which must resolve to the same keys
+    let mut ai_plain = repo.filename("Notes.md");
+    let mut human_plain = repo.filename("notes.md");
+    let mut ai_quoted = repo.filename("Naïve.md");
+    let mut human_quoted = repo.filename("naïve.md");
+    ai_plain.set_contents(lines!["AI plain".ai()]);
+    human_plain.set_contents(lines!["Human plain".human()]);
+    ai_quoted.set_contents(lines!["AI quoted".ai()]);
+    human_quoted.set_contents(lines!["Human quoted".human()]);
+    let commit = repo.stage_all_and_commit("Add case variants").unwrap();
+
+    let output = repo
+        .git_ai(&["diff", &commit.commit_sha])
+        .expect("git-ai diff should succeed");
+    let lines = parse_diff_output(&output);
+
+    let added = |content: &str| {
+        lines
+            .iter()
+            .find(|l| l.prefix == "+" && l.content.contains(content))
+            .unwrap_or_else(|| panic!("Should have added line {:?}:\n{}", content, output))
+    };
+    for content in ["AI plain", "AI quoted"] {
+        assert_diff_line(added(content), "+", content, Some("ai:"));
+    }
+    for content in ["Human plain", "Human quoted"] {
+        let line = added(content);
+        assert!(
+            line.attribution
+                .as_deref()
+                .is_some_and(|attr| !attr.starts_with("ai:")),
+            "Human file's line should not take the AI file's attribution: {:?}",
+            line
+        );
+    }
+}
+
+#[test]
+fn test_diff_cached_annotates_staged_changes() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("staged.rs");
+    file.set_contents(lines!["fn old_ai() {}".ai(), "// end".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    // Stage an AI line replacing the committed one
+    file.set_contents(lines!["fn staged() {}".ai(), "// end".human()]);
+    repo.git(&["add", "-A"]).unwrap();
+
+    // An unstaged line above it shifts the working tree, but isn't part of the diff
+    file.set_contents(lines![
+        "// unstaged".human(),
+        "fn staged() {}".ai(),
+        "// end".human()
+    ]);
+
+    let output = repo
+        .git_ai(&["diff", "--cached"])
+        .expect("git-ai diff --cached should succeed");
+
+    let lines = parse_diff_output(&output);
+    assert_diff_lines_exact(
+        &lines,
+        &[
+            ("-", "fn old_ai()", Some("ai")),
+            ("+", "fn staged()", Some("ai")),
+            (" ", "// end", None),
+        ],
+    );
+}
@@ -0,0 +1,144 @@
+//! Benchmark comparing serial and parallel attribution of `git-ai diff` lines.
+//!
+//! The equivalence test runs normally. The benchmark is ignored; run it with:
+//! cargo test --test diff_benchmark --release -- --nocapture --ignored
+
+mod repos;
+
+use git_ai::commands::diff::{
+    Attribution, DiffHunk, DiffLineKey, LinePrompts, get_diff_with_line_numbers,
+    overlay_diff_attributions, overlay_diff_attributions_serial,
+};
+use git_ai::error::GitAiError;
+use git_ai::git::repository as GitAiRepository;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// A commit adding `file_count` files, alternating between AI-written and human-written,
+/// on top of an initial commit. Returns the (parent, commit) SHAs.
+fn commit_many_files(repo: &TestRepo, file_count: usize) -> (String, String) {
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Benchmark".human()]);
+    let parent = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    for i in 0..file_count {
+        let mut file = repo.filename(&format!("src/module_{}.rs", i));
+        if i % 2 == 0 {
+            file.set_contents(lines![
+                format!("fn ai_{}() {{", i).ai(),
+                "    todo!()".ai(),
+                "}".ai()
+            ]);
+        } else {
+            file.set_contents(lines![
+                format!("fn human_{}() {{", i).human(),
+                "    todo!()".human(),
+                "}".human()
+            ]);
+        }
+    }
+    let commit = repo.stage_all_and_commit("Add modules").unwrap();
+
+    (parent.commit_sha, commit.commit_sha)
+}
+
+type Overlay = fn(
+    &GitAiRepository::Repository,
+    &str,
+    &str,
+    &[DiffHunk],
+    &mut LinePrompts,
+) -> Result<HashMap<DiffLineKey, Attribution>, GitAiError>;
+
+/// Run an overlay, returning its attributions, prompts and how long it took
+fn run_overlay(
+    overlay: Overlay,
+    repo: &GitAiRepository::Repository,
+    from: &str,
+    to: &str,
+) -> (HashMap<DiffLineKey, Attribution>, LinePrompts, Duration) {
+    let hunks = get_diff_with_line_numbers(repo, from, to, &[], false).unwrap();
+    let mut prompts = LinePrompts::default();
+    let start = Instant::now();
+    let attributions = overlay(repo, from, to, &hunks, &mut prompts).unwrap();
+    (attributions, prompts, start.elapsed())
+}
+
+fn find_repository(repo: &TestRepo) -> GitAiRepository::Repository {
+    GitAiRepository::find_repository_in_path(repo.path().to_str().unwrap())
+        .expect("Failed to find repository")
+}
+
+#[test]
+fn test_parallel_overlay_matches_serial() {
+    let repo = TestRepo::new();
+    let (from, to) = commit_many_files(&repo, 8);
+    let git_ai_repo = find_repository(&repo);
+
+    let (serial, serial_prompts, _) =
+        run_overlay(overlay_diff_attributions_serial, &git_ai_repo, &from, &to);
+    let (parallel, parallel_prompts, _) =
+        run_overlay(overlay_diff_attributions, &git_ai_repo, &from, &to);
+
+    assert_eq!(serial.len(), 8 * 3);
+    assert_eq!(parallel, serial);
+    assert_eq!(parallel_prompts.by_line, serial_prompts.by_line);
+    assert_eq!(parallel_prompts.texts, serial_prompts.texts);
+
+    let ai_lines = serial
+        .values()
+        .filter(|attribution| matches!(attribution, Attribution::Ai(_)))
+        .count();
+    assert_eq!(ai_lines, 4 * 3);
+}
+
+#[test]
+#[ignore] // Run with --ignored flag since this is a benchmark
+fn test_overlay_diff_attributions_benchmark() {
+    const FILE_COUNT: usize = 300;
+    const NUM_ITERATIONS: u32 = 5;
+
+    println!("\n========================================");
+    println!("Diff Attribution Overlay Benchmark");
+    println!("========================================");
+    println!("Files: {}, iterations: {}", FILE_COUNT, NUM_ITERATIONS);
+
+    let repo = TestRepo::new();
+    let (from, to) = commit_many_files(&repo, FILE_COUNT);
+    let git_ai_repo = find_repository(&repo);
+
+    let mut serial_total = Duration::ZERO;
+    let mut parallel_total = Duration::ZERO;
+    for i in 1..=NUM_ITERATIONS {
+        let (serial, _, serial_duration) =
+            run_overlay(overlay_diff_attributions_serial, &git_ai_repo, &from, &to);
+        let (parallel, _, parallel_duration) =
+            run_overlay(overlay_diff_attributions, &git_ai_repo, &from, &to);
+        assert_eq!(parallel, serial, "Parallel overlay should match serial");
+
+        println!(
+            "Iteration {}: serial={:.2}ms, parallel={:.2}ms",
+            i,
+            serial_duration.as_secs_f64() * 1000.0,
+            parallel_duration.as_secs_f64() * 1000.0
+        );
+        serial_total += serial_duration;
+        parallel_total += parallel_duration;
+    }
+
+    let serial_average = serial_total / NUM_ITERATIONS;
+    let parallel_average = parallel_total / NUM_ITERATIONS;
+    println!("\n========================================");
+    println!("BENCHMARK RESULTS");
+    println!("========================================");
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    println!("  Serial:   {:.2}ms", millis(serial_average));
+    println!("  Parallel: {:.2}ms", millis(parallel_average));
+    println!(
+        "  Speedup:  {:.2}x",
+        serial_average.as_secs_f64() / parallel_average.as_secs_f64()
+    );
+    println!("\n========================================\n");
+}
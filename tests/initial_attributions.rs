@@ -64,6 +64,7 @@ fn test_initial_only_no_blame_data() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
@@ -153,6 +154,7 @@ fn test_initial_wins_overlaps() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
@@ -229,6 +231,7 @@ fn test_initial_and_blame_merge() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
     prompts.insert(
@@ -246,6 +249,7 @@ fn test_initial_and_blame_merge() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
@@ -317,6 +321,7 @@ fn test_partial_file_coverage() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
@@ -406,6 +411,7 @@ fn test_initial_attributions_in_subsequent_checkpoint() {
             accepted_lines: 0,
             overriden_lines: 0,
             messages_url: None,
+            tool_version: None,
         },
     );
 
@@ -60,3 +60,47 @@ fn test_change_across_commits() {
     assert_eq!(second_ai_entry.line_ranges, vec![LineRange::Single(6)]);
     assert_ne!(second_ai_entry.hash, initial_ai_entry.hash);
 }
+
+#[test]
+fn test_ai_edit_on_earlier_ai_line_with_human_line_above() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("foo.py");
+
+    file.set_contents(lines!["one".ai(), "two".ai(), "three".ai()]);
+    let commit = repo.stage_all_and_commit("Initial AI commit").unwrap();
+    let first_entry = &commit.authorship_log.attestations[0].entries[0];
+    let first_hash = first_entry.hash.clone();
+
+    // A second prompt rewrites an earlier AI line, then a human inserts a line above it
+    file.replace_at(1, "two.upper()".ai());
+    file.insert_at(1, lines!["human".human()]);
+    let commit = repo.stage_all_and_commit("Second AI commit").unwrap();
+
+    let entries = &commit.authorship_log.attestations[0].entries;
+    assert_eq!(entries.len(), 1, "entries: {:?}", entries);
+    assert_ne!(entries[0].hash, first_hash);
+    assert_eq!(entries[0].line_ranges, vec![LineRange::Single(3)]);
+    let prompts = &commit.authorship_log.metadata.prompts;
+    assert_eq!(prompts.keys().collect::<Vec<_>>(), vec![&entries[0].hash]);
+}
+
+#[test]
+fn test_adjacent_lines_from_different_prompts_stay_separate() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("foo.py");
+
+    file.set_contents(lines!["one".human()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // Each insert is its own AI checkpoint, so the two lines come from different prompts
+    file.insert_at(1, lines!["two".ai()]);
+    file.insert_at(2, lines!["three".ai()]);
+    let commit = repo.stage_all_and_commit("Two prompts").unwrap();
+
+    let mut entries = commit.authorship_log.attestations[0].entries.clone();
+    entries.sort_by(|a, b| a.line_ranges.cmp(&b.line_ranges));
+    assert_eq!(entries.len(), 2, "entries: {:?}", entries);
+    assert_ne!(entries[0].hash, entries[1].hash);
+    assert_eq!(entries[0].line_ranges, vec![LineRange::Single(2)]);
+    assert_eq!(entries[1].line_ranges, vec![LineRange::Single(3)]);
+}
@@ -165,6 +165,188 @@ fn test_stats_cli_range() {
     );
 }
 
+#[test]
+fn test_stats_cli_merge_base() {
+    let repo = TestRepo::new();
+
+    let mut base = repo.filename("base.txt");
+    base.set_contents(lines!["Base".human()]);
+    repo.stage_all_and_commit("Initial human").unwrap();
+    let main_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut feature = repo.filename("feature.txt");
+    feature.set_contents(lines!["Feature 1".ai(), "Feature 2".ai()]);
+    repo.stage_all_and_commit("AI feature").unwrap();
+
+    // Commits landing on main after the branch point aren't part of the feature
+    repo.git(&["checkout", &main_branch]).unwrap();
+    let mut main_only = repo.filename("main.txt");
+    main_only.set_contents(lines!["Main 1".human(), "Main 2".human()]);
+    repo.stage_all_and_commit("Main moves on").unwrap();
+    repo.git(&["checkout", "feature"]).unwrap();
+
+    let raw = repo
+        .git_ai(&["stats", "--merge-base", &main_branch, "--json"])
+        .expect("git-ai stats --merge-base should succeed");
+    let output = extract_json_object(&raw);
+    let stats: git_ai::authorship::range_authorship::RangeAuthorshipStats =
+        serde_json::from_str(&output).unwrap();
+
+    assert_eq!(stats.authorship_stats.total_commits, 1);
+    assert_eq!(stats.range_stats.git_diff_added_lines, 2);
+    assert_eq!(stats.range_stats.git_diff_deleted_lines, 0);
+    assert_eq!(stats.range_stats.ai_additions, 2);
+
+    let result = repo.git_ai(&["stats", "--merge-base", &main_branch, "HEAD~1..HEAD"]);
+    assert!(result.is_err(), "--merge-base should conflict with a range");
+}
+
+#[test]
+fn test_stats_cli_fetch_dry_run() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("history.txt");
+    file.set_contents(lines!["AI Line 1".ai()]);
+    repo.stage_all_and_commit("Initial AI").unwrap();
+    file.set_contents(lines!["AI Line 1".ai(), "Human Line 2".human()]);
+    repo.stage_all_and_commit("Human adds line").unwrap();
+
+    // There's no remote to fetch from, so this only succeeds if nothing is fetched
+    let raw = repo
+        .git_ai(&["stats", "--fetch-dry-run", "HEAD~1..HEAD", "--json"])
+        .expect("git-ai stats --fetch-dry-run should succeed");
+    let branch = repo.current_branch();
+    assert!(
+        raw.contains(&format!("Would fetch refs/heads/{} from origin", branch)),
+        "expected the fetch plan in output: {}",
+        raw
+    );
+
+    let stats: git_ai::authorship::range_authorship::RangeAuthorshipStats =
+        serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    assert_eq!(stats.authorship_stats.total_commits, 1);
+
+    let result = repo.git_ai(&["stats", "--fetch", "HEAD~1..HEAD"]);
+    assert!(result.is_err(), "--fetch should fail without a remote");
+}
+
+#[test]
+fn test_stats_cli_exit_code_without_ai_authorship() {
+    let repo = TestRepo::new();
+
+    // Plain git commits carry no authorship logs
+    std::fs::write(repo.path().join("plain.txt"), "one\n").unwrap();
+    repo.git_og(&["add", "plain.txt"]).unwrap();
+    repo.git_og(&["commit", "-m", "Plain one"]).unwrap();
+    std::fs::write(repo.path().join("plain.txt"), "one\ntwo\n").unwrap();
+    repo.git_og(&["commit", "-am", "Plain two"]).unwrap();
+
+    let raw = repo
+        .git_ai(&["stats", "HEAD~1..HEAD", "--json"])
+        .expect("stats should succeed without authorship logs");
+    let stats: git_ai::authorship::range_authorship::RangeAuthorshipStats =
+        serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    assert!(!stats.has_ai_authorship);
+    assert_eq!(stats.authorship_stats.total_commits, 1);
+
+    let result = repo.git_ai(&["stats", "HEAD~1..HEAD", "--exit-code"]);
+    assert!(result.is_err(), "no authorship should exit non-zero");
+
+    let mut file = repo.filename("ai.txt");
+    file.set_contents(lines!["AI Line 1".ai()]);
+    repo.stage_all_and_commit("AI commit").unwrap();
+
+    let raw = repo
+        .git_ai(&["stats", "HEAD~1..HEAD", "--json", "--exit-code"])
+        .expect("--exit-code should succeed with authorship");
+    let stats: git_ai::authorship::range_authorship::RangeAuthorshipStats =
+        serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    assert!(stats.has_ai_authorship);
+}
+
+#[test]
+fn test_stats_cli_stat_only() {
+    let repo = TestRepo::new();
+
+    let mut file = repo.filename("badge.txt");
+    file.set_contents(lines!["Human Line 1".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    file.set_contents(lines![
+        "Human Line 1".human(),
+        "Human Line 2".human(),
+        "AI Line 1".ai(),
+        "AI Line 2".ai(),
+        "AI Line 3".ai()
+    ]);
+    repo.stage_all_and_commit("Mixed work").unwrap();
+
+    let output = repo
+        .git_ai(&["stats", "HEAD~1..HEAD", "--stat-only"])
+        .expect("stats --stat-only should succeed");
+    assert_eq!(output.trim(), "75%");
+
+    let raw = repo
+        .git_ai(&["stats", "HEAD~1..HEAD", "--stat-only", "--json"])
+        .expect("stats --stat-only --json should succeed");
+    let summary: serde_json::Value = serde_json::from_str(raw.trim()).unwrap();
+    assert_eq!(summary["ai_percent"], 75);
+    assert_eq!(summary["human_percent"], 25);
+    assert_eq!(summary["total_lines"], 4);
+    assert_eq!(summary.as_object().unwrap().len(), 3, "{}", raw);
+
+    let result = repo.git_ai(&["stats", "HEAD", "--stat-only"]);
+    assert!(result.is_err(), "--stat-only needs a range");
+}
+
+#[test]
+fn test_stats_cli_repo() {
+    let repo = TestRepo::new();
+
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["Human Line 1".human(), "Human Line 2".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let mut code = repo.filename("code.rs");
+    code.set_contents(lines!["AI Line 1".ai(), "AI Line 2".ai(), "AI Line 3".ai()]);
+    repo.stage_all_and_commit("AI work").unwrap();
+
+    // Only lines still in the tree count: one AI line is rewritten by a human
+    code.set_contents(lines![
+        "AI Line 1".ai(),
+        "Human rewrite".human(),
+        "AI Line 3".ai()
+    ]);
+    repo.stage_all_and_commit("Human edit").unwrap();
+
+    let raw = repo
+        .git_ai(&["stats", "--repo", "--json"])
+        .expect("stats --repo --json should succeed");
+    let stats: serde_json::Value = serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    assert_eq!(stats["files"], 2, "Got: {}", raw);
+    assert_eq!(stats["total_lines"], 5, "Got: {}", raw);
+    assert_eq!(stats["ai_lines"], 2, "Got: {}", raw);
+    assert_eq!(stats["human_lines"], 3, "Got: {}", raw);
+    let tools = stats["tools"].as_object().unwrap();
+    assert_eq!(tools.values().filter_map(|v| v.as_u64()).sum::<u64>(), 2);
+
+    // An older commit is measured as it was then
+    let raw = repo
+        .git_ai(&["stats", "HEAD~1", "--repo", "--json"])
+        .expect("stats <commit> --repo --json should succeed");
+    let stats: serde_json::Value = serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    assert_eq!(stats["ai_lines"], 3, "Got: {}", raw);
+
+    let output = repo
+        .git_ai(&["stats", "--repo"])
+        .expect("stats --repo should succeed");
+    assert!(output.contains("40.0%"), "Got: {}", output);
+
+    let result = repo.git_ai(&["stats", "HEAD~1..HEAD", "--repo"]);
+    assert!(result.is_err(), "--repo doesn't take a range");
+}
+
 #[test]
 fn test_stats_cli_empty_tree_range() {
     let repo = TestRepo::new();
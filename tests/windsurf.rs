@@ -0,0 +1,59 @@
+use git_ai::authorship::working_log::CheckpointKind;
+use git_ai::commands::checkpoint_agent::agent_presets::{
+    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, WindsurfPreset,
+};
+use git_ai::error::GitAiError;
+use serde_json::json;
+
+fn run_preset(hook_input: serde_json::Value) -> Result<AgentRunResult, GitAiError> {
+    WindsurfPreset.run(AgentCheckpointFlags {
+        hook_input: Some(hook_input.to_string()),
+    })
+}
+
+#[test]
+fn test_windsurf_pre_write_code_is_human_checkpoint() {
+    let result = run_preset(json!({
+        "agent_action_name": "pre_write_code",
+        "trajectory_id": "traj-123",
+        "execution_id": "exec-1",
+        "tool_info": { "file_path": "/work/repo/src/main.rs" }
+    }))
+    .expect("preset should accept pre_write_code");
+
+    assert_eq!(result.checkpoint_kind, CheckpointKind::Human);
+    assert_eq!(result.agent_id.tool, "windsurf");
+    assert_eq!(result.agent_id.id, "traj-123");
+    assert_eq!(
+        result.will_edit_filepaths,
+        Some(vec!["/work/repo/src/main.rs".to_string()])
+    );
+    assert!(result.edited_filepaths.is_none());
+}
+
+#[test]
+fn test_windsurf_post_write_code_is_ai_checkpoint() {
+    let result = run_preset(json!({
+        "agent_action_name": "post_write_code",
+        "trajectory_id": "traj-123",
+        "tool_info": { "file_path": "/work/repo/src/main.rs", "edits": [] }
+    }))
+    .expect("preset should accept post_write_code");
+
+    assert_eq!(result.checkpoint_kind, CheckpointKind::AiAgent);
+    assert_eq!(
+        result.edited_filepaths,
+        Some(vec!["/work/repo/src/main.rs".to_string()])
+    );
+    assert!(result.will_edit_filepaths.is_none());
+}
+
+#[test]
+fn test_windsurf_rejects_unrelated_actions() {
+    let result = run_preset(json!({
+        "agent_action_name": "pre_run_command",
+        "trajectory_id": "traj-123"
+    }));
+
+    assert!(result.is_err());
+}